@@ -14,6 +14,7 @@ pub mod test;
 pub mod adapter;
 
 mod _ext_impl;
+#[allow(unused_imports)]
 pub use self::_ext_impl::*;
 mod _traits;
 pub use self::_traits::*;
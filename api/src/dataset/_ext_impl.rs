@@ -21,7 +21,7 @@ where
     type Error = Infallible;
 
     #[inline]
-    fn quads(&self) -> DQuadSource<Self> {
+    fn quads(&self) -> DQuadSource<'_, Self> {
         Box::new(<[Q]>::iter(self).map(StreamedQuad::by_ref).as_quad_source())
     }
 }
@@ -34,7 +34,7 @@ where
     type Error = Infallible;
 
     #[inline]
-    fn quads(&self) -> DQuadSource<Self> {
+    fn quads(&self) -> DQuadSource<'_, Self> {
         Box::new(<[Q]>::iter(self).map(StreamedQuad::by_ref).as_quad_source())
     }
 }
@@ -116,7 +116,7 @@ where
     type Error = Infallible;
 
     #[inline]
-    fn quads(&self) -> DQuadSource<Self> {
+    fn quads(&self) -> DQuadSource<'_, Self> {
         Box::from(self.iter().map(StreamedQuad::by_ref).as_quad_source())
     }
 }
@@ -156,6 +156,7 @@ where
 /// We repeat this step until either
 /// - we reached a point where each blank node has a unique hash, or
 /// - the last step didn't change the number of distinct hash.
+///
 /// At this point, if several blank nodes share the same hash,
 /// they must be absolutely redundant.
 fn calc_bn_hashes<D, H>(
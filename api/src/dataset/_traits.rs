@@ -85,7 +85,7 @@ pub trait Dataset {
     /// # Ok(())
     /// # }
     /// ```
-    fn quads(&self) -> DQuadSource<Self>;
+    fn quads(&self) -> DQuadSource<'_, Self>;
 
     /// An iterator visiting all quads with the given subject.
     ///
@@ -711,15 +711,15 @@ pub trait MutableDataset: Dataset {
             .map_ok(|q| {
                 (
                     [q.s().clone(), q.p().clone(), q.o().clone()],
-                    q.g().map(Clone::clone),
+                    q.g().cloned(),
                 )
             })
             .collect::<std::result::Result<Vec<_>, _>>()
             .map_err(Into::into)?;
         let mut to_remove = to_remove.into_iter().as_quad_source();
-        Ok(self
+        self
             .remove_all(&mut to_remove)
-            .map_err(|err| err.inner_into())?)
+            .map_err(|err| err.inner_into())
     }
 
     /// Keep only the quads matching the given matchers.
@@ -745,7 +745,7 @@ pub trait MutableDataset: Dataset {
             .map_ok(|q| {
                 (
                     [q.s().clone(), q.p().clone(), q.o().clone()],
-                    q.g().map(Clone::clone),
+                    q.g().cloned(),
                 )
             })
             .collect::<std::result::Result<Vec<_>, _>>()
@@ -775,7 +775,6 @@ pub trait MutableDataset: Dataset {
 ///
 /// [`Dataset`]: trait.Dataset.html
 /// [`MutableDataset`]: trait.MutableDataset.html
-
 pub trait SetDataset: Dataset {}
 
 #[cfg(test)]
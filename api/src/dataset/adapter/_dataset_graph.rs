@@ -46,7 +46,7 @@ where
     type Triple = FromQuad<D::Quad>;
     type Error = D::Error;
 
-    fn triples(&self) -> GTripleSource<Self> {
+    fn triples(&self) -> GTripleSource<'_, Self> {
         Box::new(
             self.dataset
                 .borrow()
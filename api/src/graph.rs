@@ -10,6 +10,7 @@
 pub mod test;
 
 mod _ext_impl;
+#[allow(unused_imports)]
 pub use self::_ext_impl::*;
 mod _traits;
 pub use self::_traits::*;
@@ -21,7 +21,7 @@ where
     type Error = Infallible;
 
     #[inline]
-    fn triples(&self) -> GTripleSource<Self> {
+    fn triples(&self) -> GTripleSource<'_, Self> {
         Box::new(
             <[T]>::iter(self)
                 .map(StreamedTriple::by_ref)
@@ -38,7 +38,7 @@ where
     type Error = Infallible;
 
     #[inline]
-    fn triples(&self) -> GTripleSource<Self> {
+    fn triples(&self) -> GTripleSource<'_, Self> {
         Box::new(
             <[T]>::iter(self)
                 .map(StreamedTriple::by_ref)
@@ -108,7 +108,7 @@ where
     type Error = Infallible;
 
     #[inline]
-    fn triples(&self) -> GTripleSource<Self> {
+    fn triples(&self) -> GTripleSource<'_, Self> {
         Box::from(self.iter().map(StreamedTriple::by_ref).as_triple_source())
     }
 }
@@ -160,7 +160,7 @@ where
     }
 }
 
-impl<'a, T, S: BuildHasher> SetGraph for HashSet<T, S> where T: Eq + Hash + Triple {}
+impl<T, S: BuildHasher> SetGraph for HashSet<T, S> where T: Eq + Hash + Triple {}
 
 #[cfg(test)]
 mod test {
@@ -85,7 +85,7 @@ pub trait Graph {
     /// # Ok(())
     /// # }
     /// ```
-    fn triples(&self) -> GTripleSource<Self>;
+    fn triples(&self) -> GTripleSource<'_, Self>;
 
     /// An iterator visiting all triples with the given subject.
     ///
@@ -561,8 +561,8 @@ pub trait MutableGraph: Graph {
     /// and could be improved in specific implementations of the trait.
     ///
     /// [`SetGraph`]: trait.SetGraph.html
-    fn remove_matching<'s, S, P, O>(
-        &'s mut self,
+    fn remove_matching<S, P, O>(
+        &mut self,
         ms: &S,
         mp: &P,
         mo: &O,
@@ -581,9 +581,9 @@ pub trait MutableGraph: Graph {
             .collect::<std::result::Result<Vec<_>, _>>()
             .map_err(Into::into)?;
         let mut to_remove = to_remove.into_iter().as_triple_source();
-        Ok(self
+        self
             .remove_all(&mut to_remove)
-            .map_err(|err| err.inner_into())?)
+            .map_err(|err| err.inner_into())
     }
 
     /// Keep only the triples matching the given matchers.
@@ -591,8 +591,8 @@ pub trait MutableGraph: Graph {
     /// # Note to implementors
     /// The default implementation is rather naive,
     /// and could be improved in specific implementations of the trait.
-    fn retain_matching<'s, S, P, O>(
-        &'s mut self,
+    fn retain_matching<S, P, O>(
+        &mut self,
         ms: &S,
         mp: &P,
         mo: &O,
@@ -636,7 +636,6 @@ pub trait MutableGraph: Graph {
 ///
 /// [`Graph`]: trait.Graph.html
 /// [`MutableGraph`]: trait.MutableGraph.html
-
 pub trait SetGraph: Graph {}
 
 #[inline]
@@ -125,7 +125,6 @@ macro_rules! namespace {
         /// Test module for checking tha IRIs are valid
         #[cfg(test)]
         mod test_valid_iri {
-            #[test]
             $(
                 #[allow(non_snake_case)]
                 #[test]
@@ -143,7 +142,7 @@ macro_rules! namespace {
         }
     };
     ($iri_prefix:expr, $($suffix:ident),*) => {
-        namespace!($iri_prefix, $($suffix),*;);
+        $crate::namespace!($iri_prefix, $($suffix),*;);
     };
 }
 
@@ -185,6 +184,7 @@ pub mod rdf {
         // datatypes
         HTML,
         JSON,
+        dirLangString,
         langString,
         XMLLiteral,
         // properties
@@ -224,7 +224,10 @@ pub mod xsd {
         anyType,
         anySimpleType,
             duration,
+                dayTimeDuration,
+                yearMonthDuration,
             dateTime,
+                dateTimeStamp,
             time,
             date,
             gYearMonth,
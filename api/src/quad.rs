@@ -24,6 +24,7 @@ pub trait Quad {
     fn g(&self) -> Option<&Self::Term>;
 
     /// [`Triple`](../triple/trait.Triple.html) adapter owning this quad.
+    #[allow(clippy::wrong_self_convention)]
     fn as_triple(self) -> QuadAsTriple<Self>
     where
         Self: Sized,
@@ -32,7 +33,7 @@ pub trait Quad {
     }
 
     /// Iterator over the components of this triple
-    fn components(&self) -> QuadIter<Self> {
+    fn components(&self) -> QuadIter<'_, Self> {
         QuadIter(self, 0)
     }
 }
@@ -119,15 +120,15 @@ where
     type Term = T::Term;
     #[inline]
     fn s(&self) -> &Self::Term {
-        &self.0.s()
+        self.0.s()
     }
     #[inline]
     fn p(&self) -> &Self::Term {
-        &self.0.p()
+        self.0.p()
     }
     #[inline]
     fn o(&self) -> &Self::Term {
-        &self.0.o()
+        self.0.o()
     }
     #[inline]
     fn g(&self) -> Option<&Self::Term> {
@@ -138,7 +139,7 @@ where
 /// An owned `Quad` as a tuple of an array and an optional name.
 pub type TupleQuad<T> = ([T; 3], Option<T>);
 
-impl<'a, Q: Quad> Quad for &'a Q
+impl<Q: Quad> Quad for &Q
 where
     Q: Quad,
 {
@@ -38,6 +38,7 @@ pub type AsInfallibleSource<I, T> = Map<I, fn(T) -> Result<T, Infallible>>;
 /// [`Quad`]: ../trait.Quad.html
 pub trait AsQuadSource<T>: Sized {
     /// Map all items of this iterator into an Ok result.
+    #[allow(clippy::wrong_self_convention)]
     fn as_quad_source(self) -> AsInfallibleSource<Self, T>;
 }
 
@@ -1,21 +1,25 @@
 //! This module defines the API for [RDF] terms.
 //!
 //! Terms are the building blocks of an [RDF] graph.
-//! There are four types of terms: IRIs, blank nodes (BNode for short),
-//! literals and variables.
+//! There are five types of terms: IRIs, blank nodes (BNode for short),
+//! literals, variables and quoted triples.
 //!
-//! NB: variable only exist in [generalized RDF].
+//! NB: variable only exist in [generalized RDF],
+//! and quoted triples only exist in [RDF-star].
 //!
 //! [Sophia]: https://docs.rs/sophia/latest/sophia/
 //! [RDF]: https://www.w3.org/TR/rdf-primer/
 //! [Linked Data]: http://linkeddata.org/
 //! [generalized RDF]: https://docs.rs/sophia/latest/sophia/#generalized-vs-strict-rdf-model
+//! [RDF-star]: https://w3c.github.io/rdf-star/cg-spec/editors_draft.html
 
 use mownstr::MownStr;
 use std::cmp::Ordering;
 use std::error::Error;
 use std::hash::{Hash, Hasher};
 
+mod _direction;
+pub use self::_direction::*;
 mod _dyn_term;
 mod _graph_name_matcher; // is 'pub use'd by module 'matcher'
 mod _raw_value;
@@ -26,9 +30,10 @@ pub use simple_iri::SimpleIri;
 
 /// Trait for all RDF terms.
 ///
-/// Sophia supports 4 kinds of terms: IRI references (absolute or relative),
-/// literals, blank nodes and variables.
-/// Note that strict RDF does not support relative IRI references nor variables.
+/// Sophia supports 5 kinds of terms: IRI references (absolute or relative),
+/// literals, blank nodes, variables and quoted triples.
+/// Note that strict RDF does not support relative IRI references, variables
+/// nor quoted triples.
 ///
 /// Types representing terms, of one or more of the kinds above,
 /// can implement this trait and be used with the rest of the Sophia API.
@@ -37,8 +42,8 @@ pub use simple_iri::SimpleIri;
 ///
 /// The design of this trait is not as "pure" as it could have been:
 ///
-/// * it merges into a single trait four "kinds"
-///   which could arguably be considered as four different abstract types;
+/// * it merges into a single trait five "kinds"
+///   which could arguably be considered as five different abstract types;
 ///
 /// * it is rather opinionated on how implementation should store their data internally,
 ///   and has a very constrained contract (see below);
@@ -65,14 +70,15 @@ pub use simple_iri::SimpleIri;
 ///   it must be consistent with (or, even better, based on)
 ///   [`term_cmp`](./function.term_cmp.html);
 pub trait TTerm {
-    /// Returns the kind of this term (IRI, literal, blank node, variable).
+    /// Returns the kind of this term (IRI, literal, blank node, variable, quoted triple).
     fn kind(&self) -> TermKind;
 
     /// Return the "value" of this term, which depends on its kind:
     /// * for an IRI reference, its value;
     /// * for a literal, its lexical value;
     /// * for a blank node, its local identifier;
-    /// * for a variable, its name.
+    /// * for a variable, its name;
+    /// * for a quoted triple, the empty string (see [`triple`](#method.triple)).
     ///
     /// # Performance
     /// The returned `MownStr` is always borrowed (equivalent to a `&str`),
@@ -84,7 +90,7 @@ pub trait TTerm {
     /// Should not be overridden; must be consistent with [`value_raw`].
     ///
     /// [`value_raw`]: #tymethod.value_raw
-    fn value(&self) -> MownStr {
+    fn value(&self) -> MownStr<'_> {
         self.value_raw().into()
     }
 
@@ -92,7 +98,7 @@ pub trait TTerm {
     ///
     /// # Note to implementors
     /// Should not be overridden; must be consistent with [`datatype_raw`].
-    fn datatype(&self) -> Option<SimpleIri> {
+    fn datatype(&self) -> Option<SimpleIri<'_>> {
         None
     }
 
@@ -106,6 +112,19 @@ pub trait TTerm {
         None
     }
 
+    /// Return the base direction of this term
+    /// if it is a directional language-tagged literal.
+    ///
+    /// The datatype of such literals is `rdf:dirLangString`.
+    ///
+    /// # Note to implementors
+    /// The default implementation always return `None`,
+    /// so unless your type may represent a directional language-tagged literal,
+    /// you do not need to override it.
+    fn direction(&self) -> Option<Direction> {
+        None
+    }
+
     /// Return the "value" of this term, possibly split in two substrings.
     /// The second part might only be non-empty if this term is an IRI reference.
     ///
@@ -115,11 +134,22 @@ pub trait TTerm {
     /// The second part of the raw value is intended for some implementations
     /// of IRIs, storing both a "namespace" and a "suffix".
     /// For other kinds of term, the second part must always be None.
-    fn value_raw(&self) -> RawValue;
+    fn value_raw(&self) -> RawValue<'_>;
+
+    /// Return the subject, predicate and object of this term if it is a quoted triple.
+    ///
+    /// # Note to implementors
+    /// The default implementation always return `None`,
+    /// so unless your type may represent a quoted triple,
+    /// you do not need to override it.
+    fn triple(&self) -> Option<[&dyn TTerm; 3]> {
+        None
+    }
 
     /// All terms are absolute, except for:
     /// * relative IRI references,
-    /// * literals whose datatype is a relative IRI reference.
+    /// * literals whose datatype is a relative IRI reference,
+    /// * quoted triples containing a term that is not absolute.
     fn is_absolute(&self) -> bool {
         match self.kind() {
             Iri => self.value_raw().is_absolute(),
@@ -127,6 +157,7 @@ pub trait TTerm {
                 None => self.datatype().unwrap().value_raw().is_absolute(),
                 Some(_) => true,
             },
+            QuotedTriple => self.triple().unwrap().iter().all(|t| t.is_absolute()),
             _ => true,
         }
     }
@@ -146,7 +177,11 @@ pub trait TTerm {
 }
 
 /// Any [`TTerm`](./trait.TTerm.html) belongs to one those kinds.
+///
+/// New kinds may be added in the future,
+/// so `match` expressions on `TermKind` outside this crate need a wildcard arm.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+#[non_exhaustive]
 pub enum TermKind {
     /// RDF [IRI](https://www.w3.org/TR/rdf11-concepts/#section-IRIs),
     /// although in Sophia they can also be
@@ -158,6 +193,8 @@ pub enum TermKind {
     BlankNode,
     /// [variable](https://www.w3.org/TR/sparql11-query/#QSynVariables)
     Variable,
+    /// [quoted triple](https://w3c.github.io/rdf-star/cg-spec/editors_draft.html#dfn-quoted)
+    QuotedTriple,
 }
 use TermKind::*;
 
@@ -223,10 +260,16 @@ where
                     for b in tag.bytes() {
                         state.write_u8(b.to_ascii_uppercase());
                     }
+                    term.direction().hash(state);
                 }
             };
             v.hash(state);
         }
+        QuotedTriple => {
+            for t in term.triple().unwrap().iter() {
+                term_hash(*t, state);
+            }
+        }
         _ => v.hash(state),
     }
 }
@@ -243,6 +286,11 @@ where
 {
     let k1 = t1.kind();
     let k2 = t2.kind();
+    if k1 == QuotedTriple && k2 == QuotedTriple {
+        let spo1 = t1.triple().unwrap();
+        let spo2 = t2.triple().unwrap();
+        return (0..3).all(|i| term_eq(spo1[i], spo2[i]));
+    }
     k1 == k2 && {
         let v1 = t1.value_raw();
         let v2 = t2.value_raw();
@@ -252,7 +300,9 @@ where
             v1.0 == v2.0 && {
                 if matches!(k1, Literal) {
                     match (t1.language(), t2.language()) {
-                        (Some(tag1), Some(tag2)) => tag1.eq_ignore_ascii_case(tag2),
+                        (Some(tag1), Some(tag2)) => {
+                            tag1.eq_ignore_ascii_case(tag2) && t1.direction() == t2.direction()
+                        }
                         (None, None) => {
                             let dt1 = t1.datatype().unwrap();
                             let dt2 = t2.datatype().unwrap();
@@ -269,10 +319,11 @@ where
 }
 
 /// Compare two terms:
-/// * IRIs < literals < blank nodes < variables
+/// * IRIs < literals < blank nodes < variables < quoted triples
 /// * IRIs, blank nodes and variables are ordered by their value
-/// * Literals are ordered by their datatype, then their language (if any),
+/// * Literals are ordered by their datatype, then their language and base direction (if any),
 ///   then their lexical value
+/// * Quoted triples are ordered by their subject, then predicate, then object
 ///
 /// NB: literals are ordered by their *lexical* value,
 /// so for example, `"10"^^xsd:integer` come `*before* "2"^^xsd:integer`.
@@ -295,17 +346,25 @@ where
                 if let (Some(tag1), Some(tag2)) = (tag1, tag2) {
                     tag1.to_uppercase()
                         .cmp(&tag2.to_uppercase())
-                        .then_with(|| v1.0.cmp(&v2.0))
+                        .then_with(|| t1.direction().cmp(&t2.direction()))
+                        .then_with(|| v1.0.cmp(v2.0))
                 } else {
                     let dt1 = t1.datatype().unwrap();
                     let dt2 = t2.datatype().unwrap();
                     dt1.value_raw()
                         .bytes()
                         .cmp(dt2.value_raw().bytes())
-                        .then_with(|| v1.0.cmp(&v2.0))
+                        .then_with(|| v1.0.cmp(v2.0))
                 }
             }
-            _ => v1.0.cmp(&v2.0),
+            QuotedTriple => {
+                let spo1 = t1.triple().unwrap();
+                let spo2 = t2.triple().unwrap();
+                (0..3).fold(Ordering::Equal, |o, i| {
+                    o.then_with(|| term_cmp(spo1[i], spo2[i]))
+                })
+            }
+            _ => v1.0.cmp(v2.0),
         }
     })
 }
@@ -320,7 +379,7 @@ where
     match term.kind() {
         Iri => {
             w.write_char('<')?;
-            w.write_str(&v.0)?;
+            w.write_str(v.0)?;
             if let Some(suffix) = v.1 {
                 w.write_str(suffix)?;
             }
@@ -329,7 +388,11 @@ where
         Literal => {
             write!(w, "{:?}", v.0)?;
             if let Some(tag) = term.language() {
-                write!(w, "@{}", tag)
+                write!(w, "@{}", tag)?;
+                if let Some(dir) = term.direction() {
+                    write!(w, "--{}", dir)?;
+                }
+                Ok(())
             } else {
                 let dt = term.datatype().unwrap();
                 if !term_eq(&dt, &crate::ns::xsd::string) {
@@ -341,6 +404,14 @@ where
         }
         BlankNode => write!(w, "_:{}", v.0),
         Variable => write!(w, "_?{}", v.0),
+        QuotedTriple => {
+            w.write_str("<< ")?;
+            for t in term.triple().unwrap().iter() {
+                term_format(*t, w)?;
+                w.write_char(' ')?;
+            }
+            w.write_str(">>")
+        }
     }
 }
 
@@ -0,0 +1,40 @@
+//! Implementation of [`Direction`](./enum.Direction.html).
+
+use std::fmt;
+
+/// The base direction of a directional language-tagged string,
+/// as defined in [RDF 1.2](https://www.w3.org/TR/rdf12-concepts/#dfn-base-direction).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    /// Left-to-right
+    Ltr,
+    /// Right-to-left
+    Rtl,
+}
+
+impl Direction {
+    /// Parse a base direction from its textual representation (`ltr` or `rtl`).
+    ///
+    /// Return `None` for any other string.
+    pub fn parse(txt: &str) -> Option<Direction> {
+        match txt {
+            "ltr" => Some(Direction::Ltr),
+            "rtl" => Some(Direction::Rtl),
+            _ => None,
+        }
+    }
+
+    /// The textual representation of this base direction (`ltr` or `rtl`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Direction::Ltr => "ltr",
+            Direction::Rtl => "rtl",
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
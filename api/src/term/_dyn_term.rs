@@ -13,6 +13,7 @@ impl PartialEq for dyn TTerm {
 
 impl Eq for dyn TTerm {}
 
+#[allow(clippy::non_canonical_partial_ord_impl)]
 impl PartialOrd for dyn TTerm {
     fn partial_cmp(&self, other: &dyn TTerm) -> Option<Ordering> {
         Some(term_cmp(self, other))
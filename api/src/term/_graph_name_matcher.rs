@@ -63,7 +63,7 @@ where
     }
 }
 
-impl<'a, U> GraphNameMatcher for AnyOrExactlyRef<Option<&'a U>>
+impl<U> GraphNameMatcher for AnyOrExactlyRef<Option<&U>>
 where
     U: TTerm + ?Sized,
 {
@@ -103,9 +103,9 @@ fn str_absolute(txt: &str) -> Absolute {
         if b == b':' {
             return Absolute::Yes;
         }
-        if !(b'A' <= b && b <= b'Z'
-            || b'a' <= b && b <= b'z'
-            || b'0' <= b && b <= b'9'
+        if !(b.is_ascii_uppercase()
+            || b.is_ascii_lowercase()
+            || b.is_ascii_digit()
             || b == b'.'
             || b == b'+'
             || b == b'-')
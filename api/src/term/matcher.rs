@@ -117,7 +117,7 @@ where
     }
 }
 
-impl<'a, U> TermMatcher for AnyOrExactlyRef<&'a U>
+impl<U> TermMatcher for AnyOrExactlyRef<&U>
 where
     U: TTerm + ?Sized,
 {
@@ -162,7 +162,7 @@ where
     type Term = U;
     fn constant(&self) -> Option<&U> {
         if self.len() == 1 {
-            Some(&self[0])
+            Some(self[0])
         } else {
             None
         }
@@ -217,7 +217,7 @@ where
     type Term = U;
     fn constant(&self) -> Option<&U> {
         if self.len() == 1 {
-            Some(&self[0])
+            Some(self[0])
         } else {
             None
         }
@@ -404,4 +404,34 @@ mod test {
         assert!(TermMatcher::matches(&m, &t1));
         assert!(!TermMatcher::matches(&m, &t2));
     }
+
+    #[test]
+    fn test_quoted_triple_as_matcher() {
+        use crate::term::test::TestTerm;
+        let s = TestTerm::<&str>::iri("http://example.org/s");
+        let p = TestTerm::<&str>::iri2("http://example.org/", "p");
+        let o = TestTerm::<&str>::bnode("o");
+        let m = TestTerm::triple(s, p, o);
+        // same triple, with a differently cut predicate
+        let t1 = TestTerm::triple(s, TestTerm::iri("http://example.org/p"), o);
+        let t2 = TestTerm::triple(s, p, TestTerm::bnode("other"));
+        let t3 = TestTerm::triple(t1, p, o);
+
+        let mc = TermMatcher::constant(&m);
+        assert!(mc.is_some());
+        assert!(TermMatcher::matches(&m, &t1));
+        assert!(!TermMatcher::matches(&m, &t2));
+        assert!(!TermMatcher::matches(&m, &t3));
+        assert!(!TermMatcher::matches(&m, &s));
+
+        let m = [&m, &t2];
+        assert!(TermMatcher::matches(&m[..], &t1));
+        assert!(TermMatcher::matches(&m[..], &t2));
+        assert!(!TermMatcher::matches(&m[..], &t3));
+
+        let m = [|t: &dyn TTerm| t.triple().map(|spo| term_eq(spo[0], &t1)) == Some(true)];
+        assert!(TermMatcher::matches(&m, &t3));
+        assert!(!TermMatcher::matches(&m, &t1));
+        assert!(TermMatcher::matches(&ANY, &t3));
+    }
 }
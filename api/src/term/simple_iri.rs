@@ -42,7 +42,7 @@ impl<'a> TTerm for SimpleIri<'a> {
     fn kind(&self) -> TermKind {
         TermKind::Iri
     }
-    fn value_raw(&self) -> RawValue {
+    fn value_raw(&self) -> RawValue<'_> {
         RawValue(self.ns, self.suffix)
     }
     fn as_dyn(&self) -> &dyn TTerm {
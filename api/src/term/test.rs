@@ -5,12 +5,16 @@ use std::fmt;
 use std::hash;
 
 /// A naive implementation of TTerm, with no check whatsoever.
+///
+/// NB: in order to keep this type `Copy`,
+/// the components of quoted triples are leaked.
 #[derive(Clone, Copy, Debug)]
-pub struct TestTerm<T> {
+pub struct TestTerm<T: 'static> {
     kind: TermKind,
     value: T,
     extra1: Option<T>,
     extra2: Option<T>,
+    triple: Option<&'static [TestTerm<T>; 3]>,
 }
 
 impl<'a, T> TestTerm<T>
@@ -23,6 +27,7 @@ where
             value: value.into(),
             extra1: None,
             extra2: None,
+            triple: None,
         }
     }
     pub fn iri2(ns: &'a str, suffix: &'a str) -> Self {
@@ -31,6 +36,7 @@ where
             value: ns.into(),
             extra1: Some(suffix.into()),
             extra2: None,
+            triple: None,
         }
     }
     pub fn bnode(value: &'a str) -> Self {
@@ -39,6 +45,7 @@ where
             value: value.into(),
             extra1: None,
             extra2: None,
+            triple: None,
         }
     }
     pub fn var(value: &'a str) -> Self {
@@ -47,6 +54,7 @@ where
             value: value.into(),
             extra1: None,
             extra2: None,
+            triple: None,
         }
     }
     pub fn lit_dt(value: &'a str, datatype: SimpleIri<'a>) -> Self {
@@ -56,6 +64,7 @@ where
             value: value.into(),
             extra1: Some(extra1.into()),
             extra2: extra2.map(From::from),
+            triple: None,
         }
     }
     pub fn lit_lang(value: &'a str, tag: &'a str) -> Self {
@@ -64,6 +73,16 @@ where
             value: value.into(),
             extra1: None,
             extra2: Some(tag.into()),
+            triple: None,
+        }
+    }
+    pub fn triple(s: Self, p: Self, o: Self) -> Self {
+        TestTerm {
+            kind: TermKind::QuotedTriple,
+            value: "".into(),
+            extra1: None,
+            extra2: None,
+            triple: Some(Box::leak(Box::new([s, p, o]))),
         }
     }
 }
@@ -75,7 +94,7 @@ where
     fn kind(&self) -> TermKind {
         self.kind
     }
-    fn value_raw(&self) -> RawValue {
+    fn value_raw(&self) -> RawValue<'_> {
         match self.kind {
             TermKind::Iri => (
                 self.value.as_ref(),
@@ -85,7 +104,7 @@ where
             _ => self.value.as_ref().into(),
         }
     }
-    fn datatype(&self) -> Option<SimpleIri> {
+    fn datatype(&self) -> Option<SimpleIri<'_>> {
         if self.kind == TermKind::Literal {
             Some(match self.extra1.as_ref() {
                 None => rdf::langString,
@@ -104,6 +123,10 @@ where
             None
         }
     }
+    fn triple(&self) -> Option<[&dyn TTerm; 3]> {
+        self.triple
+            .map(|spo| [spo[0].as_dyn(), spo[1].as_dyn(), spo[2].as_dyn()])
+    }
     fn as_dyn(&self) -> &dyn TTerm {
         self
     }
@@ -179,6 +202,27 @@ where
                 None => TestTerm::lit_dt(raw.0, term.datatype().unwrap()),
                 Some(tag) => TestTerm::lit_lang(raw.0, tag),
             },
+            TermKind::QuotedTriple => {
+                let [s, p, o] = term.triple().unwrap();
+                TestTerm::triple(Self::copy(s), Self::copy(p), Self::copy(o))
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod test_test_term {
+    use super::*;
+
+    #[test]
+    fn copy_quoted_triple() {
+        let s = TestTerm::<&str>::iri("tag:s");
+        let o = TestTerm::<&str>::lit_lang("o", "en");
+        let inner = TestTerm::triple(s, rdf::type_.into(), o);
+        let outer = TestTerm::triple(s, rdf::value.into(), inner);
+        let copy = TestTerm::<Box<str>>::copy(&outer);
+        assert_eq!(copy.kind(), TermKind::QuotedTriple);
+        assert!(term_eq(&copy, &outer));
+        assert!(!term_eq(&copy, &inner));
+    }
+}
@@ -31,6 +31,7 @@ pub trait Triple {
 
     /// [`Quad`](../quad/trait.Quad.html) adapter owning this triple,
     /// pretending to belong to the default graph.
+    #[allow(clippy::wrong_self_convention)]
     fn as_quad(self) -> TripleAsQuad<Self>
     where
         Self: Sized,
@@ -39,6 +40,7 @@ pub trait Triple {
     }
     /// [`Quad`](../quad/trait.Quad.html) adapter owning this triple,
     /// pretending to belong to a named graph with the given name.
+    #[allow(clippy::wrong_self_convention)]
     fn as_quad_from(self, name: Self::Term) -> TripleAsQuadFrom<Self>
     where
         Self: Sized,
@@ -48,7 +50,7 @@ pub trait Triple {
     }
 
     /// Iterator over the components of this triple
-    fn components(&self) -> TripleIter<Self> {
+    fn components(&self) -> TripleIter<'_, Self> {
         TripleIter(self, 0)
     }
 }
@@ -114,7 +116,7 @@ where
     }
 }
 
-impl<'a, T: Triple> Triple for &'a T {
+impl<T: Triple> Triple for &T {
     type Term = T::Term;
     #[inline]
     fn s(&self) -> &Self::Term {
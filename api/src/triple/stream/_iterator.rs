@@ -42,6 +42,7 @@ pub type AsInfallibleSource<I, T> = Map<I, fn(T) -> Result<T, Infallible>>;
 /// [`Triple`]: ../trait.Triple.html
 pub trait AsTripleSource<T>: Sized {
     /// Map all items of this iterator into an Ok result.
+    #[allow(clippy::wrong_self_convention)]
     fn as_triple_source(self) -> AsInfallibleSource<Self, T>;
 }
 
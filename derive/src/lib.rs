@@ -0,0 +1,260 @@
+//! This crate provides the `#[derive(RdfResource)]` macro
+//! for the [`sophia`](https://docs.rs/sophia/) crate.
+//!
+//! It should not be used directly, but through
+//! [`sophia::resource`](https://docs.rs/sophia/latest/sophia/resource/index.html),
+//! which re-exports it when the `derive` feature is enabled.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, Data, DeriveInput, Error, Expr, Field, Fields, GenericArgument, Ident,
+    PathArguments, Result, Type,
+};
+
+/// Generate typed accessors over any `Graph` and `MutableGraph`
+/// for a struct whose fields are annotated with predicates.
+///
+/// See [`sophia::resource`](https://docs.rs/sophia/latest/sophia/resource/index.html)
+/// for more details.
+#[proc_macro_derive(RdfResource, attributes(rdf))]
+pub fn derive_rdf_resource(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// How many values a property can have, depending on the type of the field.
+enum Cardinality {
+    One,
+    Optional,
+    Many,
+}
+
+struct Property {
+    ident: Ident,
+    predicate: Expr,
+    cardinality: Cardinality,
+    /// The type of each value (i.e. without `Option` or `Vec`)
+    ty: Type,
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "RdfResource requires a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "RdfResource can only be derived for structs",
+            ))
+        }
+    };
+    let properties = fields.iter().map(property).collect::<Result<Vec<_>>>()?;
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let accessors = properties.iter().map(accessors);
+    let idents = properties.iter().map(|p| &p.ident);
+    let idents2 = properties.iter().map(|p| &p.ident);
+    let setters = properties.iter().map(|p| {
+        let ident = &p.ident;
+        let set = format_ident!("set_{}", ident);
+        match p.cardinality {
+            Cardinality::Optional => quote! { Self::#set(graph, subject, self.#ident.as_ref())?; },
+            _ => quote! { Self::#set(graph, subject, &self.#ident)?; },
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #(#accessors)*
+
+            /// Read all the properties of `subject` in `graph`.
+            pub fn from_graph<G_, TS_>(
+                graph: &G_,
+                subject: &TS_,
+            ) -> ::std::result::Result<Self, ::sophia::resource::ResourceError<G_::Error>>
+            where
+                G_: ::sophia::graph::Graph,
+                TS_: ::sophia::term::TTerm + ?Sized,
+            {
+                ::std::result::Result::Ok(Self {
+                    #( #idents: Self::#idents2(graph, subject)?, )*
+                })
+            }
+
+            /// Write all the properties of `subject` in `graph`,
+            /// replacing their previous values.
+            pub fn to_graph<G_, TS_>(
+                &self,
+                graph: &mut G_,
+                subject: &TS_,
+            ) -> ::sophia::graph::MGResult<G_, ()>
+            where
+                G_: ::sophia::graph::MutableGraph,
+                G_::Error: ::std::convert::Into<G_::MutationError>,
+                TS_: ::sophia::term::TTerm + ?Sized,
+            {
+                #(#setters)*
+                ::std::result::Result::Ok(())
+            }
+        }
+    })
+}
+
+fn property(field: &Field) -> Result<Property> {
+    let ident = field.ident.clone().expect("named field");
+    let attr = field
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("rdf"))
+        .ok_or_else(|| Error::new_spanned(field, "missing #[rdf(<predicate>)] attribute"))?;
+    let predicate: Expr = attr.parse_args()?;
+    let (cardinality, ty) = if let Some(ty) = wrapped(&field.ty, "Option") {
+        (Cardinality::Optional, ty)
+    } else if let Some(ty) = wrapped(&field.ty, "Vec") {
+        (Cardinality::Many, ty)
+    } else {
+        (Cardinality::One, field.ty.clone())
+    };
+    Ok(Property {
+        ident,
+        predicate,
+        cardinality,
+        ty,
+    })
+}
+
+/// If `ty` is `wrapper<T>`, return `T`.
+fn wrapped(ty: &Type, wrapper: &str) -> Option<Type> {
+    let path = match ty {
+        Type::Path(path) if path.qself.is_none() => &path.path,
+        _ => return None,
+    };
+    let segment = path.segments.last()?;
+    if segment.ident != wrapper {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(ty) => Some(ty.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn accessors(property: &Property) -> TokenStream2 {
+    let Property {
+        ident,
+        predicate,
+        cardinality,
+        ty,
+    } = property;
+    let set = format_ident!("set_{}", ident);
+    let pred = quote!(#predicate).to_string().replace(' ', "");
+    let read_bounds = quote! {
+        where
+            G_: ::sophia::graph::Graph,
+            TS_: ::sophia::term::TTerm + ?Sized,
+    };
+    let write_bounds = quote! {
+        where
+            G_: ::sophia::graph::MutableGraph,
+            G_::Error: ::std::convert::Into<G_::MutationError>,
+            TS_: ::sophia::term::TTerm + ?Sized,
+    };
+    let error = quote! { ::sophia::resource::ResourceError<G_::Error> };
+
+    match cardinality {
+        Cardinality::One => {
+            let get_doc = format!("Get the value of `{}` for `subject` in `graph`.", pred);
+            let set_doc = format!("Set the value of `{}` for `subject` in `graph`.", pred);
+            quote! {
+                #[doc = #get_doc]
+                pub fn #ident<G_, TS_>(graph: &G_, subject: &TS_) -> ::std::result::Result<#ty, #error>
+                #read_bounds
+                {
+                    ::sophia::resource::get_one(graph, subject, &#predicate)
+                }
+
+                #[doc = #set_doc]
+                pub fn #set<G_, TS_>(graph: &mut G_, subject: &TS_, value: &#ty) -> ::sophia::graph::MGResult<G_, ()>
+                #write_bounds
+                {
+                    ::sophia::resource::set_values(graph, subject, &#predicate, ::std::slice::from_ref(value))
+                }
+            }
+        }
+        Cardinality::Optional => {
+            let get_doc = format!(
+                "Get the value of `{}` for `subject` in `graph`, if any.",
+                pred
+            );
+            let set_doc = format!(
+                "Set or remove the value of `{}` for `subject` in `graph`.",
+                pred
+            );
+            quote! {
+                #[doc = #get_doc]
+                pub fn #ident<G_, TS_>(graph: &G_, subject: &TS_) -> ::std::result::Result<::std::option::Option<#ty>, #error>
+                #read_bounds
+                {
+                    ::sophia::resource::get_opt(graph, subject, &#predicate)
+                }
+
+                #[doc = #set_doc]
+                pub fn #set<G_, TS_>(graph: &mut G_, subject: &TS_, value: ::std::option::Option<&#ty>) -> ::sophia::graph::MGResult<G_, ()>
+                #write_bounds
+                {
+                    let values = value.map(::std::slice::from_ref).unwrap_or(&[]);
+                    ::sophia::resource::set_values(graph, subject, &#predicate, values)
+                }
+            }
+        }
+        Cardinality::Many => {
+            let add = format_ident!("add_{}", ident);
+            let get_doc = format!("Get all the values of `{}` for `subject` in `graph`.", pred);
+            let set_doc = format!(
+                "Replace all the values of `{}` for `subject` in `graph`.",
+                pred
+            );
+            let add_doc = format!("Add a value of `{}` for `subject` in `graph`.", pred);
+            quote! {
+                #[doc = #get_doc]
+                pub fn #ident<G_, TS_>(graph: &G_, subject: &TS_) -> ::std::result::Result<::std::vec::Vec<#ty>, #error>
+                #read_bounds
+                {
+                    ::sophia::resource::get_all(graph, subject, &#predicate)
+                }
+
+                #[doc = #set_doc]
+                pub fn #set<G_, TS_>(graph: &mut G_, subject: &TS_, values: &[#ty]) -> ::sophia::graph::MGResult<G_, ()>
+                #write_bounds
+                {
+                    ::sophia::resource::set_values(graph, subject, &#predicate, values)
+                }
+
+                #[doc = #add_doc]
+                pub fn #add<G_, TS_>(graph: &mut G_, subject: &TS_, value: &#ty) -> ::sophia::graph::MGResult<G_, bool>
+                #write_bounds
+                {
+                    ::sophia::resource::add_value(graph, subject, &#predicate, value)
+                }
+            }
+        }
+    }
+}
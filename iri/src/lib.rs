@@ -2,7 +2,8 @@
 //! an [RDF] and [Linked Data] toolkit in Rust.
 //!
 //! It provides functions for validating IRIs and IRI references,
-//! as well as for resolving IRI references agains a given base IRI.
+//! as well as for resolving IRI references agains a given base IRI,
+//! normalizing them, and mapping them to and from URIs.
 //!
 //! [Sophia]: https://docs.rs/sophia/latest/sophia/
 //! [RDF]: https://www.w3.org/TR/rdf-primer/
@@ -13,6 +14,7 @@
 mod _regex;
 pub use self::_regex::*;
 pub mod error;
+pub mod normalize;
 pub mod resolve;
 
 #[cfg(any(test, feature = "test_data"))]
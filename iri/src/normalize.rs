@@ -0,0 +1,357 @@
+//! Syntax-based normalization of IRIs,
+//! and mapping between IRIs and URIs, as per
+//! [\[RFC 3987\]](https://tools.ietf.org/html/rfc3987).
+//!
+//! [`normalize`] makes equivalent IRIs identical:
+//! ```
+//! # use sophia_iri::normalize::normalize;
+//! assert_eq!(
+//!     normalize("HTTP://Example.org:80/a/../%7euser/%c3%a9t%C3%A9")?,
+//!     "http://example.org/~user/été",
+//! );
+//! # Ok::<(), sophia_iri::error::InvalidIri>(())
+//! ```
+//!
+//! [`iri_to_uri`] and [`uri_to_iri`] convert between IRIs and URIs:
+//! ```
+//! # use sophia_iri::normalize::{iri_to_uri, uri_to_iri};
+//! assert_eq!(
+//!     iri_to_uri("http://bücher.example/été")?,
+//!     "http://xn--bcher-kva.example/%C3%A9t%C3%A9",
+//! );
+//! assert_eq!(
+//!     uri_to_iri("http://xn--bcher-kva.example/%C3%A9t%C3%A9")?,
+//!     "http://bücher.example/été",
+//! );
+//! # Ok::<(), sophia_iri::error::InvalidIri>(())
+//! ```
+//!
+//! [`normalize`]: fn.normalize.html
+//! [`iri_to_uri`]: fn.iri_to_uri.html
+//! [`uri_to_iri`]: fn.uri_to_iri.html
+
+use super::{error::*, resolve::*};
+use std::fmt::{self, Write};
+
+/// Apply syntax-based normalization
+/// ([RFC 3987 §5.3.2](https://tools.ietf.org/html/rfc3987#section-5.3.2))
+/// to the given IRI reference.
+///
+/// This consists in:
+/// * converting the scheme and host to lowercase,
+/// * decoding percent-encoded unreserved characters (including non-ASCII ones),
+///   and converting the remaining percent-encodings to uppercase,
+/// * removing dot-segments from the path (only if the IRI reference is absolute,
+///   as dot-segments are meaningful in relative references),
+/// * removing empty and default ports (for a few well known schemes),
+/// * replacing an empty path with `/` for `http` and `https`.
+pub fn normalize(iri: &str) -> Result<String> {
+    let mut c = Components::new(iri)?.map(normalize_pct);
+    c.scheme = c.scheme.map(|scheme| scheme.to_ascii_lowercase());
+    c.host = c.host.map(|host| normalize_pct(&host.to_ascii_lowercase()));
+    let default = default_port(c.scheme.as_deref());
+    if c.port
+        .as_deref()
+        .is_some_and(|port| port.is_empty() || Some(port) == default)
+    {
+        c.port = None;
+    }
+    if c.scheme.is_some() {
+        let mut path: Vec<&str> = c.path.split('/').collect();
+        remove_dot_segments(&mut path);
+        c.path = path.join("/");
+    }
+    if c.path.is_empty()
+        && c.host.is_some()
+        && matches!(c.scheme.as_deref(), Some("http" | "https"))
+    {
+        c.path.push('/');
+    }
+    Ok(c.to_string())
+}
+
+/// Map the given IRI reference to a URI reference
+/// ([RFC 3987 §3.1](https://tools.ietf.org/html/rfc3987#section-3.1)).
+///
+/// Non-ASCII characters are percent-encoded,
+/// except in internationalized domain names, which are converted to punycode.
+pub fn iri_to_uri(iri: &str) -> Result<String> {
+    let mut c = Components::new(iri)?.map(encode_non_ascii);
+    c.host = c.host.map(|host| {
+        if host.is_ascii() {
+            host
+        } else {
+            idna::domain_to_ascii(&host).unwrap_or_else(|_| encode_non_ascii(&host))
+        }
+    });
+    Ok(c.to_string())
+}
+
+/// Map the given URI reference to an IRI reference
+/// ([RFC 3987 §3.2](https://tools.ietf.org/html/rfc3987#section-3.2)).
+///
+/// Percent-encoded UTF-8 sequences are decoded if they represent unreserved characters,
+/// and punycode domain names are converted to Unicode.
+pub fn uri_to_iri(uri: &str) -> Result<String> {
+    let mut c = Components::new(uri)?.map(normalize_pct);
+    c.host = c.host.map(|host| {
+        let is_idn = host.split('.').any(|label| {
+            label
+                .get(..4)
+                .is_some_and(|p| p.eq_ignore_ascii_case("xn--"))
+        });
+        match idna::domain_to_unicode(&host) {
+            (unicode, Ok(())) if is_idn => unicode,
+            _ => normalize_pct(&host),
+        }
+    });
+    Ok(c.to_string())
+}
+
+/// The components of an IRI reference, with the authority split in its parts.
+struct Components {
+    scheme: Option<String>,
+    userinfo: Option<String>,
+    host: Option<String>,
+    port: Option<String>,
+    path: String,
+    query: Option<String>,
+    fragment: Option<String>,
+}
+
+impl Components {
+    fn new(txt: &str) -> Result<Self> {
+        let parsed = IriParsed::new(txt)?;
+        let (mut userinfo, mut host, mut port) = (None, None, None);
+        if let Some(authority) = parsed.authority {
+            let hostport = match authority.rfind('@') {
+                Some(i) => {
+                    userinfo = Some(authority[..i].to_string());
+                    &authority[i + 1..]
+                }
+                None => authority,
+            };
+            // the host may be an IP literal, containing ':'
+            let end = if hostport.starts_with('[') {
+                hostport.find(']').map_or(hostport.len(), |i| i + 1)
+            } else {
+                0
+            };
+            match hostport[end..].find(':') {
+                Some(i) => {
+                    host = Some(hostport[..end + i].to_string());
+                    port = Some(hostport[end + i + 1..].to_string());
+                }
+                None => host = Some(hostport.to_string()),
+            }
+        }
+        Ok(Components {
+            scheme: parsed.scheme.map(str::to_string),
+            userinfo,
+            host,
+            port,
+            path: parsed.path.join("/"),
+            query: parsed.query.map(str::to_string),
+            fragment: parsed.fragment.map(str::to_string),
+        })
+    }
+
+    /// Apply `f` to the userinfo, path, query and fragment.
+    fn map<F: Fn(&str) -> String>(self, f: F) -> Self {
+        Components {
+            userinfo: self.userinfo.as_deref().map(&f),
+            path: f(&self.path),
+            query: self.query.as_deref().map(&f),
+            fragment: self.fragment.as_deref().map(&f),
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for Components {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(scheme) = &self.scheme {
+            write!(f, "{}:", scheme)?;
+        }
+        if let Some(host) = &self.host {
+            f.write_str("//")?;
+            if let Some(userinfo) = &self.userinfo {
+                write!(f, "{}@", userinfo)?;
+            }
+            f.write_str(host)?;
+            if let Some(port) = &self.port {
+                write!(f, ":{}", port)?;
+            }
+        }
+        f.write_str(&self.path)?;
+        if let Some(query) = &self.query {
+            write!(f, "?{}", query)?;
+        }
+        if let Some(fragment) = &self.fragment {
+            write!(f, "#{}", fragment)?;
+        }
+        Ok(())
+    }
+}
+
+fn default_port(scheme: Option<&str>) -> Option<&'static str> {
+    match scheme? {
+        "http" | "ws" => Some("80"),
+        "https" | "wss" => Some("443"),
+        "ftp" => Some("21"),
+        _ => None,
+    }
+}
+
+/// Decode percent-encoded unreserved characters (including non-ASCII ones,
+/// except bidi formatting characters, see
+/// [RFC 3987 §4.1](https://tools.ietf.org/html/rfc3987#section-4.1)),
+/// and convert the other percent-encodings to uppercase.
+///
+/// NB: `txt` must be a part of a valid IRI reference,
+/// so that every `%` is followed by two hexadecimal digits.
+fn normalize_pct(txt: &str) -> String {
+    let mut out = String::with_capacity(txt.len());
+    let mut rest = txt;
+    while let Some(pos) = rest.find('%') {
+        out.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        let mut octets = vec![];
+        while rest.starts_with('%') {
+            octets.push(u8::from_str_radix(&rest[1..3], 16).unwrap());
+            rest = &rest[3..];
+        }
+        let mut i = 0;
+        while i < octets.len() {
+            let len = match octets[i] {
+                0x00..=0x7F => 1,
+                0xC0..=0xDF => 2,
+                0xE0..=0xEF => 3,
+                0xF0..=0xF7 => 4,
+                _ => 0,
+            };
+            let decoded = octets
+                .get(i..i + len)
+                .and_then(|bytes| std::str::from_utf8(bytes).ok())
+                .and_then(|txt| txt.chars().next())
+                .filter(|c| is_unreserved(*c) || (is_ucschar(*c) && !is_bidi_format(*c)));
+            match decoded {
+                Some(c) => {
+                    out.push(c);
+                    i += len;
+                }
+                None => {
+                    write!(out, "%{:02X}", octets[i]).unwrap();
+                    i += 1;
+                }
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn encode_non_ascii(txt: &str) -> String {
+    let mut out = String::with_capacity(txt.len());
+    let mut buffer = [0; 4];
+    for c in txt.chars() {
+        if c.is_ascii() {
+            out.push(c);
+        } else {
+            for b in c.encode_utf8(&mut buffer).bytes() {
+                write!(out, "%{:02X}", b).unwrap();
+            }
+        }
+    }
+    out
+}
+
+fn is_unreserved(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~')
+}
+
+fn is_bidi_format(c: char) -> bool {
+    matches!(c, '\u{200E}' | '\u{200F}' | '\u{202A}'..='\u{202E}')
+}
+
+fn is_ucschar(c: char) -> bool {
+    matches!(c,
+        '\u{A0}'..='\u{D7FF}'
+        | '\u{F900}'..='\u{FDCF}'
+        | '\u{FDF0}'..='\u{FFEF}'
+        | '\u{10000}'..='\u{1FFFD}'
+        | '\u{20000}'..='\u{2FFFD}'
+        | '\u{30000}'..='\u{3FFFD}'
+        | '\u{40000}'..='\u{4FFFD}'
+        | '\u{50000}'..='\u{5FFFD}'
+        | '\u{60000}'..='\u{6FFFD}'
+        | '\u{70000}'..='\u{7FFFD}'
+        | '\u{80000}'..='\u{8FFFD}'
+        | '\u{90000}'..='\u{9FFFD}'
+        | '\u{A0000}'..='\u{AFFFD}'
+        | '\u{B0000}'..='\u{BFFFD}'
+        | '\u{C0000}'..='\u{CFFFD}'
+        | '\u{D0000}'..='\u{DFFFD}'
+        | '\u{E1000}'..='\u{EFFFD}'
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::is_valid_iri_ref;
+
+    #[test]
+    fn normalization() {
+        for (txt, expected) in &[
+            ("HTTP://Example.org/a/../b", "http://example.org/b"),
+            ("http://example.org/b", "http://example.org/b"),
+            ("http://example.org", "http://example.org/"),
+            ("http://example.org:80/", "http://example.org/"),
+            ("http://example.org:/", "http://example.org/"),
+            ("http://example.org:8080/", "http://example.org:8080/"),
+            ("https://example.org:80/", "https://example.org:80/"),
+            ("http://User@Example.org/", "http://User@example.org/"),
+            ("http://[::A]:443/", "http://[::a]:443/"),
+            ("http://a/%7e%41%2f%2a", "http://a/~A%2F%2A"),
+            ("http://a/%2E%2E/b/%2e/c", "http://a/b/c"),
+            ("http://a/%C3%A9?%c3%a9#%C3%A9", "http://a/é?é#é"),
+            ("http://a/%C3", "http://a/%C3"),
+            ("http://a/%E2%80%8F", "http://a/%E2%80%8F"), // bidi control
+            ("tag:A/./B", "tag:A/B"),
+            ("../a/./%7e", "../a/./~"),
+            ("", ""),
+        ] {
+            assert_eq!(&normalize(txt).unwrap(), expected, "<{}>", txt);
+            assert_eq!(&normalize(expected).unwrap(), expected, "<{}>", expected);
+        }
+        assert!(normalize("a b").is_err());
+    }
+
+    #[test]
+    fn to_uri_and_back() {
+        for (iri, uri) in &[
+            ("http://example.org/a", "http://example.org/a"),
+            (
+                "http://例え.テスト/パス?q=é#ü",
+                "http://xn--r8jz45g.xn--zckzah/%E3%83%91%E3%82%B9?q=%C3%A9#%C3%BC",
+            ),
+            ("http://ü@a:8080/", "http://%C3%BC@a:8080/"),
+            ("../été", "../%C3%A9t%C3%A9"),
+            ("http://a/%C3%A9", "http://a/%C3%A9"),
+        ] {
+            assert_eq!(&iri_to_uri(iri).unwrap(), uri, "<{}>", iri);
+            assert!(is_valid_iri_ref(uri));
+            assert!(uri.is_ascii());
+            assert_eq!(
+                uri_to_iri(uri).unwrap(),
+                normalize(iri).unwrap(),
+                "<{}>",
+                uri
+            );
+        }
+        assert_eq!(uri_to_iri("http://a/%2F%C3").unwrap(), "http://a/%2F%C3");
+        assert!(iri_to_uri("a b").is_err());
+        assert!(uri_to_iri("a b").is_err());
+    }
+}
@@ -18,15 +18,15 @@ pub trait Resolve<S, T> {
 /// it borrows it from one (or possibly several) external `str`s.
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct IriParsed<'a> {
-    scheme: Option<&'a str>,
-    authority: Option<&'a str>,
+    pub(crate) scheme: Option<&'a str>,
+    pub(crate) authority: Option<&'a str>,
     /// NB: path complies with the following rules:
     /// - does not contain the separators ('/')
     /// - its first element is "" if the path starts with '/'
     /// - its last element is "" if the path ends with a '/'
-    path: Vec<&'a str>,
-    query: Option<&'a str>,
-    fragment: Option<&'a str>,
+    pub(crate) path: Vec<&'a str>,
+    pub(crate) query: Option<&'a str>,
+    pub(crate) fragment: Option<&'a str>,
 }
 
 impl<'a> IriParsed<'a> {
@@ -93,7 +93,7 @@ impl<'a> IriParsed<'a> {
                     path = self.path.clone();
                     query = other.query.or(self.query);
                 } else {
-                    if other.path[0] == "" {
+                    if other.path[0].is_empty() {
                         path = other.path.clone();
                     } else {
                         path = self.merged_path(&other.path);
@@ -113,6 +113,94 @@ impl<'a> IriParsed<'a> {
         }
     }
 
+    /// Compute the shortest IRI reference which,
+    /// resolved against this IRI reference as the base,
+    /// gives the same IRI as `target`.
+    ///
+    /// In other words, `self.join(&self.relativize(target))` and `self.join(target)`
+    /// are the same IRI. This is the inverse of [`join`](#method.join),
+    /// useful for serializing IRIs relative to a base.
+    ///
+    /// NB: `self` is expected to be absolute;
+    /// otherwise, the result may be `target` resolved against `self`.
+    pub fn relativize(&self, target: &IriParsed<'a>) -> IriParsed<'a> {
+        let target = self.join(target);
+        let expected = target.to_string();
+        let mut candidates = vec![];
+        if target.scheme == self.scheme && target.authority == self.authority {
+            let with_path = |path| IriParsed {
+                scheme: None,
+                authority: None,
+                path,
+                query: target.query,
+                fragment: target.fragment,
+            };
+            if target.path == self.path {
+                if target.query == self.query {
+                    candidates.push(IriParsed {
+                        fragment: target.fragment,
+                        ..IriParsed::default()
+                    });
+                }
+                if target.query.is_some() {
+                    candidates.push(with_path(vec![]));
+                }
+            }
+            if let Some(path) = self.relative_path(&target.path) {
+                candidates.push(with_path(path));
+            }
+            if target.path.len() > 1 && target.path[0].is_empty() {
+                candidates.push(with_path(target.path.clone()));
+            }
+        }
+        if target.scheme == self.scheme && target.authority.is_some() {
+            candidates.push(IriParsed {
+                scheme: None,
+                ..target.clone()
+            });
+        }
+        candidates
+            .into_iter()
+            .filter(|c| {
+                let txt = c.to_string();
+                IriParsed::new(&txt)
+                    .map(|parsed| self.join(&parsed).to_string() == expected)
+                    .unwrap_or(false)
+            })
+            .min_by_key(|c| c.to_string().len())
+            .unwrap_or(target)
+    }
+
+    /// Compute a relative path leading from `self`'s own path to `path`
+    /// (both being absolute paths), if possible.
+    fn relative_path(&self, path: &[&'a str]) -> Option<Vec<&'a str>> {
+        if self.path.is_empty()
+            || path.is_empty()
+            || !self.path[0].is_empty()
+            || !path[0].is_empty()
+        {
+            return None;
+        }
+        let base_dir = &self.path[..self.path.len() - 1];
+        let common = base_dir
+            .iter()
+            .zip(&path[..path.len() - 1])
+            .take_while(|(a, b)| a == b)
+            .count();
+        let mut rel: Vec<&'a str> = std::iter::repeat_n("..", base_dir.len() - common)
+            .chain(path[common..].iter().cloned())
+            .collect();
+        match &rel[..] {
+            [""] => rel[0] = ".",
+            [.., "..", ""] => {
+                rel.pop();
+            }
+            [first, ..] if first.is_empty() || first.contains(':') => rel.insert(0, "."),
+            _ => (),
+        }
+        Some(rel)
+    }
+
     /// Appends the given path to `self`'s own path.
     fn merged_path(&self, path: &[&'a str]) -> Vec<&'a str> {
         if self.authority.is_some() && self.path.is_empty() {
@@ -121,7 +209,7 @@ impl<'a> IriParsed<'a> {
         } else {
             self.path
                 .iter()
-                .take(self.path.len() - 1)
+                .take(self.path.len().saturating_sub(1))
                 .cloned()
                 .chain(path.iter().cloned())
                 .collect()
@@ -174,7 +262,7 @@ impl<'a, 'b> Resolve<&'a str, Result<MownStr<'a>>> for IriParsed<'b> {
     }
 }
 
-fn remove_dot_segments(path: &mut Vec<&str>) {
+pub(crate) fn remove_dot_segments(path: &mut Vec<&str>) {
     if path.is_empty() {
         return;
     }
@@ -187,7 +275,7 @@ fn remove_dot_segments(path: &mut Vec<&str>) {
         if path[i] == "." {
             path.remove(i);
         } else if path[i] == ".." {
-            if i != 0 && (i != 1 || path[0] != "") {
+            if i != 0 && (i != 1 || !path[0].is_empty()) {
                 path.remove(i - 1);
                 i -= 1;
             }
@@ -202,12 +290,13 @@ fn remove_dot_segments(path: &mut Vec<&str>) {
 mod test {
     use super::*;
     use crate::test::*;
+    use proptest::prelude::*;
 
     #[test]
     fn positive() {
         for (txt, parsed) in POSITIVE_IRIS {
             let rpi = IriParsed::new(txt);
-            assert!(rpi.is_ok(), format!("<{}> → {:?}", txt, rpi));
+            assert!(rpi.is_ok(), "<{}> → {:?}", txt, rpi);
             let pi = rpi.unwrap();
             assert_eq!(pi.is_absolute(), parsed.0);
             assert_eq!(pi.scheme, parsed.1);
@@ -223,7 +312,7 @@ mod test {
     fn negative() {
         for txt in NEGATIVE_IRIS {
             let rpi = IriParsed::new(txt);
-            assert!(rpi.is_err(), format!("<{}> → {:?}", txt, rpi));
+            assert!(rpi.is_err(), "<{}> → {:?}", txt, rpi);
         }
     }
 
@@ -263,4 +352,92 @@ mod test {
             assert!(base.resolve(*txt).is_err());
         }
     }
+
+    #[test]
+    fn relativize() {
+        let base = IriParsed::new("http://a/b/c/d;p?q").unwrap();
+        for (abs, rel) in &[
+            ("http://a/b/c/d;p?q", ""),
+            ("http://a/b/c/d;p?q#s", "#s"),
+            ("http://a/b/c/d;p?y", "?y"),
+            ("http://a/b/c/d;p", "d;p"),
+            ("http://a/b/c/g", "g"),
+            ("http://a/b/c/g/", "g/"),
+            ("http://a/b/c/", "."),
+            ("http://a/b/", ".."),
+            ("http://a/b/g", "../g"),
+            ("http://a/", "/"),
+            ("http://a/g", "/g"),
+            ("http://a/b/c/g:h", "./g:h"),
+            ("http://a/b/c//g", ".//g"),
+            ("http://a", "//a"),
+            ("http://g/b/c/d;p?q", "//g/b/c/d;p?q"),
+            ("https://a/b/c/d;p?q", "https://a/b/c/d;p?q"),
+        ] {
+            let got = base.relativize(&IriParsed::new(abs).unwrap());
+            assert_eq!(&got.to_string(), rel, "<{}>", abs);
+        }
+    }
+
+    #[test]
+    fn relativize_rfc_examples() {
+        let base = IriParsed::new("http://a/b/c/d;p?q").unwrap();
+        for (_, abs) in RELATIVE_IRIS {
+            let target = IriParsed::new(abs).unwrap();
+            let rel = base.relativize(&target).to_string();
+            let back = base.join(&IriParsed::new(&rel).unwrap());
+            assert_eq!(&back.to_string(), abs, "<{}> → <{}>", abs, rel);
+        }
+    }
+
+    fn iri_ref(absolute: bool) -> impl Strategy<Value = String> {
+        let scheme = if absolute {
+            prop::sample::select(vec!["http:", "ftp:"]).boxed()
+        } else {
+            prop::sample::select(vec!["", "http:"]).boxed()
+        };
+        let authority = prop::option::of(prop::sample::select(vec!["a", "b", "u@a:80"]));
+        let segment = prop::sample::select(vec!["", ".", "..", "a", "b", "g:h", "d;p"]);
+        let path = prop::collection::vec(segment, 0..5);
+        let query = prop::option::of(prop::sample::select(vec!["", "q", "y"]));
+        let fragment = prop::option::of(prop::sample::select(vec!["", "s"]));
+        (scheme, authority, any::<bool>(), path, query, fragment).prop_map(
+            |(scheme, authority, rooted, path, query, fragment)| {
+                let mut txt = scheme.to_string();
+                if let Some(authority) = authority {
+                    txt.push_str("//");
+                    txt.push_str(authority);
+                }
+                if rooted || (authority.is_some() && !path.is_empty()) {
+                    txt.push('/');
+                }
+                txt.push_str(&path.join("/"));
+                if let Some(query) = query {
+                    txt.push('?');
+                    txt.push_str(query);
+                }
+                if let Some(fragment) = fragment {
+                    txt.push('#');
+                    txt.push_str(fragment);
+                }
+                txt
+            },
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn relativize_roundtrip(base in iri_ref(true), target in iri_ref(false)) {
+            let base = IriParsed::new(&base).unwrap();
+            let target = match IriParsed::new(&target) {
+                Ok(target) => target,
+                Err(_) => return Ok(()), // e.g. "g:h" would be parsed as a scheme
+            };
+            let expected = base.join(&target).to_string();
+            let rel = base.relativize(&target).to_string();
+            let back = base.join(&IriParsed::new(&rel).unwrap()).to_string();
+            prop_assert_eq!(&back, &expected, "relative reference: <{}>", rel);
+            prop_assert!(rel.len() <= expected.len());
+        }
+    }
 }
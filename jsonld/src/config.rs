@@ -1,9 +1,25 @@
+use crate::frame::Frame;
+
 /// JSON-LD serializer configuration.
 #[derive(Clone, Debug, Default)]
 pub struct JsonLdConfig {
+    pub frame: Option<Frame>,
     pub rdf_direction: Option<RdfDirectionMode>,
     pub spaces: u16,
     pub spec_version: JsonLdSpecVersion,
+    /// If true, serializers writing to an `io::Write` output each node object
+    /// as soon as it is complete, instead of building the whole JSON-LD document in memory.
+    ///
+    /// This is only efficient if quads are grouped by graph name and subject,
+    /// as they are for example by [`GspoWrapper`](https://docs.rs/sophia/latest/sophia/dataset/inmem/struct.GspoWrapper.html).
+    /// In this mode, lists are not folded into `@list` objects:
+    /// list nodes are written as plain node objects, with their `rdf:first` and `rdf:rest` properties.
+    /// Compound literals can not be folded either,
+    /// so setting `rdf_direction` to `CompoundLiteral`
+    /// fails with [`JsonLdError::IncompatibleOptions`](../error/enum.JsonLdError.html)
+    /// before anything is written;
+    /// framing is not available either.
+    pub streaming: bool,
     pub use_native_types: bool,
     pub use_rdf_type: bool,
 }
@@ -13,6 +29,16 @@ impl JsonLdConfig {
         Self::default()
     }
 
+    pub fn frame(mut self, frame: Frame) -> Self {
+        self.frame = Some(frame);
+        self
+    }
+
+    pub fn rdf_direction(mut self, mode: Option<RdfDirectionMode>) -> Self {
+        self.rdf_direction = mode;
+        self
+    }
+
     pub fn spaces(mut self, spaces: u16) -> Self {
         self.spaces = spaces;
         self
@@ -23,6 +49,11 @@ impl JsonLdConfig {
         self
     }
 
+    pub fn streaming(mut self, flag: bool) -> Self {
+        self.streaming = flag;
+        self
+    }
+
     pub fn use_native_types(mut self, flag: bool) -> Self {
         self.use_native_types = flag;
         self
@@ -36,20 +67,21 @@ impl JsonLdConfig {
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Ord)]
 #[non_exhaustive]
+#[derive(Default)]
 pub enum JsonLdSpecVersion {
     JsonLd10,
+    #[default]
     JsonLd11,
 }
 
-impl Default for JsonLdSpecVersion {
-    fn default() -> Self {
-        JsonLdSpecVersion::JsonLd11
-    }
-}
-
+/// How the base direction of strings is represented in RDF
+/// (see the [`rdfDirection`](https://www.w3.org/TR/json-ld11-api/#dom-jsonldoptions-rdfdirection) option).
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 #[non_exhaustive]
 pub enum RdfDirectionMode {
+    /// Literals with a datatype in the `https://www.w3.org/ns/i18n#` namespace,
+    /// encoding the language and the direction (e.g. `i18n:en-US_rtl`).
     I18nDatatype,
+    /// Blank nodes with an `rdf:value`, an `rdf:direction` and an optional `rdf:language`.
     CompoundLiteral,
 }
@@ -6,8 +6,30 @@ use crate::config::JsonLdSpecVersion;
 pub enum JsonLdError {
     #[error("invalid JSON literal: {0}")]
     InvalidJsonLiteral(#[from] json::Error),
+    #[error("invalid frame: {0}")]
+    InvalidFrame(String),
+    #[error("invalid @embed value: {0}")]
+    InvalidEmbedValue(String),
+    #[error("loading document failed: {0}")]
+    LoadingDocumentFailed(String),
+    #[error("context overflow: {0}")]
+    ContextOverflow(String),
+    #[error("invalid remote context: {0}")]
+    InvalidRemoteContext(String),
+    #[error("invalid local context: {0}")]
+    InvalidLocalContext(String),
+    #[error("invalid term definition: {0}")]
+    InvalidTermDefinition(String),
+    #[error("invalid @id value: {0}")]
+    InvalidIdValue(String),
+    #[error("invalid type value: {0}")]
+    InvalidTypeValue(String),
+    #[error("incompatible options: {0}")]
+    IncompatibleOptions(String),
     #[error("IO Error: {0}")]
     IOError(#[from] std::io::Error),
     #[error("unsupported version: {0:?}")]
     UnsupportedVersion(JsonLdSpecVersion),
+    #[error("invalid HTML: {0}")]
+    InvalidHtml(String),
 }
@@ -0,0 +1,175 @@
+//! JSON-LD frames, used to reshape the output of the serializer.
+//!
+//! NB: as the rest of this crate, frames are only supported in [expanded document form],
+//! i.e. all property names must be full IRIs, and no `@context` is processed.
+//!
+//! See [JSON-LD 1.1 Framing](https://www.w3.org/TR/json-ld11-framing/).
+//!
+//! [expanded document form]: https://www.w3.org/TR/json-ld11/#expanded-document-form
+
+use crate::error::*;
+use json::JsonValue;
+
+/// The possible values of the `@embed` flag.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+#[derive(Default)]
+pub enum Embed {
+    /// Always embed node objects as property values, unless this would cause a circular reference.
+    Always,
+    /// Only embed a node object once; subsequent occurences are node references.
+    #[default]
+    Once,
+    /// Always use node references instead of embedded node objects.
+    Never,
+}
+
+impl Embed {
+    /// Parse the value of a `@embed` entry in a frame.
+    ///
+    /// The legacy values `true`, `false` and `"@last"` are also accepted.
+    pub fn from_json(val: &JsonValue) -> Result<Self, JsonLdError> {
+        match val {
+            JsonValue::Array(vals) if vals.len() == 1 => Self::from_json(&vals[0]),
+            JsonValue::Object(obj) if obj.get("@value").is_some() => {
+                Self::from_json(&obj["@value"])
+            }
+            JsonValue::Boolean(true) => Ok(Embed::Once),
+            JsonValue::Boolean(false) => Ok(Embed::Never),
+            _ => match val.as_str() {
+                Some("@always") => Ok(Embed::Always),
+                Some("@once") | Some("@last") => Ok(Embed::Once),
+                Some("@never") => Ok(Embed::Never),
+                _ => Err(JsonLdError::InvalidEmbedValue(val.dump())),
+            },
+        }
+    }
+
+    /// The keyword representing this value in a frame.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Embed::Always => "@always",
+            Embed::Once => "@once",
+            Embed::Never => "@never",
+        }
+    }
+}
+
+/// A JSON-LD frame, in expanded form,
+/// together with the default values of the framing flags.
+///
+/// The flags can be overridden in any (sub-)frame
+/// with the `@embed`, `@explicit`, `@omitDefault` and `@requireAll` keywords.
+#[derive(Clone, Debug)]
+pub struct Frame {
+    json: JsonValue,
+    /// Default value of the [`@embed`](https://www.w3.org/TR/json-ld11-framing/#dom-jsonldoptions-embed) flag:
+    /// how node objects are embedded when referenced several times.
+    pub embed: Embed,
+    /// Default value of the [`@explicit`](https://www.w3.org/TR/json-ld11-framing/#dom-jsonldoptions-explicit) flag:
+    /// if true, only the properties present in the frame are included in the output.
+    pub explicit: bool,
+    /// Default value of the [`@omitDefault`](https://www.w3.org/TR/json-ld11-framing/#dom-jsonldoptions-omitdefault) flag:
+    /// if true, properties of the frame missing from a node are omitted
+    /// instead of being set to `null` (or to their `@default` value).
+    pub omit_default: bool,
+    /// Default value of the [`@requireAll`](https://www.w3.org/TR/json-ld11-framing/#dom-jsonldoptions-requireall) flag:
+    /// if true, a node matches the frame only if it matches all its properties,
+    /// instead of any of them.
+    pub require_all: bool,
+}
+
+impl Frame {
+    /// Build a frame from a JSON value, with the default flags.
+    ///
+    /// The value must be a JSON object, or an array containing exactly one JSON object.
+    pub fn new(json: JsonValue) -> Result<Self, JsonLdError> {
+        let json = match json {
+            JsonValue::Array(mut vals) if vals.len() == 1 => vals.pop().unwrap(),
+            json => json,
+        };
+        if !json.is_object() {
+            return Err(JsonLdError::InvalidFrame(json.dump()));
+        }
+        validate(&json)?;
+        Ok(Frame {
+            json,
+            embed: Embed::default(),
+            explicit: false,
+            omit_default: false,
+            require_all: false,
+        })
+    }
+
+    /// Parse a frame from its JSON serialization.
+    pub fn parse(txt: &str) -> Result<Self, JsonLdError> {
+        Self::new(json::parse(txt)?)
+    }
+
+    /// Borrow the JSON object of this frame.
+    pub fn as_json(&self) -> &JsonValue {
+        &self.json
+    }
+
+    /// Set the default value of the [`embed`](#structfield.embed) flag.
+    pub fn embed(mut self, embed: Embed) -> Self {
+        self.embed = embed;
+        self
+    }
+
+    /// Set the default value of the [`explicit`](#structfield.explicit) flag.
+    pub fn explicit(mut self, flag: bool) -> Self {
+        self.explicit = flag;
+        self
+    }
+
+    /// Set the default value of the [`omit_default`](#structfield.omit_default) flag.
+    pub fn omit_default(mut self, flag: bool) -> Self {
+        self.omit_default = flag;
+        self
+    }
+
+    /// Set the default value of the [`require_all`](#structfield.require_all) flag.
+    pub fn require_all(mut self, flag: bool) -> Self {
+        self.require_all = flag;
+        self
+    }
+}
+
+/// Recursively check that a (sub-)frame is well-formed
+fn validate(frame: &JsonValue) -> Result<(), JsonLdError> {
+    match frame {
+        JsonValue::Array(vals) => vals.iter().try_for_each(validate),
+        JsonValue::Object(obj) => {
+            for (key, val) in obj.iter() {
+                match key {
+                    "@embed" => {
+                        Embed::from_json(val)?;
+                    }
+                    "@id" | "@type" => {
+                        let ok = match val {
+                            JsonValue::Array(vals) => vals.iter().all(is_id_pattern),
+                            val => is_id_pattern(val),
+                        };
+                        if !ok {
+                            return Err(JsonLdError::InvalidFrame(frame.dump()));
+                        }
+                    }
+                    "@value" | "@language" | "@default" => {}
+                    _ => validate(val)?,
+                }
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// An `@id` or `@type` in a frame can only contain strings,
+/// wildcards (`{}`) or, for `@type`, default objects.
+fn is_id_pattern(val: &JsonValue) -> bool {
+    match val {
+        JsonValue::Object(obj) => obj.is_empty() || obj.get("@default").is_some(),
+        val => val.is_string(),
+    }
+}
@@ -0,0 +1,606 @@
+//! Extraction of RDF from HTML documents.
+//!
+//! The [`HtmlParser`](struct.HtmlParser.html) extracts, in a single dataset:
+//! * the triples described by [RDFa] attributes, in the default graph;
+//! * the quads of every JSON-LD document embedded in a
+//!   `<script type="application/ld+json">` element,
+//!   as parsed by the [JSON-LD parser](../parser/index.html).
+//!
+//! Relative IRIs are resolved against the document base IRI,
+//! which is the `href` of the first `<base>` element (if any),
+//! itself resolved against the base IRI of the parser.
+//!
+//! RDFa processing follows [RDFa Core 1.1] and [HTML+RDFa 1.1],
+//! with the following limitations:
+//! * `@inlist` is not supported (the corresponding triples are generated as if it was absent);
+//! * the initial context only contains the most common prefixes;
+//! * vocabulary expansion is not performed.
+//!
+//! Blank nodes from RDFa are labelled `r0`, `r1`, etc.,
+//! so that they never clash with those of embedded JSON-LD.
+//!
+//! Documents where elements are nested more deeply than [`MAX_NESTING`](constant.MAX_NESTING.html)
+//! are rejected with an [`InvalidHtml`](../error/enum.JsonLdError.html#variant.InvalidHtml) error.
+//!
+//! [RDFa]: https://rdfa.info/
+//! [RDFa Core 1.1]: https://www.w3.org/TR/rdfa-core/
+//! [HTML+RDFa 1.1]: https://www.w3.org/TR/html-rdfa/
+
+use crate::error::*;
+use crate::loader::{DocumentLoader, NoLoader};
+use crate::parser::{resolve, JsonLdSource, ToRdf};
+use dom::Element;
+use sophia::format::BoxQuad;
+use sophia_api::ns::rdf;
+use sophia_api::parser::QuadParser;
+use sophia_api::term::{CopyTerm, TTerm};
+use sophia_iri::is_absolute_iri_ref;
+use sophia_term::iri::Iri;
+use sophia_term::BoxTerm;
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::rc::Rc;
+
+mod dom;
+#[cfg(test)]
+mod test;
+
+/// The prefixes of the [RDFa initial context](https://www.w3.org/2011/rdfa-context/rdfa-1.1).
+const INITIAL_PREFIXES: &[(&str, &str)] = &[
+    ("as", "https://www.w3.org/ns/activitystreams#"),
+    ("cc", "http://creativecommons.org/ns#"),
+    ("dc", "http://purl.org/dc/terms/"),
+    ("dc11", "http://purl.org/dc/elements/1.1/"),
+    ("dcterms", "http://purl.org/dc/terms/"),
+    ("foaf", "http://xmlns.com/foaf/0.1/"),
+    ("gr", "http://purl.org/goodrelations/v1#"),
+    ("og", "http://ogp.me/ns#"),
+    ("owl", "http://www.w3.org/2002/07/owl#"),
+    ("prov", "http://www.w3.org/ns/prov#"),
+    ("rdf", "http://www.w3.org/1999/02/22-rdf-syntax-ns#"),
+    ("rdfa", "http://www.w3.org/ns/rdfa#"),
+    ("rdfs", "http://www.w3.org/2000/01/rdf-schema#"),
+    ("schema", "http://schema.org/"),
+    ("sioc", "http://rdfs.org/sioc/ns#"),
+    ("skos", "http://www.w3.org/2004/02/skos/core#"),
+    ("vcard", "http://www.w3.org/2006/vcard/ns#"),
+    ("void", "http://rdfs.org/ns/void#"),
+    ("xhv", "http://www.w3.org/1999/xhtml/vocab#"),
+    ("xml", "http://www.w3.org/XML/1998/namespace"),
+    ("xsd", "http://www.w3.org/2001/XMLSchema#"),
+];
+
+/// The terms of the RDFa initial context for HTML.
+const INITIAL_TERMS: &[(&str, &str)] = &[
+    (
+        "describedby",
+        "http://www.w3.org/2007/05/powder-s#describedby",
+    ),
+    ("license", "http://www.w3.org/1999/xhtml/vocab#license"),
+    ("role", "http://www.w3.org/1999/xhtml/vocab#role"),
+];
+
+/// The IRI used for CURIEs without a prefix (e.g. `:next`).
+const DEFAULT_PREFIX: &str = "http://www.w3.org/1999/xhtml/vocab#";
+
+const USES_VOCABULARY: &str = "http://www.w3.org/ns/rdfa#usesVocabulary";
+
+/// The maximum nesting depth of elements accepted by [`HtmlParser`](struct.HtmlParser.html).
+pub const MAX_NESTING: usize = 512;
+
+/// Parser for RDFa and JSON-LD embedded in HTML documents.
+#[derive(Clone, Debug, Default)]
+pub struct HtmlParser<L = NoLoader> {
+    /// The base IRI of the parsed documents.
+    pub base: Option<String>,
+    /// The loader used to retrieve remote contexts of embedded JSON-LD.
+    pub loader: L,
+}
+
+impl HtmlParser {
+    /// Build a new parser, without base IRI, and unable to load remote contexts.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<L> HtmlParser<L> {
+    /// Use `loader` to retrieve remote contexts of embedded JSON-LD.
+    pub fn with_loader<L2: DocumentLoader>(self, loader: L2) -> HtmlParser<L2> {
+        HtmlParser {
+            base: self.base,
+            loader,
+        }
+    }
+}
+
+impl<L: DocumentLoader> HtmlParser<L> {
+    /// Extract quads from an HTML document.
+    ///
+    /// Return the extracted quads,
+    /// and the errors raised by the embedded JSON-LD documents that could not be parsed.
+    /// The quads of a failing JSON-LD document are discarded,
+    /// but those of other documents are still extracted.
+    ///
+    /// If the document itself is rejected (see [`MAX_NESTING`](constant.MAX_NESTING.html)),
+    /// no quad is extracted, and the only error is the cause of the rejection.
+    pub fn parse_html(&self, txt: &str) -> (Vec<BoxQuad>, Vec<JsonLdError>) {
+        let document = match dom::parse(txt) {
+            Ok(document) => document,
+            Err(err) => return (vec![], vec![err]),
+        };
+        let base = match document.find(&|e| e.name == "base" && e.attr("href").is_some()) {
+            Some(e) => Some(resolve(self.base.as_deref(), e.attr("href").unwrap())),
+            None => self.base.clone(),
+        };
+
+        let mut rdfa = Rdfa::new(base.clone());
+        rdfa.document(&document, EvalContext::initial(&base));
+
+        let mut to_rdf = ToRdf::new(&self.loader);
+        to_rdf.quads = rdfa.quads;
+        let mut errors = vec![];
+        document.for_each(&mut |e| {
+            if e.name == "script" && e.attr("type").is_some_and(is_json_ld) {
+                let len = to_rdf.quads.len();
+                let res = json::parse(&e.text())
+                    .map_err(JsonLdError::from)
+                    .and_then(|doc| to_rdf.document(&doc, base.as_deref()));
+                if let Err(err) = res {
+                    to_rdf.quads.truncate(len);
+                    errors.push(err);
+                }
+            }
+        });
+        (to_rdf.quads, errors)
+    }
+}
+
+impl<B: BufRead, L: DocumentLoader> QuadParser<B> for HtmlParser<L> {
+    type Source = JsonLdSource;
+    fn parse(&self, mut data: B) -> Self::Source {
+        let mut txt = String::new();
+        match data.read_to_string(&mut txt) {
+            Ok(_) => {
+                let (quads, errors) = self.parse_html(&txt);
+                JsonLdSource::new(quads, errors)
+            }
+            Err(err) => JsonLdSource::new(vec![], vec![err.into()]),
+        }
+    }
+}
+
+/// Convenience function for parsing a BufRead with the default parser.
+pub fn parse_bufread<B: BufRead>(bufread: B) -> JsonLdSource {
+    HtmlParser::new().parse(bufread)
+}
+
+/// Convenience function for parsing a str with the default parser.
+pub fn parse_str(txt: &str) -> JsonLdSource {
+    HtmlParser::new().parse_str(txt)
+}
+
+fn is_json_ld(media_type: &str) -> bool {
+    media_type
+        .split(';')
+        .next()
+        .unwrap()
+        .trim()
+        .eq_ignore_ascii_case("application/ld+json")
+}
+
+/// The evaluation context of RDFa processing.
+#[derive(Clone, Debug)]
+struct EvalContext {
+    parent_subject: Option<BoxTerm>,
+    parent_object: Option<BoxTerm>,
+    prefixes: HashMap<String, String>,
+    /// the pending `@rel` (forward) and `@rev` (backward) predicates
+    incomplete: Vec<(BoxTerm, bool)>,
+    language: Option<String>,
+    vocab: Option<String>,
+}
+
+impl EvalContext {
+    fn initial(base: &Option<String>) -> Self {
+        let base = base
+            .as_ref()
+            .and_then(|base| BoxTerm::new_iri(base.as_str()).ok());
+        EvalContext {
+            parent_subject: base.clone(),
+            parent_object: base,
+            prefixes: INITIAL_PREFIXES
+                .iter()
+                .map(|(p, iri)| (p.to_string(), iri.to_string()))
+                .collect(),
+            incomplete: vec![],
+            language: None,
+            vocab: None,
+        }
+    }
+}
+
+/// Extracts triples from RDFa attributes.
+struct Rdfa {
+    base: Option<String>,
+    quads: Vec<BoxQuad>,
+    labels: HashMap<String, BoxTerm>,
+    bnodes: usize,
+}
+
+impl Rdfa {
+    fn new(base: Option<String>) -> Self {
+        Rdfa {
+            base,
+            quads: vec![],
+            labels: HashMap::new(),
+            bnodes: 0,
+        }
+    }
+
+    /// Process all the elements of `document`, in document order.
+    ///
+    /// The tree is walked with an explicit stack rather than recursively,
+    /// as the processing of each element needs a large stack frame.
+    fn document(&mut self, document: &Element, ctx: EvalContext) {
+        let ctx = Rc::new(ctx);
+        let mut stack: Vec<_> = document
+            .elements()
+            .rev()
+            .map(|e| (e, ctx.clone(), true))
+            .collect();
+        while let Some((e, parent, root)) = stack.pop() {
+            let ctx = Rc::new(self.element(e, &parent, root));
+            stack.extend(e.elements().rev().map(|child| (child, ctx.clone(), false)));
+        }
+    }
+
+    /// Process `e` according to the [RDFa processing sequence](https://www.w3.org/TR/rdfa-core/#s_sequence),
+    /// and return the evaluation context of its children.
+    fn element(&mut self, e: &Element, parent: &EvalContext, root: bool) -> EvalContext {
+        let mut ctx = EvalContext {
+            incomplete: vec![],
+            ..parent.clone()
+        };
+        // HTML+RDFa: head and body behave as if they had an empty @about
+        let root = root || e.name == "head" || e.name == "body";
+
+        // steps 2-4: vocabulary, prefixes and language
+        if let Some(vocab) = e.attr("vocab") {
+            let vocab = vocab.trim();
+            if vocab.is_empty() {
+                ctx.vocab = None;
+            } else {
+                let vocab = resolve(self.base.as_deref(), vocab);
+                if let (Some(base), Ok(p), Ok(o)) = (
+                    self.base.as_deref(),
+                    BoxTerm::new_iri(USES_VOCABULARY),
+                    BoxTerm::new_iri(vocab.as_str()),
+                ) {
+                    if let Ok(s) = BoxTerm::new_iri(base) {
+                        self.emit(s, p, o);
+                    }
+                }
+                ctx.vocab = Some(vocab);
+            }
+        }
+        for (key, val) in &e.attrs {
+            if let Some(prefix) = key.strip_prefix("xmlns:") {
+                ctx.prefixes.insert(prefix.to_string(), val.to_string());
+            }
+        }
+        if let Some(prefixes) = e.attr("prefix") {
+            let mut tokens = prefixes.split_whitespace();
+            while let (Some(prefix), Some(iri)) = (tokens.next(), tokens.next()) {
+                if let Some(prefix) = prefix.strip_suffix(':') {
+                    if prefix != "_" {
+                        ctx.prefixes
+                            .insert(prefix.to_ascii_lowercase(), iri.to_string());
+                    }
+                }
+            }
+        }
+        if let Some(lang) = e.attr("xml:lang").or_else(|| e.attr("lang")) {
+            ctx.language = Some(lang.to_string()).filter(|lang| !lang.is_empty());
+        }
+
+        let property = e.attr("property").map(|v| self.terms(&ctx, v));
+        let mut rel = e.attr("rel").map(|v| self.terms(&ctx, v));
+        let mut rev = e.attr("rev").map(|v| self.terms(&ctx, v));
+        if property.is_some() {
+            // HTML+RDFa: plain terms in @rel and @rev are ignored when @property is present
+            for attr in [("rel", &mut rel), ("rev", &mut rev)].iter_mut() {
+                if let Some(val) = e.attr(attr.0) {
+                    let val: String = val
+                        .split_whitespace()
+                        .filter(|t| t.contains(':'))
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    *attr.1 = Some(self.terms(&ctx, &val)).filter(|_| !val.is_empty());
+                }
+            }
+        }
+        let types = e.attr("typeof").map(|v| self.terms(&ctx, v));
+        let about = e
+            .attr("about")
+            .and_then(|v| self.safe_curie_or_iri(&ctx, v));
+        let resource = e
+            .attr("resource")
+            .and_then(|v| self.safe_curie_or_iri(&ctx, v))
+            .or_else(|| e.attr("href").map(|v| self.iri(v)))
+            .or_else(|| e.attr("src").map(|v| self.iri(v)));
+        let base_subject = || {
+            if root {
+                ctx.parent_object.clone().filter(|_| about.is_none())
+            } else {
+                None
+            }
+        };
+
+        let mut skip = false;
+        let mut new_subject;
+        let mut current_object = None;
+        let mut typed_resource = None;
+        if rel.is_none() && rev.is_none() {
+            if property.is_some() && e.attr("content").is_none() && e.attr("datatype").is_none() {
+                // step 5.1
+                new_subject = about.clone().flatten();
+                if about.is_none() {
+                    new_subject = base_subject().or_else(|| ctx.parent_object.clone());
+                }
+                if types.is_some() {
+                    if about.is_some() || root {
+                        typed_resource = new_subject.clone();
+                    } else {
+                        typed_resource = Some(
+                            resource
+                                .clone()
+                                .flatten()
+                                .unwrap_or_else(|| self.fresh_bnode()),
+                        );
+                        current_object = typed_resource.clone();
+                    }
+                }
+            } else {
+                // step 5.2
+                if about.is_some() || resource.is_some() {
+                    new_subject = about.clone().or_else(|| resource.clone()).flatten();
+                } else if root {
+                    new_subject = base_subject();
+                } else if types.is_some() {
+                    new_subject = Some(self.fresh_bnode());
+                } else {
+                    new_subject = ctx.parent_object.clone();
+                    skip = property.is_none();
+                }
+                if types.is_some() {
+                    typed_resource = new_subject.clone();
+                }
+            }
+        } else {
+            // step 6
+            new_subject = about.clone().flatten();
+            if about.is_none() {
+                new_subject = base_subject().or_else(|| ctx.parent_object.clone());
+            }
+            if types.is_some() && about.is_some() {
+                typed_resource = new_subject.clone();
+            }
+            current_object = resource.clone().flatten();
+            if current_object.is_none() && types.is_some() && about.is_none() {
+                current_object = Some(self.fresh_bnode());
+            }
+            if types.is_some() && about.is_none() {
+                typed_resource = current_object.clone();
+            }
+        }
+
+        // step 7: types
+        if let (Some(typed), Some(types)) = (&typed_resource, &types) {
+            for t in types {
+                self.emit(typed.clone(), BoxTerm::copy(&rdf::type_), t.clone());
+            }
+        }
+
+        // steps 9-10: @rel and @rev
+        let mut incomplete = vec![];
+        if let Some(subject) = &new_subject {
+            if let Some(object) = &current_object {
+                for p in rel.iter().flatten() {
+                    self.emit(subject.clone(), p.clone(), object.clone());
+                }
+                for p in rev.iter().flatten() {
+                    self.emit(object.clone(), p.clone(), subject.clone());
+                }
+            } else if rel.is_some() || rev.is_some() {
+                incomplete.extend(rel.iter().flatten().map(|p| (p.clone(), true)));
+                incomplete.extend(rev.iter().flatten().map(|p| (p.clone(), false)));
+                current_object = Some(self.fresh_bnode());
+            }
+        }
+
+        // step 11: @property
+        if let (Some(subject), Some(property)) = (&new_subject, &property) {
+            let value = self.property_value(
+                e,
+                &ctx,
+                &resource,
+                &typed_resource,
+                rel.is_some() || rev.is_some(),
+            );
+            if let Some(value) = value {
+                for p in property {
+                    self.emit(subject.clone(), p.clone(), value.clone());
+                }
+            }
+        }
+
+        // step 12: complete the incomplete triples of the parent
+        if let (false, Some(subject), Some(parent_subject)) =
+            (skip, &new_subject, &parent.parent_subject)
+        {
+            for (p, forward) in &parent.incomplete {
+                if *forward {
+                    self.emit(parent_subject.clone(), p.clone(), subject.clone());
+                } else {
+                    self.emit(subject.clone(), p.clone(), parent_subject.clone());
+                }
+            }
+        }
+
+        // step 13: children
+        if skip {
+            ctx.incomplete = parent.incomplete.clone();
+        } else {
+            if new_subject.is_some() {
+                ctx.parent_subject = new_subject.clone();
+            }
+            ctx.parent_object = current_object
+                .or(new_subject)
+                .or(ctx.parent_subject.clone());
+            ctx.incomplete = incomplete;
+        }
+        ctx
+    }
+
+    /// The value of `@property`, as described in step 11 of the processing sequence.
+    fn property_value(
+        &mut self,
+        e: &Element,
+        ctx: &EvalContext,
+        resource: &Option<Option<BoxTerm>>,
+        typed_resource: &Option<BoxTerm>,
+        has_rel: bool,
+    ) -> Option<BoxTerm> {
+        let content = e.attr("content");
+        let datatype = e
+            .attr("datatype")
+            .map(|dt| self.terms(ctx, dt).into_iter().next());
+        match datatype {
+            Some(Some(dt)) => {
+                let dt = Iri::<Box<str>>::new(&*dt.value()).ok()?;
+                if rdf::XMLLiteral == dt || rdf::HTML == dt {
+                    let txt = content.map_or_else(|| e.inner_markup(), str::to_string);
+                    return Some(BoxTerm::new_literal_dt_unchecked(txt, dt));
+                }
+                let txt = content.map_or_else(|| e.text(), str::to_string);
+                return Some(BoxTerm::new_literal_dt_unchecked(txt, dt));
+            }
+            Some(None) if e.attr("datatype").unwrap().trim().is_empty() => {
+                let txt = content.map_or_else(|| e.text(), str::to_string);
+                return Some(plain_literal(txt, &ctx.language));
+            }
+            _ => {}
+        }
+        if let Some(content) = content {
+            Some(plain_literal(content.to_string(), &ctx.language))
+        } else if let (false, Some(resource)) = (has_rel, resource) {
+            resource.clone()
+        } else if let (true, None, Some(typed)) =
+            (e.attr("typeof").is_some(), e.attr("about"), typed_resource)
+        {
+            Some(typed.clone())
+        } else {
+            Some(plain_literal(e.text(), &ctx.language))
+        }
+    }
+
+    /// Interpret a whitespace separated list of TERMorCURIEorAbsIRIs.
+    ///
+    /// Values that can not be interpreted, as well as blank nodes, are ignored.
+    fn terms(&mut self, ctx: &EvalContext, val: &str) -> Vec<BoxTerm> {
+        val.split_whitespace()
+            .filter_map(|token| {
+                if !token.contains(':') {
+                    let iri = match &ctx.vocab {
+                        Some(vocab) => format!("{}{}", vocab, token),
+                        None => INITIAL_TERMS
+                            .iter()
+                            .find(|(term, _)| term.eq_ignore_ascii_case(token))?
+                            .1
+                            .to_string(),
+                    };
+                    BoxTerm::new_iri(iri).ok()
+                } else if token.starts_with("_:") {
+                    None
+                } else {
+                    self.curie(ctx, token).or_else(|| {
+                        if is_absolute_iri_ref(token) {
+                            BoxTerm::new_iri(token).ok()
+                        } else {
+                            None
+                        }
+                    })
+                }
+            })
+            .collect()
+    }
+
+    /// Interpret a SafeCURIEorCURIEorIRI.
+    ///
+    /// Return `None` for an invalid safe CURIE, which must be considered as absent.
+    /// Otherwise, the inner option is empty if the value can not be converted into a term.
+    fn safe_curie_or_iri(&mut self, ctx: &EvalContext, val: &str) -> Option<Option<BoxTerm>> {
+        let val = val.trim();
+        if let Some(safe) = val.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+            return self.curie(ctx, safe).map(Some);
+        }
+        Some(self.curie(ctx, val).or_else(|| self.iri(val)))
+    }
+
+    /// Interpret a CURIE, or return `None` if its prefix is not defined.
+    fn curie(&mut self, ctx: &EvalContext, val: &str) -> Option<BoxTerm> {
+        let (prefix, reference) = match val.find(':') {
+            Some(i) => (&val[..i], &val[i + 1..]),
+            None => return None,
+        };
+        if reference.starts_with("//") {
+            return None;
+        }
+        match prefix {
+            "_" => Some(self.bnode(reference)),
+            "" => BoxTerm::new_iri(format!("{}{}", DEFAULT_PREFIX, reference)).ok(),
+            _ => {
+                let ns = ctx.prefixes.get(&prefix.to_ascii_lowercase())?;
+                BoxTerm::new_iri(format!("{}{}", ns, reference)).ok()
+            }
+        }
+    }
+
+    /// Resolve `val` against the base IRI.
+    fn iri(&self, val: &str) -> Option<BoxTerm> {
+        let iri = resolve(self.base.as_deref(), val.trim());
+        if is_absolute_iri_ref(&iri) {
+            BoxTerm::new_iri(iri).ok()
+        } else {
+            None
+        }
+    }
+
+    fn bnode(&mut self, label: &str) -> BoxTerm {
+        if let Some(bnode) = self.labels.get(label) {
+            return bnode.clone();
+        }
+        let bnode = self.fresh_bnode();
+        self.labels.insert(label.to_string(), bnode.clone());
+        bnode
+    }
+
+    fn fresh_bnode(&mut self) -> BoxTerm {
+        let bnode = BoxTerm::new_bnode_unchecked(format!("r{}", self.bnodes));
+        self.bnodes += 1;
+        bnode
+    }
+
+    fn emit(&mut self, s: BoxTerm, p: BoxTerm, o: BoxTerm) {
+        self.quads.push(([s, p, o], None));
+    }
+}
+
+fn plain_literal(txt: String, language: &Option<String>) -> BoxTerm {
+    match language {
+        Some(lang) => BoxTerm::new_literal_lang(txt.as_str(), lang.to_lowercase())
+            .unwrap_or_else(|_| BoxTerm::from(txt)),
+        None => BoxTerm::from(txt),
+    }
+}
@@ -0,0 +1,353 @@
+//! A lenient HTML tokenizer, building a minimal DOM tree.
+//!
+//! This is by no means a conformant HTML5 parser:
+//! it only knows about void elements, raw text elements (`script` and `style`)
+//! and a few implicitly closed elements,
+//! which is enough to extract RDFa and embedded JSON-LD from most pages.
+
+use super::MAX_NESTING;
+use crate::error::JsonLdError;
+use std::fmt::Write;
+
+/// A node of the DOM tree.
+#[derive(Clone, Debug)]
+pub(crate) enum Node {
+    Element(Element),
+    Text(String),
+}
+
+/// An element of the DOM tree.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Element {
+    /// the lowercased tag name
+    pub(crate) name: String,
+    /// the attributes, with lowercased names
+    pub(crate) attrs: Vec<(String, String)>,
+    pub(crate) children: Vec<Node>,
+}
+
+impl Element {
+    /// The value of attribute `name`, if present.
+    pub(crate) fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, val)| val.as_str())
+    }
+
+    /// Iterate over the child elements of this element.
+    pub(crate) fn elements(&self) -> impl DoubleEndedIterator<Item = &Element> {
+        self.children.iter().filter_map(|node| match node {
+            Node::Element(e) => Some(e),
+            Node::Text(_) => None,
+        })
+    }
+
+    /// Find the first descendant element satisfying `pred`, in document order.
+    pub(crate) fn find<P>(&self, pred: &P) -> Option<&Element>
+    where
+        P: Fn(&Element) -> bool,
+    {
+        self.elements()
+            .find_map(|e| if pred(e) { Some(e) } else { e.find(pred) })
+    }
+
+    /// Call `f` on every descendant element, in document order.
+    pub(crate) fn for_each<F>(&self, f: &mut F)
+    where
+        F: FnMut(&Element),
+    {
+        for e in self.elements() {
+            f(e);
+            e.for_each(f);
+        }
+    }
+
+    /// The concatenation of all the text nodes of this element.
+    pub(crate) fn text(&self) -> String {
+        let mut txt = String::new();
+        self.write_text(&mut txt);
+        txt
+    }
+
+    fn write_text(&self, txt: &mut String) {
+        for node in &self.children {
+            match node {
+                Node::Element(e) => e.write_text(txt),
+                Node::Text(t) => txt.push_str(t),
+            }
+        }
+    }
+
+    /// The markup of the content of this element.
+    pub(crate) fn inner_markup(&self) -> String {
+        let mut txt = String::new();
+        for node in &self.children {
+            write_node(&mut txt, node);
+        }
+        txt
+    }
+}
+
+fn write_node(txt: &mut String, node: &Node) {
+    match node {
+        Node::Text(t) => escape(txt, t, false),
+        Node::Element(e) => {
+            write!(txt, "<{}", e.name).unwrap();
+            for (key, val) in &e.attrs {
+                write!(txt, " {}=\"", key).unwrap();
+                escape(txt, val, true);
+                txt.push('"');
+            }
+            if VOID.contains(&e.name.as_str()) {
+                txt.push_str("/>");
+            } else {
+                txt.push('>');
+                for child in &e.children {
+                    write_node(txt, child);
+                }
+                write!(txt, "</{}>", e.name).unwrap();
+            }
+        }
+    }
+}
+
+fn escape(txt: &mut String, raw: &str, attribute: bool) {
+    for chr in raw.chars() {
+        match chr {
+            '&' => txt.push_str("&amp;"),
+            '<' => txt.push_str("&lt;"),
+            '>' => txt.push_str("&gt;"),
+            '"' if attribute => txt.push_str("&quot;"),
+            _ => txt.push(chr),
+        }
+    }
+}
+
+/// Elements that never have content.
+const VOID: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Elements whose content is not markup.
+const RAW_TEXT: &[&str] = &["script", "style"];
+
+/// Elements implicitly closed by an opening tag with the same name.
+const SELF_CLOSING: &[&str] = &["dd", "dt", "li", "option", "p", "td", "th", "tr"];
+
+/// Parse `txt` into a DOM tree, whose root is a nameless element representing the document.
+///
+/// Fail if elements are nested more deeply than [`MAX_NESTING`](../constant.MAX_NESTING.html).
+pub(crate) fn parse(txt: &str) -> Result<Element, JsonLdError> {
+    let mut stack = vec![Element::default()];
+    let mut pos = 0;
+    while pos < txt.len() {
+        let rest = &txt[pos..];
+        if !rest.starts_with('<') {
+            let end = rest.find('<').unwrap_or(rest.len());
+            push_text(&mut stack, decode_entities(&rest[..end]));
+            pos += end;
+        } else if let Some(comment) = rest.strip_prefix("<!--") {
+            pos += 4 + comment.find("-->").map(|i| i + 3).unwrap_or(comment.len());
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            pos += rest.find('>').map(|i| i + 1).unwrap_or(rest.len());
+        } else if let Some(tag) = rest.strip_prefix("</") {
+            let end = tag.find('>').unwrap_or(tag.len());
+            let name = tag[..end].trim().to_ascii_lowercase();
+            if let Some(i) = stack.iter().rposition(|e| e.name == name) {
+                if i > 0 {
+                    close(&mut stack, i);
+                }
+            }
+            pos += 2 + (end + 1).min(tag.len());
+        } else if rest[1..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+            let (element, self_closed, len) = start_tag(rest);
+            pos += len;
+            if SELF_CLOSING.contains(&element.name.as_str()) {
+                if let Some(i) = stack.iter().rposition(|e| e.name == element.name) {
+                    if i > 0 && i == stack.len() - 1 {
+                        close(&mut stack, i);
+                    }
+                }
+            }
+            let name = element.name.clone();
+            if RAW_TEXT.contains(&name.as_str()) && !self_closed {
+                let rest = &txt[pos..];
+                let end = find_ignore_case(rest, &format!("</{}", name)).unwrap_or(rest.len());
+                let mut element = element;
+                if end > 0 {
+                    element.children.push(Node::Text(rest[..end].to_string()));
+                }
+                push_element(&mut stack, element);
+                pos += end;
+                pos += txt[pos..]
+                    .find('>')
+                    .map(|i| i + 1)
+                    .unwrap_or(txt.len() - pos);
+            } else if self_closed || VOID.contains(&name.as_str()) {
+                push_element(&mut stack, element);
+            } else if stack.len() > MAX_NESTING {
+                return Err(JsonLdError::InvalidHtml(
+                    "maximum nesting depth exceeded".to_string(),
+                ));
+            } else {
+                stack.push(element);
+            }
+        } else {
+            push_text(&mut stack, "<".to_string());
+            pos += 1;
+        }
+    }
+    close(&mut stack, 1);
+    Ok(stack.pop().unwrap())
+}
+
+/// Parse a start tag at the beginning of `txt`.
+///
+/// Return the element, whether it was self-closed (`/>`), and the length of the tag.
+fn start_tag(txt: &str) -> (Element, bool, usize) {
+    let bytes = txt.as_bytes();
+    let mut pos = 1;
+    let name_end = txt[pos..]
+        .find(|c: char| c.is_whitespace() || c == '/' || c == '>')
+        .map(|i| i + pos)
+        .unwrap_or(txt.len());
+    let mut element = Element {
+        name: txt[pos..name_end].to_ascii_lowercase(),
+        ..Element::default()
+    };
+    pos = name_end;
+    let mut self_closed = false;
+    while pos < txt.len() {
+        match bytes[pos] {
+            b'>' => return (element, self_closed, pos + 1),
+            b'/' => {
+                self_closed = true;
+                pos += 1;
+            }
+            b if b.is_ascii_whitespace() => pos += 1,
+            _ => {
+                self_closed = false;
+                let end = txt[pos..]
+                    .find(|c: char| c.is_whitespace() || c == '=' || c == '>' || c == '/')
+                    .map(|i| i + pos)
+                    .unwrap_or(txt.len());
+                let name = txt[pos..end].to_ascii_lowercase();
+                pos = end;
+                while pos < txt.len() && bytes[pos].is_ascii_whitespace() {
+                    pos += 1;
+                }
+                let mut value = String::new();
+                if pos < txt.len() && bytes[pos] == b'=' {
+                    pos += 1;
+                    while pos < txt.len() && bytes[pos].is_ascii_whitespace() {
+                        pos += 1;
+                    }
+                    if pos < txt.len() && (bytes[pos] == b'"' || bytes[pos] == b'\'') {
+                        let quote = bytes[pos] as char;
+                        let end = txt[pos + 1..]
+                            .find(quote)
+                            .map(|i| i + pos + 1)
+                            .unwrap_or(txt.len());
+                        value = decode_entities(&txt[pos + 1..end]);
+                        pos = (end + 1).min(txt.len());
+                    } else {
+                        let end = txt[pos..]
+                            .find(|c: char| c.is_whitespace() || c == '>')
+                            .map(|i| i + pos)
+                            .unwrap_or(txt.len());
+                        value = decode_entities(&txt[pos..end]);
+                        pos = end;
+                    }
+                }
+                if element.attr(&name).is_none() {
+                    element.attrs.push((name, value));
+                }
+            }
+        }
+    }
+    (element, self_closed, pos)
+}
+
+/// Close all the elements of `stack` from index `i` upwards.
+fn close(stack: &mut Vec<Element>, i: usize) {
+    while stack.len() > i {
+        let element = stack.pop().unwrap();
+        push_element(stack, element);
+    }
+}
+
+fn push_element(stack: &mut [Element], element: Element) {
+    stack
+        .last_mut()
+        .unwrap()
+        .children
+        .push(Node::Element(element));
+}
+
+fn push_text(stack: &mut [Element], txt: String) {
+    let children = &mut stack.last_mut().unwrap().children;
+    if let Some(Node::Text(prev)) = children.last_mut() {
+        prev.push_str(&txt);
+    } else {
+        children.push(Node::Text(txt));
+    }
+}
+
+fn find_ignore_case(txt: &str, pattern: &str) -> Option<usize> {
+    let pattern = pattern.as_bytes();
+    txt.as_bytes()
+        .windows(pattern.len())
+        .position(|w| w.eq_ignore_ascii_case(pattern))
+}
+
+/// Decode character references in `txt`.
+///
+/// Only numeric references and the most common named references are supported;
+/// other references are left untouched.
+pub(crate) fn decode_entities(txt: &str) -> String {
+    let mut decoded = String::with_capacity(txt.len());
+    let mut rest = txt;
+    while let Some(i) = rest.find('&') {
+        decoded.push_str(&rest[..i]);
+        rest = &rest[i..];
+        let chr = rest
+            .find(';')
+            .filter(|end| *end <= 10)
+            .and_then(|end| decode_entity(&rest[1..end]).map(|c| (c, end)));
+        match chr {
+            Some((chr, end)) => {
+                decoded.push(chr);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn decode_entity(name: &str) -> Option<char> {
+    match name {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some('\u{a0}'),
+        "copy" => Some('©'),
+        _ => {
+            let code =
+                if let Some(hex) = name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+                    u32::from_str_radix(hex, 16).ok()?
+                } else {
+                    name.strip_prefix('#')?.parse().ok()?
+                };
+            std::char::from_u32(code)
+        }
+    }
+}
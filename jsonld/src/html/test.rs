@@ -0,0 +1,139 @@
+use super::*;
+use sophia::serializer::nq::NqSerializer;
+use sophia::serializer::{QuadSerializer, Stringifier};
+
+/// Parse `txt` and return the sorted N-Quads lines of the result.
+fn to_nq(parser: &HtmlParser, txt: &str) -> Vec<String> {
+    let (quads, errors) = parser.parse_html(txt);
+    assert!(errors.is_empty(), "{:?}", errors);
+    let nq = NqSerializer::new_stringifier()
+        .serialize_quads(quads.into_iter().map(Ok::<_, JsonLdError>))
+        .unwrap()
+        .to_string();
+    let mut lines: Vec<String> = nq.lines().map(str::to_string).collect();
+    lines.sort();
+    lines
+}
+
+fn parser() -> HtmlParser {
+    HtmlParser {
+        base: Some("http://example.org/page".to_string()),
+        loader: NoLoader,
+    }
+}
+
+#[test]
+fn dom() {
+    let doc = dom::parse(
+        r#"<!DOCTYPE html><html><body><p class=a>x &amp; y<br><p>z</p>
+        <script>if (a < b) { "</p>" }</script><img src='i.png'/></body></html>"#,
+    )
+    .unwrap();
+    let body = doc.find(&|e| e.name == "body").unwrap();
+    let names: Vec<&str> = body.elements().map(|e| e.name.as_str()).collect();
+    assert_eq!(names, vec!["p", "p", "script", "img"]);
+    let p = body.elements().next().unwrap();
+    assert_eq!(p.attr("class"), Some("a"));
+    assert_eq!(p.text(), "x & y");
+    assert_eq!(p.inner_markup(), "x &amp; y<br/>");
+    let script = body.find(&|e| e.name == "script").unwrap();
+    assert_eq!(script.text(), r#"if (a < b) { "</p>" }"#);
+}
+
+#[test]
+fn rdfa() {
+    let lines = to_nq(
+        &parser(),
+        r##"<html prefix="ex: http://example.org/ns#">
+        <head><title property="dc:title">My page</title></head>
+        <body vocab="http://schema.org/">
+          <div typeof="Person" about="#alice">
+            <span property="name" lang="en">Alice</span>
+            <a rel="knows" href="bob">Bob</a>
+            <span property="ex:age" content="42" datatype="xsd:integer">forty-two</span>
+            <div rel="ex:likes">
+              <div typeof="Book"><span property="name">Dune</span></div>
+            </div>
+          </div>
+        </body>
+        </html>"##,
+    );
+    assert_eq!(
+        lines,
+        vec![
+            r#"<http://example.org/page#alice> <http://example.org/ns#age> "42"^^<http://www.w3.org/2001/XMLSchema#integer>."#,
+            r#"<http://example.org/page#alice> <http://example.org/ns#likes> _:r1."#,
+            r#"<http://example.org/page#alice> <http://schema.org/knows> <http://example.org/bob>."#,
+            r#"<http://example.org/page#alice> <http://schema.org/name> "Alice"@en."#,
+            r#"<http://example.org/page#alice> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://schema.org/Person>."#,
+            r#"<http://example.org/page> <http://purl.org/dc/terms/title> "My page"."#,
+            r#"<http://example.org/page> <http://www.w3.org/ns/rdfa#usesVocabulary> <http://schema.org/>."#,
+            r#"_:r1 <http://schema.org/name> "Dune"."#,
+            r#"_:r1 <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://schema.org/Book>."#,
+        ]
+    );
+}
+
+#[test]
+fn json_ld_scripts() {
+    let lines = to_nq(
+        &parser(),
+        r#"<html><head>
+        <base href="http://example.org/base/">
+        <script type="application/ld+json">
+          {"@context": {"@vocab": "http://schema.org/"}, "@id": "a", "name": "A"}
+        </script>
+        <script type="application/ld+json; charset=utf-8">
+          {"@id": "_:x", "http://schema.org/about": {"@id": "b"}}
+        </script>
+        <script type="text/javascript">{"@id": "ignored"}</script>
+        </head>
+        <body><link rel="license" href="lic.html"></body></html>"#,
+    );
+    assert_eq!(
+        lines,
+        vec![
+            r#"<http://example.org/base/> <http://www.w3.org/1999/xhtml/vocab#license> <http://example.org/base/lic.html>."#,
+            r#"<http://example.org/base/a> <http://schema.org/name> "A"."#,
+            r#"_:b0 <http://schema.org/about> <http://example.org/base/b>."#,
+        ]
+    );
+}
+
+#[test]
+fn errors() {
+    let (quads, errors) = parser().parse_html(
+        r#"<script type="application/ld+json">{"@id": "http://example.org/a", "@type": 42}</script>
+        <script type="application/ld+json">{ not JSON</script>
+        <script type="application/ld+json">{"@id": "http://example.org/b", "http://example.org/p": 1}</script>"#,
+    );
+    assert_eq!(quads.len(), 1);
+    assert_eq!(errors.len(), 2);
+    assert!(matches!(errors[0], JsonLdError::InvalidTypeValue(_)));
+    assert!(matches!(errors[1], JsonLdError::InvalidJsonLiteral(_)));
+
+    let mut source = parse_str(r#"<script type="application/ld+json">[</script>"#);
+    assert!(matches!(
+        source.next(),
+        Some(Err(JsonLdError::InvalidJsonLiteral(_)))
+    ));
+    assert!(source.next().is_none());
+}
+
+#[test]
+fn nesting() {
+    let nested = |n: usize| {
+        r#"<div property="dc:title">"#.repeat(n)
+            + "x"
+            + r#"<script type="application/ld+json">{"@id": "http://example.org/a", "http://example.org/p": 1}</script>"#
+            + &"</div>".repeat(n)
+    };
+    let (quads, errors) = parser().parse_html(&nested(MAX_NESTING - 1));
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(quads.len(), MAX_NESTING);
+
+    // must not overflow the stack
+    let (quads, errors) = parser().parse_html(&nested(5000));
+    assert!(quads.is_empty());
+    assert!(matches!(errors[..], [JsonLdError::InvalidHtml(_)]));
+}
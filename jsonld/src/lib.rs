@@ -1,8 +1,11 @@
 //! Serializer and parser for the [JSON-LD] concrete syntax of RDF.
 //!
-//! NB: this implementation only supports the [expanded document form] of [JSON-LD].
+//! NB: the serializer only produces the [expanded document form] of [JSON-LD].
 //!
-//! NB2: currently, only the serializer part is implemented.
+//! NB2: the serializer can optionally reshape its output according to a [frame](frame/index.html).
+//! The [parser](parser/index.html) supports compacted documents,
+//! but only implements a subset of context processing.
+//! The [html](html/index.html) module extracts RDFa and embedded JSON-LD from HTML documents.
 //!
 //! [JSON-LD]: https://www.w3.org/TR/json-ld11/
 //! [expanded document form]: https://www.w3.org/TR/json-ld11/#expanded-document-form
@@ -11,6 +14,13 @@ pub mod config;
 pub use config::*;
 pub mod error;
 pub use error::*;
+pub mod frame;
+pub use frame::*;
+pub mod html;
+pub use html::HtmlParser;
+pub mod loader;
+pub mod parser;
+pub use parser::JsonLdParser;
 pub mod serializer;
 pub use serializer::*;
 mod util_traits;
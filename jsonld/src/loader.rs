@@ -0,0 +1,282 @@
+//! Document loaders, used to retrieve remote documents such as JSON-LD contexts.
+//!
+//! See [LoadDocumentCallback](https://www.w3.org/TR/json-ld11-api/#loaddocumentcallback)
+//! in the JSON-LD 1.1 API.
+//!
+//! None of the loaders provided here access the network:
+//! * [`PreloadedLoader`](struct.PreloadedLoader.html) serves documents from memory,
+//!   and comes with a set of [well-known contexts](struct.PreloadedLoader.html#method.well_known);
+//! * [`FsLoader`](struct.FsLoader.html) maps URL prefixes to directories of the file system;
+//! * [`NoLoader`](struct.NoLoader.html) always fails.
+//!
+//! Loaders can be combined with [`DocumentLoader::or_else`](trait.DocumentLoader.html#method.or_else).
+
+use crate::error::*;
+use json::JsonValue;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// A document retrieved by a [`DocumentLoader`](trait.DocumentLoader.html).
+#[derive(Clone, Debug)]
+pub struct RemoteDocument {
+    /// The final URL of the loaded document.
+    pub document_url: String,
+    /// The media type of the loaded document, if known.
+    pub content_type: Option<String>,
+    /// The URL of a context provided in an HTTP Link header, if any.
+    pub context_url: Option<String>,
+    /// The parsed content of the document.
+    pub document: JsonValue,
+}
+
+impl RemoteDocument {
+    /// Build a JSON-LD document retrieved from `url`.
+    pub fn new<U: Into<String>>(url: U, document: JsonValue) -> Self {
+        RemoteDocument {
+            document_url: url.into(),
+            content_type: Some(JSON_LD.to_string()),
+            context_url: None,
+            document,
+        }
+    }
+}
+
+/// A document loader retrieves [`RemoteDocument`]s from their URL.
+///
+/// [`RemoteDocument`]: struct.RemoteDocument.html
+pub trait DocumentLoader {
+    /// Retrieve the document identified by `url`.
+    ///
+    /// # Errors
+    /// [`JsonLdError::LoadingDocumentFailed`](../error/enum.JsonLdError.html#variant.LoadingDocumentFailed)
+    /// if the document can not be retrieved or parsed.
+    fn load_document(&self, url: &str) -> Result<RemoteDocument, JsonLdError>;
+
+    /// Combine this loader with another one,
+    /// which is only used when this loader fails.
+    fn or_else<L>(self, other: L) -> ChainLoader<Self, L>
+    where
+        Self: Sized,
+        L: DocumentLoader,
+    {
+        ChainLoader(self, other)
+    }
+}
+
+impl<L: DocumentLoader + ?Sized> DocumentLoader for &L {
+    fn load_document(&self, url: &str) -> Result<RemoteDocument, JsonLdError> {
+        (*self).load_document(url)
+    }
+}
+
+impl<L: DocumentLoader + ?Sized> DocumentLoader for Box<L> {
+    fn load_document(&self, url: &str) -> Result<RemoteDocument, JsonLdError> {
+        self.as_ref().load_document(url)
+    }
+}
+
+/// A loader that never loads anything.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoLoader;
+
+impl DocumentLoader for NoLoader {
+    fn load_document(&self, url: &str) -> Result<RemoteDocument, JsonLdError> {
+        Err(JsonLdError::LoadingDocumentFailed(url.to_string()))
+    }
+}
+
+/// A loader that tries a first loader, then a second one if the first one failed.
+///
+/// See [`DocumentLoader::or_else`](trait.DocumentLoader.html#method.or_else).
+#[derive(Clone, Debug)]
+pub struct ChainLoader<A, B>(A, B);
+
+impl<A: DocumentLoader, B: DocumentLoader> DocumentLoader for ChainLoader<A, B> {
+    fn load_document(&self, url: &str) -> Result<RemoteDocument, JsonLdError> {
+        self.0
+            .load_document(url)
+            .or_else(|_| self.1.load_document(url))
+    }
+}
+
+/// A loader serving documents stored in memory.
+#[derive(Clone, Debug, Default)]
+pub struct PreloadedLoader {
+    documents: HashMap<String, RemoteDocument>,
+}
+
+impl PreloadedLoader {
+    /// Build an empty loader.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a loader containing a set of well-known contexts:
+    ///
+    /// * [ActivityStreams 2.0](https://www.w3.org/ns/activitystreams),
+    /// * [Verifiable Credentials](https://www.w3.org/2018/credentials/v1) (v1 and v2).
+    ///
+    /// The [schema.org](https://schema.org/) context is not embedded,
+    /// as its license is not compatible with this crate's.
+    /// A local copy can be served with [`FsLoader::map_url`](struct.FsLoader.html#method.map_url).
+    pub fn well_known() -> Self {
+        let mut loader = Self::new();
+        for (urls, txt) in WELL_KNOWN.iter() {
+            let document = json::parse(txt).expect("well-known contexts are valid JSON");
+            for url in urls.iter() {
+                loader.insert(RemoteDocument::new(*url, document.clone()));
+            }
+        }
+        loader
+    }
+
+    /// Add a document to this loader, indexed by its `document_url`.
+    ///
+    /// Any document previously associated to the same URL is replaced.
+    pub fn insert(&mut self, document: RemoteDocument) -> &mut Self {
+        self.documents
+            .insert(document.document_url.clone(), document);
+        self
+    }
+
+    /// Parse `txt` as JSON, and add it to this loader with the given URL.
+    pub fn insert_str(&mut self, url: &str, txt: &str) -> Result<&mut Self, JsonLdError> {
+        let document = json::parse(txt)?;
+        Ok(self.insert(RemoteDocument::new(url, document)))
+    }
+
+    /// Whether this loader has a document for `url`.
+    pub fn contains(&self, url: &str) -> bool {
+        self.documents.contains_key(strip_fragment(url))
+    }
+}
+
+impl DocumentLoader for PreloadedLoader {
+    fn load_document(&self, url: &str) -> Result<RemoteDocument, JsonLdError> {
+        self.documents
+            .get(strip_fragment(url))
+            .cloned()
+            .ok_or_else(|| JsonLdError::LoadingDocumentFailed(url.to_string()))
+    }
+}
+
+/// A loader serving documents from the file system.
+///
+/// Each URL prefix is mapped to a directory,
+/// and the rest of the URL is interpreted as a relative path in that directory.
+/// Files ending with `.json` are considered as plain JSON,
+/// every other file as JSON-LD.
+///
+/// Individual URLs can also be mapped to a file.
+///
+/// # Example
+/// ```
+/// # use sophia_jsonld::loader::*;
+/// let loader = FsLoader::new()
+///     .map_prefix("https://example.org/contexts/", "/usr/share/example/contexts")
+///     .map_url("https://schema.org/", "/usr/share/example/schema.org.jsonld");
+/// // https://example.org/contexts/foo.jsonld will be read from
+/// // /usr/share/example/contexts/foo.jsonld
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct FsLoader {
+    prefixes: Vec<(String, PathBuf)>,
+    urls: HashMap<String, PathBuf>,
+}
+
+impl FsLoader {
+    /// Build a loader with no prefix mapped.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Map all URLs starting with `prefix` to files in directory `dir`.
+    ///
+    /// When several prefixes match a URL, the longest one is used.
+    pub fn map_prefix<P: AsRef<Path>>(mut self, prefix: &str, dir: P) -> Self {
+        self.prefixes
+            .push((prefix.to_string(), dir.as_ref().to_path_buf()));
+        self.prefixes
+            .sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+        self
+    }
+
+    /// Map `url` to the file `path`.
+    ///
+    /// This takes precedence over the prefixes mapped with [`map_prefix`](#method.map_prefix).
+    pub fn map_url<P: AsRef<Path>>(mut self, url: &str, path: P) -> Self {
+        self.urls
+            .insert(strip_fragment(url).to_string(), path.as_ref().to_path_buf());
+        self
+    }
+
+    /// The file corresponding to `url`, if any.
+    ///
+    /// URLs trying to escape the mapped directory (with `..`) are rejected.
+    pub fn path_for(&self, url: &str) -> Option<PathBuf> {
+        let url = strip_fragment(url);
+        if let Some(path) = self.urls.get(url) {
+            return Some(path.clone());
+        }
+        let url = &url[..url.find('?').unwrap_or(url.len())];
+        let (prefix, dir) = self
+            .prefixes
+            .iter()
+            .find(|(prefix, _)| url.starts_with(prefix.as_str()))?;
+        let relative = Path::new(&url[prefix.len()..]);
+        if relative
+            .components()
+            .any(|c| !matches!(c, Component::Normal(_)))
+        {
+            return None;
+        }
+        Some(dir.join(relative))
+    }
+}
+
+impl DocumentLoader for FsLoader {
+    fn load_document(&self, url: &str) -> Result<RemoteDocument, JsonLdError> {
+        let failed = || JsonLdError::LoadingDocumentFailed(url.to_string());
+        let path = self.path_for(url).ok_or_else(failed)?;
+        let txt = fs::read_to_string(&path).map_err(|_| failed())?;
+        let document = json::parse(&txt).map_err(|_| failed())?;
+        let content_type = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => JSON,
+            _ => JSON_LD,
+        };
+        Ok(RemoteDocument {
+            document_url: strip_fragment(url).to_string(),
+            content_type: Some(content_type.to_string()),
+            context_url: None,
+            document,
+        })
+    }
+}
+
+fn strip_fragment(url: &str) -> &str {
+    &url[..url.find('#').unwrap_or(url.len())]
+}
+
+const JSON: &str = "application/json";
+const JSON_LD: &str = "application/ld+json";
+
+const ACTIVITY_STREAMS: &str = include_str!("../contexts/activitystreams.jsonld");
+const CREDENTIALS_V1: &str = include_str!("../contexts/credentials-v1.jsonld");
+const CREDENTIALS_V2: &str = include_str!("../contexts/credentials-v2.jsonld");
+
+const WELL_KNOWN: &[(&[&str], &str)] = &[
+    (
+        &[
+            "https://www.w3.org/ns/activitystreams",
+            "http://www.w3.org/ns/activitystreams",
+            "https://www.w3.org/ns/activitystreams.jsonld",
+        ],
+        ACTIVITY_STREAMS,
+    ),
+    (&["https://www.w3.org/2018/credentials/v1"], CREDENTIALS_V1),
+    (&["https://www.w3.org/ns/credentials/v2"], CREDENTIALS_V2),
+];
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,112 @@
+use super::*;
+use std::path::Path;
+
+#[test]
+fn no_loader() {
+    assert!(matches!(
+        NoLoader.load_document("https://schema.org/"),
+        Err(JsonLdError::LoadingDocumentFailed(_))
+    ));
+}
+
+#[test]
+fn well_known() {
+    let loader = PreloadedLoader::well_known();
+    for url in &[
+        "https://www.w3.org/ns/activitystreams",
+        "https://www.w3.org/2018/credentials/v1",
+        "https://www.w3.org/ns/credentials/v2",
+    ] {
+        let doc = loader.load_document(url).unwrap();
+        assert_eq!(&doc.document_url, url);
+        assert_eq!(doc.content_type.as_deref(), Some("application/ld+json"));
+        assert!(doc.document["@context"].is_object(), "{}", url);
+    }
+    let doc = loader
+        .load_document("https://www.w3.org/ns/activitystreams#Public")
+        .unwrap();
+    assert_eq!(
+        doc.document["@context"]["as"],
+        "https://www.w3.org/ns/activitystreams#"
+    );
+    assert!(loader
+        .load_document("https://example.org/context.jsonld")
+        .is_err());
+    assert!(loader.load_document("https://schema.org/").is_err());
+}
+
+#[test]
+fn preloaded() -> Result<(), JsonLdError> {
+    let mut loader = PreloadedLoader::new();
+    loader.insert_str(
+        "https://example.org/context.jsonld",
+        r#"{"@context": {"@vocab": "https://example.org/ns#"}}"#,
+    )?;
+    assert!(loader.contains("https://example.org/context.jsonld"));
+    let doc = loader.load_document("https://example.org/context.jsonld")?;
+    assert_eq!(
+        doc.document["@context"]["@vocab"],
+        "https://example.org/ns#"
+    );
+    assert!(loader.insert_str("https://example.org/bad", "{").is_err());
+    Ok(())
+}
+
+#[test]
+fn fs_loader() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("contexts");
+    let loader = FsLoader::new()
+        .map_prefix("https://example.org/", "/nonexistent")
+        .map_prefix("https://example.org/ctx/", &dir)
+        .map_url("https://example.org/as", dir.join("activitystreams.jsonld"));
+    assert_eq!(
+        loader.path_for("https://example.org/ctx/credentials-v1.jsonld"),
+        Some(dir.join("credentials-v1.jsonld"))
+    );
+    assert_eq!(
+        loader.path_for("https://example.org/as#Public"),
+        Some(dir.join("activitystreams.jsonld"))
+    );
+    assert_eq!(
+        loader.path_for("https://example.org/other.jsonld"),
+        Some(Path::new("/nonexistent").join("other.jsonld"))
+    );
+    assert_eq!(loader.path_for("https://example.org/ctx/../secret"), None);
+    assert_eq!(loader.path_for("https://example.com/ctx/foo"), None);
+
+    let doc = loader
+        .load_document("https://example.org/ctx/activitystreams.jsonld")
+        .unwrap();
+    assert_eq!(
+        doc.document_url,
+        "https://example.org/ctx/activitystreams.jsonld"
+    );
+    assert!(doc.document["@context"].is_object());
+    assert!(loader
+        .load_document("https://example.org/ctx/README.md")
+        .is_err());
+    assert!(loader
+        .load_document("https://example.org/ctx/missing.jsonld")
+        .is_err());
+}
+
+#[test]
+fn chain_loader() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("contexts");
+    let loader = PreloadedLoader::well_known()
+        .or_else(FsLoader::new().map_prefix("https://example.org/ctx/", &dir));
+    assert!(loader
+        .load_document("https://www.w3.org/ns/activitystreams")
+        .is_ok());
+    assert!(loader
+        .load_document("https://example.org/ctx/credentials-v1.jsonld")
+        .is_ok());
+    assert!(loader
+        .load_document("https://example.org/ctx/missing.jsonld")
+        .is_err());
+
+    let boxed: Box<dyn DocumentLoader> = Box::new(NoLoader.or_else(&loader));
+    assert!(boxed
+        .load_document("https://www.w3.org/ns/credentials/v2")
+        .is_ok());
+}
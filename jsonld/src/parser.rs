@@ -0,0 +1,848 @@
+//! Parser for the [JSON-LD] concrete syntax of RDF.
+//!
+//! This parser implements the [deserialization] of JSON-LD into RDF,
+//! with a simplified version of the context processing algorithm.
+//! It supports:
+//! * term definitions, with `@id`, `@reverse`, `@type`, `@container`, `@language`,
+//!   `@direction`, `@prefix` and `@context` (property-scoped contexts);
+//! * `@base`, `@vocab`, `@language`, `@direction` and `@import`;
+//! * compact IRIs and keyword aliases;
+//! * remote contexts, retrieved through a [`DocumentLoader`];
+//! * `@list`, `@set`, `@graph`, `@reverse`, `@included`, language maps and index maps.
+//!
+//! Type-scoped contexts, `@nest`, id maps and type maps are not supported,
+//! and `@protected` and `@propagate` are ignored.
+//!
+//! Strings with both a language and a base direction are converted to
+//! directional language-tagged literals, as in [RDF 1.2];
+//! the base direction of strings without a language is ignored.
+//!
+//! Blank nodes are labelled `b0`, `b1`, etc.
+//!
+//! Calling [`register_format`](fn.register_format.html) makes this parser available
+//! to the `sophia::format` module (e.g. for `.jsonld` files).
+//!
+//! [JSON-LD]: https://www.w3.org/TR/json-ld11/
+//! [deserialization]: https://www.w3.org/TR/json-ld11-api/#deserialize-json-ld-to-rdf-algorithm
+//! [RDF 1.2]: https://www.w3.org/TR/rdf12-concepts/#section-Graph-Literal
+//! [`DocumentLoader`]: ../loader/trait.DocumentLoader.html
+
+use crate::error::*;
+use crate::loader::{DocumentLoader, NoLoader};
+use json::JsonValue;
+use sophia::format::{self, BoxQuad, BoxQuadSource, Format};
+use sophia_api::ns::{rdf, xsd};
+use sophia_api::parser::QuadParser;
+use sophia_api::term::{CopyTerm, Direction, TTerm};
+use sophia_iri::is_absolute_iri_ref;
+use sophia_iri::resolve::{IriParsed, Resolve};
+use sophia_term::iri::Iri;
+use sophia_term::BoxTerm;
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::rc::Rc;
+
+#[cfg(test)]
+mod test;
+
+/// The maximum number of nested remote contexts.
+const MAX_REMOTE_CONTEXTS: usize = 32;
+
+const KEYWORDS: &[&str] = &[
+    "@base",
+    "@container",
+    "@context",
+    "@direction",
+    "@graph",
+    "@id",
+    "@import",
+    "@included",
+    "@index",
+    "@json",
+    "@language",
+    "@list",
+    "@nest",
+    "@none",
+    "@prefix",
+    "@propagate",
+    "@protected",
+    "@reverse",
+    "@set",
+    "@type",
+    "@value",
+    "@version",
+    "@vocab",
+];
+
+/// JSON-LD parser.
+#[derive(Clone, Debug, Default)]
+pub struct JsonLdParser<L = NoLoader> {
+    /// The base IRI of the parsed documents.
+    pub base: Option<String>,
+    /// The loader used to retrieve remote contexts.
+    pub loader: L,
+}
+
+impl JsonLdParser {
+    /// Build a new parser, without base IRI, and unable to load remote contexts.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<L> JsonLdParser<L> {
+    /// Use `loader` to retrieve remote contexts.
+    pub fn with_loader<L2: DocumentLoader>(self, loader: L2) -> JsonLdParser<L2> {
+        JsonLdParser {
+            base: self.base,
+            loader,
+        }
+    }
+}
+
+impl<L: DocumentLoader> JsonLdParser<L> {
+    /// Convert an already parsed JSON-LD document into quads.
+    pub fn parse_json(&self, document: &JsonValue) -> Result<Vec<BoxQuad>, JsonLdError> {
+        let mut to_rdf = ToRdf::new(&self.loader);
+        to_rdf.document(document, self.base.as_deref())?;
+        Ok(to_rdf.quads)
+    }
+}
+
+impl<B: BufRead, L: DocumentLoader> QuadParser<B> for JsonLdParser<L> {
+    type Source = JsonLdSource;
+    fn parse(&self, mut data: B) -> Self::Source {
+        let mut txt = String::new();
+        let res = data
+            .read_to_string(&mut txt)
+            .map_err(JsonLdError::from)
+            .and_then(|_| Ok(json::parse(&txt)?))
+            .and_then(|doc| self.parse_json(&doc));
+        match res {
+            Ok(quads) => JsonLdSource::new(quads, vec![]),
+            Err(err) => JsonLdSource::new(vec![], vec![err]),
+        }
+    }
+}
+
+/// Convenience function for parsing a BufRead with the default parser.
+pub fn parse_bufread<B: BufRead>(bufread: B) -> JsonLdSource {
+    JsonLdParser::new().parse(bufread)
+}
+
+/// Convenience function for parsing a str with the default parser.
+pub fn parse_str(txt: &str) -> JsonLdSource {
+    JsonLdParser::new().parse_str(txt)
+}
+
+/// Register the default parser as the parser of [`Format::JsonLd`],
+/// so that it is used by the functions of the [`sophia::format`] module.
+///
+/// [`Format::JsonLd`]: https://docs.rs/sophia/latest/sophia/format/enum.Format.html#variant.JsonLd
+/// [`sophia::format`]: https://docs.rs/sophia/latest/sophia/format/index.html
+pub fn register_format() {
+    Format::JsonLd.register_parser(parse_boxed);
+}
+
+fn parse_boxed<'a>(data: Box<dyn BufRead + 'a>) -> BoxQuadSource<'a> {
+    format::from_quads(parse_bufread(data))
+}
+
+/// The quad source returned by JSON-LD parsers.
+///
+/// It yields all the quads that could be extracted, then the errors, if any.
+pub struct JsonLdSource {
+    quads: std::vec::IntoIter<BoxQuad>,
+    errors: std::vec::IntoIter<JsonLdError>,
+}
+
+impl JsonLdSource {
+    pub(crate) fn new(quads: Vec<BoxQuad>, errors: Vec<JsonLdError>) -> Self {
+        JsonLdSource {
+            quads: quads.into_iter(),
+            errors: errors.into_iter(),
+        }
+    }
+}
+
+impl Iterator for JsonLdSource {
+    type Item = Result<BoxQuad, JsonLdError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.quads.next() {
+            Some(quad) => Some(Ok(quad)),
+            None => self.errors.next().map(Err),
+        }
+    }
+}
+
+/// Resolve `iri` against `base`, if any.
+pub(crate) fn resolve(base: Option<&str>, iri: &str) -> String {
+    match base.map(IriParsed::new) {
+        Some(Ok(base)) => match base.resolve(iri) {
+            Ok(resolved) => resolved.to_string(),
+            Err(_) => iri.to_string(),
+        },
+        _ => iri.to_string(),
+    }
+}
+
+/// An active context.
+#[derive(Clone, Debug, Default)]
+struct Context {
+    base: Option<String>,
+    vocab: Option<String>,
+    language: Option<String>,
+    direction: Option<Direction>,
+    terms: HashMap<String, Rc<TermDef>>,
+}
+
+/// A term definition.
+#[derive(Clone, Debug, Default)]
+struct TermDef {
+    /// `None` if the term is explicitly mapped to `null`
+    iri: Option<String>,
+    prefix: bool,
+    reverse: bool,
+    type_: Option<String>,
+    container: Vec<String>,
+    /// `Some(None)` if the language is explicitly set to `null`
+    language: Option<Option<String>>,
+    /// `Some(None)` if the direction is explicitly set to `null`
+    direction: Option<Option<Direction>>,
+    context: Option<JsonValue>,
+}
+
+impl TermDef {
+    fn has_container(&self, container: &str) -> bool {
+        self.container.iter().any(|c| c == container)
+    }
+}
+
+/// Converts JSON-LD documents into quads.
+pub(crate) struct ToRdf<'a, L> {
+    loader: &'a L,
+    pub(crate) quads: Vec<BoxQuad>,
+    /// the blank node labels of the current document
+    labels: HashMap<String, BoxTerm>,
+    bnodes: usize,
+}
+
+impl<'a, L: DocumentLoader> ToRdf<'a, L> {
+    pub(crate) fn new(loader: &'a L) -> Self {
+        ToRdf {
+            loader,
+            quads: vec![],
+            labels: HashMap::new(),
+            bnodes: 0,
+        }
+    }
+
+    /// Convert `document` into quads, appended to `self.quads`.
+    ///
+    /// Blank node labels are scoped to each document.
+    pub(crate) fn document(
+        &mut self,
+        document: &JsonValue,
+        base: Option<&str>,
+    ) -> Result<(), JsonLdError> {
+        self.labels.clear();
+        let ctx = Context {
+            base: base.map(str::to_string),
+            ..Context::default()
+        };
+        for item in as_array(document) {
+            if item.is_object() {
+                self.node(&ctx, item, &None, true)?;
+            }
+        }
+        Ok(())
+    }
+
+    // context processing
+
+    fn process_context(
+        &mut self,
+        active: &Context,
+        local: &JsonValue,
+        remote: usize,
+    ) -> Result<Context, JsonLdError> {
+        let mut result = active.clone();
+        let items: Vec<&JsonValue> = match local {
+            JsonValue::Array(items) => items.iter().collect(),
+            _ => vec![local],
+        };
+        for item in items {
+            if item.is_null() {
+                result = Context {
+                    base: active.base.clone(),
+                    ..Context::default()
+                };
+            } else if let Some(url) = item.as_str() {
+                if remote >= MAX_REMOTE_CONTEXTS {
+                    return Err(JsonLdError::ContextOverflow(url.to_string()));
+                }
+                let url = resolve(result.base.as_deref(), url);
+                let document = self.loader.load_document(&url)?.document;
+                if !document.has_key("@context") {
+                    return Err(JsonLdError::InvalidRemoteContext(url));
+                }
+                result = self.process_context(&result, &document["@context"], remote + 1)?;
+            } else if item.is_object() {
+                result = self.local_context(result, item, remote)?;
+            } else {
+                return Err(JsonLdError::InvalidLocalContext(item.dump()));
+            }
+        }
+        Ok(result)
+    }
+
+    fn local_context(
+        &mut self,
+        mut result: Context,
+        local: &JsonValue,
+        remote: usize,
+    ) -> Result<Context, JsonLdError> {
+        let imported;
+        let local = match local["@import"].as_str() {
+            Some(url) => {
+                let url = resolve(result.base.as_deref(), url);
+                let document = self.loader.load_document(&url)?.document;
+                let mut merged = document["@context"].clone();
+                if !merged.is_object() {
+                    return Err(JsonLdError::InvalidRemoteContext(url));
+                }
+                for (key, val) in local.entries() {
+                    if key != "@import" {
+                        merged[key] = val.clone();
+                    }
+                }
+                imported = merged;
+                &imported
+            }
+            None => local,
+        };
+        if local.has_key("@base") && remote == 0 {
+            result.base = match local["@base"].as_str() {
+                Some(base) => Some(resolve(result.base.as_deref(), base)),
+                None => None,
+            };
+        }
+        if local.has_key("@vocab") {
+            result.vocab = match local["@vocab"].as_str() {
+                Some(vocab) => self.expand(&result, vocab, true, true),
+                None => None,
+            };
+        }
+        if local.has_key("@language") {
+            result.language = local["@language"].as_str().map(str::to_lowercase);
+        }
+        if local.has_key("@direction") {
+            result.direction = local["@direction"].as_str().and_then(Direction::parse);
+        }
+        let mut defined = HashMap::new();
+        for (term, _) in local.entries() {
+            self.define(&mut result, local, term, &mut defined)?;
+        }
+        Ok(result)
+    }
+
+    /// Create the definition of `term` from the `local` context into `active`.
+    fn define(
+        &mut self,
+        active: &mut Context,
+        local: &JsonValue,
+        term: &str,
+        defined: &mut HashMap<String, bool>,
+    ) -> Result<(), JsonLdError> {
+        match defined.get(term) {
+            Some(true) => return Ok(()),
+            Some(false) => return Err(JsonLdError::InvalidTermDefinition(term.to_string())),
+            None => (),
+        }
+        if term.starts_with('@') {
+            // keywords and keyword-like terms are ignored
+            return Ok(());
+        }
+        defined.insert(term.to_string(), false);
+        let value = &local[term];
+        let mut def = TermDef::default();
+        let simple = value.is_string();
+        let id = if value.is_null() {
+            None
+        } else if let Some(id) = value.as_str() {
+            Some(Some(id))
+        } else if value.is_object() {
+            if value.has_key("@reverse") {
+                def.reverse = true;
+                Some(value["@reverse"].as_str())
+            } else if value.has_key("@id") {
+                Some(value["@id"].as_str())
+            } else {
+                None
+            }
+        } else {
+            return Err(JsonLdError::InvalidTermDefinition(term.to_string()));
+        };
+        if value.is_null() || matches!(id, Some(None)) {
+            // explicitly mapped to null
+            active.terms.insert(term.to_string(), Rc::new(def));
+            defined.insert(term.to_string(), true);
+            return Ok(());
+        }
+        def.iri = match id.flatten() {
+            Some(id) if is_keyword(id) => Some(id.to_string()),
+            Some(id) => {
+                self.define_dependencies(active, local, id, defined)?;
+                self.expand(active, id, false, true)
+            }
+            None if term.contains(':') => {
+                self.define_dependencies(active, local, term, defined)?;
+                self.expand(active, term, false, true)
+            }
+            None => active
+                .vocab
+                .as_ref()
+                .map(|vocab| format!("{}{}", vocab, term)),
+        };
+        if def.iri.is_none() {
+            return Err(JsonLdError::InvalidTermDefinition(term.to_string()));
+        }
+        if value.is_object() {
+            if let Some(type_) = value["@type"].as_str() {
+                self.define_dependencies(active, local, type_, defined)?;
+                def.type_ = self.expand(active, type_, false, true);
+            }
+            def.container = as_array(&value["@container"])
+                .filter_map(JsonValue::as_str)
+                .map(str::to_string)
+                .collect();
+            if value.has_key("@language") {
+                def.language = Some(value["@language"].as_str().map(str::to_lowercase));
+            }
+            if value.has_key("@direction") {
+                def.direction = Some(value["@direction"].as_str().and_then(Direction::parse));
+            }
+            if value.has_key("@context") {
+                def.context = Some(value["@context"].clone());
+            }
+            def.prefix = value["@prefix"].as_bool().unwrap_or(false);
+        }
+        if simple {
+            def.prefix = def
+                .iri
+                .as_deref()
+                .is_some_and(|iri| iri.ends_with(|c| ":/?#[]@".contains(c)));
+        }
+        active.terms.insert(term.to_string(), Rc::new(def));
+        defined.insert(term.to_string(), true);
+        Ok(())
+    }
+
+    /// Define the terms of `local` on which `value` depends.
+    fn define_dependencies(
+        &mut self,
+        active: &mut Context,
+        local: &JsonValue,
+        value: &str,
+        defined: &mut HashMap<String, bool>,
+    ) -> Result<(), JsonLdError> {
+        let prefix = match value.find(':') {
+            Some(i) => &value[..i],
+            None => value,
+        };
+        if local.has_key(prefix) {
+            self.define(active, local, prefix, defined)?;
+        }
+        Ok(())
+    }
+
+    /// [IRI expansion](https://www.w3.org/TR/json-ld11-api/#iri-expansion)
+    fn expand(
+        &self,
+        ctx: &Context,
+        value: &str,
+        document_relative: bool,
+        vocab: bool,
+    ) -> Option<String> {
+        if is_keyword(value) {
+            return Some(value.to_string());
+        }
+        if value.starts_with('@') && value[1..].chars().all(|c| c.is_ascii_alphabetic()) {
+            return None;
+        }
+        if vocab {
+            if let Some(def) = ctx.terms.get(value) {
+                return def.iri.clone();
+            }
+        }
+        if let Some(i) = value.find(':') {
+            let (prefix, suffix) = (&value[..i], &value[i + 1..]);
+            if prefix == "_" || suffix.starts_with("//") {
+                return Some(value.to_string());
+            }
+            if let Some(def) = ctx.terms.get(prefix) {
+                if let (true, Some(iri)) = (def.prefix, &def.iri) {
+                    return Some(format!("{}{}", iri, suffix));
+                }
+            }
+            if is_absolute_iri_ref(value) {
+                return Some(value.to_string());
+            }
+        }
+        if vocab {
+            if let Some(vocab) = &ctx.vocab {
+                return Some(format!("{}{}", vocab, value));
+            }
+        }
+        if document_relative {
+            return Some(resolve(ctx.base.as_deref(), value));
+        }
+        Some(value.to_string())
+    }
+
+    // conversion to RDF
+
+    /// Convert a node object, and return its subject.
+    fn node(
+        &mut self,
+        ctx: &Context,
+        obj: &JsonValue,
+        graph: &Option<BoxTerm>,
+        top_level: bool,
+    ) -> Result<BoxTerm, JsonLdError> {
+        let scoped;
+        let ctx = if obj.has_key("@context") {
+            scoped = self.process_context(ctx, &obj["@context"], 0)?;
+            &scoped
+        } else {
+            ctx
+        };
+        let entries: Vec<(String, &str, &JsonValue)> = obj
+            .entries()
+            .filter(|(key, _)| *key != "@context")
+            .filter_map(|(key, val)| Some((self.expand(ctx, key, false, true)?, key, val)))
+            .collect();
+        let subject = match find(&entries, "@id") {
+            Some(id) => match id.as_str() {
+                Some(id) => self.node_ref(ctx, id, false),
+                None => return Err(JsonLdError::InvalidIdValue(id.dump())),
+            },
+            None => None,
+        };
+        let has_properties = entries
+            .iter()
+            .any(|(key, ..)| !key.starts_with('@') || key == "@type" || key == "@reverse");
+        let named = subject.is_some() || has_properties || !top_level;
+        let subject = match subject {
+            Some(subject) => subject,
+            None => self.fresh_bnode(),
+        };
+
+        for (key, term, val) in &entries {
+            match key.as_str() {
+                "@graph" => {
+                    let name = if named {
+                        Some(subject.clone())
+                    } else {
+                        graph.clone()
+                    };
+                    for item in as_array(val) {
+                        if item.is_object() {
+                            self.node(ctx, item, &name, false)?;
+                        }
+                    }
+                }
+                "@type" => {
+                    for type_ in as_array(val) {
+                        let type_ = type_
+                            .as_str()
+                            .ok_or_else(|| JsonLdError::InvalidTypeValue(type_.dump()))?;
+                        if let Some(type_) = self.node_ref(ctx, type_, true) {
+                            self.emit(subject.clone(), BoxTerm::copy(&rdf::type_), type_, graph);
+                        }
+                    }
+                }
+                "@reverse" => {
+                    for (key, val) in val.entries() {
+                        if let Some(predicate) = self.predicate(ctx, key) {
+                            for object in self.values(ctx, None, val, graph)? {
+                                self.emit(object, predicate.clone(), subject.clone(), graph);
+                            }
+                        }
+                    }
+                }
+                "@included" => {
+                    for item in as_array(val) {
+                        if item.is_object() {
+                            self.node(ctx, item, graph, false)?;
+                        }
+                    }
+                }
+                _ if key.starts_with('@') => (),
+                _ => {
+                    let predicate = match self.predicate(ctx, key) {
+                        Some(predicate) => predicate,
+                        None => continue,
+                    };
+                    let def = ctx.terms.get(*term).cloned();
+                    let scoped;
+                    let ctx = match def.as_ref().and_then(|def| def.context.as_ref()) {
+                        Some(local) => {
+                            scoped = self.process_context(ctx, local, 0)?;
+                            &scoped
+                        }
+                        None => ctx,
+                    };
+                    let reverse = def.as_ref().is_some_and(|def| def.reverse);
+                    for object in self.values(ctx, def.as_deref(), val, graph)? {
+                        if reverse {
+                            self.emit(object, predicate.clone(), subject.clone(), graph);
+                        } else {
+                            self.emit(subject.clone(), predicate.clone(), object, graph);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(subject)
+    }
+
+    /// Convert the value of a property into a list of terms.
+    fn values(
+        &mut self,
+        ctx: &Context,
+        def: Option<&TermDef>,
+        val: &JsonValue,
+        graph: &Option<BoxTerm>,
+    ) -> Result<Vec<BoxTerm>, JsonLdError> {
+        let mut result = vec![];
+        let container = |c| def.is_some_and(|def| def.has_container(c));
+        if container("@list") && !is_list_object(val) {
+            result.push(self.list(ctx, def, val, graph)?);
+        } else if container("@language") && val.is_object() {
+            for (lang, val) in val.entries() {
+                for txt in as_array(val).filter_map(JsonValue::as_str) {
+                    let lit = match lang {
+                        "@none" => Some(BoxTerm::from(txt.to_string())),
+                        _ => BoxTerm::new_literal_lang(txt, lang.to_lowercase()).ok(),
+                    };
+                    result.extend(lit);
+                }
+            }
+        } else if container("@index") && val.is_object() && !val.has_key("@value") {
+            for (_, val) in val.entries() {
+                for item in as_array(val) {
+                    self.object(ctx, def, item, graph, &mut result)?;
+                }
+            }
+        } else {
+            for item in as_array(val) {
+                self.object(ctx, def, item, graph, &mut result)?;
+            }
+        }
+        Ok(result)
+    }
+
+    /// Convert `val` into terms, appended to `result`.
+    fn object(
+        &mut self,
+        ctx: &Context,
+        def: Option<&TermDef>,
+        val: &JsonValue,
+        graph: &Option<BoxTerm>,
+        result: &mut Vec<BoxTerm>,
+    ) -> Result<(), JsonLdError> {
+        let type_ = def.and_then(|def| def.type_.as_deref());
+        match val {
+            JsonValue::Null => (),
+            JsonValue::Array(items) => {
+                for item in items {
+                    self.object(ctx, def, item, graph, result)?;
+                }
+            }
+            JsonValue::Object(_) => {
+                let keyword = |kw| {
+                    val.entries()
+                        .find(|(key, _)| self.expand(ctx, key, false, true).as_deref() == Some(kw))
+                        .map(|(_, val)| val)
+                };
+                if let Some(value) = keyword("@value") {
+                    let type_ = keyword("@type").and_then(JsonValue::as_str);
+                    let type_ = type_.and_then(|t| self.expand(ctx, t, true, true));
+                    let lang = keyword("@language").and_then(JsonValue::as_str);
+                    let dir = keyword("@direction")
+                        .and_then(JsonValue::as_str)
+                        .and_then(Direction::parse);
+                    result.extend(value_object(value, type_.as_deref(), lang, dir));
+                } else if let Some(items) = keyword("@list") {
+                    result.push(self.list(ctx, def, items, graph)?);
+                } else if let Some(items) = keyword("@set") {
+                    self.object(ctx, def, items, graph, result)?;
+                } else {
+                    result.push(self.node(ctx, val, graph, false)?);
+                }
+            }
+            _ => {
+                if let Some(txt) = val.as_str() {
+                    match type_ {
+                        Some("@id") => result.extend(self.node_ref(ctx, txt, false)),
+                        Some("@vocab") => result.extend(self.node_ref(ctx, txt, true)),
+                        Some(dt) if !dt.starts_with('@') => result.extend(typed_literal(txt, dt)),
+                        _ => {
+                            let lang = match def.and_then(|def| def.language.as_ref()) {
+                                Some(lang) => lang.as_deref(),
+                                None => ctx.language.as_deref(),
+                            };
+                            let dir = match def.and_then(|def| def.direction) {
+                                Some(dir) => dir,
+                                None => ctx.direction,
+                            };
+                            result.extend(value_object(val, None, lang, dir));
+                        }
+                    }
+                } else {
+                    result.extend(value_object(val, type_, None, None));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn list(
+        &mut self,
+        ctx: &Context,
+        def: Option<&TermDef>,
+        items: &JsonValue,
+        graph: &Option<BoxTerm>,
+    ) -> Result<BoxTerm, JsonLdError> {
+        let mut terms = vec![];
+        for item in as_array(items) {
+            self.object(ctx, def, item, graph, &mut terms)?;
+        }
+        let mut list = BoxTerm::copy(&rdf::nil);
+        for term in terms.into_iter().rev() {
+            let node = self.fresh_bnode();
+            self.emit(node.clone(), BoxTerm::copy(&rdf::first), term, graph);
+            self.emit(node.clone(), BoxTerm::copy(&rdf::rest), list, graph);
+            list = node;
+        }
+        Ok(list)
+    }
+
+    fn predicate(&self, ctx: &Context, key: &str) -> Option<BoxTerm> {
+        let iri = self.expand(ctx, key, false, true)?;
+        if is_absolute_iri_ref(&iri) {
+            BoxTerm::new_iri(iri).ok()
+        } else {
+            // blank node and relative properties are dropped
+            None
+        }
+    }
+
+    /// Convert an `@id` value (or a value coerced to `@id` or `@vocab`) into a term.
+    fn node_ref(&mut self, ctx: &Context, id: &str, vocab: bool) -> Option<BoxTerm> {
+        if let Some(label) = id.strip_prefix("_:") {
+            return Some(self.bnode(label));
+        }
+        let iri = self.expand(ctx, id, true, vocab)?;
+        if let Some(label) = iri.strip_prefix("_:") {
+            Some(self.bnode(label))
+        } else if is_absolute_iri_ref(&iri) {
+            BoxTerm::new_iri(iri).ok()
+        } else {
+            None
+        }
+    }
+
+    fn bnode(&mut self, label: &str) -> BoxTerm {
+        if let Some(bnode) = self.labels.get(label) {
+            return bnode.clone();
+        }
+        let bnode = self.fresh_bnode();
+        self.labels.insert(label.to_string(), bnode.clone());
+        bnode
+    }
+
+    fn fresh_bnode(&mut self) -> BoxTerm {
+        let bnode = BoxTerm::new_bnode_unchecked(format!("b{}", self.bnodes));
+        self.bnodes += 1;
+        bnode
+    }
+
+    fn emit(&mut self, s: BoxTerm, p: BoxTerm, o: BoxTerm, graph: &Option<BoxTerm>) {
+        self.quads.push(([s, p, o], graph.clone()));
+    }
+}
+
+fn is_keyword(txt: &str) -> bool {
+    KEYWORDS.contains(&txt)
+}
+
+fn is_list_object(val: &JsonValue) -> bool {
+    val.is_object() && val.has_key("@list")
+}
+
+/// Iterate over the elements of `val` if it is an array, or over `val` itself otherwise.
+fn as_array(val: &JsonValue) -> Box<dyn Iterator<Item = &JsonValue> + '_> {
+    match val {
+        JsonValue::Array(items) => Box::new(items.iter()),
+        JsonValue::Null => Box::new(std::iter::empty()),
+        _ => Box::new(std::iter::once(val)),
+    }
+}
+
+fn find<'a>(entries: &[(String, &str, &'a JsonValue)], keyword: &str) -> Option<&'a JsonValue> {
+    entries
+        .iter()
+        .find(|(key, ..)| key == keyword)
+        .map(|(_, _, val)| *val)
+}
+
+fn typed_literal(txt: &str, datatype: &str) -> Option<BoxTerm> {
+    let datatype = Iri::<Box<str>>::new(datatype).ok()?;
+    Some(BoxTerm::new_literal_dt_unchecked(txt, datatype))
+}
+
+/// Convert a native JSON value into a literal,
+/// according to the [object to RDF conversion](https://www.w3.org/TR/json-ld11-api/#object-to-rdf-conversion).
+fn value_object(
+    val: &JsonValue,
+    type_: Option<&str>,
+    lang: Option<&str>,
+    dir: Option<Direction>,
+) -> Option<BoxTerm> {
+    if type_ == Some("@json") {
+        return typed_literal(&val.dump(), &rdf::JSON.value());
+    }
+    let (txt, default_type) = match val {
+        JsonValue::Boolean(b) => (b.to_string(), Some(xsd::boolean.value())),
+        JsonValue::Number(n) => {
+            let f = f64::from(*n);
+            if f.fract() == 0.0 && f.abs() < 1e21 && type_ != Some(&xsd::double.value()) {
+                (format!("{}", f as i64), Some(xsd::integer.value()))
+            } else {
+                (canonical_double(f), Some(xsd::double.value()))
+            }
+        }
+        _ => (val.as_str()?.to_string(), None),
+    };
+    match (type_, default_type, lang) {
+        (Some(dt), _, _) if !dt.starts_with('@') => typed_literal(&txt, dt),
+        (_, Some(dt), _) => typed_literal(&txt, &dt),
+        (_, None, Some(lang)) => match dir {
+            None => BoxTerm::new_literal_lang(txt, lang.to_lowercase()).ok(),
+            Some(dir) => BoxTerm::new_literal_lang_dir(txt, lang.to_lowercase(), dir).ok(),
+        },
+        (_, None, None) => Some(BoxTerm::from(txt)),
+    }
+}
+
+/// The canonical lexical form of an `xsd:double`, as required by JSON-LD (e.g. `1.5E1`).
+fn canonical_double(f: f64) -> String {
+    let txt = format!("{:E}", f);
+    match txt.find('E') {
+        Some(i) if !txt[..i].contains('.') => format!("{}.0{}", &txt[..i], &txt[i..]),
+        _ => txt,
+    }
+}
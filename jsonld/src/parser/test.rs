@@ -0,0 +1,202 @@
+use super::*;
+use crate::loader::PreloadedLoader;
+use sophia::serializer::nq::NqSerializer;
+use sophia::serializer::{QuadSerializer, Stringifier};
+
+/// Parse `txt` and return the sorted N-Quads lines of the result.
+fn to_nq<L: DocumentLoader>(parser: &JsonLdParser<L>, txt: &str) -> Vec<String> {
+    let quads = parser.parse_json(&json::parse(txt).unwrap()).unwrap();
+    let nq = NqSerializer::new_stringifier()
+        .serialize_quads(quads.into_iter().map(Ok::<_, JsonLdError>))
+        .unwrap()
+        .to_string();
+    let mut lines: Vec<String> = nq.lines().map(str::to_string).collect();
+    lines.sort();
+    lines
+}
+
+#[test]
+fn expanded() {
+    let lines = to_nq(
+        &JsonLdParser::new(),
+        r#"[{
+            "@id": "http://example.org/alice",
+            "@type": ["http://example.org/Person"],
+            "http://example.org/name": [{"@value": "Alice", "@language": "en"}],
+            "http://example.org/age": [{"@value": 42}],
+            "http://example.org/knows": [{"@id": "_:bob"}]
+        }]"#,
+    );
+    assert_eq!(
+        lines,
+        vec![
+            r#"<http://example.org/alice> <http://example.org/age> "42"^^<http://www.w3.org/2001/XMLSchema#integer>."#,
+            r#"<http://example.org/alice> <http://example.org/knows> _:b0."#,
+            r#"<http://example.org/alice> <http://example.org/name> "Alice"@en."#,
+            r#"<http://example.org/alice> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://example.org/Person>."#,
+        ]
+    );
+}
+
+#[test]
+fn compacted() {
+    let parser = JsonLdParser {
+        base: Some("http://example.org/doc".to_string()),
+        loader: NoLoader,
+    };
+    let lines = to_nq(
+        &parser,
+        r##"{
+            "@context": {
+                "@vocab": "http://schema.org/",
+                "ex": "http://example.org/",
+                "id": "@id",
+                "knows": {"@type": "@id"},
+                "birth": {"@id": "ex:birthDate", "@type": "http://www.w3.org/2001/XMLSchema#date"},
+                "tags": {"@id": "ex:tag", "@container": "@list"},
+                "label": {"@id": "ex:label", "@container": "@language"},
+                "children": {"@reverse": "ex:parent"}
+            },
+            "id": "#alice",
+            "knows": "bob",
+            "birth": "2000-01-01",
+            "height": 1.65,
+            "tags": ["a", "b"],
+            "label": {"fr": "Alice (fr)"},
+            "children": {"name": "Carol"}
+        }"##,
+    );
+    assert_eq!(
+        lines,
+        vec![
+            r#"<http://example.org/doc#alice> <http://example.org/birthDate> "2000-01-01"^^<http://www.w3.org/2001/XMLSchema#date>."#,
+            r#"<http://example.org/doc#alice> <http://example.org/label> "Alice (fr)"@fr."#,
+            r#"<http://example.org/doc#alice> <http://example.org/tag> _:b1."#,
+            r#"<http://example.org/doc#alice> <http://schema.org/height> "1.65E0"^^<http://www.w3.org/2001/XMLSchema#double>."#,
+            r#"<http://example.org/doc#alice> <http://schema.org/knows> <http://example.org/bob>."#,
+            r#"_:b0 <http://www.w3.org/1999/02/22-rdf-syntax-ns#first> "b"."#,
+            r#"_:b0 <http://www.w3.org/1999/02/22-rdf-syntax-ns#rest> <http://www.w3.org/1999/02/22-rdf-syntax-ns#nil>."#,
+            r#"_:b1 <http://www.w3.org/1999/02/22-rdf-syntax-ns#first> "a"."#,
+            r#"_:b1 <http://www.w3.org/1999/02/22-rdf-syntax-ns#rest> _:b0."#,
+            r#"_:b2 <http://example.org/parent> <http://example.org/doc#alice>."#,
+            r#"_:b2 <http://schema.org/name> "Carol"."#,
+        ]
+    );
+}
+
+#[test]
+fn named_graphs() {
+    let lines = to_nq(
+        &JsonLdParser::new(),
+        r#"{
+            "@context": {"ex": "http://example.org/"},
+            "@graph": [
+                {"@id": "ex:g", "@graph": {"@id": "ex:s", "ex:p": "in g"}},
+                {"@id": "ex:s", "ex:p": "in default"}
+            ]
+        }"#,
+    );
+    assert_eq!(
+        lines,
+        vec![
+            r#"<http://example.org/s> <http://example.org/p> "in default"."#,
+            r#"<http://example.org/s> <http://example.org/p> "in g" <http://example.org/g>."#,
+        ]
+    );
+}
+
+#[test]
+fn remote_context() {
+    let mut loader = PreloadedLoader::new();
+    loader
+        .insert_str(
+            "https://schema.org/",
+            r#"{"@context": {"@vocab": "http://schema.org/"}}"#,
+        )
+        .unwrap();
+    let parser = JsonLdParser::new().with_loader(loader);
+    let lines = to_nq(
+        &parser,
+        r#"{
+            "@context": "https://schema.org/",
+            "@type": "Person",
+            "name": "Alice"
+        }"#,
+    );
+    assert_eq!(
+        lines,
+        vec![
+            r#"_:b0 <http://schema.org/name> "Alice"."#,
+            r#"_:b0 <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://schema.org/Person>."#,
+        ]
+    );
+
+    let res = JsonLdParser::new()
+        .parse_json(&json::parse(r#"{"@context": "https://schema.org/"}"#).unwrap());
+    assert!(matches!(res, Err(JsonLdError::LoadingDocumentFailed(_))));
+}
+
+#[test]
+fn errors() {
+    let mut source = parse_str("{ not JSON");
+    assert!(matches!(
+        source.next(),
+        Some(Err(JsonLdError::InvalidJsonLiteral(_)))
+    ));
+    assert!(source.next().is_none());
+    let res = JsonLdParser::new().parse_json(&json::parse(r#"{"@id": 42}"#).unwrap());
+    assert!(matches!(res, Err(JsonLdError::InvalidIdValue(_))));
+    let res = JsonLdParser::new()
+        .parse_json(&json::parse(r#"{"@context": {"a": "b:x", "b": "a:y"}}"#).unwrap());
+    assert!(matches!(res, Err(JsonLdError::InvalidTermDefinition(_))));
+}
+
+#[test]
+fn direction() {
+    let lines = to_nq(
+        &JsonLdParser::new(),
+        r#"{
+            "@context": {
+                "@vocab": "http://example.org/",
+                "@language": "ar",
+                "@direction": "rtl",
+                "en": {"@id": "http://example.org/label", "@language": "en", "@direction": null}
+            },
+            "@id": "http://example.org/a",
+            "label": "مرحبا",
+            "en": "hello",
+            "other": {"@value": "hello", "@language": "en", "@direction": "ltr"},
+            "nolang": {"@value": "hello", "@direction": "ltr"}
+        }"#,
+    );
+    assert_eq!(
+        lines,
+        vec![
+            r#"<http://example.org/a> <http://example.org/label> "hello"@en."#,
+            r#"<http://example.org/a> <http://example.org/label> "مرحبا"@ar--rtl."#,
+            r#"<http://example.org/a> <http://example.org/nolang> "hello"."#,
+            r#"<http://example.org/a> <http://example.org/other> "hello"@en--ltr."#,
+        ]
+    );
+}
+
+#[test]
+fn registered_format() {
+    use sophia::format::parse_with_media_type;
+
+    register_format();
+    assert!(Format::JsonLd.can_parse());
+    let quads: Vec<_> = parse_with_media_type(
+        r#"{"@id": "http://example.org/a", "http://example.org/p": "b"}"#.as_bytes(),
+        "application/ld+json",
+    )
+    .unwrap()
+    .collect::<Result<_, _>>()
+    .unwrap();
+    assert_eq!(quads.len(), 1);
+
+    let res: Result<Vec<_>, _> = parse_with_media_type("[".as_bytes(), "application/ld+json")
+        .unwrap()
+        .collect();
+    assert!(res.is_err());
+}
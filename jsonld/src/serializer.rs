@@ -6,7 +6,9 @@ use sophia::triple::stream::{SinkError, StreamResult};
 use sophia_api::serializer::*;
 
 mod engine;
+mod framing;
 mod rdf_object;
+mod streaming;
 #[cfg(test)]
 mod test;
 
@@ -39,7 +41,11 @@ impl<W> JsonLdSerializer<W> {
     {
         let mut engine = engine::Engine::new_with_config(self.config.clone());
         engine.process_quads(source)?;
-        engine.into_json().map_err(SinkError)
+        match &self.config.frame {
+            None => engine.into_json(),
+            Some(frame) => engine.into_framed_json(frame),
+        }
+        .map_err(SinkError)
     }
 }
 
@@ -53,6 +59,22 @@ where
     where
         QS: QuadSource,
     {
+        if self.config.streaming {
+            if self.config.frame.is_some() {
+                return Err(SinkError(JsonLdError::IncompatibleOptions(
+                    "framing is not available in streaming mode".to_string(),
+                )));
+            }
+            if self.config.rdf_direction == Some(RdfDirectionMode::CompoundLiteral) {
+                return Err(SinkError(JsonLdError::IncompatibleOptions(
+                    "compound literals can not be folded in streaming mode".to_string(),
+                )));
+            }
+            let config = self.config.clone();
+            let mut engine = streaming::StreamingEngine::new_with_config(&mut self.target, config);
+            engine.process_quads(source)?;
+            return Ok(self);
+        }
         let result = self.convert_quads(source)?;
         let json_txt = match self.config.spaces {
             0 => json::stringify(result),
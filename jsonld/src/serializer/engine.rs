@@ -1,14 +1,18 @@
+use super::framing::{self, GraphMap, NodeMap};
 use super::rdf_object::*;
 use crate::config::{JsonLdSpecVersion::*, *};
 use crate::error::*;
+use crate::frame::Frame;
 use crate::util_traits::*;
 use json::object::Object;
 use json::JsonValue;
+use sophia::collection;
 use sophia::ns::{rdf, xsd};
 use sophia::quad::{stream::*, Quad};
 use sophia::triple::stream::{SinkError, StreamResult};
 use sophia_api::term::{TTerm, TermKind, TryCopyTerm};
 use sophia_term::literal::Literal;
+use sophia_term::RefTerm;
 use std::collections::hash_map::Entry::*;
 use std::collections::{HashMap, HashSet};
 
@@ -46,6 +50,11 @@ impl Engine {
         }
     }
 
+    /// Borrow this engine's configuration.
+    pub fn config(&self) -> &JsonLdConfig {
+        &self.config
+    }
+
     pub fn process_quads<QS>(&mut self, mut source: QS) -> StreamResult<(), QS::Error, JsonLdError>
     where
         QS: QuadSource,
@@ -125,6 +134,23 @@ impl Engine {
 
     /// Get the result as a JsonValue.
     pub fn into_json(mut self) -> Result<JsonValue, JsonLdError> {
+        self.detect_list_nodes_and_compound_literals();
+        self.node
+            .iter()
+            .enumerate()
+            .filter_map(|(inode, node)| self.jsonify(inode, node, true).transpose())
+            .collect::<Result<Vec<_>, _>>()
+            .map(Into::into)
+    }
+
+    /// Get the result as a JsonValue, reshaped according to `frame`.
+    pub fn into_framed_json(mut self, frame: &Frame) -> Result<JsonValue, JsonLdError> {
+        self.detect_list_nodes_and_compound_literals();
+        let graph_map = self.make_graph_map()?;
+        Ok(framing::frame(graph_map, frame, self.config.spec_version))
+    }
+
+    fn detect_list_nodes_and_compound_literals(&mut self) {
         // check all list_seeds to mark them, if appropriate, as list nodes,
         // and also recursively mark other list nodes (traversing back rdf:rest links)
         let list_seeds = std::mem::take(&mut self.list_seeds);
@@ -134,16 +160,65 @@ impl Engine {
         // check that candidate compound literals are indeed compound literels
         if self.config.rdf_direction == Some(RdfDirectionMode::CompoundLiteral) {
             let mut compound_literals = std::mem::take(&mut self.compound_literals);
-            compound_literals.retain(|is| is_compound_literal(&self.node[*is]));
+            compound_literals
+                .retain(|is| is_compound_literal(&self.node[*is]) && self.is_referenced_once(*is));
             self.compound_literals = compound_literals;
         }
+    }
 
-        self.node
-            .iter()
-            .enumerate()
-            .filter_map(|(inode, node)| self.jsonify(inode, node, true).transpose())
-            .collect::<Result<Vec<_>, _>>()
-            .map(Into::into)
+    /// Build the node map of each graph (" " being the default graph),
+    /// as expected by the framing algorithm.
+    fn make_graph_map(&self) -> Result<GraphMap, JsonLdError> {
+        let mut graph_map = GraphMap::new();
+        graph_map.insert(" ".to_string(), NodeMap::new());
+        for (inode, node) in self.node.iter().enumerate() {
+            let (g_id, s_id) = &self.gs_id[inode];
+            if node.is_empty() || self.is_embedded(inode) {
+                // nodes without outgoing arcs are added below, if they are referenced
+                continue;
+            }
+            let obj = self.make_node_object(s_id, node)?;
+            graph_map
+                .entry(g_id.to_string())
+                .or_default()
+                .insert(s_id.to_string(), obj);
+        }
+        // add an entry for every node that is referenced but not described
+        for node_map in graph_map.values_mut() {
+            let mut referenced = Vec::new();
+            for obj in node_map.values() {
+                for (key, vals) in obj.iter() {
+                    if key != "@id" && key != "@type" {
+                        collect_references(vals, &mut referenced);
+                    }
+                }
+            }
+            for id in referenced {
+                node_map.entry(id.clone()).or_insert_with(|| {
+                    let mut obj = Object::new();
+                    obj.insert("@id", id.into());
+                    obj
+                });
+            }
+        }
+        Ok(graph_map)
+    }
+
+    /// Whether this node is the object of exactly one triple, in its own graph.
+    fn is_referenced_once(&self, inode: usize) -> bool {
+        let (g_id, s_id) = &self.gs_id[inode];
+        match self.unique_parent.get(s_id) {
+            Some(Some((iparent, _))) => &self.gs_id[*iparent].0 == g_id,
+            _ => false,
+        }
+    }
+
+    /// Whether this node is rendered inside its parent (as a list or a compound literal)
+    /// rather than as a node object.
+    fn is_embedded(&self, inode: usize) -> bool {
+        self.list_node.contains_key(&self.gs_id[inode].1)
+            || (self.config.rdf_direction == Some(RdfDirectionMode::CompoundLiteral)
+                && self.compound_literals.contains(&inode))
     }
 
     /// If this node is a bnode with only 1 rdf:value & 1 rdf:rest),
@@ -198,15 +273,12 @@ impl Engine {
             // we will include it later
             return Ok(None);
         }
-        if self.list_node.contains_key(s_id)
-            || (self.config.rdf_direction == Some(RdfDirectionMode::CompoundLiteral)
-                && self.compound_literals.contains(&inode))
-        {
+        if self.is_embedded(inode) {
             //println!("=== skiped (list node or compound literal)");
             return Ok(None);
         }
         //println!("=== --- doing it");
-        let mut obj = self.make_node_object(&s_id, node)?;
+        let mut obj = self.make_node_object(s_id, node)?;
         if root {
             if let Some(ng) = node.get("@graph") {
                 //println!("=== --- @graph for {}", s_id);
@@ -225,14 +297,17 @@ impl Engine {
         Ok(Some(obj.into()))
     }
 
-    fn make_node_object(
+    pub(super) fn make_node_object(
         &self,
         id: &str,
         node: &HashMap<String, Vec<RdfObject>>,
     ) -> Result<Object, JsonLdError> {
         let mut obj = Object::new();
         obj.insert("@id", id.into());
-        for (key, vals) in node.iter() {
+        let mut keys = node.keys().collect::<Vec<_>>();
+        keys.sort();
+        for key in keys {
+            let vals = &node[key];
             if key == "@graph" {
                 continue;
             }
@@ -241,7 +316,9 @@ impl Engine {
                     .iter()
                     .filter_map(|o| match o {
                         RdfObject::Literal(_) => None,
-                        RdfObject::Node(_, nid) => Some(JsonValue::from(nid.as_str())),
+                        RdfObject::Node(_, nid) | RdfObject::NodeId(nid) => {
+                            Some(JsonValue::from(nid.as_str()))
+                        }
                     })
                     .collect::<Vec<_>>();
                 ("@type", vals)
@@ -263,10 +340,14 @@ impl Engine {
                 let value = JsonValue::from(lit.txt().as_ref());
                 match lit.lang() {
                     Some(tag) => {
-                        json::object! {
+                        let mut obj = json::object! {
                             "@value": value,
                             "@language": JsonValue::from(tag.as_ref()),
+                        };
+                        if let Some(dir) = lit.dir() {
+                            obj["@direction"] = dir.as_str().into();
                         }
+                        obj
                     }
                     None => {
                         let txt = lit.txt().as_ref();
@@ -301,7 +382,7 @@ impl Engine {
                             }
                         }
                         if dt == rdf::JSON {
-                            let json_value = json::parse(&txt)?;
+                            let json_value = json::parse(txt)?;
                             obj.insert("@value", json_value);
                             obj.insert("@type", "@json".into());
                         }
@@ -316,6 +397,17 @@ impl Engine {
                     }
                 }
             }
+            RdfObject::NodeId(id) => {
+                if id == RDF_NIL {
+                    json::object! {
+                        "@list": [],
+                    }
+                } else {
+                    json::object! {
+                        "@id": id.as_str(),
+                    }
+                }
+            }
             RdfObject::Node(inode, id) => {
                 if id == RDF_NIL {
                     json::object! {
@@ -372,22 +464,32 @@ impl Engine {
     }
 }
 
-// check if node is a list map (bnode w/ exactly 1 rdf:first and 1 rdf:rest, possibly a rdf:List)
+// collect the @id of all node references in vals (including in lists)
+fn collect_references(vals: &JsonValue, referenced: &mut Vec<String>) {
+    for val in vals.members() {
+        if val.has_key("@list") {
+            collect_references(&val["@list"], referenced);
+        } else if val.len() == 1 {
+            if let Some(id) = val["@id"].as_str() {
+                referenced.push(id.to_string());
+            }
+        }
+    }
+}
+
+// check if node is a list map (bnode w/ exactly 1 rdf:first and 1 rdf:rest, possibly a rdf:List),
+// as defined by sophia::collection::is_list_node
 // IMPORTANT: for this to be accurate, it must also hold that rdf:rest points to a list node,
 // but this function is only called in situations where this is true
 fn is_list_node(node: &HashMap<String, Vec<RdfObject>>) -> bool {
-    2 <= node.len()
-        && node.len() <= 3
-        && node.get(RDF_FIRST).map(|v| v.len() == 1).unwrap_or(false)
-        && node
-            .get(RDF_REST)
-            .map(|v| v.len() == 1 && v[0].is_node())
-            .unwrap_or(false)
-        && (node.len() == 2
-            || node
-                .get(RDF_TYPE)
-                .map(|v| v.len() == 1 && v[0].is_node() && v[0].eq_node(&RDF_LIST))
-                .unwrap_or(false))
+    let arcs: Vec<_> = node
+        .iter()
+        .flat_map(|(p, objs)| {
+            objs.iter()
+                .map(move |o| (RefTerm::new_iri_unchecked(p.as_str()), o.as_term()))
+        })
+        .collect();
+    collection::is_list_node(arcs.iter().map(|(p, o)| (p, o)))
 }
 
 // check if node is a compound literal
@@ -413,7 +515,6 @@ const NS_18N: &str = "https://www.w3.org/ns/i18n#";
 const RDF_DIRECTION: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#direction";
 const RDF_FIRST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#first";
 const RDF_LANGUAGE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#language";
-const RDF_LIST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#List";
 const RDF_NIL: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#nil";
 const RDF_REST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#rest";
 const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
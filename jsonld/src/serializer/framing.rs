@@ -0,0 +1,565 @@
+//! Implementation of the [JSON-LD 1.1 Framing Algorithm],
+//! used internally by JsonLdSerializer.
+//!
+//! [JSON-LD 1.1 Framing Algorithm]: https://www.w3.org/TR/json-ld11-framing/#framing-algorithm
+use crate::config::JsonLdSpecVersion;
+use crate::frame::*;
+use json::object::Object;
+use json::JsonValue;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Maps node identifiers to node objects
+pub type NodeMap = BTreeMap<String, Object>;
+/// Maps graph names (" " for the default graph) to node maps
+pub type GraphMap = BTreeMap<String, NodeMap>;
+
+const DEFAULT: &str = " ";
+const MERGED: &str = "@merged";
+
+/// Frame the nodes in `graph_map` according to `frame`.
+///
+/// The result is an array of node objects, in expanded form.
+pub fn frame(mut graph_map: GraphMap, frame: &Frame, version: JsonLdSpecVersion) -> JsonValue {
+    let mut top_frame = frame.as_json();
+    let graph = if top_frame.has_key("@graph") {
+        if top_frame.len() == 1 {
+            // a frame containing only @graph is equivalent to its content
+            top_frame = first(&top_frame["@graph"]);
+        }
+        DEFAULT
+    } else {
+        let merged = merge_node_maps(&graph_map);
+        graph_map.insert(MERGED.to_string(), merged);
+        MERGED
+    };
+    let mut state = State {
+        flags: Flags {
+            embed: frame.embed,
+            explicit: frame.explicit,
+            omit_default: frame.omit_default,
+            require_all: frame.require_all,
+        },
+        graph_map: &graph_map,
+        unique_embeds: HashMap::new(),
+        subject_stack: Vec::new(),
+    };
+    let subjects = graph_map[graph].keys().cloned().collect::<Vec<_>>();
+    let mut framed = JsonValue::new_array();
+    state.frame(graph, false, &subjects, top_frame, &mut framed, None);
+
+    let mut bnode_count = HashMap::new();
+    if version > JsonLdSpecVersion::JsonLd10 {
+        count_bnode_ids(&framed, &mut bnode_count);
+    }
+    cleanup(framed, &bnode_count)
+}
+
+/// The framing flags that can be overridden in each frame
+#[derive(Clone, Copy, Debug)]
+struct Flags {
+    embed: Embed,
+    explicit: bool,
+    omit_default: bool,
+    require_all: bool,
+}
+
+impl Flags {
+    /// The flags for frame, using self as default values
+    fn for_frame(&self, frame: &JsonValue) -> Flags {
+        Flags {
+            embed: match frame["@embed"] {
+                JsonValue::Null => self.embed,
+                ref val => Embed::from_json(val).unwrap_or(self.embed),
+            },
+            explicit: flag_value(&frame["@explicit"]).unwrap_or(self.explicit),
+            omit_default: flag_value(&frame["@omitDefault"]).unwrap_or(self.omit_default),
+            require_all: flag_value(&frame["@requireAll"]).unwrap_or(self.require_all),
+        }
+    }
+
+    /// The frame used for properties that are not mentioned in their parent frame
+    fn implicit_frame(&self) -> JsonValue {
+        json::object! {
+            "@embed": self.embed.as_str(),
+            "@explicit": self.explicit,
+            "@omitDefault": self.omit_default,
+            "@requireAll": self.require_all,
+        }
+    }
+}
+
+struct State<'a> {
+    flags: Flags,
+    graph_map: &'a GraphMap,
+    // the nodes already embedded, for each graph
+    unique_embeds: HashMap<String, HashSet<String>>,
+    // the (graph, id) of the nodes being embedded, to detect circular references
+    subject_stack: Vec<(String, String)>,
+}
+
+impl<'a> State<'a> {
+    /// Frame the nodes of `graph` identified by `subjects`, and add the result to `parent`.
+    fn frame(
+        &mut self,
+        graph: &str,
+        embedded: bool,
+        subjects: &[String],
+        frame: &JsonValue,
+        parent: &mut JsonValue,
+        property: Option<&str>,
+    ) {
+        let frame = first(frame);
+        let flags = self.flags.for_frame(frame);
+        let graph_map = self.graph_map;
+        let node_map = &graph_map[graph];
+        let mut ids = subjects
+            .iter()
+            .filter(|id| {
+                node_map
+                    .get(id.as_str())
+                    .map(|node| self.filter_subject(graph, node, frame, &flags))
+                    .unwrap_or(false)
+            })
+            .collect::<Vec<_>>();
+        ids.sort();
+        ids.dedup();
+
+        for id in ids {
+            let node = &node_map[id.as_str()];
+            if property.is_none() {
+                self.unique_embeds.clear();
+            }
+            let mut output = Object::new();
+            output.insert("@id", id.as_str().into());
+
+            let circular = self
+                .subject_stack
+                .iter()
+                .any(|(g, i)| g == graph && i == id);
+            if embedded && (flags.embed == Embed::Never || circular) {
+                add_output(parent, property, output.into());
+                continue;
+            }
+            let unique_embeds = self.unique_embeds.entry(graph.to_string()).or_default();
+            if embedded && flags.embed == Embed::Once && unique_embeds.contains(id) {
+                add_output(parent, property, output.into());
+                continue;
+            }
+            unique_embeds.insert(id.to_string());
+            self.subject_stack.push((graph.to_string(), id.to_string()));
+
+            // this node is also the name of a graph
+            if let Some(inner_map) = graph_map.get(id.as_str()) {
+                let (recurse, subframe) = match frame["@graph"] {
+                    JsonValue::Null => (graph != MERGED, JsonValue::new_object()),
+                    ref subframe => (true, first(subframe).clone()),
+                };
+                if recurse {
+                    let inner_subjects = inner_map.keys().cloned().collect::<Vec<_>>();
+                    let mut output_value = JsonValue::from(output);
+                    self.frame(
+                        id,
+                        false,
+                        &inner_subjects,
+                        &subframe,
+                        &mut output_value,
+                        Some("@graph"),
+                    );
+                    output = into_object(output_value);
+                }
+            }
+
+            // properties of the node
+            for (prop, objects) in node.iter() {
+                if prop.starts_with('@') {
+                    output.insert(prop, objects.clone());
+                    continue;
+                }
+                if flags.explicit && !frame.has_key(prop) {
+                    continue;
+                }
+                let subframe = match frame[prop] {
+                    JsonValue::Null => flags.implicit_frame(),
+                    ref subframe => first(subframe).clone(),
+                };
+                let mut output_value = JsonValue::from(output);
+                for o in objects.members() {
+                    if o.has_key("@list") {
+                        let list_frame = match subframe["@list"] {
+                            JsonValue::Null => flags.implicit_frame(),
+                            ref list_frame => first(list_frame).clone(),
+                        };
+                        let mut list = json::object! { "@list": [] };
+                        for item in o["@list"].members() {
+                            if is_node_reference(item) {
+                                let item_id = [item["@id"].as_str().unwrap().to_string()];
+                                self.frame(
+                                    graph,
+                                    true,
+                                    &item_id,
+                                    &list_frame,
+                                    &mut list,
+                                    Some("@list"),
+                                );
+                            } else {
+                                add_output(&mut list, Some("@list"), item.clone());
+                            }
+                        }
+                        add_output(&mut output_value, Some(prop), list);
+                    } else if is_node_reference(o) {
+                        let o_id = [o["@id"].as_str().unwrap().to_string()];
+                        self.frame(graph, true, &o_id, &subframe, &mut output_value, Some(prop));
+                    } else if value_match(&subframe, o) {
+                        add_output(&mut output_value, Some(prop), o.clone());
+                    }
+                }
+                output = into_object(output_value);
+            }
+
+            // default values of properties in the frame which are not in the node
+            for (prop, prop_frame) in frame.entries() {
+                if prop == "@type" {
+                    if !first(prop_frame).has_key("@default") {
+                        continue;
+                    }
+                } else if prop.starts_with('@') {
+                    continue;
+                }
+                if output.get(prop).is_some() {
+                    continue;
+                }
+                let prop_frame = first(prop_frame);
+                if flags.for_frame(prop_frame).omit_default {
+                    continue;
+                }
+                let preserve = match prop_frame["@default"] {
+                    JsonValue::Null => "@null".into(),
+                    ref default => default.clone(),
+                };
+                let preserve = if preserve.is_array() {
+                    preserve
+                } else {
+                    JsonValue::from(vec![preserve])
+                };
+                let mut preserve_obj = Object::new();
+                preserve_obj.insert("@preserve", preserve);
+                output.insert(prop, vec![JsonValue::from(preserve_obj)].into());
+            }
+
+            // embed the nodes having this node as the value of a reverse property
+            for (rev_prop, subframe) in frame["@reverse"].entries() {
+                let mut output_value = JsonValue::from(output);
+                for (subject, rev_node) in node_map.iter() {
+                    let references_id = rev_node
+                        .get(rev_prop)
+                        .map(|vals| vals.members().any(|v| v["@id"] == id.as_str()))
+                        .unwrap_or(false);
+                    if references_id {
+                        if !output_value.has_key("@reverse") {
+                            output_value["@reverse"] = JsonValue::new_object();
+                        }
+                        let reverse = &mut output_value["@reverse"];
+                        if !reverse.has_key(rev_prop) {
+                            reverse[rev_prop] = JsonValue::new_array();
+                        }
+                        self.frame(
+                            graph,
+                            true,
+                            &[subject.to_string()],
+                            subframe,
+                            reverse,
+                            Some(rev_prop),
+                        );
+                    }
+                }
+                output = into_object(output_value);
+            }
+
+            add_output(parent, property, output.into());
+            self.subject_stack.pop();
+        }
+    }
+
+    /// Check whether `node` matches `frame`
+    /// (see the [Frame Matching Algorithm](https://www.w3.org/TR/json-ld11-framing/#frame-matching-algorithm)).
+    fn filter_subject(&self, graph: &str, node: &Object, frame: &JsonValue, flags: &Flags) -> bool {
+        let mut wildcard = true;
+        let mut matches_some = false;
+        for (key, frame_vals) in frame.entries() {
+            let node_vals = values(node.get(key));
+            let frame_vals = values(Some(frame_vals));
+            let match_this;
+            match key {
+                "@id" => {
+                    match_this = frame_vals.first().map(|v| is_wildcard(v)).unwrap_or(true)
+                        || frame_vals.iter().any(|v| node_vals.first() == Some(v));
+                    if !flags.require_all {
+                        return match_this;
+                    }
+                }
+                "@type" => {
+                    wildcard = false;
+                    if frame_vals.is_empty() {
+                        if !node_vals.is_empty() {
+                            return false;
+                        }
+                        match_this = true;
+                    } else if frame_vals.len() == 1 && is_wildcard(frame_vals[0]) {
+                        match_this = !node_vals.is_empty();
+                    } else {
+                        match_this = frame_vals
+                            .iter()
+                            .any(|t| t.has_key("@default") || node_vals.contains(t));
+                    }
+                    if !flags.require_all {
+                        return match_this;
+                    }
+                }
+                _ if key.starts_with('@') => continue,
+                _ => {
+                    wildcard = false;
+                    let prop_frame = frame_vals.first();
+                    let has_default = prop_frame.map(|f| f.has_key("@default")).unwrap_or(false);
+                    if node_vals.is_empty() && has_default {
+                        continue;
+                    }
+                    match prop_frame {
+                        None => {
+                            // match none
+                            if !node_vals.is_empty() {
+                                return false;
+                            }
+                            match_this = true;
+                        }
+                        Some(prop_frame) if prop_frame.has_key("@list") => {
+                            let list_frame = first(&prop_frame["@list"]);
+                            match_this = node_vals
+                                .first()
+                                .map(|v| {
+                                    v["@list"].members().any(|item| {
+                                        if list_frame.has_key("@value") {
+                                            value_match(list_frame, item)
+                                        } else if list_frame.has_key("@id") {
+                                            self.node_match(graph, list_frame, item, flags)
+                                        } else {
+                                            false
+                                        }
+                                    })
+                                })
+                                .unwrap_or(false);
+                        }
+                        Some(prop_frame) if is_value_pattern(prop_frame) => {
+                            match_this = node_vals.iter().any(|v| value_match(prop_frame, v));
+                        }
+                        Some(prop_frame) if is_node_reference(prop_frame) => {
+                            match_this = node_vals
+                                .iter()
+                                .any(|v| self.node_match(graph, prop_frame, v, flags));
+                        }
+                        Some(prop_frame) if prop_frame.is_object() => {
+                            match_this = !node_vals.is_empty();
+                        }
+                        Some(_) => {
+                            match_this = false;
+                        }
+                    }
+                }
+            }
+            if !match_this && flags.require_all {
+                return false;
+            }
+            matches_some = matches_some || match_this;
+        }
+        wildcard || matches_some
+    }
+
+    /// Check whether the node referenced by `value` matches `frame`
+    fn node_match(&self, graph: &str, frame: &JsonValue, value: &JsonValue, flags: &Flags) -> bool {
+        value["@id"]
+            .as_str()
+            .and_then(|id| self.graph_map[graph].get(id))
+            .map(|node| self.filter_subject(graph, node, frame, flags))
+            .unwrap_or(false)
+    }
+}
+
+/// Check whether value object `value` matches the value pattern `pattern`
+fn value_match(pattern: &JsonValue, value: &JsonValue) -> bool {
+    let v2 = values(Some(&pattern["@value"]));
+    let t2 = values(Some(&pattern["@type"]));
+    let l2 = values(Some(&pattern["@language"]));
+    if v2.is_empty() && t2.is_empty() && l2.is_empty() {
+        return true;
+    }
+    let v1 = &value["@value"];
+    let t1 = &value["@type"];
+    let l1 = &value["@language"];
+    let value_ok = v2.contains(&v1) || v2.first().map(|v| is_wildcard(v)).unwrap_or(false);
+    let type_ok = (t1.is_null() && t2.is_empty())
+        || t2.contains(&t1)
+        || (!t1.is_null() && t2.first().map(|t| is_wildcard(t)).unwrap_or(false));
+    let lang_ok = (l1.is_null() && l2.is_empty())
+        || l2.iter().any(|l| match (l.as_str(), l1.as_str()) {
+            (Some(l), Some(l1)) => l.eq_ignore_ascii_case(l1),
+            _ => false,
+        })
+        || (!l1.is_null() && l2.first().map(|l| is_wildcard(l)).unwrap_or(false));
+    value_ok && type_ok && lang_ok
+}
+
+/// Merge the node maps of all graphs into a single one
+fn merge_node_maps(graph_map: &GraphMap) -> NodeMap {
+    let mut merged = NodeMap::new();
+    for node_map in graph_map.values() {
+        for (id, node) in node_map.iter() {
+            let merged_node = merged.entry(id.clone()).or_insert_with(|| {
+                let mut obj = Object::new();
+                obj.insert("@id", id.as_str().into());
+                obj
+            });
+            for (prop, vals) in node.iter() {
+                if prop == "@id" {
+                    continue;
+                }
+                if merged_node.get(prop).is_none() {
+                    merged_node.insert(prop, JsonValue::new_array());
+                }
+                let merged_vals = merged_node.get_mut(prop).unwrap();
+                for val in vals.members() {
+                    if !merged_vals.contains(val.clone()) {
+                        merged_vals.push(val.clone()).unwrap();
+                    }
+                }
+            }
+        }
+    }
+    merged
+}
+
+/// Add `output` to `parent`, either an array or an object (in which case `property` is used).
+fn add_output(parent: &mut JsonValue, property: Option<&str>, output: JsonValue) {
+    match property {
+        Some(property) if parent.is_object() => {
+            if !parent.has_key(property) {
+                parent[property] = JsonValue::new_array();
+            }
+            parent[property].push(output).unwrap();
+        }
+        _ => parent.push(output).unwrap(),
+    }
+}
+
+/// Count the number of node objects having each blank node identifier
+fn count_bnode_ids(val: &JsonValue, count: &mut HashMap<String, usize>) {
+    match val {
+        JsonValue::Array(vals) => vals.iter().for_each(|v| count_bnode_ids(v, count)),
+        JsonValue::Object(obj) => {
+            if obj.get("@value").is_some() {
+                return;
+            }
+            for (key, v) in obj.iter() {
+                match key {
+                    "@id" => {
+                        if let Some(id) = v.as_str().filter(|id| id.starts_with("_:")) {
+                            *count.entry(id.to_string()).or_default() += 1;
+                        }
+                    }
+                    "@type" => {
+                        for t in v.members().filter_map(JsonValue::as_str) {
+                            if t.starts_with("_:") {
+                                *count.entry(t.to_string()).or_default() += 1;
+                            }
+                        }
+                    }
+                    _ => count_bnode_ids(v, count),
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Replace `@preserve` entries with their value,
+/// and remove the blank node identifiers that are used only once
+/// (unless `bnode_count` is empty).
+fn cleanup(val: JsonValue, bnode_count: &HashMap<String, usize>) -> JsonValue {
+    match val {
+        JsonValue::Array(vals) => vals
+            .into_iter()
+            .map(|v| cleanup(v, bnode_count))
+            .filter(|v| *v != "@null")
+            .collect::<Vec<_>>()
+            .into(),
+        JsonValue::Object(mut obj) => {
+            if let Some(preserve) = obj.remove("@preserve") {
+                return cleanup(first(&preserve).clone(), bnode_count);
+            }
+            if obj.get("@value").is_some() {
+                return obj.into();
+            }
+            let prune = obj
+                .get("@id")
+                .and_then(JsonValue::as_str)
+                .map(|id| bnode_count.get(id) == Some(&1))
+                .unwrap_or(false);
+            if prune {
+                obj.remove("@id");
+            }
+            let mut cleaned = Object::with_capacity(obj.len());
+            for (key, v) in obj.iter() {
+                cleaned.insert(key, cleanup(v.clone(), bnode_count));
+            }
+            cleaned.into()
+        }
+        val => val,
+    }
+}
+
+/// The value of a boolean flag in a frame, if any
+fn flag_value(val: &JsonValue) -> Option<bool> {
+    match first(val) {
+        JsonValue::Boolean(b) => Some(*b),
+        JsonValue::Object(obj) => obj.get("@value").and_then(JsonValue::as_bool),
+        _ => None,
+    }
+}
+
+/// If `val` is an array, its first element, else `val` itself
+fn first(val: &JsonValue) -> &JsonValue {
+    match val {
+        JsonValue::Array(vals) if !vals.is_empty() => &vals[0],
+        val => val,
+    }
+}
+
+/// The values of an entry, as a vector
+fn values(val: Option<&JsonValue>) -> Vec<&JsonValue> {
+    match val {
+        None | Some(JsonValue::Null) => vec![],
+        Some(JsonValue::Array(vals)) => vals.iter().collect(),
+        Some(val) => vec![val],
+    }
+}
+
+/// Wildcards are empty objects
+fn is_wildcard(val: &JsonValue) -> bool {
+    val.is_object() && val.is_empty()
+}
+
+fn is_node_reference(val: &JsonValue) -> bool {
+    val.is_object() && val.len() == 1 && val.has_key("@id")
+}
+
+fn is_value_pattern(val: &JsonValue) -> bool {
+    val.has_key("@value") || (val.has_key("@language") && !val.has_key("@id"))
+}
+
+fn into_object(val: JsonValue) -> Object {
+    match val {
+        JsonValue::Object(obj) => obj,
+        _ => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,308 @@
+use crate::config::*;
+use crate::error::*;
+use crate::frame::*;
+use crate::serializer::Jsonifier;
+use crate::test_util::jsonld_cmp;
+use json::JsonValue;
+use sophia::triple::stream::SinkError;
+use sophia_api::serializer::QuadSerializer;
+use sophia_term::BoxTerm;
+use std::collections::HashSet;
+
+const LIBRARY: &str = r#"
+<http://example.org/library> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://example.org/vocab#Library> .
+<http://example.org/library> <http://example.org/vocab#contains> <http://example.org/library/the-republic> .
+<http://example.org/library/the-republic> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://example.org/vocab#Book> .
+<http://example.org/library/the-republic> <http://purl.org/dc/elements/1.1/creator> "Plato" .
+<http://example.org/library/the-republic> <http://purl.org/dc/elements/1.1/title> "The Republic" .
+<http://example.org/library/the-republic> <http://example.org/vocab#contains> <http://example.org/library/the-republic#introduction> .
+<http://example.org/library/the-republic#introduction> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://example.org/vocab#Chapter> .
+<http://example.org/library/the-republic#introduction> <http://purl.org/dc/elements/1.1/description> "An introductory chapter on The Republic." .
+<http://example.org/library/the-republic#introduction> <http://purl.org/dc/elements/1.1/title> "The Introduction" .
+"#;
+
+fn frame_nq(nq: &str, frame: Frame) -> Result<JsonValue, JsonLdError> {
+    let quads = sophia::parser::nq::parse_str(nq);
+    let mut dataset: HashSet<([BoxTerm; 3], Option<BoxTerm>)> = HashSet::new();
+    sophia::dataset::MutableDataset::insert_all(&mut dataset, quads).unwrap();
+    let config = JsonLdConfig::new().frame(frame);
+    let mut ser = Jsonifier::new_jsonifier_with_config(config);
+    match ser.serialize_dataset(&dataset) {
+        Ok(ser) => Ok(ser.as_json().clone()),
+        Err(SinkError(err)) => Err(err),
+        Err(_) => unreachable!(),
+    }
+}
+
+fn assert_framed(got: JsonValue, exp: &str) {
+    let exp = json::parse(exp).unwrap();
+    assert!(
+        jsonld_cmp(&got, &exp, ""),
+        "got: {}\nexpected: {}",
+        json::stringify_pretty(got, 2),
+        json::stringify_pretty(exp, 2),
+    );
+}
+
+#[test]
+fn nested_frames() {
+    let frame = Frame::parse(
+        r#"{
+            "@type": ["http://example.org/vocab#Library"],
+            "http://example.org/vocab#contains": {
+                "@type": ["http://example.org/vocab#Book"],
+                "http://example.org/vocab#contains": {
+                    "@type": ["http://example.org/vocab#Chapter"]
+                }
+            }
+        }"#,
+    )
+    .unwrap();
+    let got = frame_nq(LIBRARY, frame).unwrap();
+    assert_framed(
+        got,
+        r#"[{
+            "@id": "http://example.org/library",
+            "@type": ["http://example.org/vocab#Library"],
+            "http://example.org/vocab#contains": [{
+                "@id": "http://example.org/library/the-republic",
+                "@type": ["http://example.org/vocab#Book"],
+                "http://purl.org/dc/elements/1.1/creator": [{"@value": "Plato"}],
+                "http://purl.org/dc/elements/1.1/title": [{"@value": "The Republic"}],
+                "http://example.org/vocab#contains": [{
+                    "@id": "http://example.org/library/the-republic#introduction",
+                    "@type": ["http://example.org/vocab#Chapter"],
+                    "http://purl.org/dc/elements/1.1/description": [{"@value": "An introductory chapter on The Republic."}],
+                    "http://purl.org/dc/elements/1.1/title": [{"@value": "The Introduction"}]
+                }]
+            }]
+        }]"#,
+    );
+}
+
+#[test]
+fn explicit_and_default() {
+    let frame = Frame::parse(
+        r#"{
+            "@type": ["http://example.org/vocab#Book"],
+            "@explicit": true,
+            "http://purl.org/dc/elements/1.1/title": {},
+            "http://purl.org/dc/elements/1.1/date": {"@default": {"@value": "unknown"}},
+            "http://purl.org/dc/elements/1.1/subject": {}
+        }"#,
+    )
+    .unwrap();
+    let got = frame_nq(LIBRARY, frame).unwrap();
+    assert_framed(
+        got,
+        r#"[{
+            "@id": "http://example.org/library/the-republic",
+            "@type": ["http://example.org/vocab#Book"],
+            "http://purl.org/dc/elements/1.1/title": [{"@value": "The Republic"}],
+            "http://purl.org/dc/elements/1.1/date": [{"@value": "unknown"}],
+            "http://purl.org/dc/elements/1.1/subject": []
+        }]"#,
+    );
+}
+
+#[test]
+fn omit_default() {
+    let frame = Frame::parse(
+        r#"{
+            "@type": ["http://example.org/vocab#Book"],
+            "@explicit": true,
+            "http://purl.org/dc/elements/1.1/title": {},
+            "http://purl.org/dc/elements/1.1/subject": {}
+        }"#,
+    )
+    .unwrap()
+    .omit_default(true);
+    let got = frame_nq(LIBRARY, frame).unwrap();
+    assert_framed(
+        got,
+        r#"[{
+            "@id": "http://example.org/library/the-republic",
+            "@type": ["http://example.org/vocab#Book"],
+            "http://purl.org/dc/elements/1.1/title": [{"@value": "The Republic"}]
+        }]"#,
+    );
+}
+
+#[test]
+fn embed_never() {
+    let frame = Frame::parse(
+        r#"{
+            "@type": ["http://example.org/vocab#Library"],
+            "@embed": "@never"
+        }"#,
+    )
+    .unwrap();
+    let got = frame_nq(LIBRARY, frame).unwrap();
+    assert_framed(
+        got,
+        r#"[{
+            "@id": "http://example.org/library",
+            "@type": ["http://example.org/vocab#Library"],
+            "http://example.org/vocab#contains": [{
+                "@id": "http://example.org/library/the-republic"
+            }]
+        }]"#,
+    );
+}
+
+#[test]
+fn embed_once_and_always() {
+    let nq = r#"
+<http://example.org/a> <http://example.org/p> <http://example.org/c> .
+<http://example.org/a> <http://example.org/q> <http://example.org/c> .
+<http://example.org/c> <http://example.org/name> "c" .
+"#;
+    let frame_txt = r#"{"@id": "http://example.org/a"}"#;
+
+    let got = frame_nq(nq, Frame::parse(frame_txt).unwrap()).unwrap();
+    assert_framed(
+        got,
+        r#"[{
+            "@id": "http://example.org/a",
+            "http://example.org/p": [{
+                "@id": "http://example.org/c",
+                "http://example.org/name": [{"@value": "c"}]
+            }],
+            "http://example.org/q": [{"@id": "http://example.org/c"}]
+        }]"#,
+    );
+
+    let frame = Frame::parse(frame_txt).unwrap().embed(Embed::Always);
+    let got = frame_nq(nq, frame).unwrap();
+    assert_framed(
+        got,
+        r#"[{
+            "@id": "http://example.org/a",
+            "http://example.org/p": [{
+                "@id": "http://example.org/c",
+                "http://example.org/name": [{"@value": "c"}]
+            }],
+            "http://example.org/q": [{
+                "@id": "http://example.org/c",
+                "http://example.org/name": [{"@value": "c"}]
+            }]
+        }]"#,
+    );
+}
+
+#[test]
+fn circular_references() {
+    let nq = r#"
+<http://example.org/a> <http://example.org/knows> <http://example.org/b> .
+<http://example.org/b> <http://example.org/knows> <http://example.org/a> .
+"#;
+    let frame = Frame::parse(r#"{"@id": "http://example.org/a"}"#)
+        .unwrap()
+        .embed(Embed::Always);
+    let got = frame_nq(nq, frame).unwrap();
+    assert_framed(
+        got,
+        r#"[{
+            "@id": "http://example.org/a",
+            "http://example.org/knows": [{
+                "@id": "http://example.org/b",
+                "http://example.org/knows": [{"@id": "http://example.org/a"}]
+            }]
+        }]"#,
+    );
+}
+
+#[test]
+fn require_all() {
+    let nq = r#"
+<http://example.org/a> <http://example.org/p> "1" .
+<http://example.org/a> <http://example.org/q> "2" .
+<http://example.org/b> <http://example.org/p> "1" .
+"#;
+    let frame_txt = r#"{
+        "http://example.org/p": {},
+        "http://example.org/q": {}
+    }"#;
+
+    let got = frame_nq(nq, Frame::parse(frame_txt).unwrap().omit_default(true)).unwrap();
+    assert_eq!(got.len(), 2);
+
+    let frame = Frame::parse(frame_txt)
+        .unwrap()
+        .omit_default(true)
+        .require_all(true);
+    let got = frame_nq(nq, frame).unwrap();
+    assert_framed(
+        got,
+        r#"[{
+            "@id": "http://example.org/a",
+            "http://example.org/p": [{"@value": "1"}],
+            "http://example.org/q": [{"@value": "2"}]
+        }]"#,
+    );
+}
+
+#[test]
+fn blank_node_ids_are_pruned() {
+    let nq = r#"
+<http://example.org/a> <http://example.org/p> _:b .
+_:b <http://example.org/name> "b" .
+"#;
+    let frame = Frame::parse(r#"{"@id": "http://example.org/a"}"#).unwrap();
+    let got = frame_nq(nq, frame).unwrap();
+    assert_framed(
+        got,
+        r#"[{
+            "@id": "http://example.org/a",
+            "http://example.org/p": [{
+                "http://example.org/name": [{"@value": "b"}]
+            }]
+        }]"#,
+    );
+}
+
+#[test]
+fn named_graphs() {
+    let nq = r#"
+<http://example.org/g> <http://example.org/label> "graph" .
+<http://example.org/a> <http://example.org/name> "a" <http://example.org/g> .
+<http://example.org/b> <http://example.org/name> "b" <http://example.org/g> .
+"#;
+    let frame = Frame::parse(
+        r#"{
+            "@graph": {
+                "@id": "http://example.org/g",
+                "@graph": {"@id": "http://example.org/b"}
+            }
+        }"#,
+    )
+    .unwrap();
+    let got = frame_nq(nq, frame).unwrap();
+    assert_framed(
+        got,
+        r#"[{
+            "@id": "http://example.org/g",
+            "http://example.org/label": [{"@value": "graph"}],
+            "@graph": [{
+                "@id": "http://example.org/b",
+                "http://example.org/name": [{"@value": "b"}]
+            }]
+        }]"#,
+    );
+}
+
+#[test]
+fn invalid_frames() {
+    assert!(matches!(
+        Frame::parse(r#"{"@embed": "@link"}"#),
+        Err(JsonLdError::InvalidEmbedValue(_))
+    ));
+    assert!(matches!(
+        Frame::parse(r#"{"@id": 42}"#),
+        Err(JsonLdError::InvalidFrame(_))
+    ));
+    assert!(matches!(
+        Frame::parse(r#""@id""#),
+        Err(JsonLdError::InvalidFrame(_))
+    ));
+}
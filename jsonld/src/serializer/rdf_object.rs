@@ -1,27 +1,33 @@
 //! A private enum type used internally by JsonLdSerializer
 use sophia_term::literal::Literal;
-use sophia_term::TermData;
+use sophia_term::{RefTerm, Term, TermData};
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum RdfObject {
     Literal(Literal<Box<str>>),
+    /// A node, with its index in the engine
     Node(usize, String),
+    /// A node that is not indexed (in streaming mode)
+    NodeId(String),
 }
 
 impl RdfObject {
     pub fn is_literal(&self) -> bool {
         matches!(self, RdfObject::Literal(_))
     }
-    pub fn is_node(&self) -> bool {
-        matches!(self, RdfObject::Node(..))
-    }
-    pub fn eq_node(&self, other_id: &str) -> bool {
-        matches!(self, RdfObject::Node(_, id) if id==other_id)
+    pub fn as_term(&self) -> RefTerm<'_> {
+        match self {
+            RdfObject::Literal(lit) => Term::Literal(lit.as_ref_str()),
+            RdfObject::Node(_, id) | RdfObject::NodeId(id) => match id.strip_prefix("_:") {
+                Some(bnid) => RefTerm::new_bnode_unchecked(bnid),
+                None => RefTerm::new_iri_unchecked(id.as_str()),
+            },
+        }
     }
     pub fn as_str(&self) -> &str {
         match self {
             RdfObject::Literal(lit) => lit.txt().as_ref(),
-            RdfObject::Node(_, id) => &id,
+            RdfObject::Node(_, id) | RdfObject::NodeId(id) => id,
         }
     }
 }
@@ -0,0 +1,189 @@
+//! Streaming mode of the JSON-LD serializer
+//! (see [`JsonLdConfig::streaming`](../../config/struct.JsonLdConfig.html#structfield.streaming)).
+use super::engine::Engine;
+use super::rdf_object::*;
+use crate::config::{JsonLdSpecVersion::*, *};
+use crate::error::*;
+use crate::util_traits::*;
+use sophia::quad::{stream::*, Quad};
+use sophia::triple::stream::{SinkError, StreamResult};
+use sophia_api::term::{TTerm, TermKind, TryCopyTerm};
+use sophia_term::literal::Literal;
+use std::collections::HashMap;
+use std::io::Write;
+
+/// Writes each node object as soon as it is complete.
+///
+/// A node object is complete as soon as a quad with a different graph name or subject is received.
+/// Quads are therefore expected to be grouped by graph name and subject;
+/// if they are not, the output is still correct,
+/// but the same node (or graph) may be described by several objects.
+///
+/// Unlike [`Engine`](../engine/struct.Engine.html),
+/// this can not fold lists or compound literals,
+/// as this would require to know all the triples about a blank node
+/// before writing the node referencing it.
+/// List nodes are therefore written as plain node objects,
+/// with their `rdf:first` and `rdf:rest` properties
+/// (which is valid JSON-LD, describing the same triples);
+/// compound literals are rejected beforehand by the serializer.
+pub struct StreamingEngine<W> {
+    // used to convert nodes into JSON objects
+    engine: Engine,
+    target: W,
+    spaces: usize,
+    // whether an item has been written in the top-level array
+    started: bool,
+    // the graph currently being written (" " for the default graph)
+    g_id: String,
+    // whether an item has been written in the current @graph array
+    g_started: bool,
+    // the subject currently being described
+    s_id: String,
+    // attributes of the subject currently being described
+    node: HashMap<String, Vec<RdfObject>>,
+}
+
+impl<W: Write> StreamingEngine<W> {
+    pub fn new_with_config(target: W, config: JsonLdConfig) -> Self {
+        StreamingEngine {
+            spaces: config.spaces as usize,
+            engine: Engine::new_with_config(config),
+            target,
+            started: false,
+            g_id: " ".to_string(),
+            g_started: false,
+            s_id: String::new(),
+            node: HashMap::new(),
+        }
+    }
+
+    pub fn process_quads<QS>(&mut self, mut source: QS) -> StreamResult<(), QS::Error, JsonLdError>
+    where
+        QS: QuadSource,
+    {
+        let spec_version = self.engine.config().spec_version;
+        if spec_version > JsonLd11 {
+            return Err(SinkError(JsonLdError::UnsupportedVersion(spec_version)));
+        }
+        self.target
+            .write_all(b"[")
+            .map_err(|e| SinkError(e.into()))?;
+        source.try_for_each_quad(|q| {
+            if !q.is_jsonld() {
+                return Ok(());
+            }
+            let g_id = q.g().map(|g| g.as_id()).unwrap_or_else(|| " ".to_string());
+            let s_id = q.s().as_id();
+            if g_id != self.g_id || s_id != self.s_id {
+                self.write_node()?;
+                if g_id != self.g_id {
+                    self.close_graph()?;
+                    self.open_graph(&g_id)?;
+                    self.g_id = g_id;
+                }
+                self.s_id = s_id;
+            }
+            let obj = make_rdf_object(q.o());
+            self.node.push_if_new(q.p().as_id(), obj);
+            Ok(())
+        })?;
+        self.finish().map_err(SinkError)
+    }
+
+    fn finish(&mut self) -> Result<(), JsonLdError> {
+        self.write_node()?;
+        self.close_graph()?;
+        if self.spaces > 0 && self.started {
+            self.target.write_all(b"\n")?;
+        }
+        self.target.write_all(b"]")?;
+        Ok(())
+    }
+
+    /// Write the node currently being described, if any.
+    fn write_node(&mut self) -> Result<(), JsonLdError> {
+        if self.node.is_empty() {
+            return Ok(());
+        }
+        let node = std::mem::take(&mut self.node);
+        let obj = self.engine.make_node_object(&self.s_id, &node)?;
+        let txt = match self.spaces {
+            0 => json::stringify(obj),
+            x => json::stringify_pretty(obj, x as u16),
+        };
+        if self.g_id == " " {
+            let first = !std::mem::replace(&mut self.started, true);
+            self.write_item(&txt, 1, first)
+        } else {
+            let first = !std::mem::replace(&mut self.g_started, true);
+            self.write_item(&txt, 3, first)
+        }
+    }
+
+    /// Start an object containing the nodes of the named graph `g_id`.
+    fn open_graph(&mut self, g_id: &str) -> Result<(), JsonLdError> {
+        if g_id == " " {
+            return Ok(());
+        }
+        let first = !std::mem::replace(&mut self.started, true);
+        self.g_started = false;
+        let txt = if self.spaces == 0 {
+            format!(r#"{{"@id":{},"@graph":["#, json::stringify(g_id))
+        } else {
+            let indent = " ".repeat(self.spaces);
+            format!(
+                "{{\n{}\"@id\": {},\n{}\"@graph\": [",
+                indent,
+                json::stringify(g_id),
+                indent
+            )
+        };
+        self.write_item(&txt, 1, first)
+    }
+
+    /// End the object opened by `open_graph`, if any.
+    fn close_graph(&mut self) -> Result<(), JsonLdError> {
+        if self.g_id == " " {
+            return Ok(());
+        }
+        if self.spaces > 0 {
+            let outer = " ".repeat(self.spaces);
+            let inner = " ".repeat(self.spaces * 2);
+            write!(self.target, "\n{}]\n{}}}", inner, outer)?;
+        } else {
+            self.target.write_all(b"]}")?;
+        }
+        Ok(())
+    }
+
+    /// Write an item of an array, indented at the given depth.
+    fn write_item(&mut self, txt: &str, depth: usize, first: bool) -> Result<(), JsonLdError> {
+        if !first {
+            self.target.write_all(b",")?;
+        }
+        if self.spaces > 0 {
+            // JSON strings can not contain raw newlines, so this only affects the layout
+            let newline = format!("\n{}", " ".repeat(self.spaces * depth));
+            self.target.write_all(newline.as_bytes())?;
+            self.target
+                .write_all(txt.replace('\n', &newline).as_bytes())?;
+        } else {
+            self.target.write_all(txt.as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+fn make_rdf_object<T>(o: &T) -> RdfObject
+where
+    T: TTerm + ?Sized,
+{
+    match o.kind() {
+        TermKind::Literal => RdfObject::Literal(Literal::try_copy(o).unwrap()),
+        _ => RdfObject::NodeId(o.as_id()),
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,184 @@
+use crate::frame::Frame;
+use crate::serializer::*;
+use crate::test_util::jsonld_cmp;
+use sophia::parser::nq;
+use sophia::triple::stream::SinkError;
+use sophia_api::serializer::{QuadSerializer, Stringifier};
+
+const SORTED: &str = r#"
+<http://example.org/a> <http://example.org/name> "a" .
+<http://example.org/a> <http://example.org/knows> <http://example.org/b> .
+<http://example.org/a> <http://example.org/knows> _:c .
+<http://example.org/a> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://example.org/Person> .
+<http://example.org/b> <http://example.org/age> "42"^^<http://www.w3.org/2001/XMLSchema#integer> .
+_:c <http://example.org/name> "c"@en .
+<http://example.org/a> <http://example.org/name> "a in g1" <http://example.org/g1> .
+<http://example.org/b> <http://example.org/name> "b in g1" <http://example.org/g1> .
+<http://example.org/a> <http://example.org/name> "a in g2" <http://example.org/g2> .
+"#;
+
+fn stream_nq(nq: &str, config: JsonLdConfig) -> Result<String, JsonLdError> {
+    let mut ser = JsonLdStringifier::new_stringifier_with_config(config.streaming(true));
+    match ser.serialize_quads(nq::parse_str(nq)) {
+        Ok(_) => Ok(String::from_utf8(ser.as_utf8().to_vec()).unwrap()),
+        Err(SinkError(err)) => Err(err),
+        Err(_) => unreachable!(),
+    }
+}
+
+fn assert_same(got: &str, exp: &str) {
+    let got = json::parse(got).unwrap();
+    let exp = json::parse(exp).unwrap();
+    assert!(
+        jsonld_cmp(&got, &exp, ""),
+        "got: {}\nexpected: {}",
+        json::stringify_pretty(got, 2),
+        json::stringify_pretty(exp, 2),
+    );
+}
+
+#[test]
+fn default_graph_as_buffered() {
+    let nq = SORTED.lines().take(7).collect::<Vec<_>>().join("\n");
+    let config = JsonLdConfig::new().use_native_types(true);
+    let got = stream_nq(&nq, config.clone()).unwrap();
+
+    let mut ser = Jsonifier::new_jsonifier_with_config(config);
+    ser.serialize_quads(nq::parse_str(&nq)).unwrap();
+    assert_same(&got, &ser.as_json().dump());
+}
+
+#[test]
+fn named_graphs() {
+    let got = stream_nq(SORTED, JsonLdConfig::new()).unwrap();
+    let got = json::parse(&got).unwrap();
+    assert_eq!(got.len(), 5);
+    let exp = json::parse(
+        r#"{
+        "@id": "http://example.org/g1",
+        "@graph": [{
+            "@id": "http://example.org/a",
+            "http://example.org/name": [{"@value": "a in g1"}]
+        }, {
+            "@id": "http://example.org/b",
+            "http://example.org/name": [{"@value": "b in g1"}]
+        }]
+    }"#,
+    )
+    .unwrap();
+    assert!(jsonld_cmp(&got[3], &exp, ""));
+    assert_eq!(got[4]["@id"], "http://example.org/g2");
+    assert_eq!(got[4]["@graph"].len(), 1);
+}
+
+#[test]
+fn pretty_printing() {
+    let nq = r#"
+<http://example.org/a> <http://example.org/p> "x" .
+<http://example.org/a> <http://example.org/p> "y" <http://example.org/g> .
+"#;
+    let got = stream_nq(nq, JsonLdConfig::new().spaces(2)).unwrap();
+    let exp = r#"[
+  {
+    "@id": "http://example.org/a",
+    "http://example.org/p": [
+      {
+        "@value": "x"
+      }
+    ]
+  },
+  {
+    "@id": "http://example.org/g",
+    "@graph": [
+      {
+        "@id": "http://example.org/a",
+        "http://example.org/p": [
+          {
+            "@value": "y"
+          }
+        ]
+      }
+    ]
+  }
+]"#;
+    assert_eq!(got, exp);
+
+    let compact = stream_nq(nq, JsonLdConfig::new()).unwrap();
+    assert_eq!(json::parse(&compact).unwrap(), json::parse(exp).unwrap());
+    assert!(!compact.contains('\n'));
+}
+
+#[test]
+fn empty_dataset() {
+    assert_eq!(stream_nq("", JsonLdConfig::new()).unwrap(), "[]");
+    assert_eq!(stream_nq("", JsonLdConfig::new().spaces(2)).unwrap(), "[]");
+}
+
+#[test]
+fn unsorted_quads() {
+    let nq = r#"
+<http://example.org/a> <http://example.org/p> "1" .
+<http://example.org/b> <http://example.org/p> "2" .
+<http://example.org/a> <http://example.org/p> "3" .
+"#;
+    let got = json::parse(&stream_nq(nq, JsonLdConfig::new()).unwrap()).unwrap();
+    let ids: Vec<_> = got.members().map(|n| n["@id"].as_str().unwrap()).collect();
+    assert_eq!(
+        ids,
+        vec![
+            "http://example.org/a",
+            "http://example.org/b",
+            "http://example.org/a"
+        ]
+    );
+}
+
+#[test]
+fn lists_as_node_objects() {
+    let nq = r#"
+<http://example.org/a> <http://example.org/p> _:l .
+_:l <http://www.w3.org/1999/02/22-rdf-syntax-ns#first> "x" .
+_:l <http://www.w3.org/1999/02/22-rdf-syntax-ns#rest> <http://www.w3.org/1999/02/22-rdf-syntax-ns#nil> .
+"#;
+    let got = stream_nq(nq, JsonLdConfig::new()).unwrap();
+    assert_same(
+        &got,
+        r#"[{
+            "@id": "http://example.org/a",
+            "http://example.org/p": [{"@id": "_:l"}]
+        }, {
+            "@id": "_:l",
+            "http://www.w3.org/1999/02/22-rdf-syntax-ns#first": [{"@value": "x"}],
+            "http://www.w3.org/1999/02/22-rdf-syntax-ns#rest": [{"@list": []}]
+        }]"#,
+    );
+}
+
+#[test]
+fn empty_list_as_buffered() {
+    let nq = r#"
+<http://example.org/a> <http://example.org/p> <http://www.w3.org/1999/02/22-rdf-syntax-ns#nil> .
+"#;
+    let got = stream_nq(nq, JsonLdConfig::new()).unwrap();
+    let mut ser = Jsonifier::new_jsonifier();
+    ser.serialize_quads(nq::parse_str(nq)).unwrap();
+    assert_same(&got, &ser.as_json().dump());
+}
+
+#[test]
+fn compound_literals_are_rejected() {
+    let config = JsonLdConfig::new().rdf_direction(Some(RdfDirectionMode::CompoundLiteral));
+    assert!(matches!(
+        stream_nq(SORTED, config),
+        Err(JsonLdError::IncompatibleOptions(_))
+    ));
+}
+
+#[test]
+fn framing_is_rejected() {
+    let frame = Frame::parse("{}").unwrap();
+    assert!(matches!(
+        stream_nq(SORTED, JsonLdConfig::new().frame(frame)),
+        Err(JsonLdError::IncompatibleOptions(_))
+    ));
+}
@@ -1,13 +1,169 @@
+use crate::config::*;
+use crate::serializer::Jsonifier;
 use crate::test_util::*;
-use std::path::Path;
+use sophia_api::parser::QuadParser;
+use sophia_api::serializer::QuadSerializer;
+use sophia_term::BoxTerm;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 
-#[test]
-fn w3c_test_suite() {
-    let mpath = Path::new("..")
+fn from_rdf_manifest() -> PathBuf {
+    Path::new("..")
         .join("json-ld-api")
         .join("tests")
-        .join("fromRdf-manifest.jsonld");
+        .join("fromRdf-manifest.jsonld")
+}
+
+#[test]
+fn w3c_test_suite() {
+    let mpath = from_rdf_manifest();
     let manifest = Manifest::new(&mpath);
     let (failed, skipped, passed) = manifest.perform_all_tests(true);
     assert_eq!(0, failed, "{}/{}", failed, failed + skipped + passed);
 }
+
+#[test]
+fn w3c_test_suite_di() {
+    let mpath = from_rdf_manifest();
+    let manifest = Manifest::new(&mpath);
+    let (failed, skipped, passed) = manifest.perform_tests(|t| t.id().starts_with("#tdi"), true);
+    assert_eq!(0, failed, "{}/{}", failed, failed + skipped + passed);
+    assert!(passed > 0);
+}
+
+const I18N_NO_LANG: &str = r#"
+<http://example.com/a> <http://example.org/label> "no language"^^<https://www.w3.org/ns/i18n#_rtl> .
+"#;
+
+const I18N_LANG: &str = r#"
+<http://example.com/a> <http://example.org/label> "en-US"^^<https://www.w3.org/ns/i18n#en-US_rtl> .
+"#;
+
+const COMPOUND_NO_LANG: &str = r#"
+<http://example.com/a> <http://example.org/label> _:cl1 .
+_:cl1 <http://www.w3.org/1999/02/22-rdf-syntax-ns#value> "no language" .
+_:cl1 <http://www.w3.org/1999/02/22-rdf-syntax-ns#direction> "rtl" .
+"#;
+
+const COMPOUND_LANG: &str = r#"
+<http://example.com/a> <http://example.org/label> _:cl1 .
+_:cl1 <http://www.w3.org/1999/02/22-rdf-syntax-ns#value> "en-US" .
+_:cl1 <http://www.w3.org/1999/02/22-rdf-syntax-ns#language> "en-US" .
+_:cl1 <http://www.w3.org/1999/02/22-rdf-syntax-ns#direction> "rtl" .
+"#;
+
+const I18N_NO_LANG_AS_TYPED: &str = r#"[{
+    "@id": "http://example.com/a",
+    "http://example.org/label": [{"@value": "no language", "@type": "https://www.w3.org/ns/i18n#_rtl"}]
+}]"#;
+
+const I18N_LANG_AS_TYPED: &str = r#"[{
+    "@id": "http://example.com/a",
+    "http://example.org/label": [{"@value": "en-US", "@type": "https://www.w3.org/ns/i18n#en-US_rtl"}]
+}]"#;
+
+const COMPOUND_NO_LANG_AS_NODE: &str = r#"[{
+    "@id": "http://example.com/a",
+    "http://example.org/label": [{"@id": "_:cl1"}]
+}, {
+    "@id": "_:cl1",
+    "http://www.w3.org/1999/02/22-rdf-syntax-ns#value": [{"@value": "no language"}],
+    "http://www.w3.org/1999/02/22-rdf-syntax-ns#direction": [{"@value": "rtl"}]
+}]"#;
+
+const COMPOUND_LANG_AS_NODE: &str = r#"[{
+    "@id": "http://example.com/a",
+    "http://example.org/label": [{"@id": "_:cl1"}]
+}, {
+    "@id": "_:cl1",
+    "http://www.w3.org/1999/02/22-rdf-syntax-ns#value": [{"@value": "en-US"}],
+    "http://www.w3.org/1999/02/22-rdf-syntax-ns#language": [{"@value": "en-US"}],
+    "http://www.w3.org/1999/02/22-rdf-syntax-ns#direction": [{"@value": "rtl"}]
+}]"#;
+
+const NO_LANG_WITH_DIRECTION: &str = r#"[{
+    "@id": "http://example.com/a",
+    "http://example.org/label": [{"@value": "no language", "@direction": "rtl"}]
+}]"#;
+
+const LANG_WITH_DIRECTION: &str = r#"[{
+    "@id": "http://example.com/a",
+    "http://example.org/label": [{"@value": "en-US", "@language": "en-US", "@direction": "rtl"}]
+}]"#;
+
+/// Reproduces the `di` tests of the fromRdf manifest of the W3C test suite,
+/// so that they run even when the json-ld-api submodule is not checked out.
+#[test]
+fn rdf_direction() {
+    use RdfDirectionMode::*;
+    #[rustfmt::skip]
+    let cases: &[(&str, Option<RdfDirectionMode>, &str, &str)] = &[
+        ("di01", None, I18N_NO_LANG, I18N_NO_LANG_AS_TYPED),
+        ("di02", None, I18N_LANG, I18N_LANG_AS_TYPED),
+        ("di03", None, COMPOUND_NO_LANG, COMPOUND_NO_LANG_AS_NODE),
+        ("di04", None, COMPOUND_LANG, COMPOUND_LANG_AS_NODE),
+        ("di05", Some(I18nDatatype), I18N_NO_LANG, NO_LANG_WITH_DIRECTION),
+        ("di06", Some(I18nDatatype), I18N_LANG, LANG_WITH_DIRECTION),
+        ("di07", Some(I18nDatatype), COMPOUND_NO_LANG, COMPOUND_NO_LANG_AS_NODE),
+        ("di08", Some(I18nDatatype), COMPOUND_LANG, COMPOUND_LANG_AS_NODE),
+        ("di09", Some(CompoundLiteral), I18N_NO_LANG, I18N_NO_LANG_AS_TYPED),
+        ("di10", Some(CompoundLiteral), I18N_LANG, I18N_LANG_AS_TYPED),
+        ("di11", Some(CompoundLiteral), COMPOUND_NO_LANG, NO_LANG_WITH_DIRECTION),
+        ("di12", Some(CompoundLiteral), COMPOUND_LANG, LANG_WITH_DIRECTION),
+    ];
+    for (id, mode, nq, exp) in cases {
+        let config = JsonLdConfig::new().rdf_direction(*mode);
+        let got = jsonify_nq(nq, config);
+        let exp = json::parse(exp).unwrap();
+        assert!(
+            jsonld_cmp(&got, &exp, ""),
+            "{}: got {}",
+            id,
+            json::stringify_pretty(got, 2)
+        );
+    }
+}
+
+#[test]
+fn compound_literal_referenced_twice() {
+    let nq = r#"
+<http://example.com/a> <http://example.org/label> _:cl1 .
+<http://example.com/b> <http://example.org/label> _:cl1 .
+_:cl1 <http://www.w3.org/1999/02/22-rdf-syntax-ns#value> "no language" .
+_:cl1 <http://www.w3.org/1999/02/22-rdf-syntax-ns#direction> "rtl" .
+"#;
+    let config = JsonLdConfig::new().rdf_direction(Some(RdfDirectionMode::CompoundLiteral));
+    let got = jsonify_nq(nq, config);
+    assert_eq!(got.len(), 3);
+    assert!(got.members().any(|node| node["@id"] == "_:cl1"));
+}
+
+fn jsonify_nq(nq: &str, config: JsonLdConfig) -> json::JsonValue {
+    let quads = sophia::parser::nq::parse_str(nq);
+    let mut dataset: HashSet<([BoxTerm; 3], Option<BoxTerm>)> = HashSet::new();
+    sophia::dataset::MutableDataset::insert_all(&mut dataset, quads).unwrap();
+    let mut ser = Jsonifier::new_jsonifier_with_config(config);
+    ser.serialize_dataset(&dataset).unwrap().as_json().clone()
+}
+
+#[test]
+fn directional_literal() {
+    let nq = r#"<http://example.com/a> <http://example.org/label> "שלום"@he--rtl ."#;
+    let quads = sophia::parser::ntq::NQuadsParser::new().parse_str(nq);
+    let mut dataset: HashSet<([BoxTerm; 3], Option<BoxTerm>)> = HashSet::new();
+    sophia::dataset::MutableDataset::insert_all(&mut dataset, quads).unwrap();
+    let mut ser = Jsonifier::new_jsonifier();
+    let got = ser.serialize_dataset(&dataset).unwrap().as_json().clone();
+    let exp = json::parse(
+        r#"[{
+        "@id": "http://example.com/a",
+        "http://example.org/label": [{"@value": "שלום", "@language": "he", "@direction": "rtl"}]
+    }]"#,
+    )
+    .unwrap();
+    assert!(
+        jsonld_cmp(&got, &exp, ""),
+        "got {}",
+        json::stringify_pretty(got, 2)
+    );
+}
@@ -50,8 +50,17 @@ impl Manifest {
     /// Perform all tests in this manifest,
     /// returning the number of failed / skipped / passed
     pub fn perform_all_tests(&self, verbose: bool) -> (usize, usize, usize) {
+        self.perform_tests(|_| true, verbose)
+    }
+
+    /// Perform the tests in this manifest that satisfy `filter`,
+    /// returning the number of failed / skipped / passed
+    pub fn perform_tests<F>(&self, filter: F, verbose: bool) -> (usize, usize, usize)
+    where
+        F: Fn(&Test) -> bool,
+    {
         let (mut failed, mut skipped, mut passed) = (0, 0, 0);
-        for t in self.tests() {
+        for t in self.tests().filter(|t| filter(t)) {
             match t.perform(verbose) {
                 TestResult::Fail => failed += 1,
                 TestResult::Skip => skipped += 1,
@@ -128,11 +137,11 @@ impl<'a> Test<'a> {
         for (key, val) in self.json["option"].entries() {
             match key {
                 "rdfDirection" => {
-                    config.rdf_direction = val.as_str().map(|val| match val {
+                    config = config.rdf_direction(val.as_str().map(|val| match val {
                         "i18n-datatype" => RdfDirectionMode::I18nDatatype,
                         "compound-literal" => RdfDirectionMode::CompoundLiteral,
                         _ => panic!("Unknown rdfDirection {}", val),
-                    });
+                    }));
                 }
                 "specVersion" => {
                     let version = match val.as_str().unwrap() {
@@ -186,7 +195,7 @@ pub fn perform_from_rdf(test: &Test, verbose: bool) -> TestResult {
     if test.positive() {
         let exp = test.expected_json();
         match ser.serialize_dataset(&test.input_dataset()) {
-            Ok(got) if jsonld_cmp(&got.as_json(), &exp, "") => {
+            Ok(got) if jsonld_cmp(got.as_json(), &exp, "") => {
                 if verbose {
                     println!("PASS");
                 }
@@ -214,7 +223,7 @@ pub fn perform_from_rdf(test: &Test, verbose: bool) -> TestResult {
                 }
                 TestResult::Skip
             }
-            Err(SinkError(e)) if format!("{}", e).starts_with(&exp) => {
+            Err(SinkError(e)) if format!("{}", e).starts_with(exp) => {
                 if verbose {
                     println!("PASS");
                 }
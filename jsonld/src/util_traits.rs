@@ -9,6 +9,7 @@ pub trait TermJsonLdUtil {
     fn is_object(&self) -> bool;
     fn is_iri(&self) -> bool;
     fn is_bnode(&self) -> bool;
+    #[allow(dead_code)]
     fn is_literal(&self) -> bool;
     fn as_id(&self) -> String;
 }
@@ -0,0 +1,48 @@
+//! Asynchronous parsers and serializers, based on [tokio].
+//!
+//! This module requires the `async` feature.
+//!
+//! The [parsers] read from an [`AsyncBufRead`],
+//! and produce a [`Stream`] of triples or quads, whose terms are [`BoxTerm`]s.
+//! The [serializers] write to an [`AsyncWrite`],
+//! from either a [`TripleSource`]/[`QuadSource`] or a [`Stream`].
+//!
+//! ```
+//! # use sophia::async_io::parser::AsyncTurtleParser;
+//! # use sophia::async_io::serializer::AsyncNtSerializer;
+//! # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+//! let ttl = r#"
+//!     @prefix : <http://example.org/> .
+//!     :alice :knows :bob .
+//! "#;
+//! let triples = AsyncTurtleParser::default().parse(ttl.as_bytes());
+//! let mut ser = AsyncNtSerializer::new(Vec::new());
+//! ser.serialize_stream(triples).await?;
+//! assert_eq!(
+//!     String::from_utf8(ser.into_inner())?,
+//!     "<http://example.org/alice> <http://example.org/knows> <http://example.org/bob>.\n",
+//! );
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! # }).unwrap();
+//! ```
+//!
+//! [tokio]: https://docs.rs/tokio/
+//! [parsers]: parser/index.html
+//! [serializers]: serializer/index.html
+//! [`AsyncBufRead`]: https://docs.rs/tokio/latest/tokio/io/trait.AsyncBufRead.html
+//! [`AsyncWrite`]: https://docs.rs/tokio/latest/tokio/io/trait.AsyncWrite.html
+//! [`Stream`]: https://docs.rs/futures-core/latest/futures_core/stream/trait.Stream.html
+//! [`BoxTerm`]: ../term/type.BoxTerm.html
+//! [`TripleSource`]: ../triple/stream/trait.TripleSource.html
+//! [`QuadSource`]: ../quad/stream/trait.QuadSource.html
+
+use sophia_term::BoxTerm;
+
+pub mod parser;
+pub mod serializer;
+
+/// The type of triples produced by asynchronous parsers.
+pub type BoxTriple = [BoxTerm; 3];
+
+/// The type of quads produced by asynchronous parsers.
+pub type BoxQuad = ([BoxTerm; 3], Option<BoxTerm>);
@@ -0,0 +1,560 @@
+//! Asynchronous parsers for N-Triples, N-Quads and Turtle.
+//!
+//! Each parser produces a [`Stream`] of results,
+//! which ends after the first error, if any.
+//!
+//! N-Triples and N-Quads are parsed line by line,
+//! with the same parser as [`ntq`](../../parser/ntq/index.html).
+//! Turtle data is buffered until the end of a statement is found,
+//! and each batch of complete statements is then parsed with RIO,
+//! so the buffer does not grow larger than the largest statement (plus one read).
+//! Blank node labels are kept as is, except those starting with `riog`,
+//! which are renamed `riogu...` so that they can not clash with
+//! the labels generated for anonymous blank nodes.
+//!
+//! [`Stream`]: https://docs.rs/futures-core/latest/futures_core/stream/trait.Stream.html
+
+use super::{BoxQuad, BoxTriple};
+use crate::parser::ntq::{parse_boxed_line, NtqError};
+use crate::parser::prefix_tracker::{Declarations, Scanner};
+use crate::parser::rio_common::rio2refterm;
+use futures_util::stream::{self, Stream, StreamExt};
+use rio_api::model::GeneralizedTerm;
+use rio_api::parser::{ParseError, TriplesParser};
+use rio_turtle::{TurtleError, TurtleParser as RioTurtleParser};
+use sophia_api::parser::{Location, WithLocation};
+use sophia_api::term::CopyTerm;
+use sophia_term::BoxTerm;
+use std::collections::VecDeque;
+use std::io;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt};
+
+/// This error is raised by asynchronous parsers.
+#[derive(Debug, thiserror::Error)]
+pub enum AsyncParserError {
+    /// Errors raised by the underlying reader.
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    /// Errors raised by malformed data.
+    #[error("{message} at {location}")]
+    Syntax { message: String, location: Location },
+}
+
+impl WithLocation for AsyncParserError {
+    fn location(&self) -> Location {
+        match self {
+            AsyncParserError::Io(_) => Location::Unknown,
+            AsyncParserError::Syntax { location, .. } => location.clone(),
+        }
+    }
+}
+
+impl From<NtqError> for AsyncParserError {
+    fn from(other: NtqError) -> Self {
+        match other {
+            NtqError::Io(err) => AsyncParserError::Io(err),
+            NtqError::Syntax { message, location } => {
+                AsyncParserError::Syntax { message, location }
+            }
+        }
+    }
+}
+
+/// Asynchronous N-Triples parser.
+#[derive(Clone, Debug, Default)]
+pub struct AsyncNTriplesParser {}
+
+impl AsyncNTriplesParser {
+    /// Parse `data` into a stream of triples.
+    pub fn parse<'a, R>(
+        &self,
+        data: R,
+    ) -> impl Stream<Item = Result<BoxTriple, AsyncParserError>> + 'a
+    where
+        R: AsyncBufRead + Unpin + 'a,
+    {
+        parse_lines(data, false).map(|res| res.map(|(spo, _)| spo))
+    }
+}
+
+/// Asynchronous N-Quads parser.
+#[derive(Clone, Debug, Default)]
+pub struct AsyncNQuadsParser {}
+
+impl AsyncNQuadsParser {
+    /// Parse `data` into a stream of quads.
+    pub fn parse<'a, R>(
+        &self,
+        data: R,
+    ) -> impl Stream<Item = Result<BoxQuad, AsyncParserError>> + 'a
+    where
+        R: AsyncBufRead + Unpin + 'a,
+    {
+        parse_lines(data, true)
+    }
+}
+
+struct Lines<R> {
+    data: R,
+    buffer: Vec<u8>,
+    line_no: usize,
+    done: bool,
+}
+
+fn parse_lines<'a, R>(
+    data: R,
+    nquads: bool,
+) -> impl Stream<Item = Result<BoxQuad, AsyncParserError>> + 'a
+where
+    R: AsyncBufRead + Unpin + 'a,
+{
+    let lines = Lines {
+        data,
+        buffer: Vec::new(),
+        line_no: 0,
+        done: false,
+    };
+    stream::unfold(lines, move |mut lines| async move {
+        if lines.done {
+            return None;
+        }
+        loop {
+            lines.buffer.clear();
+            let res = match lines.data.read_until(b'\n', &mut lines.buffer).await {
+                Ok(0) => return None,
+                Ok(_) => {
+                    lines.line_no += 1;
+                    parse_boxed_line(&lines.buffer, lines.line_no, nquads).map_err(Into::into)
+                }
+                Err(err) => Err(err.into()),
+            };
+            match res {
+                Ok(None) => continue,
+                Ok(Some(quad)) => return Some((Ok(quad), lines)),
+                Err(err) => {
+                    lines.done = true;
+                    return Some((Err(err), lines));
+                }
+            }
+        }
+    })
+}
+
+/// Asynchronous Turtle parser.
+///
+/// See also [`TurtleParser`](../../parser/turtle/struct.TurtleParser.html).
+#[derive(Clone, Debug, Default)]
+pub struct AsyncTurtleParser {
+    pub base: Option<String>,
+}
+
+impl AsyncTurtleParser {
+    /// Parse `data` into a stream of triples.
+    pub fn parse<'a, R>(
+        &self,
+        data: R,
+    ) -> impl Stream<Item = Result<BoxTriple, AsyncParserError>> + 'a
+    where
+        R: AsyncBufRead + Unpin + 'a,
+    {
+        let batches = Batches::new(data, self.base.clone());
+        stream::unfold(batches, |mut batches| async move {
+            loop {
+                if let Some(res) = batches.pending.pop_front() {
+                    return Some((res, batches));
+                }
+                if batches.done {
+                    return None;
+                }
+                batches.read().await;
+            }
+        })
+    }
+}
+
+/// Splits Turtle data into batches of complete statements.
+struct Batches<R> {
+    data: R,
+    scanner: Scanner,
+    // data not parsed yet
+    buffer: Vec<u8>,
+    // offset of the start of buffer in the data
+    start: usize,
+    // number of bytes of buffer already fed to the scanner
+    fed: usize,
+    // end of the last complete statement in buffer
+    end: usize,
+    // declarations in effect at the start of buffer, and at end
+    start_declarations: Declarations,
+    end_declarations: Declarations,
+    end_version: usize,
+    // position of the start of buffer (line and byte column, starting at 0)
+    line: usize,
+    column: usize,
+    // number of blank nodes generated by RIO in previous batches
+    bnodes: usize,
+    pending: VecDeque<Result<BoxTriple, AsyncParserError>>,
+    done: bool,
+}
+
+impl<R: AsyncBufRead + Unpin> Batches<R> {
+    fn new(data: R, base: Option<String>) -> Self {
+        let mut scanner = Scanner::new(base);
+        scanner.track_rio_labels();
+        let declarations = scanner.declarations().clone();
+        Batches {
+            data,
+            end_version: scanner.version(),
+            scanner,
+            buffer: Vec::new(),
+            start: 0,
+            fed: 0,
+            end: 0,
+            start_declarations: declarations.clone(),
+            end_declarations: declarations,
+            line: 0,
+            column: 0,
+            bnodes: 0,
+            pending: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    /// Read more data, and parse the statements completed by it.
+    async fn read(&mut self) {
+        let read = match self.data.fill_buf().await {
+            Ok(buf) => {
+                self.buffer.extend_from_slice(buf);
+                buf.len()
+            }
+            Err(err) => {
+                self.pending.push_back(Err(err.into()));
+                self.done = true;
+                return;
+            }
+        };
+        self.data.consume(read);
+        if read == 0 {
+            self.scanner.finish();
+            self.parse_batch(self.buffer.len());
+            self.done = true;
+            return;
+        }
+        while let Some((end, fed)) = self
+            .scanner
+            .feed_until_statement_end(&self.buffer[self.fed..])
+        {
+            self.end = self.fed + end;
+            self.fed += fed;
+            if self.scanner.version() != self.end_version {
+                self.end_version = self.scanner.version();
+                self.end_declarations = self.scanner.declarations().clone();
+            }
+        }
+        self.fed = self.buffer.len();
+        if self.end > 0 {
+            self.parse_batch(self.end);
+        }
+    }
+
+    /// Parse the first `end` bytes of the buffer, and remove them from it.
+    fn parse_batch(&mut self, end: usize) {
+        // prefixes declared in previous batches are prepended on a separate line
+        let mut txt: Vec<u8> = Vec::with_capacity(end);
+        for (prefix, iri) in self.start_declarations.prefixes() {
+            txt.extend_from_slice(format!("@prefix {}: <{}> . ", prefix, iri).as_bytes());
+        }
+        let header_lines = if txt.is_empty() {
+            0
+        } else {
+            txt.push(b'\n');
+            1
+        };
+        // user labels starting with `riog` are renamed `riogu...`,
+        // so that they can not be confused with the labels generated by RIO;
+        // the position of each inserted `u` is kept, as (line, byte in line),
+        // both starting at 1 as in the positions of RIO errors
+        let mut inserted = vec![];
+        let mut copied = 0;
+        for label in self.scanner.take_rio_labels(self.start + end) {
+            let label = label - self.start;
+            txt.extend_from_slice(&self.buffer[copied..label]);
+            let line = txt.iter().filter(|b| **b == b'\n').count();
+            let line_start = txt.iter().rposition(|b| *b == b'\n').map_or(0, |i| i + 1);
+            inserted.push((line + 1, txt.len() - line_start + 1));
+            txt.push(b'u');
+            copied = label;
+        }
+        txt.extend_from_slice(&self.buffer[copied..end]);
+
+        let base = self.start_declarations.base().unwrap_or("x-no-base:///");
+        let (line, column, offset) = (self.line, self.column, self.bnodes);
+        let mut max_bnode = 0;
+        let pending = &mut self.pending;
+        let res = RioTurtleParser::new(&txt[..], base).and_then(|mut parser| {
+            parser.parse_all(&mut |t| -> Result<(), TurtleError> {
+                let mut convert = |term: GeneralizedTerm| match generated_bnode(&term) {
+                    Some(n) => {
+                        max_bnode = max_bnode.max(n);
+                        BoxTerm::new_bnode_unchecked(format!("riog{:08}", n + offset))
+                    }
+                    None => BoxTerm::copy(&rio2refterm(term)),
+                };
+                let s = convert(t.subject.into());
+                let p = convert(t.predicate.into());
+                let o = convert(t.object.into());
+                pending.push_back(Ok([s, p, o]));
+                Ok(())
+            })
+        });
+        if let Err(err) = res {
+            let location = match err.textual_position() {
+                Some(pos) if pos.line_number() >= header_lines => {
+                    let l = pos.line_number() - header_lines;
+                    let shift = inserted
+                        .iter()
+                        .filter(|(il, ib)| *il == pos.line_number() && *ib < pos.byte_number())
+                        .count();
+                    let c = if l == 0 { column } else { 0 } + pos.byte_number() - shift;
+                    Location::from_lico(line + l + 1, c + 1)
+                }
+                _ => Location::Unknown,
+            };
+            let message = err.to_string();
+            let message = match message.rfind(" on line ") {
+                Some(i) => message[..i].to_string(),
+                None => message,
+            };
+            self.pending
+                .push_back(Err(AsyncParserError::Syntax { message, location }));
+            self.done = true;
+        }
+
+        let batch = &self.buffer[..end];
+        match batch.iter().rposition(|b| *b == b'\n') {
+            Some(i) => {
+                self.line += batch.iter().filter(|b| **b == b'\n').count();
+                self.column = end - i - 1;
+            }
+            None => self.column += end,
+        }
+        self.bnodes += max_bnode;
+        self.buffer.drain(..end);
+        self.start += end;
+        self.fed -= end.min(self.fed);
+        self.end = 0;
+        self.start_declarations = self.end_declarations.clone();
+    }
+}
+
+/// If `term` is a blank node generated by RIO, return its number.
+fn generated_bnode(term: &GeneralizedTerm) -> Option<usize> {
+    match term {
+        GeneralizedTerm::BlankNode(b)
+            if b.id.len() == 12
+                && b.id.starts_with("riog")
+                && b.id[4..].bytes().all(|c| c.is_ascii_digit()) =>
+        {
+            b.id[4..].parse().ok()
+        }
+        _ => None,
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::inmem::FastGraph;
+    use crate::graph::Graph;
+    use crate::serializer::nt::NtSerializer;
+    use crate::serializer::{Stringifier, TripleSerializer};
+    use crate::triple::stream::TripleSource;
+    use sophia_api::term::TTerm;
+    use sophia_term::StaticTerm;
+    use tokio::io::BufReader;
+
+    fn block_on<F: std::future::Future>(f: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(f)
+    }
+
+    async fn collect<T>(
+        s: impl Stream<Item = Result<T, AsyncParserError>>,
+    ) -> Vec<Result<T, AsyncParserError>> {
+        s.collect().await
+    }
+
+    #[test]
+    fn nt() {
+        let nt = r#"
+            <tag:s> <tag:p> "a" .
+            # comment
+            <tag:s> <tag:p> _:b .
+        "#;
+        let res = block_on(collect(AsyncNTriplesParser {}.parse(nt.as_bytes())));
+        assert_eq!(res.len(), 2);
+        let t = res[1].as_ref().unwrap();
+        assert_eq!(&t[0], &StaticTerm::new_iri("tag:s").unwrap());
+        assert_eq!(&t[2], &StaticTerm::new_bnode("b").unwrap());
+    }
+
+    #[test]
+    fn nq() {
+        let nq = "<tag:s> <tag:p> <tag:o> <tag:g> .\n<tag:s> <tag:p> <tag:o> .\n";
+        let res = block_on(collect(AsyncNQuadsParser {}.parse(nq.as_bytes())));
+        assert_eq!(res.len(), 2);
+        let g = &res[0].as_ref().unwrap().1;
+        assert_eq!(g, &Some(BoxTerm::new_iri("tag:g").unwrap()));
+        assert!(res[1].as_ref().unwrap().1.is_none());
+    }
+
+    #[test]
+    fn nt_error_ends_stream() {
+        let nt = "<tag:s> <tag:p> \"a\" .\n<tag:s> <tag:p> \"b\"\n<tag:s> <tag:p> \"c\" .\n";
+        let res = block_on(collect(AsyncNTriplesParser {}.parse(nt.as_bytes())));
+        assert_eq!(res.len(), 2);
+        let err = res[1].as_ref().unwrap_err();
+        assert_eq!(err.location().to_string(), "2:20");
+    }
+
+    const TTL: &str = r#"@prefix : <http://example.org/ns/> .
+        @base <http://example.org/base/> .
+
+        <#me> :knows [ a :Person ; :name "Alice" ], [ :name """Bob.
+            Jr.""" ] .
+        PREFIX foaf: <http://xmlns.com/foaf/0.1/>
+        <#me> foaf:age 42. <#me> foaf:nick ( "al" "ali" ) .
+    "#;
+
+    /// Reading byte by byte splits the data into as many batches as possible.
+    #[test]
+    fn turtle_same_as_sync() {
+        assert_same_as_sync(TTL, 11);
+    }
+
+    #[test]
+    fn turtle_dots() {
+        let ttl = r#"@prefix ex: <http://example.org/> .
+            ex:a.b ex:p "x. y", 'z.', """a.
+            "b".""", ex:c.d, 1.5, .5, 1.0e3 .
+            ex:e ex:p ex:f. ex:g ex:p "h"@en. ex:i ex:p "j"^^ex:k.
+            ex:l ex:p ( ex:m.n ex:o ) ."#;
+        assert_same_as_sync(ttl, 15);
+    }
+
+    #[test]
+    fn turtle_base_changes() {
+        let ttl = r#"@base <http://example.org/a/> .
+            <s> <p> <o> .
+            @base <b/> .
+            @prefix r: <rel#> .
+            <s> <p> r:o .
+            BASE <http://example.org/c/>
+            <s> <p> r:o, <o> ."#;
+        assert_same_as_sync(ttl, 4);
+    }
+
+    #[test]
+    fn turtle_bnode_labels() {
+        let ttl = r#"_:x <tag:p> _:y .
+            _:y <tag:p> [ <tag:p> _:x ] .
+            [] <tag:p> _:x, [] .
+            _:x <tag:p> _:y ."#;
+        assert_same_as_sync(ttl, 5);
+    }
+
+    /// Labels that look like those generated by RIO must not be confused with them.
+    #[test]
+    fn turtle_rio_like_bnode_labels() {
+        let ttl = r#"_:riog00000001 <tag:p> [] .
+            _:riog00000002 <tag:p> [] .
+            [] <tag:p> _:riog00000001, _:riog00000002, _:riogx."#;
+        for capacity in &[1, 3, 64, 8192] {
+            let data = BufReader::with_capacity(*capacity, ttl.as_bytes());
+            let res = block_on(collect(AsyncTurtleParser::default().parse(data)));
+            let triples: Vec<_> = res.into_iter().map(Result::unwrap).collect();
+            assert_eq!(triples.len(), 5, "capacity {}", capacity);
+            let labels: Vec<_> = triples
+                .iter()
+                .flat_map(|t| vec![&t[0], &t[2]])
+                .map(|t| t.value().to_string())
+                .collect();
+            let mut distinct = labels.clone();
+            distinct.sort();
+            distinct.dedup();
+            // 3 generated blank nodes + 3 labelled ones
+            assert_eq!(distinct.len(), 6, "capacity {}: {:?}", capacity, labels);
+            assert_eq!(labels[0], labels[5], "capacity {}", capacity);
+            assert_eq!(labels[2], labels[7], "capacity {}", capacity);
+            assert_eq!(labels[4], labels[6], "capacity {}", capacity);
+            assert_eq!(labels[4], labels[8], "capacity {}", capacity);
+        }
+    }
+
+    fn assert_same_as_sync(ttl: &str, count: usize) {
+        let g2: FastGraph = crate::parser::turtle::parse_str(ttl)
+            .collect_triples()
+            .unwrap();
+        for capacity in &[1, 2, 3, 5, 64, 8192] {
+            let data = BufReader::with_capacity(*capacity, ttl.as_bytes());
+            let res = block_on(collect(AsyncTurtleParser::default().parse(data)));
+            let mut g1 = FastGraph::new();
+            for t in res {
+                let [s, p, o] = t.unwrap();
+                crate::graph::MutableGraph::insert(&mut g1, &s, &p, &o).unwrap();
+            }
+            assert_eq!(g1.triples().count(), count, "capacity {}", capacity);
+            // blank node identifiers are expected to be the same as with the sync parser
+            assert_eq!(sorted_nt(&g1), sorted_nt(&g2), "capacity {}", capacity);
+        }
+    }
+
+    fn sorted_nt(g: &FastGraph) -> Vec<String> {
+        let mut ser = NtSerializer::new_stringifier();
+        let mut lines: Vec<_> = ser
+            .serialize_graph(g)
+            .unwrap()
+            .as_str()
+            .lines()
+            .map(str::to_string)
+            .collect();
+        lines.sort();
+        lines
+    }
+
+    /// Errors are expected to be located as with the sync parser.
+    #[test]
+    fn turtle_error_location() {
+        let ttl = "@prefix : <tag:> .\n:a :b :c .\n:d :e ?f .\n:g :h :i .\n";
+        assert_same_error_location(ttl, 2);
+        // inside the batch, labels starting with riog are renamed
+        let ttl = "@prefix : <tag:> .\n:a :b :c .\n_:riog1 :b _:riog2, _:riog3, ?f .\n";
+        assert_same_error_location(ttl, 4);
+    }
+
+    fn assert_same_error_location(ttl: &str, len: usize) {
+        let sync_err = crate::parser::turtle::parse_str(ttl)
+            .for_each_triple(|_| ())
+            .unwrap_err();
+        let sync_loc = crate::parser::turtle::SophiaTurtleError(sync_err).location();
+        for capacity in &[1, 4, 8192] {
+            let data = BufReader::with_capacity(*capacity, ttl.as_bytes());
+            let res = block_on(collect(AsyncTurtleParser::default().parse(data)));
+            assert_eq!(res.len(), len, "capacity {}", capacity);
+            assert_eq!(res[0].as_ref().unwrap()[2].value().as_ref(), "tag:c");
+            let err = res[len - 1].as_ref().unwrap_err();
+            assert_eq!(
+                err.location().to_string(),
+                sync_loc.to_string(),
+                "capacity {}",
+                capacity
+            );
+        }
+    }
+}
@@ -0,0 +1,246 @@
+//! Asynchronous serializers for N-Triples and N-Quads.
+//!
+//! The serialized data is buffered,
+//! and written to the underlying [`AsyncWrite`] by chunks.
+//!
+//! [`AsyncWrite`]: https://docs.rs/tokio/latest/tokio/io/trait.AsyncWrite.html
+
+use crate::dataset::Dataset;
+use crate::graph::Graph;
+use crate::quad::stream::QuadSource;
+use crate::quad::Quad;
+use crate::serializer::nq::write_quad;
+use crate::serializer::nt::write_triple;
+use crate::triple::stream::{SinkError, SourceError, StreamResult, TripleSource};
+use crate::triple::Triple;
+use futures_util::pin_mut;
+use futures_util::stream::{Stream, StreamExt};
+use std::error::Error;
+use std::io;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+/// The size above which the buffer is written to the underlying [`AsyncWrite`].
+const CHUNK_SIZE: usize = 1 << 16;
+
+/// Asynchronous N-Triples serializer.
+pub struct AsyncNtSerializer<W> {
+    write: W,
+    buffer: Vec<u8>,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncNtSerializer<W> {
+    /// Build a new N-Triples serializer writing to `write`.
+    pub fn new(write: W) -> Self {
+        AsyncNtSerializer {
+            write,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Return the underlying `write`.
+    pub fn into_inner(self) -> W {
+        self.write
+    }
+
+    /// Serialize all triples from the given [`TripleSource`](../../triple/stream/trait.TripleSource.html).
+    pub async fn serialize_triples<TS>(
+        &mut self,
+        mut source: TS,
+    ) -> StreamResult<&mut Self, TS::Error, io::Error>
+    where
+        TS: TripleSource,
+    {
+        loop {
+            let buffer = &mut self.buffer;
+            let more = source.try_for_some_triple(&mut |t| write_triple(buffer, &t))?;
+            if !more || self.buffer.len() >= CHUNK_SIZE {
+                write_buffer(&mut self.write, &mut self.buffer).await?;
+            }
+            if !more {
+                return Ok(self);
+            }
+        }
+    }
+
+    /// Serialize a whole [`Graph`](../../graph/trait.Graph.html).
+    pub async fn serialize_graph<G>(
+        &mut self,
+        graph: &G,
+    ) -> StreamResult<&mut Self, G::Error, io::Error>
+    where
+        G: Graph,
+    {
+        self.serialize_triples(graph.triples()).await
+    }
+
+    /// Serialize all triples from the given stream,
+    /// for example a stream produced by an [asynchronous parser](../parser/index.html).
+    pub async fn serialize_stream<S, T, E>(
+        &mut self,
+        stream: S,
+    ) -> StreamResult<&mut Self, E, io::Error>
+    where
+        S: Stream<Item = Result<T, E>>,
+        T: Triple,
+        E: Error + 'static,
+    {
+        pin_mut!(stream);
+        while let Some(res) = stream.next().await {
+            let t = res.map_err(SourceError)?;
+            write_triple(&mut self.buffer, &t).map_err(SinkError)?;
+            if self.buffer.len() >= CHUNK_SIZE {
+                write_buffer(&mut self.write, &mut self.buffer).await?;
+            }
+        }
+        write_buffer(&mut self.write, &mut self.buffer).await?;
+        Ok(self)
+    }
+}
+
+/// Asynchronous N-Quads serializer.
+pub struct AsyncNqSerializer<W> {
+    write: W,
+    buffer: Vec<u8>,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncNqSerializer<W> {
+    /// Build a new N-Quads serializer writing to `write`.
+    pub fn new(write: W) -> Self {
+        AsyncNqSerializer {
+            write,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Return the underlying `write`.
+    pub fn into_inner(self) -> W {
+        self.write
+    }
+
+    /// Serialize all quads from the given [`QuadSource`](../../quad/stream/trait.QuadSource.html).
+    pub async fn serialize_quads<QS>(
+        &mut self,
+        mut source: QS,
+    ) -> StreamResult<&mut Self, QS::Error, io::Error>
+    where
+        QS: QuadSource,
+    {
+        loop {
+            let buffer = &mut self.buffer;
+            let more = source.try_for_some_quad(&mut |q| write_quad(buffer, &q))?;
+            if !more || self.buffer.len() >= CHUNK_SIZE {
+                write_buffer(&mut self.write, &mut self.buffer).await?;
+            }
+            if !more {
+                return Ok(self);
+            }
+        }
+    }
+
+    /// Serialize a whole [`Dataset`](../../dataset/trait.Dataset.html).
+    pub async fn serialize_dataset<D>(
+        &mut self,
+        dataset: &D,
+    ) -> StreamResult<&mut Self, D::Error, io::Error>
+    where
+        D: Dataset,
+    {
+        self.serialize_quads(dataset.quads()).await
+    }
+
+    /// Serialize all quads from the given stream,
+    /// for example a stream produced by an [asynchronous parser](../parser/index.html).
+    pub async fn serialize_stream<S, Q, E>(
+        &mut self,
+        stream: S,
+    ) -> StreamResult<&mut Self, E, io::Error>
+    where
+        S: Stream<Item = Result<Q, E>>,
+        Q: Quad,
+        E: Error + 'static,
+    {
+        pin_mut!(stream);
+        while let Some(res) = stream.next().await {
+            let q = res.map_err(SourceError)?;
+            write_quad(&mut self.buffer, &q).map_err(SinkError)?;
+            if self.buffer.len() >= CHUNK_SIZE {
+                write_buffer(&mut self.write, &mut self.buffer).await?;
+            }
+        }
+        write_buffer(&mut self.write, &mut self.buffer).await?;
+        Ok(self)
+    }
+}
+
+/// Write and clear `buffer`, and flush `write`.
+async fn write_buffer<W, E>(write: &mut W, buffer: &mut Vec<u8>) -> StreamResult<(), E, io::Error>
+where
+    W: AsyncWrite + Unpin,
+    E: Error + 'static,
+{
+    write.write_all(buffer).await.map_err(SinkError)?;
+    buffer.clear();
+    write.flush().await.map_err(SinkError)
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::async_io::parser::AsyncNQuadsParser;
+    use crate::dataset::inmem::FastDataset;
+    use crate::graph::inmem::FastGraph;
+
+    fn block_on<F: std::future::Future>(f: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(f)
+    }
+
+    const NT: &str = "<tag:s> <tag:p> \"a\\nb\"@en.\n";
+    const NQ: &str = "<tag:s> <tag:p> _:o <tag:g>.\n<tag:s> <tag:p> <tag:o>.\n";
+
+    #[test]
+    fn nt_from_graph() {
+        let g: FastGraph = crate::parser::nt::parse_str(NT).collect_triples().unwrap();
+        let mut ser = AsyncNtSerializer::new(Vec::new());
+        block_on(ser.serialize_graph(&g)).unwrap();
+        assert_eq!(String::from_utf8(ser.into_inner()).unwrap(), NT);
+    }
+
+    #[test]
+    fn nq_from_dataset() {
+        let d: FastDataset = crate::parser::nq::parse_str(NQ).collect_quads().unwrap();
+        let mut ser = AsyncNqSerializer::new(Vec::new());
+        block_on(ser.serialize_dataset(&d)).unwrap();
+        let txt = String::from_utf8(ser.into_inner()).unwrap();
+        let mut lines: Vec<_> = txt.lines().collect();
+        lines.sort();
+        assert_eq!(
+            lines,
+            vec!["<tag:s> <tag:p> <tag:o>.", "<tag:s> <tag:p> _:o <tag:g>.",]
+        );
+    }
+
+    #[test]
+    fn nq_from_stream() {
+        let quads = AsyncNQuadsParser {}.parse(NQ.as_bytes());
+        let mut ser = AsyncNqSerializer::new(Vec::new());
+        block_on(ser.serialize_stream(quads)).unwrap();
+        assert_eq!(String::from_utf8(ser.into_inner()).unwrap(), NQ);
+    }
+
+    #[test]
+    fn stream_error() {
+        let quads = AsyncNQuadsParser {}.parse("<tag:s> <tag:p> .\n".as_bytes());
+        let mut ser = AsyncNqSerializer::new(Vec::new());
+        match block_on(ser.serialize_stream(quads)) {
+            Err(SourceError(_)) => (),
+            _ => panic!("expected a source error"),
+        }
+    }
+}
@@ -0,0 +1,701 @@
+//! Reading and writing RDF collections (`rdf:List`)
+//! and containers (`rdf:Seq`, `rdf:Bag`, `rdf:Alt`).
+//!
+//! The methods of [`CollectionGraph`] are available on any [`Graph`],
+//! and those of [`MutableCollectionGraph`] on any [`MutableGraph`].
+//!
+//! Lists are read with [`list_items`], which checks that the list is well-formed,
+//! i.e. that every node has exactly one `rdf:first` and one `rdf:rest`,
+//! and that the list is not cyclic.
+//! They are written with [`insert_list`], [`replace_list`] and [`remove_list`].
+//!
+//! ```
+//! # use sophia::collection::*;
+//! # use sophia::graph::inmem::FastGraph;
+//! # use sophia::ns::rdf;
+//! # use sophia::parser::turtle;
+//! # use sophia::term::BoxTerm;
+//! # use sophia::triple::stream::TripleSource;
+//! let mut graph: FastGraph = turtle::parse_str(r#"
+//!     @prefix : <http://example.org/>.
+//!     :alice :likes (:bob :carol).
+//! "#).collect_triples()?;
+//! let alice = BoxTerm::new_iri("http://example.org/alice")?;
+//! let likes = BoxTerm::new_iri("http://example.org/likes")?;
+//! let carol = BoxTerm::new_iri("http://example.org/carol")?;
+//!
+//! let head = graph.replace_list(&alice, &likes, vec![carol.clone()])?;
+//! assert_eq!(graph.read_list(&head)?, vec![carol.clone()]);
+//!
+//! let bag = graph.insert_container(&rdf::Bag, vec![carol.clone(), carol.clone()])?;
+//! assert_eq!(graph.container_items(&bag)?, vec![carol.clone(), carol]);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [`CollectionGraph`]: trait.CollectionGraph.html
+//! [`MutableCollectionGraph`]: trait.MutableCollectionGraph.html
+//! [`Graph`]: ../graph/trait.Graph.html
+//! [`MutableGraph`]: ../graph/trait.MutableGraph.html
+//! [`list_items`]: trait.CollectionGraph.html#method.list_items
+//! [`insert_list`]: trait.MutableCollectionGraph.html#method.insert_list
+//! [`replace_list`]: trait.MutableCollectionGraph.html#method.replace_list
+//! [`remove_list`]: trait.MutableCollectionGraph.html#method.remove_list
+
+use sophia_api::graph::{GResult, Graph, MGResult, MutableGraph};
+use sophia_api::ns::rdf;
+use sophia_api::term::{term_eq, CopyTerm, TTerm, TermKind};
+use sophia_api::triple::Triple;
+use sophia_term::BoxTerm;
+use std::collections::hash_map::RandomState;
+use std::collections::HashSet;
+use std::error::Error;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+
+/// The error raised when reading or writing a list.
+#[derive(Debug, thiserror::Error)]
+pub enum ListError<E>
+where
+    E: Error + 'static,
+{
+    /// Errors raised by the underlying graph.
+    #[error(transparent)]
+    Graph(E),
+    /// A node of the list does not have exactly one `rdf:first` and one `rdf:rest`.
+    #[error("malformed list node {node}: {message}")]
+    Malformed {
+        /// The offending node
+        node: BoxTerm,
+        /// A description of the problem
+        message: String,
+    },
+    /// The list loops back to a node already visited.
+    #[error("cyclic list through node {0}")]
+    Cyclic(BoxTerm),
+}
+
+impl<E> ListError<E>
+where
+    E: Error + 'static,
+{
+    fn map_graph<F, E2>(self, f: F) -> ListError<E2>
+    where
+        F: FnOnce(E) -> E2,
+        E2: Error + 'static,
+    {
+        match self {
+            ListError::Graph(err) => ListError::Graph(f(err)),
+            ListError::Malformed { node, message } => ListError::Malformed { node, message },
+            ListError::Cyclic(node) => ListError::Cyclic(node),
+        }
+    }
+}
+
+/// Type alias for the result of writing a list in a graph `G`.
+pub type MListResult<G, T> = Result<T, ListError<<G as MutableGraph>::MutationError>>;
+
+/// Methods for reading collections, available on any [`Graph`](../graph/trait.Graph.html).
+pub trait CollectionGraph: Graph + Sized {
+    /// Iterate over the items of the list starting at `head` in this graph.
+    ///
+    /// The iterator stops after the first error,
+    /// which is raised when the list is malformed or cyclic.
+    fn list_items<T>(&self, head: &T) -> ListItems<'_, Self>
+    where
+        T: TTerm + ?Sized,
+    {
+        ListItems {
+            graph: self,
+            node: Some(BoxTerm::copy(head)),
+            visited: HashSet::new(),
+        }
+    }
+
+    /// Collect the items of the list starting at `head` in this graph.
+    fn read_list<T>(&self, head: &T) -> Result<Vec<BoxTerm>, ListError<Self::Error>>
+    where
+        T: TTerm + ?Sized,
+    {
+        self.list_items(head).collect()
+    }
+
+    /// Get the items of `container` in this graph,
+    /// ordered by their membership property (`rdf:_1`, `rdf:_2`, ...).
+    fn container_items<T>(&self, container: &T) -> GResult<Self, Vec<BoxTerm>>
+    where
+        T: TTerm + ?Sized,
+    {
+        let mut items = vec![];
+        for t in self.triples_with_s(container) {
+            let t = t?;
+            if let Some(n) = membership_index(t.p()) {
+                items.push((n, BoxTerm::copy(t.o())));
+            }
+        }
+        items.sort_by_key(|(n, _)| *n);
+        Ok(items.into_iter().map(|(_, item)| item).collect())
+    }
+
+    /// A blank node which is not used in this graph.
+    ///
+    /// Its label is made of a random 64-bit prefix, drawn once per process,
+    /// and of a counter shared by all graphs,
+    /// so that it can not clash with the labels of parsed documents,
+    /// nor with the blank nodes previously returned by this method,
+    /// even if they are only used inside quoted triples.
+    /// As a safeguard, labels used as subject or object in this graph are skipped.
+    fn fresh_bnode(&self) -> GResult<Self, BoxTerm> {
+        Ok(fresh_bnodes(self, 1, |_| false)?.pop().unwrap())
+    }
+}
+
+impl<G: Graph> CollectionGraph for G {}
+
+/// Methods for writing collections, available on any [`MutableGraph`](../graph/trait.MutableGraph.html).
+///
+/// The nodes created by these methods are [fresh](trait.CollectionGraph.html#method.fresh_bnode)
+/// blank nodes.
+pub trait MutableCollectionGraph: CollectionGraph + MutableGraph
+where
+    Self::Error: Into<Self::MutationError>,
+{
+    /// Write `items` as a new list in this graph, and return the head of the list.
+    ///
+    /// An empty list is represented by `rdf:nil`.
+    fn insert_list<I>(&mut self, items: I) -> MGResult<Self, BoxTerm>
+    where
+        I: IntoIterator,
+        I::Item: TTerm,
+    {
+        let items: Vec<_> = items.into_iter().collect();
+        let nodes = fresh_bnodes(self, items.len(), |_| false).map_err(Into::into)?;
+        let mut head = BoxTerm::copy(&rdf::nil);
+        for (item, node) in items.iter().rev().zip(nodes) {
+            self.insert(&node, &rdf::first, item)?;
+            self.insert(&node, &rdf::rest, &head)?;
+            head = node;
+        }
+        Ok(head)
+    }
+
+    /// Remove the list starting at `head` from this graph,
+    /// and return its items.
+    ///
+    /// Only the `rdf:first` and `rdf:rest` triples of the list nodes are removed,
+    /// and nothing is removed if the list is malformed.
+    fn remove_list<T>(&mut self, head: &T) -> MListResult<Self, Vec<BoxTerm>>
+    where
+        T: TTerm + ?Sized,
+    {
+        let mut nodes = vec![];
+        let mut items = self.list_items(head);
+        let mut values = vec![];
+        loop {
+            let node = items.node.clone();
+            match items.next() {
+                None => break,
+                Some(item) => {
+                    values.push(item.map_err(|e| e.map_graph(Into::into))?);
+                    nodes.push(node.unwrap());
+                }
+            }
+        }
+        let nil = BoxTerm::copy(&rdf::nil);
+        for (i, (node, item)) in nodes.iter().zip(&values).enumerate() {
+            let rest = nodes.get(i + 1).unwrap_or(&nil);
+            self.remove(node, &rdf::first, item)
+                .map_err(ListError::Graph)?;
+            self.remove(node, &rdf::rest, rest)
+                .map_err(ListError::Graph)?;
+        }
+        Ok(values)
+    }
+
+    /// Replace the list which is the value of `predicate` for `subject` in this graph
+    /// with a new list containing `items`, and return the head of the new list.
+    ///
+    /// If `subject` has no value for `predicate`, the new list is simply added.
+    /// Fail if `subject` has several values for `predicate`, or if the old list is malformed.
+    fn replace_list<TS, TP, I>(
+        &mut self,
+        subject: &TS,
+        predicate: &TP,
+        items: I,
+    ) -> MListResult<Self, BoxTerm>
+    where
+        TS: TTerm + ?Sized,
+        TP: TTerm + ?Sized,
+        I: IntoIterator,
+        I::Item: TTerm,
+    {
+        let mut old = self
+            .triples_with_sp(subject, predicate)
+            .map(|t| t.map(|t| BoxTerm::copy(t.o())))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| ListError::Graph(e.into()))?;
+        if old.len() > 1 {
+            let message = format!("{} values for <{}>", old.len(), predicate.value());
+            return Err(malformed(BoxTerm::copy(subject), message));
+        }
+        if let Some(old) = old.pop() {
+            self.remove_list(&old)?;
+            self.remove(subject, predicate, &old)
+                .map_err(ListError::Graph)?;
+        }
+        let head = self.insert_list(items).map_err(ListError::Graph)?;
+        self.insert(subject, predicate, &head)
+            .map_err(ListError::Graph)?;
+        Ok(head)
+    }
+
+    /// Write `items` as a new container of type `kind` (e.g. `rdf:Seq`) in this graph,
+    /// and return the container, which is a fresh blank node.
+    fn insert_container<TK, I>(&mut self, kind: &TK, items: I) -> MGResult<Self, BoxTerm>
+    where
+        TK: TTerm + ?Sized,
+        I: IntoIterator,
+        I::Item: TTerm,
+    {
+        let container = self.fresh_bnode().map_err(Into::into)?;
+        self.insert(&container, &rdf::type_, kind)?;
+        self.replace_container(&container, items)?;
+        Ok(container)
+    }
+
+    /// Replace the items of `container` in this graph with `items`.
+    ///
+    /// The type of the container is left unchanged.
+    fn replace_container<T, I>(&mut self, container: &T, items: I) -> MGResult<Self, ()>
+    where
+        T: TTerm + ?Sized,
+        I: IntoIterator,
+        I::Item: TTerm,
+    {
+        remove_members(self, container)?;
+        for (i, item) in items.into_iter().enumerate() {
+            self.insert(container, &membership_property(i + 1), &item)?;
+        }
+        Ok(())
+    }
+
+    /// Remove `container` from this graph,
+    /// i.e. its membership triples and its type (if `rdf:Seq`, `rdf:Bag` or `rdf:Alt`),
+    /// and return its items.
+    fn remove_container<T>(&mut self, container: &T) -> MGResult<Self, Vec<BoxTerm>>
+    where
+        T: TTerm + ?Sized,
+    {
+        let items = self.container_items(container).map_err(Into::into)?;
+        remove_members(self, container)?;
+        for kind in &[rdf::Seq, rdf::Bag, rdf::Alt] {
+            self.remove(container, &rdf::type_, kind)?;
+        }
+        Ok(items)
+    }
+}
+
+impl<G> MutableCollectionGraph for G
+where
+    G: MutableGraph,
+    G::Error: Into<G::MutationError>,
+{
+}
+
+/// The iterator returned by [`list_items`](trait.CollectionGraph.html#method.list_items).
+pub struct ListItems<'g, G> {
+    graph: &'g G,
+    node: Option<BoxTerm>,
+    visited: HashSet<BoxTerm>,
+}
+
+impl<'g, G> ListItems<'g, G>
+where
+    G: Graph,
+{
+    fn step(&mut self, node: BoxTerm) -> Result<Option<BoxTerm>, ListError<G::Error>> {
+        if rdf::nil == node {
+            return Ok(None);
+        }
+        if node.kind() == TermKind::Literal {
+            return Err(malformed(node, "a literal can not be a list node"));
+        }
+        if self.visited.contains(&node) {
+            return Err(ListError::Cyclic(node));
+        }
+        let first = self.single_object(&node, &rdf::first)?;
+        let rest = self.single_object(&node, &rdf::rest)?;
+        self.visited.insert(node);
+        self.node = Some(rest);
+        Ok(Some(first))
+    }
+
+    fn single_object<T>(
+        &self,
+        node: &BoxTerm,
+        predicate: &T,
+    ) -> Result<BoxTerm, ListError<G::Error>>
+    where
+        T: TTerm + ?Sized,
+    {
+        let mut objects = self
+            .graph
+            .triples_with_sp(node, predicate)
+            .map(|t| t.map(|t| BoxTerm::copy(t.o())))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(ListError::Graph)?;
+        if objects.len() != 1 {
+            let message = format!("{} values for <{}>", objects.len(), predicate.value());
+            return Err(malformed(node.clone(), message));
+        }
+        Ok(objects.pop().unwrap())
+    }
+}
+
+impl<'g, G> Iterator for ListItems<'g, G>
+where
+    G: Graph,
+{
+    type Item = Result<BoxTerm, ListError<G::Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.node.take()?;
+        self.step(node).transpose()
+    }
+}
+
+/// Whether a node with the given outgoing arcs (predicate and object)
+/// is a well-formed list node that can be rendered as an item of a list,
+/// i.e. whether it has exactly one `rdf:first`, one `rdf:rest` which is not a literal,
+/// at most one `rdf:type` which must be `rdf:List`, and no other arc.
+pub fn is_list_node<'a, I, P, O>(arcs: I) -> bool
+where
+    I: IntoIterator<Item = (&'a P, &'a O)>,
+    P: TTerm + ?Sized + 'a,
+    O: TTerm + ?Sized + 'a,
+{
+    let (mut first, mut rest, mut type_) = (0, 0, 0);
+    for (p, o) in arcs {
+        if term_eq(p, &rdf::first) {
+            first += 1;
+        } else if term_eq(p, &rdf::rest) && o.kind() != TermKind::Literal {
+            rest += 1;
+        } else if term_eq(p, &rdf::type_) && term_eq(o, &rdf::List) {
+            type_ += 1;
+        } else {
+            return false;
+        }
+    }
+    first == 1 && rest == 1 && type_ <= 1
+}
+
+/// The membership property `rdf:_n`.
+pub fn membership_property(n: usize) -> BoxTerm {
+    BoxTerm::new_iri_suffixed(rdf::PREFIX, format!("_{}", n)).unwrap()
+}
+
+/// If `term` is a membership property `rdf:_n`, return `n`.
+pub fn membership_index<T>(term: &T) -> Option<usize>
+where
+    T: TTerm + ?Sized,
+{
+    if term.kind() != TermKind::Iri {
+        return None;
+    }
+    let value = term.value();
+    let digits = value.strip_prefix(rdf::PREFIX)?.strip_prefix('_')?;
+    if digits.starts_with('0') || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok().filter(|n| *n > 0)
+}
+
+fn remove_members<G, T>(graph: &mut G, container: &T) -> MGResult<G, ()>
+where
+    G: MutableGraph,
+    G::Error: Into<G::MutationError>,
+    T: TTerm + ?Sized,
+{
+    let members = graph
+        .triples_with_s(container)
+        .filter_map(|t| match t {
+            Ok(t) if membership_index(t.p()).is_some() => {
+                Some(Ok([BoxTerm::copy(t.p()), BoxTerm::copy(t.o())]))
+            }
+            Ok(_) => None,
+            Err(err) => Some(Err(err)),
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(Into::into)?;
+    for [p, o] in members {
+        graph.remove(container, &p, &o)?;
+    }
+    Ok(())
+}
+
+/// The counter used to label fresh blank nodes, shared by all graphs.
+static FRESH_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// The prefix of the labels of fresh blank nodes, drawn at random once per process.
+fn fresh_prefix() -> &'static str {
+    static PREFIX: OnceLock<String> = OnceLock::new();
+    PREFIX.get_or_init(|| {
+        let seed = RandomState::new().build_hasher().finish();
+        format!("c{:016x}_", seed)
+    })
+}
+
+/// `n` blank nodes which are not used in `graph`, and whose label is not `taken`.
+///
+/// See [`CollectionGraph::fresh_bnode`](trait.CollectionGraph.html#method.fresh_bnode).
+pub(crate) fn fresh_bnodes<G, F>(graph: &G, n: usize, taken: F) -> GResult<G, Vec<BoxTerm>>
+where
+    G: Graph,
+    F: Fn(&str) -> bool,
+{
+    let prefix = fresh_prefix();
+    let mut bnodes = Vec::with_capacity(n);
+    while bnodes.len() < n {
+        let label = format!(
+            "{}{}",
+            prefix,
+            FRESH_COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+        if taken(&label) {
+            continue;
+        }
+        let bnode = BoxTerm::new_bnode(label).unwrap();
+        if !is_used(graph, &bnode)? {
+            bnodes.push(bnode);
+        }
+    }
+    Ok(bnodes)
+}
+
+/// Whether `term` is used as subject or object in `graph`.
+fn is_used<G, T>(graph: &G, term: &T) -> GResult<G, bool>
+where
+    G: Graph,
+    T: TTerm + ?Sized,
+{
+    Ok(graph.triples_with_s(term).next().transpose()?.is_some()
+        || graph.triples_with_o(term).next().transpose()?.is_some())
+}
+
+fn malformed<E, M>(node: BoxTerm, message: M) -> ListError<E>
+where
+    E: Error + 'static,
+    M: ToString,
+{
+    ListError::Malformed {
+        node,
+        message: message.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::inmem::FastGraph;
+    use crate::parser::turtle;
+    use sophia_api::triple::stream::TripleSource;
+
+    const TTL: &str = r#"
+        @prefix : <http://example.org/>.
+        @prefix rdf: <http://www.w3.org/1999/02/22-rdf-syntax-ns#>.
+
+        :ok :list (:a :b :c); :empty ().
+        :cyclic :list _:c1. _:c1 rdf:first :a; rdf:rest _:c2. _:c2 rdf:first :b; rdf:rest _:c1.
+        :two_firsts :list _:t1. _:t1 rdf:first :a, :b; rdf:rest rdf:nil.
+        :no_rest :list _:r1. _:r1 rdf:first :a.
+        :seq a rdf:Seq; rdf:_2 :b; rdf:_10 :c; rdf:_1 :a; rdf:_01 :x; rdf:value :y.
+    "#;
+
+    fn ex(suffix: &str) -> BoxTerm {
+        BoxTerm::new_iri(format!("http://example.org/{}", suffix)).unwrap()
+    }
+
+    fn graph() -> FastGraph {
+        turtle::parse_str(TTL).collect_triples().unwrap()
+    }
+
+    fn list_of(g: &FastGraph, s: &str) -> BoxTerm {
+        let (s, p) = (ex(s), ex("list"));
+        let mut heads = g.triples_with_sp(&s, &p);
+        BoxTerm::copy(heads.next().unwrap().unwrap().o())
+    }
+
+    #[test]
+    fn read() {
+        let g = graph();
+        let ok = list_of(&g, "ok");
+        assert_eq!(g.read_list(&ok).unwrap(), vec![ex("a"), ex("b"), ex("c")]);
+        assert!(g.read_list(&rdf::nil).unwrap().is_empty());
+        // the iterator is lazy, and stops after an error
+        let mut items = g.list_items(&list_of(&g, "cyclic"));
+        assert_eq!(items.next().unwrap().unwrap(), ex("a"));
+        assert_eq!(items.next().unwrap().unwrap(), ex("b"));
+        assert!(matches!(items.next(), Some(Err(ListError::Cyclic(_)))));
+        assert!(items.next().is_none());
+    }
+
+    #[test]
+    fn malformed() {
+        let g = graph();
+        for s in &["two_firsts", "no_rest"] {
+            assert!(
+                matches!(
+                    g.read_list(&list_of(&g, s)),
+                    Err(ListError::Malformed { .. })
+                ),
+                "{}",
+                s
+            );
+        }
+        assert!(matches!(
+            g.read_list(&ex("not_a_list")),
+            Err(ListError::Malformed { .. })
+        ));
+        let lit = BoxTerm::new_literal_dt("a", sophia_api::ns::xsd::string).unwrap();
+        assert!(matches!(
+            g.read_list(&lit),
+            Err(ListError::Malformed { .. })
+        ));
+    }
+
+    #[test]
+    fn write() {
+        let mut g = graph();
+        let before = g.triples().count();
+        let head = g.insert_list(vec![ex("x"), ex("y")]).unwrap();
+        assert_eq!(g.read_list(&head).unwrap(), vec![ex("x"), ex("y")]);
+        assert_eq!(g.triples().count(), before + 4);
+        assert_eq!(g.remove_list(&head).unwrap(), vec![ex("x"), ex("y")]);
+        assert_eq!(g.triples().count(), before);
+        assert_eq!(g.insert_list(Vec::<BoxTerm>::new()).unwrap(), rdf::nil);
+
+        let head = g
+            .replace_list(&ex("ok"), &ex("list"), vec![ex("z")])
+            .unwrap();
+        assert_eq!(list_of(&g, "ok"), head);
+        assert_eq!(g.read_list(&head).unwrap(), vec![ex("z")]);
+        assert_eq!(g.triples().count(), before - 4);
+
+        let cyclic = list_of(&g, "cyclic");
+        assert!(matches!(
+            g.replace_list(&ex("cyclic"), &ex("list"), vec![ex("z")]),
+            Err(ListError::Cyclic(_))
+        ));
+        assert_eq!(list_of(&g, "cyclic"), cyclic);
+    }
+
+    #[test]
+    fn containers() {
+        let mut g = graph();
+        assert_eq!(
+            g.container_items(&ex("seq")).unwrap(),
+            vec![ex("a"), ex("b"), ex("c")]
+        );
+        g.replace_container(&ex("seq"), vec![ex("c"), ex("c")])
+            .unwrap();
+        assert_eq!(
+            g.container_items(&ex("seq")).unwrap(),
+            vec![ex("c"), ex("c")]
+        );
+        assert_eq!(
+            g.remove_container(&ex("seq")).unwrap(),
+            vec![ex("c"), ex("c")]
+        );
+        assert!(!g.contains(&ex("seq"), &rdf::type_, &rdf::Seq).unwrap());
+        assert_eq!(g.triples_with_s(&ex("seq")).count(), 2);
+
+        let alt = g.insert_container(&rdf::Alt, vec![ex("a")]).unwrap();
+        assert!(g.contains(&alt, &rdf::type_, &rdf::Alt).unwrap());
+        assert_eq!(g.container_items(&alt).unwrap(), vec![ex("a")]);
+    }
+
+    #[test]
+    fn fresh_bnodes() {
+        let mut g: FastGraph = turtle::parse_str("_:c0 <tag:p> _:c1. _:c2 <tag:p> <tag:o>.")
+            .collect_triples()
+            .unwrap();
+        let fresh = g.fresh_bnode().unwrap();
+        assert_eq!(fresh.kind(), TermKind::BlankNode);
+        assert_eq!(g.triples_with_s(&fresh).count(), 0);
+        assert_eq!(g.triples_with_o(&fresh).count(), 0);
+        let head = g.insert_list(vec![ex("x"), ex("y")]).unwrap();
+        assert_eq!(g.read_list(&head).unwrap(), vec![ex("x"), ex("y")]);
+        assert_eq!(g.triples().count(), 6);
+        let fresh2 = g.fresh_bnode().unwrap();
+        assert_ne!(fresh2, fresh);
+        assert_eq!(g.triples_with_s(&fresh2).count(), 0);
+    }
+
+    #[test]
+    fn fresh_bnodes_in_quoted_triples() {
+        let mut g: FastGraph = turtle::parse_str("<tag:s> <tag:p> <tag:o>.")
+            .collect_triples()
+            .unwrap();
+        // blank nodes used only inside quoted triples
+        let mut quoted = vec![BoxTerm::new_bnode("c0").unwrap(), g.fresh_bnode().unwrap()];
+        for bnode in &quoted {
+            let triple = BoxTerm::new_triple(
+                bnode.clone(),
+                BoxTerm::copy(&rdf::value),
+                BoxTerm::copy(&rdf::nil),
+            );
+            g.insert(&triple, &rdf::type_, &rdf::Statement).unwrap();
+        }
+        let container = g.insert_container(&rdf::Bag, vec![ex("a")]).unwrap();
+        assert!(!quoted.contains(&container));
+        quoted.push(container);
+        g.insert_list(vec![ex("a"), ex("b")]).unwrap();
+        for t in g.triples_with_p(&rdf::first) {
+            assert!(!quoted.contains(&BoxTerm::copy(t.unwrap().s())));
+        }
+    }
+
+    #[test]
+    fn list_node_shape() {
+        let list = BoxTerm::copy(&rdf::List);
+        let first = BoxTerm::copy(&rdf::first);
+        let rest = BoxTerm::copy(&rdf::rest);
+        let type_ = BoxTerm::copy(&rdf::type_);
+        let nil = BoxTerm::copy(&rdf::nil);
+        let lit = BoxTerm::new_literal_dt("a", sophia_api::ns::xsd::string).unwrap();
+        assert!(is_list_node(vec![(&first, &lit), (&rest, &nil)]));
+        assert!(is_list_node(vec![
+            (&type_, &list),
+            (&first, &lit),
+            (&rest, &nil)
+        ]));
+        assert!(!is_list_node(vec![(&first, &lit)]));
+        assert!(!is_list_node(vec![(&first, &lit), (&rest, &lit)]));
+        assert!(!is_list_node(vec![
+            (&first, &lit),
+            (&first, &lit),
+            (&rest, &nil)
+        ]));
+        assert!(!is_list_node(vec![
+            (&type_, &nil),
+            (&first, &lit),
+            (&rest, &nil)
+        ]));
+        assert!(!is_list_node(vec![
+            (&first, &lit),
+            (&rest, &nil),
+            (&ex("p"), &lit)
+        ]));
+    }
+
+    #[test]
+    fn membership() {
+        assert_eq!(membership_index(&membership_property(12)), Some(12));
+        assert_eq!(membership_index(&rdf::first), None);
+        assert_eq!(membership_index(&ex("_1")), None);
+        assert_eq!(
+            membership_index(&BoxTerm::new_iri(format!("{}_0", rdf::PREFIX)).unwrap()),
+            None
+        );
+    }
+}
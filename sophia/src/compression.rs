@@ -0,0 +1,440 @@
+//! Transparent compression and decompression of RDF documents.
+//!
+//! With the `compression` feature enabled,
+//! [`Decompressor`] detects gzip, bzip2 and zstd data by their magic bytes,
+//! and decompresses them on the fly.
+//! Without the feature, [`Decompressor`] passes uncompressed data through,
+//! but fails with an explicit error on compressed data.
+//!
+//! The `parse_bufread` and `parse_file` functions of the [parser] modules
+//! always wrap their input in a [`Decompressor`],
+//! so that their signature does not depend on the feature,
+//! and compressed documents can be parsed like uncompressed ones when it is enabled.
+//!
+//! [`Compressor`] can be used to produce compressed output,
+//! for example with the [N-Triples](../serializer/nt/struct.NtSerializer.html#method.new_compressed)
+//! or [N-Quads](../serializer/nq/struct.NqSerializer.html#method.new_compressed) serializers.
+//!
+//! [`Decompressor`]: struct.Decompressor.html
+//! [`Compressor`]: struct.Compressor.html
+//! [parser]: ../parser/index.html
+
+use std::io::{self, BufRead, Read, Write};
+
+/// A compression format.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Compression {
+    Uncompressed,
+    Gzip,
+    Bzip2,
+    Zstd,
+}
+
+impl Compression {
+    /// Guess the compression of some data from its first bytes.
+    pub fn sniff(data: &[u8]) -> Compression {
+        if data.starts_with(&[0x1f, 0x8b]) {
+            Compression::Gzip
+        } else if data.starts_with(b"BZh") {
+            Compression::Bzip2
+        } else if data.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Compression::Zstd
+        } else {
+            Compression::Uncompressed
+        }
+    }
+
+    /// The compression format corresponding to the given file extension, if any.
+    pub fn from_extension(ext: &str) -> Option<Compression> {
+        match ext {
+            "gz" => Some(Compression::Gzip),
+            "bz2" => Some(Compression::Bzip2),
+            "zst" => Some(Compression::Zstd),
+            _ => None,
+        }
+    }
+
+    /// The usual file extension for this compression format.
+    pub fn extension(&self) -> Option<&'static str> {
+        match self {
+            Compression::Uncompressed => None,
+            Compression::Gzip => Some("gz"),
+            Compression::Bzip2 => Some("bz2"),
+            Compression::Zstd => Some("zst"),
+        }
+    }
+
+    /// Whether this compression format is supported by this build of Sophia.
+    pub fn is_supported(&self) -> bool {
+        *self == Compression::Uncompressed || cfg!(feature = "compression")
+    }
+}
+
+/// A [`BufRead`] decompressing the underlying data on the fly, if required.
+///
+/// The compression format is detected on the first read.
+/// Detection relies on the first bytes made available by the underlying reader,
+/// which must therefore not return less than 4 bytes on its first `fill_buf`
+/// (unless the data is shorter than that).
+///
+/// [`BufRead`]: https://doc.rust-lang.org/std/io/trait.BufRead.html
+pub struct Decompressor<B: BufRead> {
+    state: State<B>,
+}
+
+enum State<B: BufRead> {
+    // compression has not been detected yet
+    Unknown(B),
+    // only while detecting compression
+    Detecting,
+    Plain(B),
+    #[cfg(feature = "compression")]
+    Gzip(io::BufReader<flate2::bufread::MultiGzDecoder<B>>),
+    #[cfg(feature = "compression")]
+    Bzip2(io::BufReader<bzip2::bufread::MultiBzDecoder<B>>),
+    #[cfg(feature = "compression")]
+    Zstd(io::BufReader<zstd::Decoder<'static, B>>),
+}
+
+impl<B: BufRead> Decompressor<B> {
+    /// Wrap `data`, which may or may not be compressed.
+    ///
+    /// Without the `compression` feature,
+    /// reading compressed data fails with an [`InvalidData`] error.
+    ///
+    /// [`InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+    pub fn new(data: B) -> Self {
+        Decompressor {
+            state: State::Unknown(data),
+        }
+    }
+
+    /// The compression detected in the underlying data,
+    /// or `None` if it has not been detected yet (i.e. nothing has been read).
+    pub fn compression(&self) -> Option<Compression> {
+        match &self.state {
+            State::Unknown(_) | State::Detecting => None,
+            State::Plain(_) => Some(Compression::Uncompressed),
+            #[cfg(feature = "compression")]
+            State::Gzip(_) => Some(Compression::Gzip),
+            #[cfg(feature = "compression")]
+            State::Bzip2(_) => Some(Compression::Bzip2),
+            #[cfg(feature = "compression")]
+            State::Zstd(_) => Some(Compression::Zstd),
+        }
+    }
+
+    fn detect(&mut self) -> io::Result<()> {
+        match self.state {
+            State::Unknown(_) => (),
+            // a previous call failed after the underlying data was handed over to the decoder
+            State::Detecting => return Err(io::Error::other("failed to initialize decompression")),
+            _ => return Ok(()),
+        }
+        let mut data = match std::mem::replace(&mut self.state, State::Detecting) {
+            State::Unknown(data) => data,
+            _ => unreachable!(),
+        };
+        let compression = match data.fill_buf() {
+            Ok(buf) => Compression::sniff(buf),
+            Err(err) => {
+                self.state = State::Unknown(data);
+                return Err(err);
+            }
+        };
+        self.state = match compression {
+            Compression::Uncompressed => State::Plain(data),
+            #[cfg(feature = "compression")]
+            Compression::Gzip => State::Gzip(io::BufReader::new(
+                flate2::bufread::MultiGzDecoder::new(data),
+            )),
+            #[cfg(feature = "compression")]
+            Compression::Bzip2 => State::Bzip2(io::BufReader::new(
+                bzip2::bufread::MultiBzDecoder::new(data),
+            )),
+            #[cfg(feature = "compression")]
+            Compression::Zstd => State::Zstd(io::BufReader::new(zstd::Decoder::with_buffer(data)?)),
+            #[cfg(not(feature = "compression"))]
+            compression => {
+                self.state = State::Unknown(data);
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "{:?} compressed data requires the `compression` feature",
+                        compression
+                    ),
+                ));
+            }
+        };
+        Ok(())
+    }
+}
+
+impl<B: BufRead> Read for Decompressor<B> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.detect()?;
+        match &mut self.state {
+            State::Plain(data) => data.read(buf),
+            #[cfg(feature = "compression")]
+            State::Gzip(data) => data.read(buf),
+            #[cfg(feature = "compression")]
+            State::Bzip2(data) => data.read(buf),
+            #[cfg(feature = "compression")]
+            State::Zstd(data) => data.read(buf),
+            State::Unknown(_) | State::Detecting => unreachable!(),
+        }
+    }
+}
+
+impl<B: BufRead> BufRead for Decompressor<B> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.detect()?;
+        match &mut self.state {
+            State::Plain(data) => data.fill_buf(),
+            #[cfg(feature = "compression")]
+            State::Gzip(data) => data.fill_buf(),
+            #[cfg(feature = "compression")]
+            State::Bzip2(data) => data.fill_buf(),
+            #[cfg(feature = "compression")]
+            State::Zstd(data) => data.fill_buf(),
+            State::Unknown(_) | State::Detecting => unreachable!(),
+        }
+    }
+
+    fn consume(&mut self, amt: usize) {
+        match &mut self.state {
+            State::Unknown(data) | State::Plain(data) => data.consume(amt),
+            #[cfg(feature = "compression")]
+            State::Gzip(data) => data.consume(amt),
+            #[cfg(feature = "compression")]
+            State::Bzip2(data) => data.consume(amt),
+            #[cfg(feature = "compression")]
+            State::Zstd(data) => data.consume(amt),
+            State::Detecting => unreachable!(),
+        }
+    }
+}
+
+/// A [`Write`] compressing the data written to it.
+///
+/// [`finish`](#method.finish) must be called once all data has been written,
+/// in order to properly terminate the compressed stream.
+///
+/// [`Write`]: https://doc.rust-lang.org/std/io/trait.Write.html
+pub struct Compressor<W: Write> {
+    encoder: Encoder<W>,
+}
+
+enum Encoder<W: Write> {
+    Plain(W),
+    #[cfg(feature = "compression")]
+    Gzip(flate2::write::GzEncoder<W>),
+    #[cfg(feature = "compression")]
+    Bzip2(bzip2::write::BzEncoder<W>),
+    #[cfg(feature = "compression")]
+    Zstd(zstd::Encoder<'static, W>),
+}
+
+impl<W: Write> Compressor<W> {
+    /// Wrap `write`, compressing data with the given compression format.
+    ///
+    /// Fails if `compression` is not [supported](enum.Compression.html#method.is_supported).
+    pub fn new(write: W, compression: Compression) -> io::Result<Self> {
+        let encoder = match compression {
+            Compression::Uncompressed => Encoder::Plain(write),
+            #[cfg(feature = "compression")]
+            Compression::Gzip => Encoder::Gzip(flate2::write::GzEncoder::new(
+                write,
+                flate2::Compression::default(),
+            )),
+            #[cfg(feature = "compression")]
+            Compression::Bzip2 => Encoder::Bzip2(bzip2::write::BzEncoder::new(
+                write,
+                bzip2::Compression::default(),
+            )),
+            #[cfg(feature = "compression")]
+            Compression::Zstd => Encoder::Zstd(zstd::Encoder::new(write, 0)?),
+            #[cfg(not(feature = "compression"))]
+            _ => {
+                return Err(io::Error::other(format!(
+                    "{:?} compression requires the `compression` feature",
+                    compression
+                )))
+            }
+        };
+        Ok(Compressor { encoder })
+    }
+
+    /// Terminate the compressed stream, and return the underlying [`Write`].
+    ///
+    /// [`Write`]: https://doc.rust-lang.org/std/io/trait.Write.html
+    pub fn finish(self) -> io::Result<W> {
+        match self.encoder {
+            Encoder::Plain(write) => Ok(write),
+            #[cfg(feature = "compression")]
+            Encoder::Gzip(encoder) => encoder.finish(),
+            #[cfg(feature = "compression")]
+            Encoder::Bzip2(encoder) => encoder.finish(),
+            #[cfg(feature = "compression")]
+            Encoder::Zstd(encoder) => encoder.finish(),
+        }
+    }
+}
+
+impl<W: Write> Write for Compressor<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match &mut self.encoder {
+            Encoder::Plain(write) => write.write(buf),
+            #[cfg(feature = "compression")]
+            Encoder::Gzip(encoder) => encoder.write(buf),
+            #[cfg(feature = "compression")]
+            Encoder::Bzip2(encoder) => encoder.write(buf),
+            #[cfg(feature = "compression")]
+            Encoder::Zstd(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.encoder {
+            Encoder::Plain(write) => write.flush(),
+            #[cfg(feature = "compression")]
+            Encoder::Gzip(encoder) => encoder.flush(),
+            #[cfg(feature = "compression")]
+            Encoder::Bzip2(encoder) => encoder.flush(),
+            #[cfg(feature = "compression")]
+            Encoder::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// Define the convenience functions `parse_bufread`, `parse_str` and `parse_file`
+/// in a parser module.
+///
+/// Unlike `sophia_api::def_mod_functions_for_bufread_parser`,
+/// `parse_bufread` and `parse_file` wrap their input in a [`Decompressor`],
+/// regardless of the `compression` feature.
+#[rustfmt::skip] // rustfmt mangles the return type of parse_bufread
+macro_rules! def_mod_functions_for_bufread_parser {
+    ($parser_type: ident, $parser_trait: ident) => {
+        /// Convenience function for parsing a BufRead with the default parser.
+        ///
+        /// With the `compression` feature, compressed data is transparently decompressed
+        /// (see [`Decompressor`](../../compression/struct.Decompressor.html)).
+        pub fn parse_bufread<B: std::io::BufRead>(
+            bufread: B,
+        ) -> <$parser_type as $crate::parser::$parser_trait<
+            $crate::compression::Decompressor<B>,
+        >>::Source {
+            $parser_type::default().parse($crate::compression::Decompressor::new(bufread))
+        }
+
+        /// Convenience function for parsing a str with the default parser.
+        pub fn parse_str(
+            txt: &str,
+        ) -> <$parser_type as $crate::parser::$parser_trait<&[u8]>>::Source {
+            $parser_type::default().parse_str(txt)
+        }
+
+        /// Convenience function for parsing a file with the default parser.
+        ///
+        /// With the `compression` feature, compressed files are transparently decompressed
+        /// (see [`Decompressor`](../../compression/struct.Decompressor.html)).
+        pub fn parse_file<P: AsRef<std::path::Path>>(
+            path: P,
+        ) -> std::io::Result<
+            <$parser_type as $crate::parser::$parser_trait<
+                $crate::compression::Decompressor<std::io::BufReader<std::fs::File>>,
+            >>::Source,
+        > {
+            let file = std::fs::File::open(path)?;
+            Ok(parse_bufread(std::io::BufReader::new(file)))
+        }
+    };
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_case::test_case;
+
+    const NT: &str = "<tag:s> <tag:p> \"o\" .\n";
+
+    #[test_case(&[0x1f, 0x8b, 8, 0], Compression::Gzip)]
+    #[test_case(b"BZh91AY", Compression::Bzip2)]
+    #[test_case(&[0x28, 0xb5, 0x2f, 0xfd, 0], Compression::Zstd)]
+    #[test_case(b"<tag:s> <tag:p> <tag:o> .", Compression::Uncompressed)]
+    #[test_case(b"", Compression::Uncompressed)]
+    fn sniff(data: &[u8], exp: Compression) {
+        assert_eq!(Compression::sniff(data), exp);
+    }
+
+    #[test]
+    fn uncompressed() -> io::Result<()> {
+        let mut d = Decompressor::new(NT.as_bytes());
+        let mut txt = String::new();
+        d.read_to_string(&mut txt)?;
+        assert_eq!(txt, NT);
+        assert_eq!(d.compression(), Some(Compression::Uncompressed));
+        Ok(())
+    }
+
+    #[cfg(feature = "compression")]
+    #[test_case(Compression::Gzip)]
+    #[test_case(Compression::Bzip2)]
+    #[test_case(Compression::Zstd)]
+    fn round_trip(compression: Compression) {
+        let mut c = Compressor::new(vec![], compression).unwrap();
+        c.write_all(NT.as_bytes()).unwrap();
+        let compressed = c.finish().unwrap();
+        assert_eq!(Compression::sniff(&compressed), compression);
+
+        let mut d = Decompressor::new(&compressed[..]);
+        let mut txt = String::new();
+        d.read_to_string(&mut txt).unwrap();
+        assert_eq!(txt, NT);
+        assert_eq!(d.compression(), Some(compression));
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn parse_and_serialize_compressed() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::graph::inmem::FastGraph;
+        use crate::graph::Graph;
+        use crate::serializer::nt::NtSerializer;
+        use crate::serializer::TripleSerializer;
+        use crate::triple::stream::TripleSource;
+
+        let g: FastGraph = crate::parser::nt::parse_str(NT).collect_triples()?;
+        let mut ser = NtSerializer::new_compressed(vec![], Compression::Gzip)?;
+        ser.serialize_graph(&g)?;
+        let compressed = ser.finish()?;
+        assert_eq!(Compression::sniff(&compressed), Compression::Gzip);
+
+        let g2: FastGraph = crate::parser::nt::parse_bufread(&compressed[..]).collect_triples()?;
+        assert_eq!(g2.triples().count(), 1);
+        Ok(())
+    }
+
+    #[cfg(not(feature = "compression"))]
+    #[test]
+    fn unsupported() {
+        use crate::term::BoxTerm;
+        use crate::triple::stream::TripleSource;
+
+        assert!(!Compression::Gzip.is_supported());
+        assert!(Compressor::new(vec![], Compression::Gzip).is_err());
+
+        let mut d = Decompressor::new(&[0x1f, 0x8b, 8, 0][..]);
+        let err = d.read_to_end(&mut vec![]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("`compression` feature"));
+
+        let mut v = Vec::<[BoxTerm; 3]>::new();
+        let res = crate::parser::nt::parse_bufread(&[0x1f, 0x8b, 8, 0][..]).add_to_graph(&mut v);
+        assert!(res.is_err());
+    }
+}
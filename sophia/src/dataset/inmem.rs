@@ -31,6 +31,8 @@ mod _gspo_wrapper;
 pub use self::_gspo_wrapper::*;
 mod _ogps_wrapper;
 pub use self::_ogps_wrapper::*;
+#[cfg(feature = "serde")]
+mod _serde;
 
 /// A generic in-memory dataset.
 ///
@@ -177,7 +177,7 @@ where
     type Quad = ByTermRefs<Term<<Self as IndexedDataset>::TermData>>;
     type Error = Infallible;
 
-    fn quads(&self) -> DQuadSource<Self> {
+    fn quads(&self) -> DQuadSource<'_, Self> {
         Box::from(self.quads.iter().map(move |[si, pi, oi, gi]| {
             Ok(StreamedQuad::by_term_refs(
                 self.terms.get_term(*si).unwrap(),
@@ -0,0 +1,192 @@
+// this module is transparently re-exported by its parent `dataset::inmem`
+// (only available with the `serde` feature)
+//
+// Datasets are serialized as a pair `(terms, quads)`,
+// where `terms` is a sequence of distinct terms,
+// and `quads` is a sequence of `([u32; 3], Option<u32>)` indexing those terms.
+// This mirrors the encoding of graphs in `graph::inmem`.
+
+use std::hash::Hash;
+
+use super::*;
+use crate::graph::inmem::{get_term, DenseIndex};
+use serde::ser::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sophia_api::quad::streaming_mode::ByTermRefs;
+use sophia_api::quad::Quad;
+use sophia_term::index_map::TermIndexMap;
+
+impl<I> Serialize for HashDataset<I>
+where
+    I: TermIndexMap,
+    I::Index: Hash,
+    <I::Factory as TermFactory>::TermData: 'static,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_indexed(self, serializer)
+    }
+}
+
+impl<'de, I> Deserialize<'de> for HashDataset<I>
+where
+    I: TermIndexMap,
+    I::Index: Hash,
+    <I::Factory as TermFactory>::TermData: 'static,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_indexed(deserializer)
+    }
+}
+
+impl<T> Serialize for GspoWrapper<T>
+where
+    T: IndexedDataset + Dataset<Quad = ByTermRefs<Term<<T as IndexedDataset>::TermData>>>,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_indexed(self, serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for GspoWrapper<T>
+where
+    T: IndexedDataset + Dataset<Quad = ByTermRefs<Term<<T as IndexedDataset>::TermData>>>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_indexed(deserializer)
+    }
+}
+
+impl<T> Serialize for OgpsWrapper<T>
+where
+    T: IndexedDataset + Dataset<Quad = ByTermRefs<Term<<T as IndexedDataset>::TermData>>>,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_indexed(self, serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for OgpsWrapper<T>
+where
+    T: IndexedDataset + Dataset<Quad = ByTermRefs<Term<<T as IndexedDataset>::TermData>>>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_indexed(deserializer)
+    }
+}
+
+fn serialize_indexed<D, S>(dataset: &D, serializer: S) -> Result<S::Ok, S::Error>
+where
+    D: IndexedDataset + Dataset<Quad = ByTermRefs<Term<<D as IndexedDataset>::TermData>>>,
+    S: Serializer,
+{
+    let mut index = DenseIndex::new();
+    let mut quads = Vec::new();
+    for q in dataset.quads() {
+        let q = q.map_err(S::Error::custom)?;
+        let mut spo = [0; 3];
+        for (dense, term) in spo.iter_mut().zip([q.s(), q.p(), q.o()]) {
+            *dense = index.get(dataset.get_index(term), |i| dataset.get_term(i))?;
+        }
+        let g = match q.g() {
+            Some(g) => Some(index.get(dataset.get_index(g), |i| dataset.get_term(i))?),
+            None => None,
+        };
+        quads.push((spo, g));
+    }
+    (index.terms, quads).serialize(serializer)
+}
+
+fn deserialize_indexed<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: IndexedDataset,
+    D: Deserializer<'de>,
+{
+    type Quads = Vec<([u32; 3], Option<u32>)>;
+    let (terms, quads) = <(Vec<BoxTerm>, Quads)>::deserialize(deserializer)?;
+    let mut dataset = T::with_capacity(quads.len());
+    for ([s, p, o], g) in quads {
+        let g = match g {
+            Some(g) => Some(get_term::<D::Error>(&terms, g)?),
+            None => None,
+        };
+        dataset.insert_indexed(
+            get_term::<D::Error>(&terms, s)?,
+            get_term::<D::Error>(&terms, p)?,
+            get_term::<D::Error>(&terms, o)?,
+            g,
+        );
+    }
+    Ok(dataset)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sophia_api::ns::{rdf, rdfs};
+    use sophia_api::quad::stream::QuadSource;
+
+    fn dataset() -> FastDataset {
+        let mut d = FastDataset::new();
+        let alice = BoxTerm::new_iri("http://example.org/alice").unwrap();
+        let g1 = BoxTerm::new_iri("http://example.org/g1").unwrap();
+        let b1 = BoxTerm::new_bnode("b1").unwrap();
+        d.insert(
+            &alice,
+            &rdf::type_,
+            &rdfs::Resource,
+            None as Option<&BoxTerm>,
+        )
+        .unwrap();
+        d.insert(&alice, &rdf::type_, &rdfs::Resource, Some(&g1))
+            .unwrap();
+        d.insert(&alice, &rdfs::seeAlso, &b1, Some(&b1)).unwrap();
+        d.insert(
+            &g1,
+            &rdfs::label,
+            &BoxTerm::new_literal_lang("graph 1", "en").unwrap(),
+            Some(&g1),
+        )
+        .unwrap();
+        d
+    }
+
+    fn check_same(d1: &FastDataset, d2: &FastDataset) {
+        assert_eq!(d1.quads().count(), d2.quads().count());
+        d1.quads()
+            .for_each_quad(|q| assert!(d2.contains(q.s(), q.p(), q.o(), q.g()).unwrap()))
+            .unwrap();
+        // also check that the GSPO and OGPS indexes were rebuilt
+        assert_eq!(
+            d1.quads_with_o(&rdfs::Resource).count(),
+            d2.quads_with_o(&rdfs::Resource).count()
+        );
+        assert_eq!(
+            d1.graph_names().unwrap().len(),
+            d2.graph_names().unwrap().len()
+        );
+    }
+
+    #[test]
+    fn json_roundtrip() {
+        let d = dataset();
+        let json = serde_json::to_string(&d).unwrap();
+        let back: FastDataset = serde_json::from_str(&json).unwrap();
+        check_same(&d, &back);
+    }
+
+    #[test]
+    fn bincode_roundtrip() {
+        let d = dataset();
+        let data = bincode::serialize(&d).unwrap();
+        let back: FastDataset = bincode::deserialize(&data).unwrap();
+        check_same(&d, &back);
+        let back: LightDataset = bincode::deserialize(&data).unwrap();
+        assert_eq!(back.quads().count(), 4);
+    }
+
+    #[test]
+    fn out_of_range() {
+        let json = r#"[[{"Iri":"tag:a"}],[[[0,0,0],1]]]"#;
+        assert!(serde_json::from_str::<FastDataset>(json).is_err());
+    }
+}
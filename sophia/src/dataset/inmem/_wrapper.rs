@@ -31,7 +31,7 @@ pub trait DatasetWrapper {
 
     #[inline]
     /// Mimmic the [`iter`](../trait.Dataset.html#tymethod.iter) method.
-    fn dw_quads(&self) -> DQuadSource<Self::Wrapped> {
+    fn dw_quads(&self) -> DQuadSource<'_, Self::Wrapped> {
         self.get_wrapped().quads()
     }
 
@@ -330,7 +330,7 @@ macro_rules! impl_dataset_for_wrapper {
         type Error = <<Self as $crate::dataset::inmem::DatasetWrapper>::Wrapped as $crate::dataset::Dataset>::Error;
 
         #[inline]
-        fn quads(&self) -> $crate::dataset::DQuadSource<Self> {
+        fn quads(&self) -> $crate::dataset::DQuadSource<'_, Self> {
             DatasetWrapper::dw_quads(self)
         }
         #[inline]
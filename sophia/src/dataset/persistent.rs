@@ -0,0 +1,384 @@
+//! A persistent implementation of RDF datasets,
+//! stored on disk in a [sled](https://docs.rs/sled) database
+//! (only available with the `persistent` feature).
+//!
+//! This works exactly as [`PersistentGraph`],
+//! except that quads are stored in six indexes
+//! (GSPO, GPOS, GOSP, SPOG, POSG and OSPG),
+//! and that the default graph is stored under a reserved identifier.
+//!
+//! ```
+//! # use sophia::dataset::{Dataset, MutableDataset};
+//! # use sophia::dataset::persistent::PersistentDataset;
+//! # use sophia::ns::{rdf, rdfs};
+//! # use sophia::term::BoxTerm;
+//! # let dir = tempfile::tempdir()?;
+//! # let path = dir.path().join("db");
+//! let mut dataset = PersistentDataset::open(&path)?;
+//! dataset.insert(&rdfs::Class, &rdf::type_, &rdfs::Class, Some(&rdfs::Class))?;
+//! assert_eq!(dataset.quads_with_g(Some(&rdfs::Class)).count(), 1);
+//! assert_eq!(dataset.quads_with_g(None as Option<&BoxTerm>).count(), 0);
+//! dataset.flush()?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [`PersistentGraph`]: ../../graph/persistent/struct.PersistentGraph.html
+
+use crate::graph::persistent::{Bound, PersistentError, Store};
+use sophia_api::dataset::{DQuadSource, DResult, Dataset, MDResult, MutableDataset, SetDataset};
+use sophia_api::quad::streaming_mode::{ByValue, StreamedQuad};
+use sophia_api::term::TTerm;
+use sophia_term::BoxTerm;
+use std::path::Path;
+
+/// The identifier of the default graph.
+const DEFAULT_GRAPH: u64 = 0;
+
+/// The trees used to index quads, in (S, P, O, G) order.
+const INDEXES: [(&str, [usize; 4]); 6] = [
+    ("gspo", [3, 0, 1, 2]),
+    ("gpos", [3, 1, 2, 0]),
+    ("gosp", [3, 2, 0, 1]),
+    ("spog", [0, 1, 2, 3]),
+    ("posg", [1, 2, 0, 3]),
+    ("ospg", [2, 0, 1, 3]),
+];
+
+/// A dataset stored on disk.
+///
+/// See the [module documentation](index.html) for more details.
+pub struct PersistentDataset {
+    store: Store<4>,
+}
+
+impl PersistentDataset {
+    /// Open (or create) the dataset stored in the directory `path`.
+    ///
+    /// The directory is locked as long as the dataset is open;
+    /// NB that sled may release this lock slightly *after* the dataset is dropped.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, PersistentError> {
+        Self::from_db(&sled::open(path)?)
+    }
+
+    /// Open (or create) the dataset stored in `db`.
+    ///
+    /// The same database can also hold a
+    /// [`PersistentGraph`](../../graph/persistent/struct.PersistentGraph.html),
+    /// sharing the same term dictionary.
+    pub fn from_db(db: &sled::Db) -> Result<Self, PersistentError> {
+        Ok(PersistentDataset {
+            store: Store::new(db, &INDEXES)?,
+        })
+    }
+
+    /// Create a dataset in a temporary database, which is deleted when the dataset is dropped.
+    pub fn temporary() -> Result<Self, PersistentError> {
+        Self::from_db(&sled::Config::new().temporary(true).open()?)
+    }
+
+    /// The number of quads in this dataset.
+    pub fn len(&self) -> usize {
+        self.store.len()
+    }
+
+    /// Whether this dataset is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Write all pending changes to disk,
+    /// and return the number of bytes flushed.
+    ///
+    /// Changes are also flushed periodically, and when the dataset is dropped.
+    pub fn flush(&self) -> Result<usize, PersistentError> {
+        self.store.flush()
+    }
+
+    fn scan(&self, terms: [Bound; 4]) -> DQuadSource<'_, Self> {
+        Box::new(self.store.matching(terms).map(move |ids| {
+            let [s, p, o, g] = ids?;
+            let g = match g {
+                DEFAULT_GRAPH => None,
+                g => Some(self.store.term(g)?),
+            };
+            Ok(StreamedQuad::by_value((
+                [
+                    self.store.term(s)?,
+                    self.store.term(p)?,
+                    self.store.term(o)?,
+                ],
+                g,
+            )))
+        }))
+    }
+}
+
+fn bound<T: TTerm + ?Sized>(t: &T) -> Bound<'_> {
+    Some(Some(t.as_dyn()))
+}
+
+fn graph_bound<T: TTerm + ?Sized>(g: Option<&T>) -> Bound<'_> {
+    Some(g.map(TTerm::as_dyn))
+}
+
+impl Dataset for PersistentDataset {
+    type Quad = ByValue<([BoxTerm; 3], Option<BoxTerm>)>;
+    type Error = PersistentError;
+
+    fn quads(&self) -> DQuadSource<'_, Self> {
+        self.scan([None, None, None, None])
+    }
+    fn quads_with_s<'s, TS>(&'s self, s: &'s TS) -> DQuadSource<'s, Self>
+    where
+        TS: TTerm + ?Sized,
+    {
+        self.scan([bound(s), None, None, None])
+    }
+    fn quads_with_p<'s, TP>(&'s self, p: &'s TP) -> DQuadSource<'s, Self>
+    where
+        TP: TTerm + ?Sized,
+    {
+        self.scan([None, bound(p), None, None])
+    }
+    fn quads_with_o<'s, TO>(&'s self, o: &'s TO) -> DQuadSource<'s, Self>
+    where
+        TO: TTerm + ?Sized,
+    {
+        self.scan([None, None, bound(o), None])
+    }
+    fn quads_with_g<'s, TG>(&'s self, g: Option<&'s TG>) -> DQuadSource<'s, Self>
+    where
+        TG: TTerm + ?Sized,
+    {
+        self.scan([None, None, None, graph_bound(g)])
+    }
+    fn quads_with_sp<'s, TS, TP>(&'s self, s: &'s TS, p: &'s TP) -> DQuadSource<'s, Self>
+    where
+        TS: TTerm + ?Sized,
+        TP: TTerm + ?Sized,
+    {
+        self.scan([bound(s), bound(p), None, None])
+    }
+    fn quads_with_so<'s, TS, TO>(&'s self, s: &'s TS, o: &'s TO) -> DQuadSource<'s, Self>
+    where
+        TS: TTerm + ?Sized,
+        TO: TTerm + ?Sized,
+    {
+        self.scan([bound(s), None, bound(o), None])
+    }
+    fn quads_with_sg<'s, TS, TG>(&'s self, s: &'s TS, g: Option<&'s TG>) -> DQuadSource<'s, Self>
+    where
+        TS: TTerm + ?Sized,
+        TG: TTerm + ?Sized,
+    {
+        self.scan([bound(s), None, None, graph_bound(g)])
+    }
+    fn quads_with_po<'s, TP, TO>(&'s self, p: &'s TP, o: &'s TO) -> DQuadSource<'s, Self>
+    where
+        TP: TTerm + ?Sized,
+        TO: TTerm + ?Sized,
+    {
+        self.scan([None, bound(p), bound(o), None])
+    }
+    fn quads_with_pg<'s, TP, TG>(&'s self, p: &'s TP, g: Option<&'s TG>) -> DQuadSource<'s, Self>
+    where
+        TP: TTerm + ?Sized,
+        TG: TTerm + ?Sized,
+    {
+        self.scan([None, bound(p), None, graph_bound(g)])
+    }
+    fn quads_with_og<'s, TO, TG>(&'s self, o: &'s TO, g: Option<&'s TG>) -> DQuadSource<'s, Self>
+    where
+        TO: TTerm + ?Sized,
+        TG: TTerm + ?Sized,
+    {
+        self.scan([None, None, bound(o), graph_bound(g)])
+    }
+    fn quads_with_spo<'s, TS, TP, TO>(
+        &'s self,
+        s: &'s TS,
+        p: &'s TP,
+        o: &'s TO,
+    ) -> DQuadSource<'s, Self>
+    where
+        TS: TTerm + ?Sized,
+        TP: TTerm + ?Sized,
+        TO: TTerm + ?Sized,
+    {
+        self.scan([bound(s), bound(p), bound(o), None])
+    }
+    fn quads_with_spg<'s, TS, TP, TG>(
+        &'s self,
+        s: &'s TS,
+        p: &'s TP,
+        g: Option<&'s TG>,
+    ) -> DQuadSource<'s, Self>
+    where
+        TS: TTerm + ?Sized,
+        TP: TTerm + ?Sized,
+        TG: TTerm + ?Sized,
+    {
+        self.scan([bound(s), bound(p), None, graph_bound(g)])
+    }
+    fn quads_with_sog<'s, TS, TO, TG>(
+        &'s self,
+        s: &'s TS,
+        o: &'s TO,
+        g: Option<&'s TG>,
+    ) -> DQuadSource<'s, Self>
+    where
+        TS: TTerm + ?Sized,
+        TO: TTerm + ?Sized,
+        TG: TTerm + ?Sized,
+    {
+        self.scan([bound(s), None, bound(o), graph_bound(g)])
+    }
+    fn quads_with_pog<'s, TP, TO, TG>(
+        &'s self,
+        p: &'s TP,
+        o: &'s TO,
+        g: Option<&'s TG>,
+    ) -> DQuadSource<'s, Self>
+    where
+        TP: TTerm + ?Sized,
+        TO: TTerm + ?Sized,
+        TG: TTerm + ?Sized,
+    {
+        self.scan([None, bound(p), bound(o), graph_bound(g)])
+    }
+    fn quads_with_spog<'s, TS, TP, TO, TG>(
+        &'s self,
+        s: &'s TS,
+        p: &'s TP,
+        o: &'s TO,
+        g: Option<&'s TG>,
+    ) -> DQuadSource<'s, Self>
+    where
+        TS: TTerm + ?Sized,
+        TP: TTerm + ?Sized,
+        TO: TTerm + ?Sized,
+        TG: TTerm + ?Sized,
+    {
+        self.scan([bound(s), bound(p), bound(o), graph_bound(g)])
+    }
+    fn contains<'s, TS, TP, TO, TG>(
+        &'s self,
+        s: &'s TS,
+        p: &'s TP,
+        o: &'s TO,
+        g: Option<&'s TG>,
+    ) -> DResult<Self, bool>
+    where
+        TS: TTerm + ?Sized,
+        TP: TTerm + ?Sized,
+        TO: TTerm + ?Sized,
+        TG: TTerm + ?Sized,
+    {
+        match self.quads_with_spog(s, p, o, g).next() {
+            None => Ok(false),
+            Some(res) => res.map(|_| true),
+        }
+    }
+}
+
+impl MutableDataset for PersistentDataset {
+    type MutationError = PersistentError;
+
+    fn insert<TS, TP, TO, TG>(
+        &mut self,
+        s: &TS,
+        p: &TP,
+        o: &TO,
+        g: Option<&TG>,
+    ) -> MDResult<Self, bool>
+    where
+        TS: TTerm + ?Sized,
+        TP: TTerm + ?Sized,
+        TO: TTerm + ?Sized,
+        TG: TTerm + ?Sized,
+    {
+        let ids = [
+            self.store.get_or_insert_id(s)?,
+            self.store.get_or_insert_id(p)?,
+            self.store.get_or_insert_id(o)?,
+            match g {
+                None => DEFAULT_GRAPH,
+                Some(g) => self.store.get_or_insert_id(g)?,
+            },
+        ];
+        self.store.insert(ids)
+    }
+    fn remove<TS, TP, TO, TG>(
+        &mut self,
+        s: &TS,
+        p: &TP,
+        o: &TO,
+        g: Option<&TG>,
+    ) -> MDResult<Self, bool>
+    where
+        TS: TTerm + ?Sized,
+        TP: TTerm + ?Sized,
+        TO: TTerm + ?Sized,
+        TG: TTerm + ?Sized,
+    {
+        match self
+            .store
+            .ids([bound(s), bound(p), bound(o), graph_bound(g)])?
+        {
+            Some([Some(s), Some(p), Some(o), Some(g)]) => self.store.remove([s, p, o, g]),
+            _ => Ok(false),
+        }
+    }
+}
+
+impl SetDataset for PersistentDataset {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::persistent::reopen;
+    use sophia_api::ns::{rdf, rdfs};
+    use sophia_api::quad::stream::QuadSource;
+    use sophia_api::test_dataset_impl;
+    use sophia_api::triple::stream::{SinkError, StreamResult};
+
+    fn collect<QS: QuadSource>(
+        quads: QS,
+    ) -> StreamResult<PersistentDataset, QS::Error, PersistentError> {
+        let mut dataset = PersistentDataset::temporary().map_err(SinkError)?;
+        dataset.insert_all(quads)?;
+        Ok(dataset)
+    }
+
+    test_dataset_impl!(test, PersistentDataset, true, true, collect);
+
+    #[test]
+    fn persistence() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("db");
+        let bnode = BoxTerm::new_bnode("b").unwrap();
+        {
+            let mut d = PersistentDataset::open(&path).unwrap();
+            d.insert(&bnode, &rdf::type_, &rdfs::Class, Some(&bnode))
+                .unwrap();
+            d.insert(&bnode, &rdf::type_, &rdfs::Class, None as Option<&BoxTerm>)
+                .unwrap();
+            d.insert(&rdfs::Class, &rdf::type_, &rdfs::Class, Some(&bnode))
+                .unwrap();
+            d.remove(&rdfs::Class, &rdf::type_, &rdfs::Class, Some(&bnode))
+                .unwrap();
+            d.flush().unwrap();
+        }
+        let d = PersistentDataset::from_db(&reopen(&path)).unwrap();
+        assert_eq!(d.len(), 2);
+        assert!(d
+            .contains(&bnode, &rdf::type_, &rdfs::Class, Some(&bnode))
+            .unwrap());
+        assert!(d
+            .contains(&bnode, &rdf::type_, &rdfs::Class, None as Option<&BoxTerm>)
+            .unwrap());
+        assert_eq!(d.quads_with_g(None as Option<&BoxTerm>).count(), 1);
+        assert_eq!(d.quads_with_g(Some(&bnode)).count(), 1);
+        assert_eq!(d.quads_with_s(&rdfs::Class).count(), 0);
+    }
+}
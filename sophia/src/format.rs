@@ -0,0 +1,708 @@
+//! A registry of the RDF concrete syntaxes known to Sophia.
+//!
+//! [`Format`] maps media types and file extensions to the corresponding parsers and serializers,
+//! and can guess the format of some data from its first bytes.
+//!
+//! Since the parsers of the different formats produce different types of sources,
+//! the functions of this module return a [`BoxQuadSource`],
+//! where all terms are copied into [`BoxTerm`]s,
+//! and triples are converted into quads in the default graph.
+//! When the format is known in advance,
+//! using the corresponding parser directly is more efficient.
+//!
+//! ```
+//! # use sophia::format::*;
+//! # use sophia::dataset::inmem::FastDataset;
+//! # use sophia::quad::stream::QuadSource;
+//! let ttl = r#"
+//!     @prefix : <http://example.org/> .
+//!     :alice :knows :bob .
+//! "#;
+//! let source = parse_with_media_type(ttl.as_bytes(), "text/turtle; charset=utf-8")?;
+//! let dataset: FastDataset = source.collect_quads()?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! The functions of this module transparently decompress their input
+//! when the `compression` feature is enabled (see [`compression`]).
+//!
+//! Parsers implemented in other crates can be plugged into this module
+//! with [`Format::register_parser`]
+//! (see for example `sophia_jsonld::parser::register_format`).
+//!
+//! [`Format`]: enum.Format.html
+//! [`Format::register_parser`]: enum.Format.html#method.register_parser
+//! [`BoxQuadSource`]: type.BoxQuadSource.html
+//! [`BoxTerm`]: ../term/type.BoxTerm.html
+//! [`compression`]: ../compression/index.html
+
+use crate::compression::{Compression, Decompressor};
+use crate::parser::{gtrig, n3, nq, nt, ntq, trig, turtle};
+use crate::parser::{QuadParser, TripleParser};
+use crate::quad::stream::QuadSource;
+use crate::quad::Quad;
+use crate::serializer::nq::NqSerializer;
+use crate::serializer::nt::NtSerializer;
+use crate::serializer::{QuadSerializer, TripleSerializer};
+use crate::triple::stream::{SinkError, SourceError, TripleSource};
+use crate::triple::Triple;
+use sophia_api::term::CopyTerm;
+use sophia_term::BoxTerm;
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+use std::sync::RwLock;
+
+/// An RDF concrete syntax.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum Format {
+    NTriples,
+    NQuads,
+    Turtle,
+    TriG,
+    /// Generalized TriG, allowing any kind of term in any position.
+    GTriG,
+    N3,
+    RdfXml,
+    TriX,
+    /// JSON-LD is implemented in the `sophia_jsonld` crate, which depends on this one.
+    /// [`Format::parse`](#method.parse) therefore returns
+    /// [`FormatError::Unsupported`](enum.FormatError.html#variant.Unsupported) for it,
+    /// unless `sophia_jsonld::parser::register_format` has been called.
+    JsonLd,
+}
+
+impl Format {
+    /// All the known formats.
+    pub const ALL: &'static [Format] = &[
+        Format::NTriples,
+        Format::NQuads,
+        Format::Turtle,
+        Format::TriG,
+        Format::GTriG,
+        Format::N3,
+        Format::RdfXml,
+        Format::TriX,
+        Format::JsonLd,
+    ];
+
+    /// The media types of this format, the preferred one first.
+    ///
+    /// Generic media types (such as `text/plain` or `application/xml`)
+    /// are deliberately not included, as they are used by many non-RDF documents.
+    pub fn media_types(&self) -> &'static [&'static str] {
+        match self {
+            Format::NTriples => &["application/n-triples"],
+            Format::NQuads => &["application/n-quads"],
+            Format::Turtle => &["text/turtle"],
+            Format::TriG => &["application/trig"],
+            Format::GTriG => &["application/x-gtrig"],
+            Format::N3 => &["text/n3"],
+            Format::RdfXml => &["application/rdf+xml"],
+            Format::TriX => &["application/trix"],
+            Format::JsonLd => &["application/ld+json"],
+        }
+    }
+
+    /// The preferred media type of this format.
+    pub fn media_type(&self) -> &'static str {
+        self.media_types()[0]
+    }
+
+    /// The file extensions of this format (without the dot), the preferred one first.
+    ///
+    /// Generic extensions (such as `.xml` or `.json`) are deliberately not included.
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            Format::NTriples => &["nt"],
+            Format::NQuads => &["nq"],
+            Format::Turtle => &["ttl"],
+            Format::TriG => &["trig"],
+            Format::GTriG => &["gtrig"],
+            Format::N3 => &["n3"],
+            Format::RdfXml => &["rdf"],
+            Format::TriX => &["trix"],
+            Format::JsonLd => &["jsonld"],
+        }
+    }
+
+    /// The format with the given media type, if any.
+    ///
+    /// The comparison is case-insensitive, and parameters (such as `charset`) are ignored.
+    pub fn from_media_type(media_type: &str) -> Option<Format> {
+        let media_type = media_type.split(';').next().unwrap().trim();
+        Format::ALL.iter().copied().find(|f| {
+            f.media_types()
+                .iter()
+                .any(|mt| mt.eq_ignore_ascii_case(media_type))
+        })
+    }
+
+    /// The format with the given file extension (without the dot), if any.
+    ///
+    /// The comparison is case-insensitive.
+    pub fn from_extension(extension: &str) -> Option<Format> {
+        Format::ALL.iter().copied().find(|f| {
+            f.extensions()
+                .iter()
+                .any(|ext| ext.eq_ignore_ascii_case(extension))
+        })
+    }
+
+    /// The format of the file at `path`, according to its extension, if any.
+    ///
+    /// The extension of a compressed file (e.g. `.gz`) is ignored,
+    /// so `data.nt.gz` is recognized as N-Triples.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Format> {
+        let path = path.as_ref();
+        let mut ext = path.extension().and_then(|ext| ext.to_str())?;
+        if Compression::from_extension(ext).is_some() {
+            ext = Path::new(path.file_stem()?)
+                .extension()
+                .and_then(|ext| ext.to_str())?;
+        }
+        Format::from_extension(ext)
+    }
+
+    /// Guess the format of a document from its first bytes.
+    ///
+    /// This is only a heuristic, which may fail on unusual documents.
+    pub fn sniff(data: &[u8]) -> Option<Format> {
+        let txt = match std::str::from_utf8(data) {
+            Ok(txt) => txt,
+            // data may have been truncated in the middle of a char
+            Err(err) => std::str::from_utf8(&data[..err.valid_up_to()]).unwrap(),
+        };
+        let txt = txt.trim_start_matches('\u{feff}').trim_start();
+        if txt.starts_with("<TriX") || (txt.starts_with("<?xml") && txt.contains("<TriX")) {
+            return Some(Format::TriX);
+        }
+        if txt.starts_with("<?xml") || txt.starts_with("<rdf:RDF") {
+            return Some(Format::RdfXml);
+        }
+        if looks_like_json_ld(txt) {
+            return Some(Format::JsonLd);
+        }
+        let mut lines = txt
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+        let first = lines.next()?;
+        if let Some(in_graph) = ntq::sniff_statement(first) {
+            // the first line is a valid N-Quads statement;
+            // N-Triples is assumed unless a graph name is found in the following lines
+            let nquads = in_graph || lines.any(|line| ntq::sniff_statement(line) == Some(true));
+            return Some(if nquads {
+                Format::NQuads
+            } else {
+                Format::NTriples
+            });
+        }
+        if txt.contains('{') {
+            Some(Format::TriG)
+        } else {
+            Some(Format::Turtle)
+        }
+    }
+
+    /// Whether Sophia has a parser for this format,
+    /// either built in or [registered](#method.register_parser).
+    ///
+    /// NB: [`Format::JsonLd`](#variant.JsonLd) has no built-in parser.
+    pub fn can_parse(&self) -> bool {
+        match self {
+            Format::RdfXml | Format::TriX if cfg!(feature = "xml") => true,
+            Format::RdfXml | Format::TriX | Format::JsonLd => self.registered_parser().is_some(),
+            _ => true,
+        }
+    }
+
+    /// Register `parser` as the parser of this format,
+    /// for formats that have no built-in parser (such as [`Format::JsonLd`](#variant.JsonLd)).
+    ///
+    /// The registered parser is used by [`parse`](#method.parse)
+    /// and the functions of this module.
+    /// It replaces any parser previously registered for this format,
+    /// and is ignored if Sophia has a built-in parser for this format.
+    pub fn register_parser(&self, parser: ParseFn) {
+        let mut parsers = PARSERS.write().unwrap();
+        parsers.retain(|(format, _)| format != self);
+        parsers.push((*self, parser));
+    }
+
+    fn registered_parser(&self) -> Option<ParseFn> {
+        PARSERS
+            .read()
+            .unwrap()
+            .iter()
+            .find(|(format, _)| format == self)
+            .map(|(_, parser)| *parser)
+    }
+
+    /// Whether Sophia has a serializer for this format.
+    ///
+    /// See [`serialize_triples`](#method.serialize_triples)
+    /// and [`serialize_quads`](#method.serialize_quads).
+    pub fn can_serialize(&self) -> bool {
+        match self {
+            Format::TriX => cfg!(feature = "xml"),
+            _ => matches!(self, Format::NTriples | Format::NQuads),
+        }
+    }
+
+    /// Parse `data` in this format.
+    pub fn parse<'a, B: BufRead + 'a>(&self, data: B) -> Result<BoxQuadSource<'a>, FormatError> {
+        Ok(match self {
+            Format::NTriples => from_triples(nt::NTriplesParser {}.parse(data)),
+            Format::NQuads => from_quads(nq::NQuadsParser {}.parse(data)),
+            Format::Turtle => from_triples(turtle::TurtleParser::default().parse(data)),
+            Format::TriG => from_quads(trig::TriGParser::default().parse(data)),
+            Format::GTriG => from_quads(gtrig::GTriGParser::default().parse(data)),
+            Format::N3 => from_quads(n3::N3Parser::default().parse(data)),
+            #[cfg(feature = "xml")]
+            Format::RdfXml => from_triples(crate::parser::xml::RdfXmlParser::default().parse(data)),
+            #[cfg(feature = "xml")]
+            Format::TriX => from_quads(crate::parser::trix::TrixParser::default().parse(data)),
+            _ => match self.registered_parser() {
+                Some(parser) => parser(Box::new(data)),
+                None => return Err(FormatError::Unsupported(*self)),
+            },
+        })
+    }
+
+    /// Serialize `source` in this format to `target`.
+    ///
+    /// Only N-Triples and TriX are supported for now.
+    pub fn serialize_triples<TS, W>(&self, source: TS, target: W) -> Result<(), FormatError>
+    where
+        TS: TripleSource,
+        TS::Error: Send + Sync,
+        W: io::Write,
+    {
+        let res = match self {
+            Format::NTriples => NtSerializer::new(target)
+                .serialize_triples(source)
+                .map(|_| ()),
+            #[cfg(feature = "xml")]
+            Format::TriX => crate::serializer::trix::TrixSerializer::new(target)
+                .serialize_triples(source)
+                .map(|_| ()),
+            _ => return Err(FormatError::Unsupported(*self)),
+        };
+        res.map_err(|err| match err {
+            SourceError(err) => FormatError::Serialize(Box::new(err)),
+            SinkError(err) => FormatError::Io(err),
+        })
+    }
+
+    /// Serialize `source` in this format to `target`.
+    ///
+    /// Only N-Quads and TriX are supported for now.
+    pub fn serialize_quads<QS, W>(&self, source: QS, target: W) -> Result<(), FormatError>
+    where
+        QS: QuadSource,
+        QS::Error: Send + Sync,
+        W: io::Write,
+    {
+        let res = match self {
+            Format::NQuads => NqSerializer::new(target)
+                .serialize_quads(source)
+                .map(|_| ()),
+            #[cfg(feature = "xml")]
+            Format::TriX => crate::serializer::trix::TrixSerializer::new(target)
+                .serialize_quads(source)
+                .map(|_| ()),
+            _ => return Err(FormatError::Unsupported(*self)),
+        };
+        res.map_err(|err| match err {
+            SourceError(err) => FormatError::Serialize(Box::new(err)),
+            SinkError(err) => FormatError::Io(err),
+        })
+    }
+}
+
+impl std::fmt::Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Format::NTriples => "N-Triples",
+            Format::NQuads => "N-Quads",
+            Format::Turtle => "Turtle",
+            Format::TriG => "TriG",
+            Format::GTriG => "Generalized TriG",
+            Format::N3 => "N3",
+            Format::RdfXml => "RDF/XML",
+            Format::TriX => "TriX",
+            Format::JsonLd => "JSON-LD",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// This error is raised by the functions of this module.
+#[derive(Debug, thiserror::Error)]
+pub enum FormatError {
+    #[error("unknown format: {0}")]
+    UnknownFormat(String),
+    #[error("no parser or serializer available for {0}")]
+    Unsupported(Format),
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    /// Errors raised when parsing data.
+    #[error("{0}")]
+    Parse(#[source] Box<dyn Error + Send + Sync>),
+    /// Errors raised by the source of the statements being serialized.
+    #[error("error in serialized source: {0}")]
+    Serialize(#[source] Box<dyn Error + Send + Sync>),
+}
+
+/// The quad source returned by the functions of this module.
+pub type BoxQuadSource<'a> = Box<dyn Iterator<Item = Result<BoxQuad, FormatError>> + 'a>;
+
+/// The type of quads yielded by a [`BoxQuadSource`](type.BoxQuadSource.html).
+pub type BoxQuad = ([BoxTerm; 3], Option<BoxTerm>);
+
+/// The type of parsers accepted by [`Format::register_parser`](enum.Format.html#method.register_parser).
+pub type ParseFn = for<'a> fn(Box<dyn BufRead + 'a>) -> BoxQuadSource<'a>;
+
+/// The parsers registered for formats without a built-in parser.
+static PARSERS: RwLock<Vec<(Format, ParseFn)>> = RwLock::new(Vec::new());
+
+/// Parse the file at `path`, guessing its format from its extension,
+/// or from its content if the extension is unknown.
+pub fn parse_file<P: AsRef<Path>>(path: P) -> Result<BoxQuadSource<'static>, FormatError> {
+    let path = path.as_ref();
+    let mut data = Decompressor::new(BufReader::new(File::open(path)?));
+    let format = match Format::from_path(path) {
+        Some(format) => format,
+        None => Format::sniff(data.fill_buf()?)
+            .ok_or_else(|| FormatError::UnknownFormat(path.display().to_string()))?,
+    };
+    format.parse(data)
+}
+
+/// Parse `data` in the format identified by `media_type`.
+pub fn parse_with_media_type<'a, B: BufRead + 'a>(
+    data: B,
+    media_type: &str,
+) -> Result<BoxQuadSource<'a>, FormatError> {
+    Format::from_media_type(media_type)
+        .ok_or_else(|| FormatError::UnknownFormat(media_type.to_string()))?
+        .parse(Decompressor::new(data))
+}
+
+/// Parse `data`, guessing its format from its first bytes.
+pub fn parse_sniffed<'a, B: BufRead + 'a>(data: B) -> Result<BoxQuadSource<'a>, FormatError> {
+    let mut data = Decompressor::new(data);
+    Format::sniff(data.fill_buf()?)
+        .ok_or_else(|| FormatError::UnknownFormat("(sniffing failed)".to_string()))?
+        .parse(data)
+}
+
+/// Whether `txt` (already trimmed) starts like a JSON-LD document,
+/// i.e. a JSON object (or an array of objects) whose first key is a keyword such as `@context`.
+///
+/// TriG documents may also start with `{`, and Turtle documents with `[`,
+/// so the first key of the object must be found;
+/// JSON documents that are not JSON-LD are not recognized.
+fn looks_like_json_ld(txt: &str) -> bool {
+    let mut txt = txt;
+    while let Some(rest) = txt.strip_prefix('[') {
+        txt = rest.trim_start();
+    }
+    match txt.strip_prefix('{') {
+        Some(rest) => rest.trim_start().starts_with("\"@"),
+        None => false,
+    }
+}
+
+/// Convert a triple source into a [`BoxQuadSource`](type.BoxQuadSource.html),
+/// for use in a [registered parser](enum.Format.html#method.register_parser).
+pub fn from_triples<'a, TS>(source: TS) -> BoxQuadSource<'a>
+where
+    TS: TripleSource + 'a,
+    TS::Error: Send + Sync,
+{
+    Box::new(Buffered::new(
+        source,
+        |source: &mut TS, buffer: &mut VecDeque<BoxQuad>| {
+            source.try_for_some_triple(&mut |t| -> Result<(), FormatError> {
+                buffer.push_back(([copy(t.s()), copy(t.p()), copy(t.o())], None));
+                Ok(())
+            })
+        },
+    ))
+}
+
+/// Convert a quad source into a [`BoxQuadSource`](type.BoxQuadSource.html),
+/// for use in a [registered parser](enum.Format.html#method.register_parser).
+pub fn from_quads<'a, QS>(source: QS) -> BoxQuadSource<'a>
+where
+    QS: QuadSource + 'a,
+    QS::Error: Send + Sync,
+{
+    Box::new(Buffered::new(
+        source,
+        |source: &mut QS, buffer: &mut VecDeque<BoxQuad>| {
+            source.try_for_some_quad(&mut |q| -> Result<(), FormatError> {
+                buffer.push_back(([copy(q.s()), copy(q.p()), copy(q.o())], q.g().map(copy)));
+                Ok(())
+            })
+        },
+    ))
+}
+
+fn copy<T: sophia_api::term::TTerm + ?Sized>(t: &T) -> BoxTerm {
+    BoxTerm::copy(t)
+}
+
+/// Turns a triple or quad source into an iterator,
+/// buffering the statements produced by each step of the source.
+struct Buffered<S, F> {
+    source: S,
+    step: F,
+    buffer: VecDeque<BoxQuad>,
+    done: bool,
+}
+
+impl<S, F> Buffered<S, F> {
+    fn new(source: S, step: F) -> Self {
+        Buffered {
+            source,
+            step,
+            buffer: VecDeque::new(),
+            done: false,
+        }
+    }
+}
+
+impl<S, F, E> Iterator for Buffered<S, F>
+where
+    F: FnMut(
+        &mut S,
+        &mut VecDeque<BoxQuad>,
+    ) -> Result<bool, crate::triple::stream::StreamError<E, FormatError>>,
+    E: Error + Send + Sync + 'static,
+{
+    type Item = Result<BoxQuad, FormatError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(quad) = self.buffer.pop_front() {
+                return Some(Ok(quad));
+            }
+            if self.done {
+                return None;
+            }
+            match (self.step)(&mut self.source, &mut self.buffer) {
+                Ok(true) => {}
+                Ok(false) => self.done = true,
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(match err {
+                        SourceError(err) => FormatError::Parse(Box::new(err)),
+                        SinkError(err) => err,
+                    }));
+                }
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dataset::inmem::FastDataset;
+    use crate::dataset::Dataset;
+    use test_case::test_case;
+
+    const TTL: &str = r#"
+        @prefix : <http://example.org/> .
+        :alice :knows :bob .
+    "#;
+
+    const NT: &str = r#"
+        # a comment
+        <http://example.org/alice> <http://example.org/knows> <http://example.org/bob> .
+        <http://example.org/bob> <http://example.org/knows> <http://example.org/alice> .
+    "#;
+
+    const NQ: &str = r#"
+        <http://example.org/alice> <http://example.org/knows> <http://example.org/bob> .
+        <http://example.org/bob> <http://example.org/knows> <http://example.org/alice> <http://example.org/g> .
+    "#;
+
+    const TRIG: &str = r#"
+        @prefix : <http://example.org/> .
+        :g { :alice :knows :bob . }
+    "#;
+
+    #[test_case("text/turtle", Some(Format::Turtle))]
+    #[test_case("Text/Turtle; charset=utf-8", Some(Format::Turtle))]
+    #[test_case("application/n-quads", Some(Format::NQuads))]
+    #[test_case("application/ld+json", Some(Format::JsonLd))]
+    #[test_case("application/json", None)]
+    #[test_case("text/plain", None)]
+    #[test_case("application/xml", None)]
+    #[test_case("image/png", None)]
+    fn test_from_media_type(mt: &str, exp: Option<Format>) {
+        assert_eq!(Format::from_media_type(mt), exp);
+    }
+
+    #[test_case("data/foo.ttl", Some(Format::Turtle))]
+    #[test_case("foo.NT", Some(Format::NTriples))]
+    #[test_case("foo.trig", Some(Format::TriG))]
+    #[test_case("foo.rdf", Some(Format::RdfXml))]
+    #[test_case("foo.jsonld", Some(Format::JsonLd))]
+    #[test_case("foo.n3", Some(Format::N3))]
+    #[test_case("foo.trix", Some(Format::TriX))]
+    #[test_case("foo.nt.gz", Some(Format::NTriples))]
+    #[test_case("foo.ttl.zst", Some(Format::Turtle))]
+    #[test_case("foo.gz", None)]
+    #[test_case("foo.txt", None)]
+    #[test_case("foo.json", None)]
+    #[test_case("foo.xml", None)]
+    #[test_case("foo", None)]
+    fn test_from_path(path: &str, exp: Option<Format>) {
+        assert_eq!(Format::from_path(path), exp);
+    }
+
+    #[test_case(TTL, Format::Turtle)]
+    #[test_case(NT, Format::NTriples)]
+    #[test_case(NQ, Format::NQuads)]
+    #[test_case(TRIG, Format::TriG)]
+    #[test_case("<?xml version='1.0'?><rdf:RDF/>", Format::RdfXml)]
+    #[test_case(
+        "<?xml version='1.0'?>\n<TriX xmlns='http://www.w3.org/2004/03/trix/trix-1/'/>",
+        Format::TriX
+    )]
+    #[test_case("\u{feff}  [{\"@id\": \"http://example.org/\"}]", Format::JsonLd)]
+    #[test_case("{\n  \"@context\": {}\n}", Format::JsonLd)]
+    #[test_case("[ [ {\"@graph\": []} ] ]", Format::JsonLd)]
+    #[test_case("{ <tag:s> <tag:p> <tag:o> }", Format::TriG)]
+    #[test_case("{ _:s <tag:p> \"{\" }", Format::TriG)]
+    #[test_case("[ <tag:p> <tag:o> ] <tag:q> \"[\" .", Format::Turtle)]
+    #[test_case("[] <tag:p> <tag:o> .", Format::Turtle)]
+    fn test_sniff(data: &str, exp: Format) {
+        assert_eq!(Format::sniff(data.as_bytes()), Some(exp));
+    }
+
+    #[test]
+    fn test_sniff_empty() {
+        assert_eq!(Format::sniff(b"  \n# only a comment\n"), None);
+    }
+
+    #[test_case("[1, 2, 3]" ; "array")]
+    #[test_case("[{\"name\": \"alice\"}]" ; "array of objects")]
+    #[test_case("{}" ; "empty object")]
+    fn test_sniff_plain_json(data: &str) {
+        assert_ne!(Format::sniff(data.as_bytes()), Some(Format::JsonLd));
+    }
+
+    #[test]
+    fn test_register_parser() {
+        // GTriG has a built-in parser, so registering one has no effect
+        fn fail<'a>(_: Box<dyn BufRead + 'a>) -> BoxQuadSource<'a> {
+            panic!("registered parser should not be used")
+        }
+        Format::GTriG.register_parser(fail);
+        assert!(Format::GTriG.can_parse());
+        let count = Format::GTriG.parse(TRIG.as_bytes()).unwrap().count();
+        assert_eq!(count, 1);
+    }
+
+    #[test_case(TTL, "text/turtle", 1, 0)]
+    #[test_case(NT, "application/n-triples", 2, 0)]
+    #[test_case(NQ, "application/n-quads", 2, 1)]
+    #[test_case(TRIG, "application/trig", 1, 1)]
+    #[test_case(
+        "{ <tag:s> <tag:p> <tag:o> } => { <tag:s> <tag:q> <tag:o> } .",
+        "text/n3",
+        3,
+        2
+    )]
+    fn test_parse_with_media_type(data: &str, mt: &str, quads: usize, in_graphs: usize) {
+        let dataset: FastDataset = parse_with_media_type(data.as_bytes(), mt)
+            .unwrap()
+            .collect_quads()
+            .unwrap();
+        assert_eq!(dataset.quads().count(), quads);
+        assert_eq!(
+            dataset
+                .quads()
+                .filter(|q| q.as_ref().unwrap().g().is_some())
+                .count(),
+            in_graphs
+        );
+    }
+
+    #[test]
+    fn test_parse_sniffed() {
+        let dataset: FastDataset = parse_sniffed(TRIG.as_bytes())
+            .unwrap()
+            .collect_quads()
+            .unwrap();
+        assert_eq!(dataset.graph_names().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(
+            parse_with_media_type(TTL.as_bytes(), "image/png"),
+            Err(FormatError::UnknownFormat(_))
+        ));
+        assert!(matches!(
+            parse_with_media_type(TTL.as_bytes(), "application/ld+json"),
+            Err(FormatError::Unsupported(Format::JsonLd))
+        ));
+        let res: Result<Vec<_>, _> = parse_with_media_type("<a> <b> .".as_bytes(), "text/turtle")
+            .unwrap()
+            .collect();
+        assert!(matches!(res, Err(FormatError::Parse(_))));
+    }
+
+    #[test]
+    fn test_parse_file() {
+        let dir = std::env::temp_dir().join(format!("sophia-format-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for (name, data) in &[("a.ttl", TTL), ("b.data", NQ)] {
+            std::fs::write(dir.join(name), data).unwrap();
+        }
+        let count = parse_file(dir.join("a.ttl")).unwrap().count();
+        assert_eq!(count, 1);
+        let dataset: FastDataset = parse_file(dir.join("b.data"))
+            .unwrap()
+            .collect_quads()
+            .unwrap();
+        assert_eq!(dataset.graph_names().unwrap().len(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_serialize() {
+        let mut out = Vec::new();
+        let source = parse_with_media_type(NQ.as_bytes(), "application/n-quads").unwrap();
+        Format::NQuads.serialize_quads(source, &mut out).unwrap();
+        let txt = String::from_utf8(out).unwrap();
+        assert_eq!(txt.lines().count(), 2);
+        assert!(txt.contains("<http://example.org/g>"));
+
+        let source = parse_with_media_type(NQ.as_bytes(), "application/n-quads").unwrap();
+        assert!(matches!(
+            Format::Turtle.serialize_quads(source, io::sink()),
+            Err(FormatError::Unsupported(Format::Turtle))
+        ));
+
+        let source = parse_with_media_type("<a> <b> .".as_bytes(), "text/turtle").unwrap();
+        assert!(matches!(
+            Format::NQuads.serialize_quads(source, io::sink()),
+            Err(FormatError::Serialize(_))
+        ));
+    }
+}
@@ -48,6 +48,10 @@ mod _ops_wrapper;
 pub use self::_ops_wrapper::*;
 mod _term_index_map_u;
 pub use self::_term_index_map_u::*;
+#[cfg(feature = "serde")]
+mod _serde;
+#[cfg(feature = "serde")]
+pub(crate) use self::_serde::{get_term, DenseIndex};
 
 /// A generic in-memory graph.
 ///
@@ -74,6 +78,48 @@ sophia_api::test_graph_impl!(test_fastg, FastGraph);
 #[cfg(all(test, feature = "all_tests"))]
 sophia_api::test_graph_impl!(test_lightg, LightGraph);
 
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::{Graph, MutableGraph};
+    use sophia_api::ns::rdf;
+    use sophia_api::term::matcher::ANY;
+    use sophia_api::term::TTerm;
+    use sophia_term::BoxTerm;
+
+    #[test]
+    fn quoted_triples() {
+        let ex = |s: &str| BoxTerm::new_iri(format!("http://example.org/{}", s)).unwrap();
+        let quoted = |s, p, o| BoxTerm::new_triple(ex(s), ex(p), ex(o));
+        let t1 = quoted("a", "b", "c");
+        let t2 = BoxTerm::new_triple(ex("a"), ex("b"), t1.clone());
+        let mut g = FastGraph::new();
+        assert!(g.insert(&t1, &rdf::type_, &ex("Claim")).unwrap());
+        assert!(g.insert(&ex("alice"), &ex("says"), &t1).unwrap());
+        assert!(g.insert(&t2, &ex("source"), &ex("doc")).unwrap());
+        // the same quoted triple, built separately, is not inserted again
+        assert!(!g
+            .insert(&quoted("a", "b", "c"), &rdf::type_, &ex("Claim"))
+            .unwrap());
+        assert_eq!(g.triples().count(), 3);
+
+        assert_eq!(g.triples_with_s(&quoted("a", "b", "c")).count(), 1);
+        assert_eq!(g.triples_with_o(&t1).count(), 1);
+        assert_eq!(g.triples_with_s(&t2).count(), 1);
+        assert!(g.contains(&ex("alice"), &ex("says"), &t1).unwrap());
+        assert!(!g.contains(&ex("alice"), &ex("says"), &t2).unwrap());
+        let quoted_subjects = g
+            .triples_matching(&[|t: &dyn TTerm| t.triple().is_some()], &ANY, &ANY)
+            .count();
+        assert_eq!(quoted_subjects, 2);
+        assert_eq!(g.subjects().unwrap().len(), 3);
+
+        assert!(g.remove(&t2, &ex("source"), &ex("doc")).unwrap());
+        assert_eq!(g.triples_with_s(&t2).count(), 0);
+        assert_eq!(g.triples_with_s(&t1).count(), 1);
+    }
+}
+
 /// Flavors of Graph implementations with a smaller memory-footprint.
 ///
 /// The trade-off is that these implementations can only contain a small number (2^16) of terms.
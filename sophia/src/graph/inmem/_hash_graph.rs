@@ -140,7 +140,7 @@ where
     type Triple = ByTermRefs<Term<<Self as IndexedGraph>::TermData>>;
     type Error = Infallible;
 
-    fn triples(&self) -> GTripleSource<Self> {
+    fn triples(&self) -> GTripleSource<'_, Self> {
         Box::from(self.triples.iter().map(move |[si, pi, oi]| {
             Ok(StreamedTriple::by_term_refs(
                 self.terms.get_term(*si).unwrap(),
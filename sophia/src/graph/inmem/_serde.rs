@@ -0,0 +1,224 @@
+// this module is transparently re-exported by its parent `graph::inmem`
+// (only available with the `serde` feature)
+//
+// Graphs are serialized as a pair `(terms, triples)`,
+// where `terms` is a sequence of distinct terms,
+// and `triples` is a sequence of `[u32; 3]` indexing those terms.
+// Each term is therefore serialized only once.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::hash::Hash;
+
+use super::*;
+use crate::triple::streaming_mode::ByTermRefs;
+use serde::ser::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sophia_api::triple::Triple;
+use sophia_term::index_map::TermIndexMap;
+
+impl<I> Serialize for HashGraph<I>
+where
+    I: TermIndexMap,
+    I::Index: Hash,
+    <I::Factory as TermFactory>::TermData: 'static,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_indexed(self, serializer)
+    }
+}
+
+impl<'de, I> Deserialize<'de> for HashGraph<I>
+where
+    I: TermIndexMap,
+    I::Index: Hash,
+    <I::Factory as TermFactory>::TermData: 'static,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_indexed(deserializer)
+    }
+}
+
+impl<T> Serialize for SpoWrapper<T>
+where
+    T: IndexedGraph + Graph<Triple = ByTermRefs<Term<<T as IndexedGraph>::TermData>>>,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_indexed(self, serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for SpoWrapper<T>
+where
+    T: IndexedGraph + Graph<Triple = ByTermRefs<Term<<T as IndexedGraph>::TermData>>>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_indexed(deserializer)
+    }
+}
+
+impl<T> Serialize for OpsWrapper<T>
+where
+    T: IndexedGraph + Graph<Triple = ByTermRefs<Term<<T as IndexedGraph>::TermData>>>,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_indexed(self, serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for OpsWrapper<T>
+where
+    T: IndexedGraph + Graph<Triple = ByTermRefs<Term<<T as IndexedGraph>::TermData>>>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_indexed(deserializer)
+    }
+}
+
+/// Map the internal indices of a graph or dataset to dense `u32` indices.
+pub(crate) struct DenseIndex<'a, I, TD: TermData> {
+    indices: HashMap<I, u32>,
+    pub(crate) terms: Vec<&'a Term<TD>>,
+}
+
+impl<'a, I, TD> DenseIndex<'a, I, TD>
+where
+    I: Copy + Eq + Hash,
+    TD: TermData,
+{
+    pub(crate) fn new() -> Self {
+        DenseIndex {
+            indices: HashMap::new(),
+            terms: vec![],
+        }
+    }
+
+    pub(crate) fn get<E, F>(&mut self, i: Option<I>, get_term: F) -> Result<u32, E>
+    where
+        E: serde::ser::Error,
+        F: FnOnce(I) -> Option<&'a Term<TD>>,
+    {
+        let i = i.ok_or_else(|| E::custom("term not found in its own graph"))?;
+        if let Some(dense) = self.indices.get(&i) {
+            return Ok(*dense);
+        }
+        let dense = u32::try_from(self.terms.len()).map_err(|_| E::custom("too many terms"))?;
+        let term = get_term(i).ok_or_else(|| E::custom("index not found in its own graph"))?;
+        self.terms.push(term);
+        self.indices.insert(i, dense);
+        Ok(dense)
+    }
+}
+
+fn serialize_indexed<G, S>(graph: &G, serializer: S) -> Result<S::Ok, S::Error>
+where
+    G: IndexedGraph + Graph<Triple = ByTermRefs<Term<<G as IndexedGraph>::TermData>>>,
+    S: Serializer,
+{
+    let mut index = DenseIndex::new();
+    let mut triples = Vec::new();
+    for t in graph.triples() {
+        let t = t.map_err(S::Error::custom)?;
+        let mut spo = [0; 3];
+        for (dense, term) in spo.iter_mut().zip([t.s(), t.p(), t.o()]) {
+            *dense = index.get(graph.get_index(term), |i| graph.get_term(i))?;
+        }
+        triples.push(spo);
+    }
+    (index.terms, triples).serialize(serializer)
+}
+
+fn deserialize_indexed<'de, G, D>(deserializer: D) -> Result<G, D::Error>
+where
+    G: IndexedGraph,
+    D: Deserializer<'de>,
+{
+    let (terms, triples) = <(Vec<BoxTerm>, Vec<[u32; 3]>)>::deserialize(deserializer)?;
+    let mut graph = G::with_capacity(triples.len());
+    for [s, p, o] in triples {
+        graph.insert_indexed(
+            get_term::<D::Error>(&terms, s)?,
+            get_term::<D::Error>(&terms, p)?,
+            get_term::<D::Error>(&terms, o)?,
+        );
+    }
+    Ok(graph)
+}
+
+pub(crate) fn get_term<E: serde::de::Error>(terms: &[BoxTerm], i: u32) -> Result<&BoxTerm, E> {
+    terms
+        .get(i as usize)
+        .ok_or_else(|| E::custom(format!("term index out of range: {}", i)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sophia_api::graph::Graph;
+    use sophia_api::ns::{rdf, rdfs, xsd};
+    use sophia_api::triple::stream::TripleSource;
+
+    fn graph() -> FastGraph {
+        let mut g = FastGraph::new();
+        let alice = BoxTerm::new_iri("http://example.org/alice").unwrap();
+        let b1 = BoxTerm::new_bnode("b1").unwrap();
+        g.insert(&alice, &rdf::type_, &rdfs::Resource).unwrap();
+        g.insert(
+            &alice,
+            &rdfs::label,
+            &BoxTerm::new_literal_lang("Alice \"A.\"", "en").unwrap(),
+        )
+        .unwrap();
+        g.insert(&alice, &rdfs::seeAlso, &b1).unwrap();
+        g.insert(
+            &b1,
+            &rdf::value,
+            &BoxTerm::new_literal_dt("42", xsd::integer).unwrap(),
+        )
+        .unwrap();
+        g
+    }
+
+    fn check_same(g1: &FastGraph, g2: &FastGraph) {
+        assert_eq!(g1.triples().count(), g2.triples().count());
+        g1.triples()
+            .for_each_triple(|t| assert!(g2.contains(t.s(), t.p(), t.o()).unwrap()))
+            .unwrap();
+        // also check that the OPS index was rebuilt
+        assert_eq!(
+            g1.triples_with_o(&rdfs::Resource).count(),
+            g2.triples_with_o(&rdfs::Resource).count()
+        );
+    }
+
+    #[test]
+    fn json_roundtrip() {
+        let g = graph();
+        let json = serde_json::to_string(&g).unwrap();
+        let back: FastGraph = serde_json::from_str(&json).unwrap();
+        check_same(&g, &back);
+    }
+
+    #[test]
+    fn bincode_roundtrip() {
+        let g = graph();
+        let data = bincode::serialize(&g).unwrap();
+        let back: FastGraph = bincode::deserialize(&data).unwrap();
+        check_same(&g, &back);
+        let back: LightGraph = bincode::deserialize(&data).unwrap();
+        assert_eq!(back.triples().count(), 4);
+    }
+
+    #[test]
+    fn terms_are_shared() {
+        let json = serde_json::to_value(graph()).unwrap();
+        assert_eq!(json[0].as_array().unwrap().len(), 9);
+        assert_eq!(json[1].as_array().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn out_of_range() {
+        let json = r#"[[{"Iri":"tag:a"}],[[0,0,1]]]"#;
+        assert!(serde_json::from_str::<FastGraph>(json).is_err());
+    }
+}
@@ -230,6 +230,25 @@ mod test {
         assert_term_index_map_works(&mut ti);
     }
 
+    #[test]
+    fn test_term_index_quoted_triple() {
+        let mut ti = TermIndexMapU::<u16, RcTermFactory>::default();
+        let iri = RefTerm::new_iri("http://example.org/").unwrap();
+        let bnode = RefTerm::new_bnode("b").unwrap();
+        let t1 = RefTerm::new_triple(iri.clone(), iri.clone(), bnode.clone());
+        let t2 = RefTerm::new_triple(iri.clone(), iri.clone(), t1.clone());
+        let i1 = ti.make_index(&t1);
+        let i2 = ti.make_index(&t2);
+        assert!(i1 != i2);
+        // an equal quoted triple, built separately, gets the same index
+        let t1bis = RefTerm::new_triple(iri.clone(), iri, bnode);
+        assert_eq!(ti.get_index(&t1bis), Some(i1));
+        assert_eq!(ti.get_term(i2).unwrap(), &t2);
+        ti.dec_ref(i1);
+        assert!(ti.get_index(&t1).is_none());
+        assert_eq!(ti.get_index(&t2), Some(i2));
+    }
+
     #[test]
     fn test_term_index_inner() {
         let mut ti = TermIndexMapU::<u16, RcTermFactory>::default();
@@ -30,7 +30,7 @@ pub trait GraphWrapper {
 
     #[inline]
     /// Mimmic the [`iter`](../trait.Graph.html#tymethod.iter) method.
-    fn gw_triples(&self) -> GTripleSource<Self::Wrapped> {
+    fn gw_triples(&self) -> GTripleSource<'_, Self::Wrapped> {
         self.get_wrapped().triples()
     }
 
@@ -206,7 +206,7 @@ macro_rules! impl_graph_for_wrapper {
             <<Self as $crate::graph::inmem::GraphWrapper>::Wrapped as $crate::graph::Graph>::Error;
 
         #[inline]
-        fn triples(&self) -> $crate::graph::GTripleSource<Self> {
+        fn triples(&self) -> $crate::graph::GTripleSource<'_, Self> {
             $crate::graph::inmem::GraphWrapper::gw_triples(self)
         }
         #[inline]
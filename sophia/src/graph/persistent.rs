@@ -0,0 +1,270 @@
+//! A persistent implementation of RDF graphs,
+//! stored on disk in a [sled](https://docs.rs/sled) database
+//! (only available with the `persistent` feature).
+//!
+//! Terms are stored once in a persistent dictionary, which maps them to integer identifiers;
+//! triples are stored as keys in three indexes (SPO, POS and OSP),
+//! so that any pattern of [`Graph::triples_with_*`] methods is answered by a prefix scan.
+//! Every insertion or removal updates all indexes in a single transaction.
+//!
+//! ```
+//! # use sophia::graph::{Graph, MutableGraph};
+//! # use sophia::graph::persistent::PersistentGraph;
+//! # use sophia::ns::{rdf, rdfs};
+//! # let dir = tempfile::tempdir()?;
+//! # let path = dir.path().join("db");
+//! let mut graph = PersistentGraph::open(&path)?;
+//! graph.insert(&rdfs::Class, &rdf::type_, &rdfs::Class)?;
+//! assert!(graph.contains(&rdfs::Class, &rdf::type_, &rdfs::Class)?);
+//! graph.flush()?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! NB: terms are never removed from the dictionary,
+//! even when they are no longer used by any triple.
+//!
+//! [`Graph::triples_with_*`]: ../trait.Graph.html#method.triples_with_s
+
+use sophia_api::graph::{GResult, GTripleSource, Graph, MGResult, MutableGraph, SetGraph};
+use sophia_api::term::TTerm;
+use sophia_api::triple::streaming_mode::{ByValue, StreamedTriple};
+use sophia_term::BoxTerm;
+use std::path::Path;
+
+mod _store;
+#[cfg(test)]
+pub(crate) use self::_store::test::reopen;
+pub(crate) use self::_store::{Bound, Store};
+
+/// The error raised by [`PersistentGraph`](struct.PersistentGraph.html)
+/// and [`PersistentDataset`](../../dataset/persistent/struct.PersistentDataset.html).
+#[derive(Debug, thiserror::Error)]
+pub enum PersistentError {
+    /// Errors raised by the underlying database.
+    #[error(transparent)]
+    Storage(#[from] sled::Error),
+    /// The database does not contain what was expected.
+    #[error("corrupted store: {0}")]
+    Corrupted(String),
+}
+
+impl From<std::convert::Infallible> for PersistentError {
+    fn from(_: std::convert::Infallible) -> Self {
+        unreachable!()
+    }
+}
+
+/// The trees used to index triples, in (S, P, O) order.
+const INDEXES: [(&str, [usize; 3]); 3] =
+    [("spo", [0, 1, 2]), ("pos", [1, 2, 0]), ("osp", [2, 0, 1])];
+
+/// A graph stored on disk.
+///
+/// See the [module documentation](index.html) for more details.
+pub struct PersistentGraph {
+    store: Store<3>,
+}
+
+impl PersistentGraph {
+    /// Open (or create) the graph stored in the directory `path`.
+    ///
+    /// The directory is locked as long as the graph is open;
+    /// NB that sled may release this lock slightly *after* the graph is dropped.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, PersistentError> {
+        Self::from_db(&sled::open(path)?)
+    }
+
+    /// Open (or create) the graph stored in `db`.
+    ///
+    /// The same database can also hold a
+    /// [`PersistentDataset`](../../dataset/persistent/struct.PersistentDataset.html),
+    /// sharing the same term dictionary.
+    pub fn from_db(db: &sled::Db) -> Result<Self, PersistentError> {
+        Ok(PersistentGraph {
+            store: Store::new(db, &INDEXES)?,
+        })
+    }
+
+    /// Create a graph in a temporary database, which is deleted when the graph is dropped.
+    pub fn temporary() -> Result<Self, PersistentError> {
+        Self::from_db(&sled::Config::new().temporary(true).open()?)
+    }
+
+    /// The number of triples in this graph.
+    pub fn len(&self) -> usize {
+        self.store.len()
+    }
+
+    /// Whether this graph is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Write all pending changes to disk,
+    /// and return the number of bytes flushed.
+    ///
+    /// Changes are also flushed periodically, and when the graph is dropped.
+    pub fn flush(&self) -> Result<usize, PersistentError> {
+        self.store.flush()
+    }
+
+    fn scan(&self, terms: [Bound; 3]) -> GTripleSource<'_, Self> {
+        Box::new(self.store.matching(terms).map(move |ids| {
+            let [s, p, o] = ids?;
+            Ok(StreamedTriple::by_value([
+                self.store.term(s)?,
+                self.store.term(p)?,
+                self.store.term(o)?,
+            ]))
+        }))
+    }
+}
+
+impl Graph for PersistentGraph {
+    type Triple = ByValue<[BoxTerm; 3]>;
+    type Error = PersistentError;
+
+    fn triples(&self) -> GTripleSource<'_, Self> {
+        self.scan([None, None, None])
+    }
+    fn triples_with_s<'s, TS>(&'s self, s: &'s TS) -> GTripleSource<'s, Self>
+    where
+        TS: TTerm + ?Sized,
+    {
+        self.scan([Some(Some(s.as_dyn())), None, None])
+    }
+    fn triples_with_p<'s, TP>(&'s self, p: &'s TP) -> GTripleSource<'s, Self>
+    where
+        TP: TTerm + ?Sized,
+    {
+        self.scan([None, Some(Some(p.as_dyn())), None])
+    }
+    fn triples_with_o<'s, TO>(&'s self, o: &'s TO) -> GTripleSource<'s, Self>
+    where
+        TO: TTerm + ?Sized,
+    {
+        self.scan([None, None, Some(Some(o.as_dyn()))])
+    }
+    fn triples_with_sp<'s, TS, TP>(&'s self, s: &'s TS, p: &'s TP) -> GTripleSource<'s, Self>
+    where
+        TS: TTerm + ?Sized,
+        TP: TTerm + ?Sized,
+    {
+        self.scan([Some(Some(s.as_dyn())), Some(Some(p.as_dyn())), None])
+    }
+    fn triples_with_so<'s, TS, TO>(&'s self, s: &'s TS, o: &'s TO) -> GTripleSource<'s, Self>
+    where
+        TS: TTerm + ?Sized,
+        TO: TTerm + ?Sized,
+    {
+        self.scan([Some(Some(s.as_dyn())), None, Some(Some(o.as_dyn()))])
+    }
+    fn triples_with_po<'s, TP, TO>(&'s self, p: &'s TP, o: &'s TO) -> GTripleSource<'s, Self>
+    where
+        TP: TTerm + ?Sized,
+        TO: TTerm + ?Sized,
+    {
+        self.scan([None, Some(Some(p.as_dyn())), Some(Some(o.as_dyn()))])
+    }
+    fn triples_with_spo<'s, TS, TP, TO>(
+        &'s self,
+        s: &'s TS,
+        p: &'s TP,
+        o: &'s TO,
+    ) -> GTripleSource<'s, Self>
+    where
+        TS: TTerm + ?Sized,
+        TP: TTerm + ?Sized,
+        TO: TTerm + ?Sized,
+    {
+        self.scan([
+            Some(Some(s.as_dyn())),
+            Some(Some(p.as_dyn())),
+            Some(Some(o.as_dyn())),
+        ])
+    }
+    fn contains<TS, TP, TO>(&self, s: &TS, p: &TP, o: &TO) -> GResult<Self, bool>
+    where
+        TS: TTerm + ?Sized,
+        TP: TTerm + ?Sized,
+        TO: TTerm + ?Sized,
+    {
+        match self.triples_with_spo(s, p, o).next() {
+            None => Ok(false),
+            Some(res) => res.map(|_| true),
+        }
+    }
+}
+
+impl MutableGraph for PersistentGraph {
+    type MutationError = PersistentError;
+
+    fn insert<TS, TP, TO>(&mut self, s: &TS, p: &TP, o: &TO) -> MGResult<Self, bool>
+    where
+        TS: TTerm + ?Sized,
+        TP: TTerm + ?Sized,
+        TO: TTerm + ?Sized,
+    {
+        let ids = [
+            self.store.get_or_insert_id(s)?,
+            self.store.get_or_insert_id(p)?,
+            self.store.get_or_insert_id(o)?,
+        ];
+        self.store.insert(ids)
+    }
+    fn remove<TS, TP, TO>(&mut self, s: &TS, p: &TP, o: &TO) -> MGResult<Self, bool>
+    where
+        TS: TTerm + ?Sized,
+        TP: TTerm + ?Sized,
+        TO: TTerm + ?Sized,
+    {
+        let terms = [
+            Some(Some(s.as_dyn())),
+            Some(Some(p.as_dyn())),
+            Some(Some(o.as_dyn())),
+        ];
+        match self.store.ids(terms)? {
+            Some([Some(s), Some(p), Some(o)]) => self.store.remove([s, p, o]),
+            _ => Ok(false),
+        }
+    }
+}
+
+impl SetGraph for PersistentGraph {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sophia_api::ns::{rdf, rdfs};
+    use sophia_api::test_graph_impl;
+    use sophia_api::triple::stream::{SinkError, StreamResult, TripleSource};
+
+    fn collect<TS: TripleSource>(
+        triples: TS,
+    ) -> StreamResult<PersistentGraph, TS::Error, PersistentError> {
+        let mut graph = PersistentGraph::temporary().map_err(SinkError)?;
+        graph.insert_all(triples)?;
+        Ok(graph)
+    }
+
+    test_graph_impl!(test, PersistentGraph, true, true, collect);
+
+    #[test]
+    fn persistence() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("db");
+        let bnode = BoxTerm::new_bnode("b").unwrap();
+        {
+            let mut g = PersistentGraph::open(&path).unwrap();
+            g.insert(&bnode, &rdf::type_, &rdfs::Class).unwrap();
+            g.insert(&rdfs::Class, &rdf::type_, &rdfs::Class).unwrap();
+            g.remove(&rdfs::Class, &rdf::type_, &rdfs::Class).unwrap();
+            g.flush().unwrap();
+        }
+        let g = PersistentGraph::from_db(&reopen(&path)).unwrap();
+        assert_eq!(g.len(), 1);
+        assert!(g.contains(&bnode, &rdf::type_, &rdfs::Class).unwrap());
+        assert_eq!(g.triples_with_o(&rdfs::Class).count(), 1);
+        assert_eq!(g.triples_with_s(&rdfs::Class).count(), 0);
+    }
+}
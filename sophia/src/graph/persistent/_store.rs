@@ -0,0 +1,421 @@
+// this module is used by `graph::persistent` and `dataset::persistent`
+//
+// A store consists of
+// * a term dictionary, mapping each term to a u64 identifier, in two trees:
+//   `terms` (id → encoded term) and `term_ids` (encoded term → id);
+// * a set of index trees, whose keys are the concatenation of N big-endian ids,
+//   each in a different order (e.g. SPO, POS, OSP), and whose values are empty.
+//
+// Identifiers start at 1; 0 is reserved for the default graph.
+
+use super::PersistentError;
+use sled::transaction::{TransactionError, TransactionalTree};
+use sled::{Db, Transactional, Tree};
+use sophia_api::term::{Direction, TTerm, TermKind};
+use sophia_term::iri::Iri;
+use sophia_term::BoxTerm;
+use std::convert::TryInto;
+use std::iter::{empty, once};
+
+type Result<T> = std::result::Result<T, PersistentError>;
+
+/// A term to look up:
+/// `None` for any term, `Some(None)` for the default graph,
+/// and `Some(Some(t))` for `t`.
+pub(crate) type Bound<'a> = Option<Option<&'a dyn TTerm>>;
+
+pub(crate) struct Store<const N: usize> {
+    db: Db,
+    terms: Tree,
+    ids: Tree,
+    indexes: Vec<Index<N>>,
+}
+
+struct Index<const N: usize> {
+    tree: Tree,
+    /// The positions of the components, in the order of the keys
+    order: [usize; N],
+}
+
+impl<const N: usize> Store<N> {
+    /// Open the store with the given indexes in `db`.
+    /// The first index is used as the reference for `contains`.
+    pub(crate) fn new(db: &Db, indexes: &[(&str, [usize; N])]) -> Result<Self> {
+        Ok(Store {
+            db: db.clone(),
+            terms: db.open_tree("terms")?,
+            ids: db.open_tree("term_ids")?,
+            indexes: indexes
+                .iter()
+                .map(|(name, order)| {
+                    Ok(Index {
+                        tree: db.open_tree(name)?,
+                        order: *order,
+                    })
+                })
+                .collect::<Result<_>>()?,
+        })
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.indexes[0].tree.len()
+    }
+
+    pub(crate) fn flush(&self) -> Result<usize> {
+        Ok(self.db.flush()?)
+    }
+
+    /// The identifier of `term`, if it is in the dictionary.
+    pub(crate) fn id<T: TTerm + ?Sized>(&self, term: &T) -> Result<Option<u64>> {
+        self.ids
+            .get(encode(term))?
+            .map(|id| decode_id(&id))
+            .transpose()
+    }
+
+    /// The identifier of `term`, adding it to the dictionary if needed.
+    pub(crate) fn get_or_insert_id<T: TTerm + ?Sized>(&self, term: &T) -> Result<u64> {
+        let key = encode(term);
+        if let Some(id) = self.ids.get(&key)? {
+            return decode_id(&id);
+        }
+        let id = self.db.generate_id()? + 1;
+        let id_bytes = id.to_be_bytes();
+        self.terms.insert(id_bytes, key.as_slice())?;
+        match self
+            .ids
+            .compare_and_swap(&key, None as Option<&[u8]>, Some(&id_bytes[..]))?
+        {
+            Ok(()) => Ok(id),
+            Err(cas) => {
+                // another thread inserted the same term concurrently
+                self.terms.remove(id_bytes)?;
+                decode_id(&cas.current.unwrap_or_default())
+            }
+        }
+    }
+
+    /// The term identified by `id`.
+    pub(crate) fn term(&self, id: u64) -> Result<BoxTerm> {
+        let bytes = self
+            .terms
+            .get(id.to_be_bytes())?
+            .ok_or_else(|| corrupted(format!("unknown term id {}", id)))?;
+        let (term, rest) = decode(&bytes)?;
+        if !rest.is_empty() {
+            return Err(corrupted("trailing bytes in term"));
+        }
+        Ok(term)
+    }
+
+    /// The identifiers of the given terms,
+    /// or `None` if one of them is not in the dictionary.
+    pub(crate) fn ids(&self, terms: [Bound; N]) -> Result<Option<[Option<u64>; N]>> {
+        let mut ids = [None; N];
+        for (id, term) in ids.iter_mut().zip(terms) {
+            *id = match term {
+                None => None,
+                Some(None) => Some(0),
+                Some(Some(term)) => match self.id(term)? {
+                    None => return Ok(None),
+                    id => id,
+                },
+            }
+        }
+        Ok(Some(ids))
+    }
+
+    /// Iterate over the entries matching the given terms.
+    pub(crate) fn matching(&self, terms: [Bound; N]) -> Box<dyn Iterator<Item = Result<[u64; N]>>> {
+        let bound = match self.ids(terms) {
+            Ok(Some(bound)) => bound,
+            Ok(None) => return Box::new(empty()),
+            Err(err) => return Box::new(once(Err(err))),
+        };
+        // use the index whose keys start with the most bound components
+        let leading = |index: &Index<N>| {
+            index
+                .order
+                .iter()
+                .take_while(|i| bound[**i].is_some())
+                .count()
+        };
+        let index = self.indexes.iter().max_by_key(|i| leading(i)).unwrap();
+        let prefix: Vec<u8> = index.order[..leading(index)]
+            .iter()
+            .flat_map(|i| bound[*i].unwrap().to_be_bytes())
+            .collect();
+        let order = index.order;
+        Box::new(
+            index
+                .tree
+                .scan_prefix(prefix)
+                .map(move |entry| {
+                    let (key, _) = entry?;
+                    if key.len() != 8 * N {
+                        return Err(corrupted("invalid key length"));
+                    }
+                    let mut ids = [0; N];
+                    for (chunk, i) in key.chunks(8).zip(order) {
+                        ids[i] = u64::from_be_bytes(chunk.try_into().unwrap());
+                    }
+                    Ok(ids)
+                })
+                .filter(move |ids| match ids {
+                    Ok(ids) => ids
+                        .iter()
+                        .zip(&bound)
+                        .all(|(id, b)| b.is_none_or(|b| b == *id)),
+                    Err(_) => true,
+                }),
+        )
+    }
+
+    /// Add an entry to all indexes; return `false` if it was already present.
+    pub(crate) fn insert(&self, ids: [u64; N]) -> Result<bool> {
+        self.transaction(ids, |trees, keys| {
+            if trees[0].get(&keys[0])?.is_some() {
+                return Ok(false);
+            }
+            for (tree, key) in trees.iter().zip(keys) {
+                tree.insert(key.as_slice(), &[])?;
+            }
+            Ok(true)
+        })
+    }
+
+    /// Remove an entry from all indexes; return `false` if it was not present.
+    pub(crate) fn remove(&self, ids: [u64; N]) -> Result<bool> {
+        self.transaction(ids, |trees, keys| {
+            if trees[0].get(&keys[0])?.is_none() {
+                return Ok(false);
+            }
+            for (tree, key) in trees.iter().zip(keys) {
+                tree.remove(key.as_slice())?;
+            }
+            Ok(true)
+        })
+    }
+
+    fn transaction<F>(&self, ids: [u64; N], f: F) -> Result<bool>
+    where
+        F: Fn(
+            &[TransactionalTree],
+            &[Vec<u8>],
+        ) -> sled::transaction::ConflictableTransactionResult<bool, sled::Error>,
+    {
+        let keys: Vec<Vec<u8>> = self
+            .indexes
+            .iter()
+            .map(|index| {
+                index
+                    .order
+                    .iter()
+                    .flat_map(|i| ids[*i].to_be_bytes())
+                    .collect()
+            })
+            .collect();
+        let trees: Vec<&Tree> = self.indexes.iter().map(|index| &index.tree).collect();
+        trees[..]
+            .transaction(|trees| f(trees, &keys))
+            .map_err(|err| match err {
+                TransactionError::Abort(err) | TransactionError::Storage(err) => err.into(),
+            })
+    }
+}
+
+// Terms are encoded as a tag byte followed by length-prefixed strings,
+// or by three encoded terms for quoted triples.
+
+const IRI: u8 = 0;
+const BNODE: u8 = 1;
+const LITERAL_DT: u8 = 2;
+const LITERAL_LANG: u8 = 3;
+const LITERAL_LANG_DIR: u8 = 4;
+const VARIABLE: u8 = 5;
+const TRIPLE: u8 = 6;
+
+fn encode<T: TTerm + ?Sized>(term: &T) -> Vec<u8> {
+    let mut buffer = vec![];
+    encode_into(term.as_dyn(), &mut buffer);
+    buffer
+}
+
+fn encode_into(term: &dyn TTerm, buffer: &mut Vec<u8>) {
+    let push_str = |buffer: &mut Vec<u8>, txt: &str| {
+        buffer.extend_from_slice(&(txt.len() as u32).to_be_bytes());
+        buffer.extend_from_slice(txt.as_bytes());
+    };
+    match term.kind() {
+        TermKind::Iri => {
+            buffer.push(IRI);
+            push_str(buffer, &term.value());
+        }
+        TermKind::BlankNode => {
+            buffer.push(BNODE);
+            push_str(buffer, &term.value());
+        }
+        TermKind::Variable => {
+            buffer.push(VARIABLE);
+            push_str(buffer, &term.value());
+        }
+        // language tags are compared case-insensitively (see term_eq),
+        // so they are stored in lowercase
+        TermKind::Literal => match (term.language(), term.direction()) {
+            (Some(tag), Some(dir)) => {
+                buffer.push(LITERAL_LANG_DIR);
+                push_str(buffer, &term.value());
+                push_str(buffer, &tag.to_ascii_lowercase());
+                push_str(buffer, dir.as_str());
+            }
+            (Some(tag), None) => {
+                buffer.push(LITERAL_LANG);
+                push_str(buffer, &term.value());
+                push_str(buffer, &tag.to_ascii_lowercase());
+            }
+            (None, _) => {
+                buffer.push(LITERAL_DT);
+                push_str(buffer, &term.value());
+                push_str(buffer, &term.datatype().unwrap().value());
+            }
+        },
+        TermKind::QuotedTriple => {
+            buffer.push(TRIPLE);
+            for t in term.triple().unwrap().iter() {
+                encode_into(*t, buffer);
+            }
+        }
+        kind => unimplemented!("encoding of {:?} terms", kind),
+    }
+}
+
+fn decode(bytes: &[u8]) -> Result<(BoxTerm, &[u8])> {
+    let (tag, mut rest) = bytes.split_first().ok_or_else(|| corrupted("empty term"))?;
+    let mut next_str = || -> Result<&str> {
+        let (len, tail) = split(rest, 4)?;
+        let (txt, tail) = split(tail, u32::from_be_bytes(len.try_into().unwrap()) as usize)?;
+        rest = tail;
+        std::str::from_utf8(txt).map_err(|_| corrupted("invalid UTF-8"))
+    };
+    let term = match *tag {
+        IRI => BoxTerm::new_iri_unchecked(next_str()?),
+        BNODE => BoxTerm::new_bnode_unchecked(next_str()?),
+        VARIABLE => BoxTerm::new_variable_unchecked(next_str()?),
+        LITERAL_DT => {
+            let txt = next_str()?;
+            let dt = Iri::<Box<str>>::new_unchecked(next_str()?);
+            BoxTerm::new_literal_dt_unchecked(txt, dt)
+        }
+        LITERAL_LANG => {
+            let txt = next_str()?;
+            BoxTerm::new_literal_lang_unchecked(txt, next_str()?)
+        }
+        LITERAL_LANG_DIR => {
+            let txt = next_str()?;
+            let tag = next_str()?;
+            let dir =
+                Direction::parse(next_str()?).ok_or_else(|| corrupted("invalid direction"))?;
+            BoxTerm::new_literal_lang_dir_unchecked(txt, tag, dir)
+        }
+        TRIPLE => {
+            let (s, tail) = decode(rest)?;
+            let (p, tail) = decode(tail)?;
+            let (o, tail) = decode(tail)?;
+            rest = tail;
+            BoxTerm::new_triple(s, p, o)
+        }
+        _ => return Err(corrupted(format!("invalid term tag {}", tag))),
+    };
+    Ok((term, rest))
+}
+
+fn split(bytes: &[u8], len: usize) -> Result<(&[u8], &[u8])> {
+    if bytes.len() < len {
+        return Err(corrupted("truncated term"));
+    }
+    Ok(bytes.split_at(len))
+}
+
+fn decode_id(bytes: &[u8]) -> Result<u64> {
+    let bytes = bytes.try_into().map_err(|_| corrupted("invalid term id"))?;
+    Ok(u64::from_be_bytes(bytes))
+}
+
+fn corrupted<M: ToString>(message: M) -> PersistentError {
+    PersistentError::Corrupted(message.to_string())
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use sophia_api::ns::{rdf, xsd};
+    use sophia_api::term::CopyTerm;
+    use std::path::Path;
+    use std::time::Duration;
+
+    /// Open the database at `path`,
+    /// waiting for sled to release the lock of a previous instance.
+    pub(crate) fn reopen(path: &Path) -> Db {
+        for _ in 0..50 {
+            match sled::open(path) {
+                Err(sled::Error::Io(err)) if err.kind() == std::io::ErrorKind::Other => {
+                    std::thread::sleep(Duration::from_millis(100))
+                }
+                res => return res.unwrap(),
+            }
+        }
+        sled::open(path).unwrap()
+    }
+
+    #[test]
+    fn encoding_roundtrip() {
+        let terms = vec![
+            BoxTerm::new_iri("http://example.org/a").unwrap(),
+            BoxTerm::new_iri_suffixed("http://example.org/", "b").unwrap(),
+            BoxTerm::new_bnode("b1").unwrap(),
+            BoxTerm::new_variable("v").unwrap(),
+            BoxTerm::new_literal_dt("hello \"world\"\n", xsd::string).unwrap(),
+            BoxTerm::new_literal_lang("chat", "fr").unwrap(),
+            BoxTerm::new_literal_lang_dir("שלום", "he", Direction::Rtl).unwrap(),
+            BoxTerm::new_triple(
+                BoxTerm::new_bnode("b1").unwrap(),
+                BoxTerm::copy(&rdf::type_),
+                BoxTerm::new_literal_lang("x", "en").unwrap(),
+            ),
+        ];
+        for term in terms {
+            let bytes = encode(&term);
+            let (back, rest) = decode(&bytes).unwrap();
+            assert_eq!(back, term);
+            assert!(rest.is_empty());
+            assert!(decode(&bytes[..bytes.len() - 1]).is_err());
+        }
+        assert!(decode(&[42]).is_err());
+    }
+
+    #[test]
+    fn lang_tag_case() {
+        let lower = BoxTerm::new_literal_lang("chat", "fr-be").unwrap();
+        let mixed = BoxTerm::new_literal_lang("chat", "FR-be").unwrap();
+        assert_eq!(encode(&lower), encode(&mixed));
+        let lower = BoxTerm::new_literal_lang_dir("chat", "fr", Direction::Ltr).unwrap();
+        let mixed = BoxTerm::new_literal_lang_dir("chat", "Fr", Direction::Ltr).unwrap();
+        assert_eq!(encode(&lower), encode(&mixed));
+
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        let store = Store::<3>::new(&db, &[("spo", [0, 1, 2])]).unwrap();
+        let ids = [
+            store.get_or_insert_id(&rdf::value).unwrap(),
+            store.get_or_insert_id(&rdf::value).unwrap(),
+            store
+                .get_or_insert_id(&BoxTerm::new_literal_lang("chat", "fr").unwrap())
+                .unwrap(),
+        ];
+        assert!(store.insert(ids).unwrap());
+        let mixed = BoxTerm::new_literal_lang("chat", "FR").unwrap();
+        assert_eq!(store.id(&mixed).unwrap(), Some(ids[2]));
+        assert_eq!(store.get_or_insert_id(&mixed).unwrap(), ids[2]);
+        assert!(!store.insert(ids).unwrap());
+        assert_eq!(store.len(), 1);
+    }
+}
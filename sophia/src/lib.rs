@@ -63,7 +63,19 @@
 //! # Ok::<(), Box<dyn std::error::Error>>(())
 //! ```
 
+// allows the code generated by sophia_derive to be used in this crate
+extern crate self as sophia;
+
+#[cfg(feature = "async")]
+pub mod async_io;
+pub mod collection;
+#[macro_use]
+pub mod compression;
+pub mod format;
 pub mod query;
+pub mod resource;
+#[cfg(feature = "serde")]
+pub mod serde_rdf;
 
 /// This module re-exports symbols from
 /// [`sophia_api::dataset`](https://docs.rs/sophia_api/latest/sophia_api/dataset/),
@@ -72,6 +84,8 @@ pub mod dataset {
     pub use sophia_api::dataset::*;
     pub mod indexed;
     pub mod inmem;
+    #[cfg(feature = "persistent")]
+    pub mod persistent;
 }
 /// This module re-exports symbols from
 /// [`sophia_api::graph`](https://docs.rs/sophia_api/latest/sophia_api/graph/),
@@ -80,6 +94,8 @@ pub mod graph {
     pub use sophia_api::graph::*;
     pub mod indexed;
     pub mod inmem;
+    #[cfg(feature = "persistent")]
+    pub mod persistent;
 }
 /// This module re-exports symbols from
 /// [`sophia_api::ns`](https://docs.rs/sophia_api/latest/sophia_api/ns/).
@@ -92,11 +108,18 @@ pub mod ns {
 pub mod parser {
     pub use sophia_api::parser::*;
     pub mod gtrig;
+    pub mod n3;
     pub mod nq;
     pub mod nt;
+    pub mod ntq;
+    pub(crate) mod prefix_tracker;
     pub mod rio_common;
     pub mod trig;
+    #[cfg(feature = "xml")]
+    pub mod trix;
     pub mod turtle;
+    pub mod turtle_star;
+    pub mod validating;
     #[cfg(feature = "xml")]
     pub mod xml;
     #[cfg(feature = "xml")]
@@ -114,6 +137,9 @@ pub mod serializer {
     pub use sophia_api::serializer::*;
     pub mod nq;
     pub mod nt;
+    #[cfg(feature = "xml")]
+    pub mod trix;
+    pub mod turtle_star;
 }
 /// This module re-exports symbols from
 /// [`sophia_api::term`](https://docs.rs/sophia_api/latest/sophia_api/term/)
@@ -15,14 +15,14 @@ impl<B: BufRead> QuadParser<B> for GTriGParser {
     type Source = GeneralizedRioSource<RioGTriGParser<B>, TurtleError>;
     fn parse(&self, data: B) -> Self::Source {
         let base: &str = match &self.base {
-            Some(base) => &base,
+            Some(base) => base,
             None => "",
         };
         GeneralizedRioSource::from(RioGTriGParser::new(data, base))
     }
 }
 
-sophia_api::def_mod_functions_for_bufread_parser!(GTriGParser, QuadParser);
+def_mod_functions_for_bufread_parser!(GTriGParser, QuadParser);
 
 // ---------------------------------------------------------------------------------
 //                                      tests
@@ -53,7 +53,7 @@ mod test {
 
         let mut d = FastDataset::new();
         let p = GTriGParser { base: None };
-        let c = p.parse_str(&gtrig).add_to_dataset(&mut d)?;
+        let c = p.parse_str(gtrig).add_to_dataset(&mut d)?;
         assert_eq!(c, 3);
         assert!(d
             .quads_matching(
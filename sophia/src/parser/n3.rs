@@ -0,0 +1,1016 @@
+//! Parser for [Notation3] (N3).
+//!
+//! N3 extends Turtle with formulae, variables and a few shorthands
+//! (such as `=>` for `log:implies`, or paths like `:joe!:mother`).
+//! Since Sophia has no native notion of formula,
+//! they are mapped onto the generalized RDF model:
+//!
+//! * each formula `{ ... }` is represented by a fresh blank node,
+//!   which is also the graph name of the triples contained in the formula;
+//! * quick variables (`?x`), and IRIs declared with `@forAll`, become variables;
+//! * IRIs declared with `@forSome` become fresh blank nodes.
+//!
+//! This parser also accepts the quoted triples (`<< s p o >>`)
+//! and annotations (`s p o {| p2 o2 |}`) of [Turtle-star].
+//!
+//! Top-level triples are in the default graph.
+//! Blank node labels (`_:x`) are mapped to fresh blank nodes,
+//! so the labels of the document are not preserved.
+//! Note that this parser reads the whole document before producing any quad.
+//! Documents nested more deeply than [`MAX_NESTING`](constant.MAX_NESTING.html) are rejected.
+//!
+//! [Notation3]: https://w3c.github.io/N3/spec/
+//! [Turtle-star]: https://w3c.github.io/rdf-star/cg-spec/editors_draft.html#turtle-star
+
+use crate::format::BoxQuad;
+use sophia_api::ns::{owl, rdf, xsd};
+use sophia_api::parser::{Location, QuadParser, WithLocation};
+use sophia_api::term::{CopyTerm, TTerm};
+use sophia_term::iri::{Iri, IriParsed, Resolve};
+use sophia_term::{BoxTerm, TermError};
+use std::collections::HashMap;
+use std::io::{self, BufRead};
+
+const LOG_IMPLIES: &str = "http://www.w3.org/2000/10/swap/log#implies";
+
+/// The maximum nesting depth of blank node property lists, collections,
+/// formulae, quoted triples and annotations accepted by the parser.
+///
+/// Deeper documents are rejected with a syntax error,
+/// rather than exhausting the stack.
+pub const MAX_NESTING: usize = 128;
+
+/// N3 parser.
+#[derive(Clone, Debug, Default)]
+pub struct N3Parser {
+    pub base: Option<String>,
+}
+
+impl<B: BufRead> QuadParser<B> for N3Parser {
+    type Source = N3Source;
+    fn parse(&self, mut data: B) -> Self::Source {
+        let mut txt = String::new();
+        if let Err(err) = data.read_to_string(&mut txt) {
+            return N3Source::new(vec![], Some(err.into()));
+        }
+        let mut parser = Parser::new(&txt, self.base.clone());
+        let res = parser.document();
+        N3Source::new(parser.quads, res.err())
+    }
+}
+
+def_mod_functions_for_bufread_parser!(N3Parser, QuadParser);
+
+/// This error is raised when parsing N3 documents.
+#[derive(Debug, thiserror::Error)]
+pub enum N3Error {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    #[error("{message} at {location}")]
+    Syntax { message: String, location: Location },
+}
+
+impl WithLocation for N3Error {
+    fn location(&self) -> Location {
+        match self {
+            N3Error::Io(_) => Location::Unknown,
+            N3Error::Syntax { location, .. } => location.clone(),
+        }
+    }
+}
+
+/// The quad source returned by [`N3Parser`](struct.N3Parser.html).
+///
+/// It yields the quads parsed before the first error, if any, then that error.
+pub struct N3Source {
+    quads: std::vec::IntoIter<BoxQuad>,
+    error: Option<N3Error>,
+}
+
+impl N3Source {
+    pub(crate) fn new(quads: Vec<BoxQuad>, error: Option<N3Error>) -> Self {
+        N3Source {
+            quads: quads.into_iter(),
+            error,
+        }
+    }
+}
+
+impl Iterator for N3Source {
+    type Item = Result<BoxQuad, N3Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.quads.next() {
+            Some(quad) => Some(Ok(quad)),
+            None => self.error.take().map(Err),
+        }
+    }
+}
+
+type Result<T, E = N3Error> = std::result::Result<T, E>;
+
+/// A recursive descent parser for N3.
+pub(crate) struct Parser<'a> {
+    /// If true, only accept Turtle(-star) syntax
+    pub(crate) turtle: bool,
+    txt: &'a str,
+    pos: usize,
+    base: Option<String>,
+    prefixes: HashMap<String, String>,
+    /// IRIs declared with `@forAll` or `@forSome`, for each nested formula
+    scopes: Vec<HashMap<String, BoxTerm>>,
+    graph: Option<BoxTerm>,
+    pub(crate) quads: Vec<BoxQuad>,
+    /// the blank node labels of the document
+    labels: HashMap<String, BoxTerm>,
+    bnodes: usize,
+    /// the current nesting depth (see [`MAX_NESTING`](constant.MAX_NESTING.html))
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    pub(crate) fn new(txt: &'a str, base: Option<String>) -> Self {
+        Parser {
+            turtle: false,
+            txt,
+            pos: 0,
+            base,
+            prefixes: HashMap::new(),
+            scopes: vec![HashMap::new()],
+            graph: None,
+            quads: vec![],
+            labels: HashMap::new(),
+            bnodes: 0,
+            depth: 0,
+        }
+    }
+
+    // statements
+
+    pub(crate) fn document(&mut self) -> Result<()> {
+        self.statements()?;
+        if self.peek().is_some() {
+            return self.error("unexpected '}'");
+        }
+        Ok(())
+    }
+
+    /// Parse statements until the end of the document or of the current formula.
+    fn statements(&mut self) -> Result<()> {
+        loop {
+            self.skip_ws();
+            match self.peek() {
+                None | Some('}') => return Ok(()),
+                _ => (),
+            }
+            if self.sparql_directive()? {
+                continue;
+            }
+            self.statement()?;
+            if self.eat(".") {
+                continue;
+            }
+            self.skip_ws();
+            match self.peek() {
+                Some('}') if self.graph.is_some() => return Ok(()),
+                _ => return self.error("expected '.'"),
+            }
+        }
+    }
+
+    fn sparql_directive(&mut self) -> Result<bool> {
+        if self.keyword_ci("PREFIX") {
+            self.prefix_declaration()?;
+            Ok(true)
+        } else if self.keyword_ci("BASE") {
+            self.base_declaration()?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn statement(&mut self) -> Result<()> {
+        if self.keyword("@prefix") {
+            self.prefix_declaration()
+        } else if self.keyword("@base") {
+            self.base_declaration()
+        } else if self.turtle {
+            self.triples()
+        } else if self.keyword("@forAll") {
+            self.quantification(true)
+        } else if self.keyword("@forSome") {
+            self.quantification(false)
+        } else if self.keyword("@keywords") {
+            self.error("@keywords is not supported")
+        } else {
+            self.triples()
+        }
+    }
+
+    fn prefix_declaration(&mut self) -> Result<()> {
+        self.skip_ws();
+        let start = self.pos;
+        self.take_while(|c| is_name_char(c) && c != ':');
+        let prefix = self.txt[start..self.pos].to_string();
+        if prefix.ends_with('.') || !self.eat(":") {
+            return self.error("expected a prefix name");
+        }
+        self.skip_ws();
+        let iri = self.iriref()?;
+        self.prefixes.insert(prefix, iri);
+        Ok(())
+    }
+
+    fn base_declaration(&mut self) -> Result<()> {
+        self.skip_ws();
+        let iri = self.iriref()?;
+        self.base = Some(iri);
+        Ok(())
+    }
+
+    fn quantification(&mut self, universal: bool) -> Result<()> {
+        loop {
+            self.skip_ws();
+            let iri = match self.peek() {
+                Some('<') => self.iriref()?,
+                _ => self.prefixed_name()?,
+            };
+            let term = if universal {
+                let local = iri.rsplit(['#', '/', ':']).next().unwrap();
+                match BoxTerm::new_variable(local) {
+                    Ok(var) => var,
+                    Err(_) => self.term(BoxTerm::new_variable(format!("v{}", self.bnodes)))?,
+                }
+            } else {
+                self.fresh_bnode()
+            };
+            self.scopes.last_mut().unwrap().insert(iri, term);
+            if !self.eat(",") {
+                return Ok(());
+            }
+        }
+    }
+
+    fn triples(&mut self) -> Result<()> {
+        let subject = self.expression()?;
+        self.skip_ws();
+        match self.peek() {
+            None | Some('.') | Some('}') => Ok(()),
+            _ => self.predicate_object_list(&subject),
+        }
+    }
+
+    fn predicate_object_list(&mut self, subject: &BoxTerm) -> Result<()> {
+        loop {
+            let (verb, inverse) = self.verb()?;
+            loop {
+                let object = self.expression()?;
+                if inverse {
+                    self.emit(object, verb.clone(), subject.clone());
+                } else {
+                    self.emit(subject.clone(), verb.clone(), object);
+                }
+                if self.eat("{|") {
+                    let [s, p, o] = self.quads.last().unwrap().0.clone();
+                    let annotated = BoxTerm::new_triple(s, p, o);
+                    self.nested(|p| p.predicate_object_list(&annotated))?;
+                    self.expect("|}")?;
+                }
+                if !self.eat(",") {
+                    break;
+                }
+            }
+            if !self.eat(";") {
+                return Ok(());
+            }
+            while self.eat(";") {}
+            self.skip_ws();
+            match self.peek() {
+                None | Some('.') | Some(']') | Some('}') | Some('|') => return Ok(()),
+                _ => (),
+            }
+        }
+    }
+
+    /// Parse a verb, and return it with a flag indicating whether it is inverted.
+    fn verb(&mut self) -> Result<(BoxTerm, bool)> {
+        self.skip_ws();
+        if self.turtle {
+            if self.keyword("a") {
+                return Ok((BoxTerm::copy(&rdf::type_), false));
+            }
+            return Ok((self.path_item()?, false));
+        }
+        if self.eat("=>") {
+            Ok((self.iri_term(LOG_IMPLIES.to_string())?, false))
+        } else if self.starts_with("<=") && self.is_operator() {
+            self.pos += 2;
+            Ok((self.iri_term(LOG_IMPLIES.to_string())?, true))
+        } else if self.eat("=") {
+            Ok((BoxTerm::copy(&owl::sameAs), false))
+        } else if self.starts_with("<-") && self.is_operator() {
+            self.pos += 2;
+            Ok((self.expression()?, true))
+        } else if self.keyword("a") || self.keyword("@a") {
+            Ok((BoxTerm::copy(&rdf::type_), false))
+        } else if self.keyword("has") || self.keyword("@has") {
+            Ok((self.expression()?, false))
+        } else if self.keyword("is") || self.keyword("@is") {
+            let verb = self.expression()?;
+            if !(self.keyword("of") || self.keyword("@of")) {
+                return self.error("expected 'of'");
+            }
+            Ok((verb, true))
+        } else {
+            Ok((self.expression()?, false))
+        }
+    }
+
+    /// Whether the `<` at the current position starts an operator rather than an IRI.
+    fn is_operator(&self) -> bool {
+        let rest = &self.txt[self.pos + 1..];
+        match rest.find(|c: char| c == '>' || !is_iri_char(c)) {
+            Some(i) => !rest[i..].starts_with('>'),
+            None => true,
+        }
+    }
+
+    // expressions
+
+    fn expression(&mut self) -> Result<BoxTerm> {
+        let mut term = self.path_item()?;
+        if self.turtle {
+            return Ok(term);
+        }
+        loop {
+            if self.starts_with("!") {
+                self.pos += 1;
+                let predicate = self.path_item()?;
+                let node = self.fresh_bnode();
+                self.emit(term, predicate, node.clone());
+                term = node;
+            } else if self.starts_with("^") && !self.starts_with("^^") {
+                self.pos += 1;
+                let predicate = self.path_item()?;
+                let node = self.fresh_bnode();
+                self.emit(node.clone(), predicate, term);
+                term = node;
+            } else {
+                return Ok(term);
+            }
+        }
+    }
+
+    fn path_item(&mut self) -> Result<BoxTerm> {
+        self.skip_ws();
+        match self.peek() {
+            Some('<') if self.starts_with("<<") => self.nested(Self::quoted_triple),
+            Some('<') => {
+                let iri = self.iriref()?;
+                self.iri_term(iri)
+            }
+            Some('_') if self.starts_with("_:") => {
+                self.pos += 2;
+                let label = self.name()?;
+                self.term(BoxTerm::new_bnode(label))?;
+                Ok(self.bnode(label))
+            }
+            Some('?') | Some('{') if self.turtle => self.error("unexpected character"),
+            Some('?') => {
+                self.pos += 1;
+                let name = self.name()?;
+                self.term(BoxTerm::new_variable(name))
+            }
+            Some('[') => {
+                self.pos += 1;
+                let node = self.fresh_bnode();
+                if !self.eat("]") {
+                    self.nested(|p| p.predicate_object_list(&node))?;
+                    self.expect("]")?;
+                }
+                Ok(node)
+            }
+            Some('(') => self.nested(Self::collection),
+            Some('{') => self.nested(Self::formula),
+            Some('"') | Some('\'') => self.rdf_literal(),
+            Some(c) if c.is_ascii_digit() || c == '+' || c == '-' || c == '.' => {
+                self.numeric_literal()
+            }
+            _ => {
+                if self.keyword("true") || self.keyword("@true") {
+                    Ok(BoxTerm::new_literal_dt_unchecked(
+                        "true",
+                        xsd_iri(&xsd::boolean),
+                    ))
+                } else if self.keyword("false") || self.keyword("@false") {
+                    Ok(BoxTerm::new_literal_dt_unchecked(
+                        "false",
+                        xsd_iri(&xsd::boolean),
+                    ))
+                } else {
+                    let iri = self.prefixed_name()?;
+                    self.iri_term(iri)
+                }
+            }
+        }
+    }
+
+    fn quoted_triple(&mut self) -> Result<BoxTerm> {
+        self.expect("<<")?;
+        let s = self.path_item()?;
+        let (p, inverse) = self.verb()?;
+        let o = self.path_item()?;
+        self.expect(">>")?;
+        if inverse {
+            Ok(BoxTerm::new_triple(o, p, s))
+        } else {
+            Ok(BoxTerm::new_triple(s, p, o))
+        }
+    }
+
+    fn collection(&mut self) -> Result<BoxTerm> {
+        self.expect("(")?;
+        let mut items = vec![];
+        while !self.eat(")") {
+            if self.peek().is_none() {
+                return self.error("expected ')'");
+            }
+            items.push(self.expression()?);
+        }
+        let mut list = BoxTerm::copy(&rdf::nil);
+        for item in items.into_iter().rev() {
+            let node = self.fresh_bnode();
+            self.emit(node.clone(), BoxTerm::copy(&rdf::first), item);
+            self.emit(node.clone(), BoxTerm::copy(&rdf::rest), list);
+            list = node;
+        }
+        Ok(list)
+    }
+
+    fn formula(&mut self) -> Result<BoxTerm> {
+        self.expect("{")?;
+        let node = self.fresh_bnode();
+        let parent = self.graph.replace(node.clone());
+        self.scopes.push(HashMap::new());
+        self.statements()?;
+        self.expect("}")?;
+        self.scopes.pop();
+        self.graph = parent;
+        Ok(node)
+    }
+
+    fn rdf_literal(&mut self) -> Result<BoxTerm> {
+        let txt = self.string()?;
+        if self.starts_with("@") {
+            self.pos += 1;
+            let start = self.pos;
+            self.take_while(|c| c.is_ascii_alphanumeric() || c == '-');
+            let lang = &self.txt[start..self.pos];
+            self.term(BoxTerm::new_literal_lang(txt, lang))
+        } else if self.starts_with("^^") {
+            self.pos += 2;
+            let dt = match self.peek() {
+                Some('<') => self.iriref()?,
+                _ => self.prefixed_name()?,
+            };
+            let dt = self.term(Iri::<Box<str>>::new(dt))?;
+            Ok(BoxTerm::new_literal_dt_unchecked(txt, dt))
+        } else {
+            Ok(BoxTerm::from(txt))
+        }
+    }
+
+    fn numeric_literal(&mut self) -> Result<BoxTerm> {
+        let start = self.pos;
+        if self.starts_with("+") || self.starts_with("-") {
+            self.pos += 1;
+        }
+        self.take_while(|c| c.is_ascii_digit());
+        let mut dt = &xsd::integer;
+        let rest = &self.txt[self.pos..];
+        if rest.starts_with('.') && rest[1..].starts_with(|c: char| c.is_ascii_digit()) {
+            self.pos += 1;
+            self.take_while(|c| c.is_ascii_digit());
+            dt = &xsd::decimal;
+        }
+        if self.starts_with("e") || self.starts_with("E") {
+            self.pos += 1;
+            if self.starts_with("+") || self.starts_with("-") {
+                self.pos += 1;
+            }
+            let exp = self.pos;
+            self.take_while(|c| c.is_ascii_digit());
+            if self.pos == exp {
+                return self.error("invalid exponent");
+            }
+            dt = &xsd::double;
+        }
+        let txt = &self.txt[start..self.pos];
+        if !txt.contains(|c: char| c.is_ascii_digit()) {
+            self.pos = start;
+            return self.error("unexpected character");
+        }
+        Ok(BoxTerm::new_literal_dt_unchecked(txt, xsd_iri(dt)))
+    }
+
+    // lexical elements
+
+    /// Parse an IRI reference, and resolve it against the current base.
+    fn iriref(&mut self) -> Result<String> {
+        self.expect("<")?;
+        let mut iri = String::new();
+        loop {
+            match self.next_char() {
+                Some('>') => break,
+                Some('\\') => iri.push(self.escape(true)?),
+                Some(c) if is_iri_char(c) => iri.push(c),
+                _ => return self.error("invalid IRI"),
+            }
+        }
+        let resolved = match self.base.as_deref().map(IriParsed::new) {
+            Some(Ok(base)) => base.resolve(&iri[..]).ok().map(|r| r.to_string()),
+            _ => None,
+        };
+        Ok(resolved.unwrap_or(iri))
+    }
+
+    fn prefixed_name(&mut self) -> Result<String> {
+        let start = self.pos;
+        self.take_while(|c| is_name_char(c) && c != ':');
+        if !self.starts_with(":") || self.txt[start..self.pos].ends_with('.') {
+            self.pos = start;
+            return self.error("unexpected character");
+        }
+        let prefix = &self.txt[start..self.pos];
+        let ns = match self.prefixes.get(prefix) {
+            Some(ns) => ns.clone(),
+            None => {
+                self.pos = start;
+                return self.error(&format!("undeclared prefix '{}'", prefix));
+            }
+        };
+        self.pos += 1;
+        let mut local = String::new();
+        loop {
+            match self.peek() {
+                Some('\\') => {
+                    self.pos += 1;
+                    match self.next_char() {
+                        Some(c) if "_~.-!$&'()*+,;=/?#@%".contains(c) => local.push(c),
+                        _ => return self.error("invalid escape sequence"),
+                    }
+                }
+                Some('%') => {
+                    let hex = self.txt.get(self.pos + 1..self.pos + 3).unwrap_or("");
+                    if hex.len() != 2 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                        return self.error("invalid percent-encoding");
+                    }
+                    local.push('%');
+                    local.push_str(hex);
+                    self.pos += 3;
+                }
+                Some(c) if is_name_char(c) => {
+                    // a local name can not end with a '.'
+                    if c == '.' && !self.txt[self.pos + 1..].starts_with(is_name_char) {
+                        break;
+                    }
+                    local.push(c);
+                    self.pos += c.len_utf8();
+                }
+                _ => break,
+            }
+        }
+        Ok(ns + &local)
+    }
+
+    /// Parse the name of a blank node or a variable.
+    fn name(&mut self) -> Result<&'a str> {
+        let start = self.pos;
+        self.take_while(|c| c.is_alphanumeric() || c == '_' || c == '-');
+        if self.pos == start {
+            return self.error("expected a name");
+        }
+        Ok(&self.txt[start..self.pos])
+    }
+
+    fn string(&mut self) -> Result<String> {
+        let quote = if self.starts_with("\"\"\"") {
+            "\"\"\""
+        } else if self.starts_with("'''") {
+            "'''"
+        } else if self.starts_with("\"") {
+            "\""
+        } else {
+            "'"
+        };
+        self.pos += quote.len();
+        let long = quote.len() == 3;
+        let mut txt = String::new();
+        loop {
+            if self.starts_with(quote) {
+                self.pos += quote.len();
+                return Ok(txt);
+            }
+            match self.next_char() {
+                None => return self.error("unterminated string"),
+                Some('\n') | Some('\r') if !long => return self.error("unterminated string"),
+                Some('\\') => txt.push(self.escape(false)?),
+                Some(c) => txt.push(c),
+            }
+        }
+    }
+
+    /// Parse an escape sequence, after the backslash.
+    fn escape(&mut self, unicode_only: bool) -> Result<char> {
+        let c = match self.next_char() {
+            Some('u') => return self.unicode_escape(4),
+            Some('U') => return self.unicode_escape(8),
+            _ if unicode_only => None,
+            Some('t') => Some('\t'),
+            Some('b') => Some('\u{8}'),
+            Some('n') => Some('\n'),
+            Some('r') => Some('\r'),
+            Some('f') => Some('\u{c}'),
+            Some(c) if c == '"' || c == '\'' || c == '\\' => Some(c),
+            _ => None,
+        };
+        match c {
+            Some(c) => Ok(c),
+            None => self.error("invalid escape sequence"),
+        }
+    }
+
+    fn unicode_escape(&mut self, len: usize) -> Result<char> {
+        let hex = self.txt.get(self.pos..self.pos + len).unwrap_or("");
+        match u32::from_str_radix(hex, 16)
+            .ok()
+            .and_then(std::char::from_u32)
+        {
+            Some(c) if hex.len() == len => {
+                self.pos += len;
+                Ok(c)
+            }
+            _ => self.error("invalid escape sequence"),
+        }
+    }
+
+    // utility methods
+
+    /// Call `f` one nesting level deeper,
+    /// or fail if [`MAX_NESTING`](constant.MAX_NESTING.html) is reached.
+    fn nested<T, F>(&mut self, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut Self) -> Result<T>,
+    {
+        if self.depth >= MAX_NESTING {
+            return self.error("maximum nesting depth exceeded");
+        }
+        self.depth += 1;
+        let res = f(self);
+        self.depth -= 1;
+        res
+    }
+
+    fn emit(&mut self, s: BoxTerm, p: BoxTerm, o: BoxTerm) {
+        self.quads.push(([s, p, o], self.graph.clone()));
+    }
+
+    fn fresh_bnode(&mut self) -> BoxTerm {
+        self.bnodes += 1;
+        BoxTerm::new_bnode_unchecked(format!("n3g{}", self.bnodes))
+    }
+
+    /// The blank node for `label` in this document.
+    ///
+    /// Labels are mapped to fresh blank nodes,
+    /// so that they can not clash with the blank nodes generated by the parser.
+    fn bnode(&mut self, label: &str) -> BoxTerm {
+        if let Some(bnode) = self.labels.get(label) {
+            return bnode.clone();
+        }
+        let bnode = self.fresh_bnode();
+        self.labels.insert(label.to_string(), bnode.clone());
+        bnode
+    }
+
+    /// Build an IRI term, unless `iri` has been declared as a variable or an existential.
+    fn iri_term(&self, iri: String) -> Result<BoxTerm> {
+        match self.scopes.iter().rev().find_map(|scope| scope.get(&iri)) {
+            Some(term) => Ok(term.clone()),
+            None => self.term(BoxTerm::new_iri(iri)),
+        }
+    }
+
+    fn term<T>(&self, res: std::result::Result<T, TermError>) -> Result<T> {
+        res.or_else(|err| self.error(&err.to_string()))
+    }
+
+    fn error<T>(&self, message: &str) -> Result<T> {
+        let before = &self.txt[..self.pos];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let column = before[line_start..].chars().count() + 1;
+        Err(N3Error::Syntax {
+            message: message.to_string(),
+            location: Location::from_lico(line, column),
+        })
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.txt[self.pos..].chars().next()
+    }
+
+    fn next_char(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn starts_with(&self, s: &str) -> bool {
+        self.txt[self.pos..].starts_with(s)
+    }
+
+    fn take_while<F: Fn(char) -> bool>(&mut self, f: F) {
+        let rest = &self.txt[self.pos..];
+        self.pos += rest.find(|c| !f(c)).unwrap_or(rest.len());
+    }
+
+    /// Skip whitespaces and comments.
+    fn skip_ws(&mut self) {
+        loop {
+            self.take_while(char::is_whitespace);
+            if !self.starts_with("#") {
+                return;
+            }
+            self.take_while(|c| c != '\n');
+        }
+    }
+
+    /// Skip whitespaces, then consume `s` if present.
+    fn eat(&mut self, s: &str) -> bool {
+        self.skip_ws();
+        let found = self.starts_with(s);
+        if found {
+            self.pos += s.len();
+        }
+        found
+    }
+
+    fn expect(&mut self, s: &str) -> Result<()> {
+        if self.eat(s) {
+            Ok(())
+        } else {
+            self.error(&format!("expected '{}'", s))
+        }
+    }
+
+    /// Skip whitespaces, then consume keyword `kw` if present.
+    fn keyword(&mut self, kw: &str) -> bool {
+        self.skip_ws();
+        let rest = &self.txt[self.pos..];
+        let found = rest.starts_with(kw) && !rest[kw.len()..].starts_with(is_name_char);
+        if found {
+            self.pos += kw.len();
+        }
+        found
+    }
+
+    /// Same as `keyword`, but case-insensitive.
+    fn keyword_ci(&mut self, kw: &str) -> bool {
+        self.skip_ws();
+        let rest = &self.txt[self.pos..];
+        let found = rest
+            .get(..kw.len())
+            .is_some_and(|s| s.eq_ignore_ascii_case(kw))
+            && !rest[kw.len()..].starts_with(is_name_char);
+        if found {
+            self.pos += kw.len();
+        }
+        found
+    }
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-' || c == '.' || c == ':' || c == '\u{b7}'
+}
+
+fn is_iri_char(c: char) -> bool {
+    !(c <= ' ' || "<>\"{}|^`\\".contains(c))
+}
+
+fn xsd_iri<T: TTerm + ?Sized>(dt: &T) -> Iri<Box<str>> {
+    Iri::new_unchecked(dt.value().to_string())
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dataset::inmem::FastDataset;
+    use crate::dataset::Dataset;
+    use crate::quad::stream::QuadSource;
+    use crate::serializer::nq::NqSerializer;
+    use crate::serializer::{QuadSerializer, Stringifier};
+    use sophia_api::quad::Quad;
+    use sophia_api::term::matcher::ANY;
+    use sophia_api::term::TermKind;
+    use sophia_term::StaticTerm;
+    use test_case::test_case;
+
+    fn to_nq(n3: &str) -> String {
+        let quads: Vec<BoxQuad> = parse_str(n3).collect::<Result<_, _>>().unwrap();
+        NqSerializer::new_stringifier()
+            .serialize_quads(quads.into_iter().map(Ok::<_, N3Error>))
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn turtle_subset() {
+        let n3 = r#"
+            @prefix : <http://example.org/> .
+            PREFIX xsd: <http://www.w3.org/2001/XMLSchema#>
+            :alice a :Person ;
+                :name "Alice"@en, 'Al\'' ;
+                :age 42, 4.2, 4e2 ;
+                :knows [ :name """Bob""" ] ;
+                :likes ( :cheese true ) ;
+                :height "1.65"^^xsd:decimal .
+        "#;
+        let d: FastDataset = parse_str(n3).collect_quads().unwrap();
+        assert_eq!(d.quads().count(), 14);
+        assert!(d.graph_names().unwrap().is_empty());
+    }
+
+    #[test]
+    fn n3_shorthands() {
+        let nq = to_nq(
+            r#"
+            @prefix : <tag:> .
+            :a = :b .
+            :a => :b .
+            :a <= :b .
+            :a has :p :b .
+            :a is :p of :b .
+            :a <- :p :b .
+        "#,
+        );
+        assert_eq!(
+            nq,
+            "<tag:a> <http://www.w3.org/2002/07/owl#sameAs> <tag:b>.
+<tag:a> <http://www.w3.org/2000/10/swap/log#implies> <tag:b>.
+<tag:b> <http://www.w3.org/2000/10/swap/log#implies> <tag:a>.
+<tag:a> <tag:p> <tag:b>.
+<tag:b> <tag:p> <tag:a>.
+<tag:b> <tag:p> <tag:a>.
+"
+        );
+    }
+
+    #[test]
+    fn paths() {
+        let nq = to_nq("@prefix : <tag:> . :joe!:mother^:child :p :o .");
+        assert_eq!(
+            nq,
+            "<tag:joe> <tag:mother> _:n3g1.
+_:n3g2 <tag:child> _:n3g1.
+_:n3g2 <tag:p> <tag:o>.
+"
+        );
+    }
+
+    #[test]
+    fn formulae_and_variables() {
+        let n3 = r#"
+            @prefix : <http://example.org/> .
+            { ?x a :Man } => { ?x a :Mortal } .
+            :alice :says { :bob :knows :carol. :carol :knows :dave } .
+        "#;
+        let d: FastDataset = parse_str(n3).collect_quads().unwrap();
+        assert_eq!(d.quads().count(), 6);
+        let implies = StaticTerm::new_iri(LOG_IMPLIES).unwrap();
+        let rule = d
+            .quads_matching(&ANY, &implies, &ANY, &ANY)
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(rule.s().kind(), TermKind::BlankNode);
+        assert!(rule.g().is_none());
+        let x = StaticTerm::new_variable("x").unwrap();
+        let man = d
+            .quads_matching(&x, &rdf::type_, &ANY, &Some(rule.s()))
+            .count();
+        assert_eq!(man, 1);
+        let mortal = d
+            .quads_matching(&x, &rdf::type_, &ANY, &Some(rule.o()))
+            .count();
+        assert_eq!(mortal, 1);
+        assert_eq!(d.graph_names().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn quantification() {
+        let nq = to_nq(
+            r#"
+            @prefix : <tag:> .
+            @forAll :x .
+            { @forSome :y . :x :p :y } => { :x :q :y } .
+        "#,
+        );
+        assert_eq!(
+            nq,
+            "?x <tag:p> _:n3g2 _:n3g1.
+?x <tag:q> <tag:y> _:n3g3.
+_:n3g1 <http://www.w3.org/2000/10/swap/log#implies> _:n3g3.
+"
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let mut src = parse_str("<tag:s> <tag:p> <tag:o> .\n<tag:s> <tag:p> .");
+        assert!(src.next().unwrap().is_ok());
+        match src.next() {
+            Some(Err(err)) => assert_eq!(
+                err.location().to_string(),
+                Location::from_lico(2, 17).to_string()
+            ),
+            _ => panic!("expected an error"),
+        }
+        assert!(src.next().is_none());
+        assert!(parse_str("undeclared:x <tag:p> <tag:o> .")
+            .next()
+            .unwrap()
+            .is_err());
+        let res: Result<Vec<_>, _> = parse_str("{ <tag:s> <tag:p> <tag:o> .").collect();
+        assert!(res.is_err());
+    }
+
+    #[test_case("(", ")"; "collections")]
+    #[test_case("{ <tag:s> <tag:p> ", "}"; "formulae")]
+    #[test_case("[ <tag:p> ", "]"; "property lists")]
+    #[test_case("<< <tag:s> <tag:p> ", ">>"; "quoted triples")]
+    fn nesting(open: &str, close: &str) {
+        let nested = |n: usize| {
+            format!(
+                "<tag:s> <tag:p> {}<tag:o>{} .",
+                open.repeat(n),
+                close.repeat(n)
+            )
+        };
+        assert!(parse_str(&nested(MAX_NESTING)).all(|res| res.is_ok()));
+        match parse_str(&nested(MAX_NESTING + 1)).last() {
+            Some(Err(err)) => assert!(err.to_string().contains("nesting depth")),
+            _ => panic!("expected an error"),
+        }
+        // must not overflow the stack
+        assert!(parse_str(&nested(10_000)).last().unwrap().is_err());
+    }
+
+    #[test]
+    fn annotation_nesting() {
+        let nested = |n: usize| {
+            format!(
+                "<tag:s> <tag:p> <tag:o> {} .",
+                "{| <tag:p> <tag:o> ".repeat(n) + &"|}".repeat(n)
+            )
+        };
+        assert!(parse_str(&nested(MAX_NESTING)).all(|res| res.is_ok()));
+        assert!(parse_str(&nested(10_000)).last().unwrap().is_err());
+    }
+
+    #[test]
+    fn quoted_triples() {
+        let nq = to_nq(
+            r#"
+            @prefix : <tag:> .
+            << :a :b :c >> :p :o .
+            :s :p :o {| :source << _:x :q [] >> |} .
+        "#,
+        );
+        assert_eq!(
+            nq,
+            "<< <tag:a> <tag:b> <tag:c> >> <tag:p> <tag:o>.
+<tag:s> <tag:p> <tag:o>.
+<< <tag:s> <tag:p> <tag:o> >> <tag:source> << _:n3g1 <tag:q> _:n3g2 >>.
+"
+        );
+    }
+
+    #[test]
+    fn bnode_labels() {
+        // user labels can not clash with generated blank nodes
+        let nq = to_nq("_:n3g1 <tag:p> <tag:a>. [ <tag:p> <tag:b> ]. _:x <tag:q> _:n3g1.");
+        assert_eq!(
+            nq,
+            "_:n3g1 <tag:p> <tag:a>.
+_:n3g2 <tag:p> <tag:b>.
+_:n3g3 <tag:q> _:n3g1.
+"
+        );
+    }
+}
@@ -18,7 +18,7 @@ impl<B: BufRead> TripleParser<B> for NTriplesParser {
     }
 }
 
-sophia_api::def_mod_functions_for_bufread_parser!(NTriplesParser, TripleParser);
+def_mod_functions_for_bufread_parser!(NTriplesParser, TripleParser);
 
 // ---------------------------------------------------------------------------------
 //                                      tests
@@ -44,7 +44,7 @@ mod test {
 
         let mut g = FastGraph::new();
         let p = NTriplesParser {};
-        let c = p.parse_str(&turtle).add_to_graph(&mut g)?;
+        let c = p.parse_str(turtle).add_to_graph(&mut g)?;
         assert_eq!(c, 3);
         assert!(g
             .triples_matching(
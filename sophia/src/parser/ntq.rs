@@ -0,0 +1,994 @@
+//! Native parser for [N-Triples] and [N-Quads].
+//!
+//! Unlike the parsers of modules [`nt`](../nt/index.html) and [`nq`](../nq/index.html),
+//! this parser does not rely on RIO.
+//! It yields [`RefTerm`]s borrowed from its input buffer
+//! (only IRIs and literals containing escape sequences need to be copied).
+//!
+//! It also accepts the quoted triples of [N-Triples-star] and [N-Quads-star]
+//! (e.g. `<< _:a <tag:p> "o" >> <tag:source> <tag:s> .`),
+//! in subject and object position,
+//! as well as the directional language-tagged strings of [RDF 1.2]
+//! (e.g. `"שלום"@he--rtl`).
+//! Quoted triples nested more deeply than [`MAX_NESTING`](constant.MAX_NESTING.html)
+//! are rejected.
+//!
+//! It also has a [lenient](struct.NTriplesParser.html#method.lenient) mode,
+//! where malformed lines are skipped instead of stopping the parser.
+//! The corresponding errors, with their location, can be retrieved afterwards:
+//!
+//! ```
+//! # use sophia::parser::ntq::NTriplesParser;
+//! # use sophia::parser::TripleParser;
+//! # use sophia::triple::stream::TripleSource;
+//! let nt = r#"
+//!     <http://example.org/a> <http://example.org/p> "ok" .
+//!     <http://example.org/a> <http://example.org/p> "missing dot"
+//!     <http://example.org/a> <http://example.org/p> "ok again" .
+//! "#;
+//! let mut source = NTriplesParser::lenient().parse_str(nt);
+//! let mut count = 0;
+//! source.for_each_triple(|_| count += 1)?;
+//! assert_eq!(count, 2);
+//! assert_eq!(source.errors().len(), 1);
+//! assert_eq!(source.errors()[0].to_string(), "expected '.' at 3:64");
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [N-Triples]: https://www.w3.org/TR/n-triples/
+//! [N-Quads]: https://www.w3.org/TR/n-quads/
+//! [N-Triples-star]: https://w3c.github.io/rdf-star/cg-spec/editors_draft.html#n-triples-star
+//! [N-Quads-star]: https://w3c.github.io/rdf-star/cg-spec/editors_draft.html#n-quads-star
+//! [RDF 1.2]: https://www.w3.org/TR/rdf12-n-triples/
+//! [`RefTerm`]: https://docs.rs/sophia_term/latest/sophia_term/type.RefTerm.html
+
+use sophia_api::parser::{Location, QuadParser, TripleParser, WithLocation};
+use sophia_api::quad::stream::QuadSource;
+use sophia_api::quad::streaming_mode::StreamedQuad;
+use sophia_api::term::Direction;
+use sophia_api::triple::stream::*;
+use sophia_api::triple::streaming_mode::StreamedTriple;
+use sophia_term::iri::is_absolute_iri_ref;
+use sophia_term::literal::convert::AsLiteral;
+use sophia_term::RefTerm;
+use std::borrow::Cow;
+use std::error::Error;
+use std::io::BufRead;
+
+#[cfg(feature = "parallel")]
+pub mod parallel;
+
+/// Native N-Triples parser.
+#[derive(Clone, Debug, Default)]
+pub struct NTriplesParser {
+    /// If true, malformed lines are skipped (see [`NtSource::errors`](struct.NtSource.html#method.errors)).
+    pub lenient: bool,
+}
+
+impl NTriplesParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a parser skipping malformed lines.
+    pub fn lenient() -> Self {
+        NTriplesParser { lenient: true }
+    }
+}
+
+impl<B: BufRead> TripleParser<B> for NTriplesParser {
+    type Source = NtSource<B>;
+    fn parse(&self, data: B) -> Self::Source {
+        NtSource(Lines::new(data, self.lenient))
+    }
+}
+
+/// Native N-Quads parser.
+#[derive(Clone, Debug, Default)]
+pub struct NQuadsParser {
+    /// If true, malformed lines are skipped (see [`NqSource::errors`](struct.NqSource.html#method.errors)).
+    pub lenient: bool,
+}
+
+impl NQuadsParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a parser skipping malformed lines.
+    pub fn lenient() -> Self {
+        NQuadsParser { lenient: true }
+    }
+}
+
+impl<B: BufRead> QuadParser<B> for NQuadsParser {
+    type Source = NqSource<B>;
+    fn parse(&self, data: B) -> Self::Source {
+        NqSource(Lines::new(data, self.lenient))
+    }
+}
+
+/// This error is raised when parsing N-Triples or N-Quads.
+#[derive(Debug, thiserror::Error)]
+pub enum NtqError {
+    /// Errors raised by the underlying reader.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    /// Errors raised by malformed lines.
+    #[error("{message} at {location}")]
+    Syntax { message: String, location: Location },
+}
+
+impl WithLocation for NtqError {
+    fn location(&self) -> Location {
+        match self {
+            NtqError::Io(_) => Location::Unknown,
+            NtqError::Syntax { location, .. } => location.clone(),
+        }
+    }
+}
+
+pub type NtqTriple<'a> = [RefTerm<'a>; 3];
+sophia_api::make_scoped_triple_streaming_mode!(ScopedNtqTriple, NtqTriple);
+
+/// The triple source produced by [`NTriplesParser`](struct.NTriplesParser.html).
+pub struct NtSource<B>(Lines<B>);
+
+impl<B> NtSource<B> {
+    /// The errors encountered so far, in lenient mode.
+    ///
+    /// Only the first [`MAX_ERRORS`](constant.MAX_ERRORS.html) errors are kept,
+    /// see also [`error_count`](#method.error_count).
+    pub fn errors(&self) -> &[NtqError] {
+        &self.0.errors
+    }
+
+    /// The number of errors encountered so far, in lenient mode,
+    /// including those that were not kept.
+    pub fn error_count(&self) -> usize {
+        self.0.error_count
+    }
+}
+
+impl<B: BufRead> TripleSource for NtSource<B> {
+    type Error = NtqError;
+    type Triple = ScopedNtqTriple;
+
+    fn try_for_some_triple<F, E>(&mut self, f: &mut F) -> StreamResult<bool, NtqError, E>
+    where
+        F: FnMut(StreamedTriple<Self::Triple>) -> Result<(), E>,
+        E: Error,
+    {
+        self.0.parse_step(false, |spo, _| {
+            f(StreamedTriple::scoped([
+                spo[0].as_term(),
+                spo[1].as_term(),
+                spo[2].as_term(),
+            ]))
+        })
+    }
+}
+
+pub type NtqQuad<'a> = ([RefTerm<'a>; 3], Option<RefTerm<'a>>);
+sophia_api::make_scoped_quad_streaming_mode!(ScopedNtqQuad, NtqQuad);
+
+/// The quad source produced by [`NQuadsParser`](struct.NQuadsParser.html).
+pub struct NqSource<B>(Lines<B>);
+
+impl<B> NqSource<B> {
+    /// The errors encountered so far, in lenient mode.
+    ///
+    /// Only the first [`MAX_ERRORS`](constant.MAX_ERRORS.html) errors are kept,
+    /// see also [`error_count`](#method.error_count).
+    pub fn errors(&self) -> &[NtqError] {
+        &self.0.errors
+    }
+
+    /// The number of errors encountered so far, in lenient mode,
+    /// including those that were not kept.
+    pub fn error_count(&self) -> usize {
+        self.0.error_count
+    }
+}
+
+impl<B: BufRead> QuadSource for NqSource<B> {
+    type Error = NtqError;
+    type Quad = ScopedNtqQuad;
+
+    fn try_for_some_quad<F, E>(&mut self, f: &mut F) -> StreamResult<bool, NtqError, E>
+    where
+        F: FnMut(StreamedQuad<Self::Quad>) -> Result<(), E>,
+        E: Error,
+    {
+        self.0.parse_step(true, |spo, g| {
+            f(StreamedQuad::scoped((
+                [spo[0].as_term(), spo[1].as_term(), spo[2].as_term()],
+                g.map(Token::as_term),
+            )))
+        })
+    }
+}
+
+/// The maximum number of errors kept by sources in lenient mode.
+pub const MAX_ERRORS: usize = 1000;
+
+/// The maximum nesting depth of quoted triples accepted by the parser.
+///
+/// Deeper statements are rejected with a syntax error,
+/// rather than exhausting the stack.
+pub const MAX_NESTING: usize = 128;
+
+/// Reads the input line by line.
+struct Lines<B> {
+    reader: B,
+    buffer: Vec<u8>,
+    line_no: usize,
+    lenient: bool,
+    errors: Vec<NtqError>,
+    error_count: usize,
+}
+
+impl<B> Lines<B> {
+    fn new(reader: B, lenient: bool) -> Self {
+        Lines {
+            reader,
+            buffer: Vec::new(),
+            line_no: 0,
+            lenient,
+            errors: Vec::new(),
+            error_count: 0,
+        }
+    }
+}
+
+impl<B: BufRead> Lines<B> {
+    /// Read lines until a statement is found, and pass it to `f`.
+    ///
+    /// Return `false` once the end of the input is reached.
+    fn parse_step<F, E>(&mut self, nquads: bool, f: F) -> StreamResult<bool, NtqError, E>
+    where
+        F: FnOnce(&[Token; 3], Option<&Token>) -> Result<(), E>,
+        E: Error,
+    {
+        loop {
+            self.buffer.clear();
+            let read = self
+                .reader
+                .read_until(b'\n', &mut self.buffer)
+                .map_err(|err| SourceError(err.into()))?;
+            if read == 0 {
+                return Ok(false);
+            }
+            self.line_no += 1;
+            match parse_raw_line(&self.buffer, self.line_no, nquads) {
+                Ok(None) => continue,
+                Ok(Some((spo, g))) => {
+                    f(&spo, g.as_ref()).map_err(SinkError)?;
+                    return Ok(true);
+                }
+                Err(err) if self.lenient => {
+                    self.error_count += 1;
+                    if self.errors.len() < MAX_ERRORS {
+                        self.errors.push(err);
+                    }
+                }
+                Err(err) => return Err(SourceError(err)),
+            }
+        }
+    }
+}
+
+/// Parse line number `line_no`, which may contain a statement, or only whitespace and comments.
+fn parse_raw_line(
+    buffer: &[u8],
+    line_no: usize,
+    nquads: bool,
+) -> Result<Option<Statement<'_>>, NtqError> {
+    match std::str::from_utf8(buffer) {
+        Ok(line) => parse_line(line, nquads)
+            .map_err(|(message, offset)| syntax_error(message, line_no, column(line, offset))),
+        Err(err) => {
+            let valid = std::str::from_utf8(&buffer[..err.valid_up_to()]).unwrap();
+            let col = valid.chars().count() + 1;
+            Err(syntax_error("invalid UTF-8".into(), line_no, col))
+        }
+    }
+}
+
+/// Same as `parse_raw_line`, but copies the terms of the statement, if any.
+#[cfg(feature = "async")]
+pub(crate) fn parse_boxed_line(
+    buffer: &[u8],
+    line_no: usize,
+    nquads: bool,
+) -> Result<Option<crate::async_io::BoxQuad>, NtqError> {
+    use sophia_api::term::CopyTerm;
+    use sophia_term::BoxTerm;
+    Ok(
+        parse_raw_line(buffer, line_no, nquads)?.map(|([s, p, o], g)| {
+            (
+                [
+                    BoxTerm::copy(&s.as_term()),
+                    BoxTerm::copy(&p.as_term()),
+                    BoxTerm::copy(&o.as_term()),
+                ],
+                g.map(|g| BoxTerm::copy(&g.as_term())),
+            )
+        }),
+    )
+}
+
+fn syntax_error(message: String, line: usize, column: usize) -> NtqError {
+    NtqError::Syntax {
+        message,
+        location: Location::from_lico(line, column),
+    }
+}
+
+/// The column (starting at 1) of the given byte offset in `line`.
+fn column(line: &str, offset: usize) -> usize {
+    line[..offset].chars().count() + 1
+}
+
+/// A term, as parsed from a line.
+#[derive(Debug)]
+enum Token<'a> {
+    Iri(Cow<'a, str>),
+    BNode(&'a str),
+    Literal(Cow<'a, str>, Suffix<'a>),
+    Triple(Box<[Token<'a>; 3]>),
+}
+
+#[derive(Debug)]
+enum Suffix<'a> {
+    None,
+    Datatype(Cow<'a, str>),
+    Lang(&'a str, Option<Direction>),
+}
+
+impl<'a> Token<'a> {
+    fn as_term(&self) -> RefTerm<'_> {
+        match self {
+            Token::Iri(iri) => RefTerm::new_iri_unchecked(iri.as_ref()),
+            Token::BNode(id) => RefTerm::new_bnode_unchecked(*id),
+            Token::Literal(txt, Suffix::None) => txt.as_ref().as_literal().into(),
+            Token::Literal(txt, Suffix::Datatype(dt)) => RefTerm::new_literal_dt_unchecked(
+                txt.as_ref(),
+                RefTerm::new_iri_unchecked(dt.as_ref()),
+            ),
+            Token::Literal(txt, Suffix::Lang(tag, None)) => {
+                RefTerm::new_literal_lang_unchecked(txt.as_ref(), *tag)
+            }
+            Token::Literal(txt, Suffix::Lang(tag, Some(dir))) => {
+                RefTerm::new_literal_lang_dir_unchecked(txt.as_ref(), *tag, *dir)
+            }
+            Token::Triple(spo) => {
+                RefTerm::new_triple(spo[0].as_term(), spo[1].as_term(), spo[2].as_term())
+            }
+        }
+    }
+}
+
+type ParseResult<T> = Result<T, (String, usize)>;
+type Statement<'a> = ([Token<'a>; 3], Option<Token<'a>>);
+
+/// Parse a line, which may contain a statement, or only whitespace and comments.
+fn parse_line(line: &str, nquads: bool) -> ParseResult<Option<Statement<'_>>> {
+    let mut cur = Cursor {
+        txt: line,
+        pos: 0,
+        depth: 0,
+    };
+    cur.skip_ws();
+    if cur.at_end() {
+        return Ok(None);
+    }
+    let s = cur.subject()?;
+    cur.skip_ws();
+    let p = cur.iri()?;
+    cur.skip_ws();
+    let o = cur.object()?;
+    cur.skip_ws();
+    let g = if nquads && cur.peek() != Some(b'.') {
+        let g = cur.graph_name()?;
+        cur.skip_ws();
+        Some(g)
+    } else {
+        None
+    };
+    cur.expect(b'.')?;
+    cur.skip_ws();
+    if !cur.at_end() {
+        return Err(cur.error("expected end of line"));
+    }
+    Ok(Some(([s, p, o], g)))
+}
+
+/// If `line` is a valid N-Quads statement, whether it has a graph name.
+pub(crate) fn sniff_statement(line: &str) -> Option<bool> {
+    match parse_line(line, true) {
+        Ok(Some((_, g))) => Some(g.is_some()),
+        _ => None,
+    }
+}
+
+struct Cursor<'a> {
+    txt: &'a str,
+    pos: usize,
+    /// the nesting depth of quoted triples
+    depth: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.txt.as_bytes().get(self.pos).copied()
+    }
+
+    fn error(&self, message: &str) -> (String, usize) {
+        (message.to_string(), self.pos)
+    }
+
+    fn expect(&mut self, b: u8) -> ParseResult<()> {
+        if self.peek() == Some(b) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", b as char)))
+        }
+    }
+
+    /// Skip spaces and tabs.
+    fn skip_ws(&mut self) {
+        while let Some(b' ') | Some(b'\t') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    /// Whether only an end-of-line or a comment remains.
+    fn at_end(&self) -> bool {
+        let rest = &self.txt[self.pos..];
+        rest.starts_with('#') || rest.trim_end_matches(&['\r', '\n'][..]).is_empty()
+    }
+
+    fn subject(&mut self) -> ParseResult<Token<'a>> {
+        match self.peek() {
+            Some(b'<') if self.txt[self.pos..].starts_with("<<") => self.quoted_triple(),
+            _ => self.graph_name(),
+        }
+    }
+
+    fn graph_name(&mut self) -> ParseResult<Token<'a>> {
+        match self.peek() {
+            Some(b'<') => self.iri(),
+            Some(b'_') => self.bnode(),
+            _ => Err(self.error("expected IRI or blank node")),
+        }
+    }
+
+    fn object(&mut self) -> ParseResult<Token<'a>> {
+        match self.peek() {
+            Some(b'<') if self.txt[self.pos..].starts_with("<<") => self.quoted_triple(),
+            Some(b'<') => self.iri(),
+            Some(b'_') => self.bnode(),
+            Some(b'"') => self.literal(),
+            _ => Err(self.error("expected IRI, blank node or literal")),
+        }
+    }
+
+    fn quoted_triple(&mut self) -> ParseResult<Token<'a>> {
+        if self.depth >= MAX_NESTING {
+            return Err(self.error("maximum nesting depth exceeded"));
+        }
+        self.depth += 1;
+        self.pos += 2;
+        self.skip_ws();
+        let s = self.subject()?;
+        self.skip_ws();
+        let p = self.iri()?;
+        self.skip_ws();
+        let o = self.object()?;
+        self.skip_ws();
+        if !self.txt[self.pos..].starts_with(">>") {
+            return Err(self.error("expected '>>'"));
+        }
+        self.pos += 2;
+        self.depth -= 1;
+        Ok(Token::Triple(Box::new([s, p, o])))
+    }
+
+    fn iri(&mut self) -> ParseResult<Token<'a>> {
+        Ok(Token::Iri(self.iriref()?))
+    }
+
+    /// Parse an IRIREF, and return its (unescaped) content.
+    fn iriref(&mut self) -> ParseResult<Cow<'a, str>> {
+        let start = self.pos;
+        self.expect(b'<')?;
+        let forbidden =
+            |c| c <= ' ' || matches!(c, '<' | '>' | '"' | '{' | '}' | '|' | '^' | '`' | '\\');
+        let txt = self.escaped_until(b'>', forbidden)?;
+        // UCHARs can not be used to sneak forbidden chars into the IRI
+        if matches!(txt, Cow::Owned(_)) && txt.chars().any(forbidden) {
+            return Err((format!("invalid IRI <{}>", txt.escape_debug()), start));
+        }
+        if !is_absolute_iri_ref(&txt) {
+            return Err((format!("invalid IRI <{}>", txt), start));
+        }
+        Ok(txt)
+    }
+
+    fn bnode(&mut self) -> ParseResult<Token<'a>> {
+        if !self.txt[self.pos..].starts_with("_:") {
+            return Err(self.error("expected blank node"));
+        }
+        self.pos += 2;
+        let start = self.pos;
+        let mut end = start;
+        for (i, c) in self.txt[start..].char_indices() {
+            let ok = if i == 0 {
+                is_pn_chars_u(c) || c.is_ascii_digit()
+            } else {
+                is_pn_chars(c) || c == '.'
+            };
+            if !ok {
+                break;
+            }
+            self.pos = start + i + c.len_utf8();
+            if c != '.' {
+                end = self.pos;
+            }
+        }
+        if end == start {
+            return Err(self.error("invalid blank node label"));
+        }
+        // a label can not end with '.', which is then the end of the statement
+        self.pos = end;
+        Ok(Token::BNode(&self.txt[start..end]))
+    }
+
+    fn literal(&mut self) -> ParseResult<Token<'a>> {
+        self.expect(b'"')?;
+        let txt = self.escaped_until(b'"', |_| false)?;
+        let suffix = match self.peek() {
+            Some(b'^') => {
+                self.pos += 1;
+                self.expect(b'^')?;
+                Suffix::Datatype(self.iriref()?)
+            }
+            Some(b'@') => {
+                self.pos += 1;
+                let start = self.pos;
+                let len = langtag_len(&self.txt[start..]);
+                if len == 0 {
+                    return Err(self.error("invalid language tag"));
+                }
+                self.pos += len;
+                let tag = &self.txt[start..self.pos];
+                let mut dir = None;
+                if self.txt[self.pos..].starts_with("--") {
+                    self.pos += 2;
+                    let start = self.pos;
+                    self.pos += self.txt[start..]
+                        .bytes()
+                        .take_while(u8::is_ascii_alphabetic)
+                        .count();
+                    dir = Direction::parse(&self.txt[start..self.pos]);
+                    if dir.is_none() {
+                        self.pos = start;
+                        return Err(self.error("invalid base direction"));
+                    }
+                }
+                Suffix::Lang(tag, dir)
+            }
+            _ => Suffix::None,
+        };
+        Ok(Token::Literal(txt, suffix))
+    }
+
+    /// Consume text up to the `end` delimiter (excluded), unescaping it if necessary.
+    ///
+    /// Chars for which `forbidden` returns true are rejected.
+    fn escaped_until<F>(&mut self, end: u8, forbidden: F) -> ParseResult<Cow<'a, str>>
+    where
+        F: Fn(char) -> bool,
+    {
+        let start = self.pos;
+        let mut owned: Option<String> = None;
+        let mut chunk = start;
+        loop {
+            let c = match self.txt[self.pos..].chars().next() {
+                None | Some('\n') | Some('\r') => {
+                    return Err(self.error(&format!("expected '{}'", end as char)))
+                }
+                Some(c) => c,
+            };
+            if c == end as char {
+                let txt = match owned {
+                    None => Cow::Borrowed(&self.txt[start..self.pos]),
+                    Some(mut txt) => {
+                        txt.push_str(&self.txt[chunk..self.pos]);
+                        Cow::Owned(txt)
+                    }
+                };
+                self.pos += 1;
+                return Ok(txt);
+            }
+            if c == '\\' {
+                let owned = owned.get_or_insert_with(String::new);
+                owned.push_str(&self.txt[chunk..self.pos]);
+                owned.push(self.escape(end == b'"')?);
+                chunk = self.pos;
+            } else if forbidden(c) {
+                return Err(self.error(&format!("invalid character {:?}", c)));
+            } else {
+                self.pos += c.len_utf8();
+            }
+        }
+    }
+
+    /// Consume an escape sequence (UCHAR, or also ECHAR if `echar` is true),
+    /// and return the corresponding char.
+    fn escape(&mut self, echar: bool) -> ParseResult<char> {
+        let start = self.pos;
+        let bytes = self.txt.as_bytes();
+        let len = match bytes.get(start + 1) {
+            Some(b'u') => 4,
+            Some(b'U') => 8,
+            Some(b) if echar => {
+                let c = match b {
+                    b't' => '\t',
+                    b'b' => '\u{8}',
+                    b'n' => '\n',
+                    b'r' => '\r',
+                    b'f' => '\u{c}',
+                    b'"' => '"',
+                    b'\'' => '\'',
+                    b'\\' => '\\',
+                    _ => return Err(self.error("invalid escape sequence")),
+                };
+                self.pos += 2;
+                return Ok(c);
+            }
+            _ => return Err(self.error("invalid escape sequence")),
+        };
+        let hex = self
+            .txt
+            .get(start + 2..start + 2 + len)
+            .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+            .ok_or_else(|| self.error("invalid escape sequence"))?;
+        let c = u32::from_str_radix(hex, 16)
+            .ok()
+            .and_then(std::char::from_u32)
+            .ok_or_else(|| self.error("invalid code point"))?;
+        self.pos += 2 + len;
+        Ok(c)
+    }
+}
+
+/// The length of the LANGTAG at the start of `txt` (without the '@'), or 0.
+///
+/// LANGTAG ::= '@' [a-zA-Z]+ ('-' [a-zA-Z0-9]+)*
+fn langtag_len(txt: &str) -> usize {
+    let bytes = txt.as_bytes();
+    let mut len = bytes.iter().take_while(|b| b.is_ascii_alphabetic()).count();
+    if len == 0 {
+        return 0;
+    }
+    while bytes.get(len) == Some(&b'-') {
+        let sub = bytes[len + 1..]
+            .iter()
+            .take_while(|b| b.is_ascii_alphanumeric())
+            .count();
+        if sub == 0 {
+            break;
+        }
+        len += 1 + sub;
+    }
+    len
+}
+
+/// PN_CHARS_U, as defined by N-Triples (i.e. including ':').
+fn is_pn_chars_u(c: char) -> bool {
+    c == '_' || c == ':' || is_pn_chars_base(c)
+}
+
+fn is_pn_chars(c: char) -> bool {
+    is_pn_chars_u(c)
+        || c == '-'
+        || c.is_ascii_digit()
+        || c == '\u{00B7}'
+        || ('\u{0300}'..='\u{036F}').contains(&c)
+        || ('\u{203F}'..='\u{2040}').contains(&c)
+}
+
+fn is_pn_chars_base(c: char) -> bool {
+    matches!(c,
+        'A'..='Z'
+        | 'a'..='z'
+        | '\u{00C0}'..='\u{00D6}'
+        | '\u{00D8}'..='\u{00F6}'
+        | '\u{00F8}'..='\u{02FF}'
+        | '\u{0370}'..='\u{037D}'
+        | '\u{037F}'..='\u{1FFF}'
+        | '\u{200C}'..='\u{200D}'
+        | '\u{2070}'..='\u{218F}'
+        | '\u{2C00}'..='\u{2FEF}'
+        | '\u{3001}'..='\u{D7FF}'
+        | '\u{F900}'..='\u{FDCF}'
+        | '\u{FDF0}'..='\u{FFFD}'
+        | '\u{10000}'..='\u{EFFFF}'
+    )
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dataset::inmem::FastDataset;
+    use crate::dataset::Dataset;
+    use crate::graph::inmem::FastGraph;
+    use crate::graph::Graph;
+    use sophia_api::ns::{rdf, xsd};
+    use sophia_api::term::matcher::ANY;
+    use sophia_api::term::TTerm;
+    use sophia_api::triple::Triple;
+    use sophia_term::literal::convert::AsLiteral;
+    use sophia_term::StaticTerm;
+
+    #[test]
+    fn test_simple_nt_string() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let nt = r#"
+            # a comment
+            <http://localhost/ex#me> <http://example.org/ns/knows> _:b1.
+            _:b1 <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://example.org/ns/Person> . # another comment
+            _:b1 <http://example.org/ns/name> "Alice".
+            _:b1 <http://example.org/ns/name> "Alicia"@es-ES .
+            _:b1 <http://example.org/ns/age> "42"^^<http://www.w3.org/2001/XMLSchema#integer> .
+        "#;
+
+        let mut g = FastGraph::new();
+        let c = NTriplesParser::new().parse_str(nt).add_to_graph(&mut g)?;
+        assert_eq!(c, 5);
+        assert!(g
+            .triples_matching(
+                &ANY,
+                &StaticTerm::new_iri("http://example.org/ns/name").unwrap(),
+                &StaticTerm::new_literal_dt("Alice", xsd::string).unwrap(),
+            )
+            .next()
+            .is_some());
+        assert!(g
+            .triples_matching(
+                &ANY,
+                &ANY,
+                &StaticTerm::new_literal_lang("Alicia", "es-ES").unwrap(),
+            )
+            .next()
+            .is_some());
+        assert!(g
+            .triples_matching(
+                &ANY,
+                &ANY,
+                &StaticTerm::new_literal_dt("42", xsd::integer).unwrap(),
+            )
+            .next()
+            .is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_simple_nq_string() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let nq = r#"
+            <http://localhost/ex#me> <http://example.org/ns/knows> _:b1.
+            _:b1 <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://example.org/ns/Person> <tag:g1>.
+            _:b1 <http://example.org/ns/name> "Alice" _:g2 .
+        "#;
+
+        let mut d = FastDataset::new();
+        let c = NQuadsParser::new().parse_str(nq).add_to_dataset(&mut d)?;
+        assert_eq!(c, 3);
+        assert_eq!(d.graph_names().unwrap().len(), 2);
+        assert!(d
+            .quads_matching(
+                &ANY,
+                &ANY,
+                &StaticTerm::new_literal_dt("Alice", xsd::string).unwrap(),
+                &Some(&StaticTerm::new_bnode("g2").unwrap()),
+            )
+            .next()
+            .is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_escapes() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let nt = r#"<http://example.org/é> <http://example.org/p> "a\tb\"c\\d\U0001F600" ."#;
+        let mut values = vec![];
+        NTriplesParser::new().parse_str(nt).for_each_triple(|t| {
+            values.push(t.s().value().to_string());
+            values.push(t.o().value().to_string());
+        })?;
+        assert_eq!(values, vec!["http://example.org/é", "a\tb\"c\\d😀"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_terms_are_borrowed() {
+        let nt = r#"<http://example.org/a> <http://example.org/p> "no escape" ."#;
+        let (spo, _) = parse_line(nt, false).unwrap().unwrap();
+        assert!(matches!(spo[0], Token::Iri(Cow::Borrowed(_))));
+        assert!(matches!(spo[2], Token::Literal(Cow::Borrowed(_), _)));
+    }
+
+    #[test]
+    fn test_strict_stops_at_error() {
+        let nt = r#"<http://example.org/a> <http://example.org/p> "ok" .
+<http://example.org/a> <http://example.org/p> ."#;
+        let mut count = 0;
+        let res = NTriplesParser::new()
+            .parse_str(nt)
+            .for_each_triple(|_| count += 1);
+        assert_eq!(count, 1);
+        let err = res.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expected IRI, blank node or literal at 2:47"
+        );
+    }
+
+    #[test]
+    fn test_lenient_skips_lines() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let nq = "<http://example.org/a> <http://example.org/p> \"1\" .\n\
+                  <a> <http://example.org/p> \"relative IRI\" .\n\
+                  <http://example.org/a> <http://example.org/p> \"2\" <tag:g> .\n\
+                  _:b <http://example.org/p> \"bad lang\"@- .\n\
+                  _:b <http://example.org/p> \"unterminated .\n\
+                  _:b <http://example.org/p> \"3\" . garbage\n\
+                  _:b <http://example.org/p> \"4\" .\n";
+        let mut source = NQuadsParser::lenient().parse_str(nq);
+        let mut count = 0;
+        source.for_each_quad(|_| count += 1)?;
+        assert_eq!(count, 3);
+        let errors: Vec<_> = source.errors().iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "invalid IRI <a> at 2:1",
+                "invalid language tag at 4:39",
+                "expected '\"' at 5:43",
+                "expected end of line at 6:34",
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_lenient_caps_errors() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let nt = "<a> <http://example.org/p> \"relative IRI\" .\n".repeat(MAX_ERRORS + 10);
+        let mut source = NTriplesParser::lenient().parse_str(&nt);
+        source.for_each_triple(|_| ())?;
+        assert_eq!(source.errors().len(), MAX_ERRORS);
+        assert_eq!(source.error_count(), MAX_ERRORS + 10);
+        Ok(())
+    }
+
+    #[test]
+    fn test_escaped_iri_is_checked() {
+        for esc in &["\\u0020", "\\u003E", "\\u005C", "\\U0000007B", "\\u000A"] {
+            let nt = format!("<http://a/{}b> <http://example.org/p> \"x\" .", esc);
+            let err = parse_line(&nt, false).unwrap_err();
+            assert!(err.0.starts_with("invalid IRI"), "{}", esc);
+            assert_eq!(err.1, 0);
+        }
+        let nt = r#"<http://a/\u00E9> <http://example.org/p> "x" ."#;
+        let (spo, _) = parse_line(nt, false).unwrap().unwrap();
+        assert_eq!(spo[0].as_term().value(), "http://a/é");
+    }
+
+    #[test]
+    fn test_invalid_utf8() {
+        let data: &[u8] = b"<http://example.org/a> <http://example.org/p> \"\xff\" .\n";
+        let mut source = NTriplesParser::lenient().parse(data);
+        source.for_each_triple(|_| ()).unwrap();
+        assert_eq!(source.errors()[0].to_string(), "invalid UTF-8 at 1:48");
+    }
+
+    #[test]
+    fn test_quoted_triples() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let nq = r#"
+            << _:a <http://example.org/p> "x" >> <http://example.org/source> <http://example.org/s> .
+            <http://example.org/s> <http://example.org/says> <<<< _:a <http://example.org/p> "x" >> <http://example.org/q> _:b>> <tag:g> .
+        "#;
+        let mut d = FastDataset::new();
+        let c = NQuadsParser::new().parse_str(nq).add_to_dataset(&mut d)?;
+        assert_eq!(c, 2);
+        let a = StaticTerm::new_bnode("a").unwrap();
+        let p = StaticTerm::new_iri("http://example.org/p").unwrap();
+        let quoted = StaticTerm::new_triple(a, p, "x".as_literal().into());
+        assert_eq!(d.quads_matching(&quoted, &ANY, &ANY, &ANY).count(), 1);
+        let nested = StaticTerm::new_triple(
+            quoted,
+            StaticTerm::new_iri("http://example.org/q").unwrap(),
+            StaticTerm::new_bnode("b").unwrap(),
+        );
+        assert_eq!(d.quads_matching(&ANY, &ANY, &nested, &ANY).count(), 1);
+
+        let err = NQuadsParser::new()
+            .parse_str("<tag:s> <tag:p> <tag:o> << <tag:s> <tag:p> <tag:o> >> .")
+            .for_each_quad(|_| ())
+            .unwrap_err();
+        assert_eq!(err.to_string(), "invalid character '<' at 1:26");
+        let err = NTriplesParser::new()
+            .parse_str("<< <tag:s> <tag:p> <tag:o> <tag:s> <tag:p> <tag:o> .")
+            .for_each_triple(|_| ())
+            .unwrap_err();
+        assert_eq!(err.to_string(), "expected '>>' at 1:28");
+        Ok(())
+    }
+
+    #[test]
+    fn test_quoted_triple_nesting() {
+        let nested = |n: usize| {
+            format!(
+                "{}<tag:s>{} <tag:p> <tag:o> .\n",
+                "<< ".repeat(n),
+                " <tag:p> <tag:o> >>".repeat(n)
+            )
+        };
+        let mut g = FastGraph::new();
+        let c = NTriplesParser::new()
+            .parse_str(&nested(MAX_NESTING))
+            .add_to_graph(&mut g);
+        assert_eq!(c.unwrap(), 1);
+
+        let err = NTriplesParser::new()
+            .parse_str(&nested(MAX_NESTING + 1))
+            .for_each_triple(|_| ())
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("maximum nesting depth exceeded"));
+
+        // in lenient mode, the offending line is skipped
+        let nt = nested(50_000) + "<tag:s> <tag:p> <tag:o> .\n";
+        let mut source = NTriplesParser::lenient().parse_str(&nt);
+        let mut count = 0;
+        source.for_each_triple(|_| count += 1).unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(source.error_count(), 1);
+    }
+
+    #[test]
+    fn test_directional_literals() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let nt = r#"
+            <http://example.org/s> <http://example.org/p> "שלום"@he--rtl .
+            <http://example.org/s> <http://example.org/p> "שלום"@he .
+            <http://example.org/s> <http://example.org/p> "hello"@en--ltr .
+        "#;
+        let mut g = FastGraph::new();
+        let c = NTriplesParser::new().parse_str(nt).add_to_graph(&mut g)?;
+        assert_eq!(c, 3);
+        assert_eq!(g.triples().count(), 3);
+        let rtl = StaticTerm::new_literal_lang_dir("שלום", "he", Direction::Rtl)?;
+        let t = g.triples_matching(&ANY, &ANY, &rtl).next().unwrap()?;
+        assert_eq!(t.o().direction(), Some(Direction::Rtl));
+        assert_eq!(t.o().datatype().unwrap(), rdf::dirLangString);
+
+        let err = NTriplesParser::new()
+            .parse_str(r#"<tag:s> <tag:p> "x"@en--up ."#)
+            .for_each_triple(|_| ())
+            .unwrap_err();
+        assert_eq!(err.to_string(), "invalid base direction at 1:25");
+        Ok(())
+    }
+
+    #[test]
+    fn test_graph_name_in_nt() {
+        let nt = r#"<http://example.org/a> <http://example.org/p> "1" <tag:g> ."#;
+        let res = NTriplesParser::new().parse_str(nt).for_each_triple(|_| ());
+        assert_eq!(res.unwrap_err().to_string(), "expected '.' at 1:51");
+    }
+}
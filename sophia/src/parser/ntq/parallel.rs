@@ -0,0 +1,623 @@
+//! Parallel parsing of N-Triples and N-Quads (requires the `parallel` feature).
+//!
+//! As these formats are line-based,
+//! the input can be split into chunks at line boundaries,
+//! and the chunks can be parsed concurrently on the [rayon] thread pool.
+//! The input is typically a [memory-mapped file](fn.map_file.html),
+//! but a [`BufRead`] can also be parsed, one batch of chunks at a time.
+//!
+//! Statements can be fed into a thread-safe sink
+//! (note that they are then received in no particular order):
+//!
+//! ```
+//! # use sophia::parser::ntq::parallel::ParallelParser;
+//! # use std::sync::atomic::{AtomicUsize, Ordering};
+//! let nq = r#"
+//!     <http://example.org/a> <http://example.org/p> "1" .
+//!     <http://example.org/a> <http://example.org/p> "2" <http://example.org/g> .
+//! "#;
+//! let count = AtomicUsize::new(0);
+//! let report = ParallelParser::new().parse_quads(nq.as_bytes(), |_| {
+//!     count.fetch_add(1, Ordering::Relaxed);
+//!     Ok(()) as Result<(), std::convert::Infallible>
+//! })?;
+//! assert_eq!(report.count, 2);
+//! assert_eq!(count.load(Ordering::Relaxed), 2);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! or collected into per-thread [`sync::LightGraph`]s (or [`sync::LightDataset`]s),
+//! which are merged at the end:
+//!
+//! ```no_run
+//! # use sophia::parser::ntq::parallel::*;
+//! // SAFETY: dump.nt is not modified while it is being parsed
+//! let data = unsafe { map_file("dump.nt")? };
+//! let (graph, report) = ParallelParser::lenient().collect_graph(&data)?;
+//! for err in report.errors {
+//!     eprintln!("{}", err);
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! Whatever the chunk in which they occur,
+//! errors are located by their line and column in the whole input.
+//!
+//! [rayon]: https://docs.rs/rayon/
+//! [`BufRead`]: https://doc.rust-lang.org/std/io/trait.BufRead.html
+//! [`sync::LightGraph`]: ../../../graph/inmem/sync/type.LightGraph.html
+//! [`sync::LightDataset`]: ../../../dataset/inmem/sync/type.LightDataset.html
+
+use super::*;
+use crate::dataset::inmem::sync::LightDataset;
+use crate::dataset::{Dataset, MutableDataset};
+use crate::graph::inmem::sync::LightGraph;
+use crate::graph::{Graph, MutableGraph};
+use crate::quad::Quad;
+use crate::triple::Triple;
+use memmap2::Mmap;
+use rayon::prelude::*;
+use sophia_api::parser::Position;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// Parses N-Triples or N-Quads in parallel.
+#[derive(Clone, Debug)]
+pub struct ParallelParser {
+    /// If true, malformed lines are skipped (and reported in [`ParseReport::errors`]).
+    ///
+    /// [`ParseReport::errors`]: struct.ParseReport.html#structfield.errors
+    pub lenient: bool,
+    /// The approximate size, in bytes, of the chunks parsed by each task.
+    pub chunk_size: usize,
+}
+
+impl Default for ParallelParser {
+    fn default() -> Self {
+        ParallelParser {
+            lenient: false,
+            chunk_size: 1 << 20,
+        }
+    }
+}
+
+/// The outcome of a successful parallel parsing.
+#[derive(Debug, Default)]
+pub struct ParseReport {
+    /// The number of statements parsed.
+    pub count: usize,
+    /// The errors of the skipped lines, in lenient mode (sorted by line).
+    ///
+    /// Only the first [`MAX_ERRORS`](../constant.MAX_ERRORS.html) errors are kept,
+    /// see also [`error_count`](#structfield.error_count).
+    pub errors: Vec<NtqError>,
+    /// The number of skipped lines, in lenient mode,
+    /// including those whose error was not kept.
+    pub error_count: usize,
+}
+
+impl ParseReport {
+    /// Add `count` errors to this report, keeping only the first [`MAX_ERRORS`] of them.
+    ///
+    /// [`MAX_ERRORS`]: ../constant.MAX_ERRORS.html
+    fn add_errors<I: IntoIterator<Item = NtqError>>(&mut self, errors: I, count: usize) {
+        let room = MAX_ERRORS.saturating_sub(self.errors.len());
+        self.errors.extend(errors.into_iter().take(room));
+        self.error_count += count;
+    }
+}
+
+impl ParallelParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a parser skipping malformed lines.
+    pub fn lenient() -> Self {
+        ParallelParser {
+            lenient: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size;
+        self
+    }
+
+    /// Parse N-Triples from `data`, passing each triple to `sink`.
+    ///
+    /// In strict mode, parsing stops at the first error;
+    /// `sink` may however have received triples located after that error.
+    pub fn parse_triples<F, E>(
+        &self,
+        data: &[u8],
+        sink: F,
+    ) -> StreamResult<ParseReport, NtqError, E>
+    where
+        F: Fn(StreamedTriple<ScopedNtqTriple>) -> Result<(), E> + Sync,
+        E: Error + Send + 'static,
+    {
+        let results = split_lines(data, self.chunk_size)
+            .into_par_iter()
+            .map(|chunk| parse_chunk(chunk, self.lenient, false, |spo, _| sink(triple(spo))))
+            .collect();
+        merge_results(results)
+    }
+
+    /// Parse N-Quads from `data`, passing each quad to `sink`.
+    ///
+    /// In strict mode, parsing stops at the first error;
+    /// `sink` may however have received quads located after that error.
+    pub fn parse_quads<F, E>(&self, data: &[u8], sink: F) -> StreamResult<ParseReport, NtqError, E>
+    where
+        F: Fn(StreamedQuad<ScopedNtqQuad>) -> Result<(), E> + Sync,
+        E: Error + Send + 'static,
+    {
+        let results = split_lines(data, self.chunk_size)
+            .into_par_iter()
+            .map(|chunk| parse_chunk(chunk, self.lenient, true, |spo, g| sink(quad(spo, g))))
+            .collect();
+        merge_results(results)
+    }
+
+    /// Parse N-Triples from `reader`, passing each triple to `sink`.
+    ///
+    /// The reader is consumed by batches of chunks, each batch being parsed in parallel.
+    pub fn parse_triples_bufread<R, F, E>(
+        &self,
+        reader: R,
+        sink: F,
+    ) -> StreamResult<ParseReport, NtqError, E>
+    where
+        R: BufRead,
+        F: Fn(StreamedTriple<ScopedNtqTriple>) -> Result<(), E> + Sync,
+        E: Error + Send + 'static,
+    {
+        self.for_each_batch(reader, |batch, first_line| {
+            let report = self.parse_triples(batch, &sink);
+            relocate_result(report, first_line)
+        })
+    }
+
+    /// Parse N-Quads from `reader`, passing each quad to `sink`.
+    ///
+    /// The reader is consumed by batches of chunks, each batch being parsed in parallel.
+    pub fn parse_quads_bufread<R, F, E>(
+        &self,
+        reader: R,
+        sink: F,
+    ) -> StreamResult<ParseReport, NtqError, E>
+    where
+        R: BufRead,
+        F: Fn(StreamedQuad<ScopedNtqQuad>) -> Result<(), E> + Sync,
+        E: Error + Send + 'static,
+    {
+        self.for_each_batch(reader, |batch, first_line| {
+            let report = self.parse_quads(batch, &sink);
+            relocate_result(report, first_line)
+        })
+    }
+
+    /// Parse N-Triples from `data` into a new graph.
+    ///
+    /// Each thread fills its own graph,
+    /// and the other graphs are merged into the first one at the end.
+    pub fn collect_graph(&self, data: &[u8]) -> Result<(LightGraph, ParseReport), NtqError> {
+        let lenient = self.lenient;
+        let parts: Vec<(LightGraph, Vec<(usize, ChunkResult<_>)>)> =
+            split_lines(data, self.chunk_size)
+                .into_par_iter()
+                .enumerate()
+                .fold(
+                    || (LightGraph::new(), Vec::new()),
+                    |(mut graph, mut results), (i, chunk)| {
+                        let res = parse_chunk(chunk, lenient, false, |spo, _| {
+                            let t = triple(spo);
+                            graph.insert(t.s(), t.p(), t.o()).map(|_| ())
+                        });
+                        results.push((i, res));
+                        (graph, results)
+                    },
+                )
+                .collect();
+        let mut parts = parts.into_iter();
+        let (mut graph, mut results) = parts
+            .next()
+            .unwrap_or_else(|| (LightGraph::new(), Vec::new()));
+        for (part, part_results) in parts {
+            graph
+                .insert_all(part.triples())
+                .map_err(|err| -> NtqError {
+                    match err {
+                        SourceError(err) => match err {},
+                        SinkError(err) => match err {},
+                    }
+                })?;
+            results.extend(part_results);
+        }
+        results.sort_by_key(|(i, _)| *i);
+        let results = results.into_iter().map(|(_, res)| res).collect();
+        let report = merge_results(results).map_err(|err| match err {
+            SourceError(err) => err,
+            SinkError(err) => match err {},
+        })?;
+        Ok((graph, report))
+    }
+
+    /// Parse N-Quads from `data` into a new dataset.
+    ///
+    /// Each thread fills its own dataset,
+    /// and the other datasets are merged into the first one at the end.
+    pub fn collect_dataset(&self, data: &[u8]) -> Result<(LightDataset, ParseReport), NtqError> {
+        let lenient = self.lenient;
+        let parts: Vec<(LightDataset, Vec<(usize, ChunkResult<_>)>)> =
+            split_lines(data, self.chunk_size)
+                .into_par_iter()
+                .enumerate()
+                .fold(
+                    || (LightDataset::new(), Vec::new()),
+                    |(mut dataset, mut results), (i, chunk)| {
+                        let res = parse_chunk(chunk, lenient, true, |spo, g| {
+                            let q = quad(spo, g);
+                            dataset.insert(q.s(), q.p(), q.o(), q.g()).map(|_| ())
+                        });
+                        results.push((i, res));
+                        (dataset, results)
+                    },
+                )
+                .collect();
+        let mut parts = parts.into_iter();
+        let (mut dataset, mut results) = parts
+            .next()
+            .unwrap_or_else(|| (LightDataset::new(), Vec::new()));
+        for (part, part_results) in parts {
+            dataset
+                .insert_all(part.quads())
+                .map_err(|err| -> NtqError {
+                    match err {
+                        SourceError(err) => match err {},
+                        SinkError(err) => match err {},
+                    }
+                })?;
+            results.extend(part_results);
+        }
+        results.sort_by_key(|(i, _)| *i);
+        let results = results.into_iter().map(|(_, res)| res).collect();
+        let report = merge_results(results).map_err(|err| match err {
+            SourceError(err) => err,
+            SinkError(err) => match err {},
+        })?;
+        Ok((dataset, report))
+    }
+
+    /// Read `reader` by batches of whole lines, and call `f` on each of them,
+    /// with the number of lines preceding the batch.
+    fn for_each_batch<R, F, E>(
+        &self,
+        mut reader: R,
+        mut f: F,
+    ) -> StreamResult<ParseReport, NtqError, E>
+    where
+        R: BufRead,
+        F: FnMut(&[u8], usize) -> StreamResult<ParseReport, NtqError, E>,
+        E: Error + 'static,
+    {
+        let batch_size = self.chunk_size.max(1) * rayon::current_num_threads();
+        let mut report = ParseReport::default();
+        let mut batch = Vec::with_capacity(batch_size);
+        let mut first_line = 0;
+        loop {
+            batch.clear();
+            (&mut reader)
+                .take(batch_size as u64)
+                .read_to_end(&mut batch)
+                .map_err(|err| SourceError(err.into()))?;
+            if batch.is_empty() {
+                return Ok(report);
+            }
+            if batch.last() != Some(&b'\n') {
+                reader
+                    .read_until(b'\n', &mut batch)
+                    .map_err(|err| SourceError(err.into()))?;
+            }
+            let batch_report = f(&batch, first_line)?;
+            report.count += batch_report.count;
+            report.add_errors(batch_report.errors, batch_report.error_count);
+            first_line += batch.iter().filter(|b| **b == b'\n').count();
+        }
+    }
+}
+
+/// Map the file at `path` into memory, so that it can be parsed by a [`ParallelParser`].
+///
+/// # Safety
+///
+/// The file must not be modified or truncated (by this process or another one)
+/// as long as the returned map is alive,
+/// otherwise reading the map is undefined behaviour.
+/// When this can not be guaranteed,
+/// use [`ParallelParser::parse_triples_bufread`] (or its quad counterpart) instead.
+///
+/// [`ParallelParser`]: struct.ParallelParser.html
+/// [`ParallelParser::parse_triples_bufread`]: struct.ParallelParser.html#method.parse_triples_bufread
+pub unsafe fn map_file<P: AsRef<Path>>(path: P) -> io::Result<Mmap> {
+    let file = File::open(path)?;
+    // SAFETY: the caller guarantees that the file is not modified while mapped
+    unsafe { Mmap::map(&file) }
+}
+
+/// Split `data` into chunks of approximately `chunk_size` bytes,
+/// so that each chunk (except the last one) ends with a newline.
+pub fn split_lines(data: &[u8], chunk_size: usize) -> Vec<&[u8]> {
+    let mut chunks = Vec::with_capacity(data.len() / chunk_size.max(1) + 1);
+    let mut start = 0;
+    while start < data.len() {
+        let mut end = (start + chunk_size.max(1)).min(data.len());
+        match data[end - 1..].iter().position(|b| *b == b'\n') {
+            Some(i) => end += i,
+            None => end = data.len(),
+        }
+        chunks.push(&data[start..end]);
+        start = end;
+    }
+    chunks
+}
+
+/// The outcome of parsing a single chunk.
+struct ChunkResult<E: Error + 'static> {
+    count: usize,
+    lines: usize,
+    errors: Vec<NtqError>,
+    error_count: usize,
+    failure: Option<StreamError<NtqError, E>>,
+}
+
+fn parse_chunk<F, E>(chunk: &[u8], lenient: bool, nquads: bool, mut f: F) -> ChunkResult<E>
+where
+    F: FnMut(&[Token; 3], Option<&Token>) -> Result<(), E>,
+    E: Error + 'static,
+{
+    let mut lines = Lines::new(chunk, lenient);
+    let mut count = 0;
+    let failure = loop {
+        match lines.parse_step(nquads, |spo, g| f(spo, g)) {
+            Ok(true) => count += 1,
+            Ok(false) => break None,
+            Err(err) => break Some(err),
+        }
+    };
+    ChunkResult {
+        count,
+        lines: lines.line_no,
+        errors: lines.errors,
+        error_count: lines.error_count,
+        failure,
+    }
+}
+
+/// Merge the results of consecutive chunks,
+/// locating their errors relatively to the start of the first chunk.
+fn merge_results<E: Error + 'static>(
+    results: Vec<ChunkResult<E>>,
+) -> StreamResult<ParseReport, NtqError, E> {
+    let mut report = ParseReport::default();
+    let mut offset = 0;
+    for res in results {
+        report.count += res.count;
+        report.add_errors(
+            res.errors.into_iter().map(|err| relocate(err, offset)),
+            res.error_count,
+        );
+        match res.failure {
+            None => {}
+            Some(SourceError(err)) => return Err(SourceError(relocate(err, offset))),
+            Some(SinkError(err)) => return Err(SinkError(err)),
+        }
+        offset += res.lines;
+    }
+    Ok(report)
+}
+
+fn relocate_result<E: Error + 'static>(
+    res: StreamResult<ParseReport, NtqError, E>,
+    offset: usize,
+) -> StreamResult<ParseReport, NtqError, E> {
+    match res {
+        Ok(mut report) => {
+            report.errors = report
+                .errors
+                .into_iter()
+                .map(|err| relocate(err, offset))
+                .collect();
+            Ok(report)
+        }
+        Err(SourceError(err)) => Err(SourceError(relocate(err, offset))),
+        Err(err) => Err(err),
+    }
+}
+
+/// Shift the line of `err` by `offset`.
+fn relocate(err: NtqError, offset: usize) -> NtqError {
+    match err {
+        NtqError::Syntax {
+            message,
+            location: Location::Pos(Position::LiCo(line, column)),
+        } => NtqError::Syntax {
+            message,
+            location: Location::from_lico(line + offset, column),
+        },
+        err => err,
+    }
+}
+
+fn triple<'a>(spo: &'a [Token; 3]) -> StreamedTriple<'a, ScopedNtqTriple> {
+    StreamedTriple::scoped([spo[0].as_term(), spo[1].as_term(), spo[2].as_term()])
+}
+
+fn quad<'a>(spo: &'a [Token; 3], g: Option<&'a Token>) -> StreamedQuad<'a, ScopedNtqQuad> {
+    StreamedQuad::scoped((
+        [spo[0].as_term(), spo[1].as_term(), spo[2].as_term()],
+        g.map(Token::as_term),
+    ))
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sophia_api::term::TTerm;
+    use std::convert::Infallible;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn make_nt(n: usize) -> String {
+        (0..n)
+            .map(|i| {
+                format!(
+                    "<http://example.org/s{}> <http://example.org/p> \"{}\" .\n",
+                    i % 17,
+                    i
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_split_lines() {
+        let data = b"a\nbb\nccc\ndddd\n";
+        let chunks = split_lines(data, 3);
+        assert_eq!(chunks, vec![&b"a\nbb\n"[..], &b"ccc\n"[..], &b"dddd\n"[..]]);
+        assert_eq!(split_lines(b"abc", 1), vec![&b"abc"[..]]);
+        assert_eq!(split_lines(b"a\nb", 100), vec![&b"a\nb"[..]]);
+        assert!(split_lines(b"", 100).is_empty());
+    }
+
+    #[test]
+    fn test_parse_triples_with_sink() {
+        let nt = make_nt(1000);
+        let count = AtomicUsize::new(0);
+        let report = ParallelParser::new()
+            .chunk_size(100)
+            .parse_triples(nt.as_bytes(), |t| {
+                assert_eq!(t.p().value(), "http://example.org/p");
+                count.fetch_add(1, Ordering::Relaxed);
+                Ok(()) as Result<(), Infallible>
+            })
+            .unwrap();
+        assert_eq!(report.count, 1000);
+        assert_eq!(count.load(Ordering::Relaxed), 1000);
+    }
+
+    #[test]
+    fn test_collect_graph() {
+        let nt = make_nt(1000);
+        let (graph, report) = ParallelParser::new()
+            .chunk_size(128)
+            .collect_graph(nt.as_bytes())
+            .unwrap();
+        assert_eq!(report.count, 1000);
+        assert_eq!(graph.triples().count(), 1000);
+        assert_eq!(graph.subjects().unwrap().len(), 17);
+    }
+
+    #[test]
+    fn test_collect_dataset() {
+        let nq: String = (0..500)
+            .map(|i| {
+                format!(
+                    "<http://example.org/s> <http://example.org/p> \"{}\" <http://example.org/g{}> .\n",
+                    i,
+                    i % 3
+                )
+            })
+            .collect();
+        let (dataset, report) = ParallelParser::new()
+            .chunk_size(64)
+            .collect_dataset(nq.as_bytes())
+            .unwrap();
+        assert_eq!(report.count, 500);
+        assert_eq!(dataset.quads().count(), 500);
+        assert_eq!(dataset.graph_names().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_errors_are_located_globally() {
+        let mut nt = make_nt(100);
+        nt.push_str("<http://example.org/s> <http://example.org/p> .\n");
+        nt.push_str(&make_nt(100));
+        nt.push_str("<http://example.org/s> \"not a predicate\" \"x\" .\n");
+
+        let (graph, report) = ParallelParser::lenient()
+            .chunk_size(100)
+            .collect_graph(nt.as_bytes())
+            .unwrap();
+        assert_eq!(graph.triples().count(), 100);
+        let errors: Vec<_> = report.errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "expected IRI, blank node or literal at 101:47",
+                "expected '<' at 202:24",
+            ]
+        );
+
+        let err = match ParallelParser::new()
+            .chunk_size(100)
+            .collect_graph(nt.as_bytes())
+        {
+            Ok(_) => panic!("strict parsing should fail"),
+            Err(err) => err,
+        };
+        assert_eq!(
+            err.to_string(),
+            "expected IRI, blank node or literal at 101:47"
+        );
+    }
+
+    #[test]
+    fn test_parse_bufread() {
+        let mut nt = make_nt(1000);
+        nt.push_str("garbage\n");
+        let count = AtomicUsize::new(0);
+        let report = ParallelParser::lenient()
+            .chunk_size(50)
+            .parse_triples_bufread(nt.as_bytes(), |_| {
+                count.fetch_add(1, Ordering::Relaxed);
+                Ok(()) as Result<(), Infallible>
+            })
+            .unwrap();
+        assert_eq!(report.count, 1000);
+        assert_eq!(count.load(Ordering::Relaxed), 1000);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].location().to_string(), "1001:1");
+    }
+
+    #[test]
+    fn test_errors_are_capped() {
+        let mut nt = "garbage\n".repeat(MAX_ERRORS + 10);
+        nt.push_str(&make_nt(10));
+        let (graph, report) = ParallelParser::lenient()
+            .chunk_size(64)
+            .collect_graph(nt.as_bytes())
+            .unwrap();
+        assert_eq!(graph.triples().count(), 10);
+        assert_eq!(report.errors.len(), MAX_ERRORS);
+        assert_eq!(report.error_count, MAX_ERRORS + 10);
+        assert_eq!(
+            report.errors[MAX_ERRORS - 1].location().to_string(),
+            "1000:1"
+        );
+
+        let report = ParallelParser::lenient()
+            .chunk_size(64)
+            .parse_triples_bufread(nt.as_bytes(), |_| Ok(()) as Result<(), Infallible>)
+            .unwrap();
+        assert_eq!(report.count, 10);
+        assert_eq!(report.errors.len(), MAX_ERRORS);
+        assert_eq!(report.error_count, MAX_ERRORS + 10);
+    }
+}
@@ -0,0 +1,515 @@
+//! A reader wrapper keeping track of the prefixes and base IRI declared in Turtle and TriG.
+//!
+//! RIO does not expose the prefixes it reads,
+//! so [`PrefixTracker`] lexes the bytes consumed by the underlying parser,
+//! just enough to recognize `@prefix`, `@base`, `PREFIX` and `BASE` directives.
+//! As this costs a second pass over the data,
+//! the Turtle and TriG parsers only do it on demand, in `parse_with_declarations`.
+
+use sophia_term::iri::{IriParsed, Resolve};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io::{BufRead, Read, Result as IoResult};
+use std::rc::Rc;
+
+/// The prefixes and base IRI declared so far in a Turtle or TriG document.
+#[derive(Clone, Debug, Default)]
+pub struct Declarations {
+    prefixes: Vec<(String, String)>,
+    base: Option<String>,
+}
+
+impl Declarations {
+    /// The prefixes declared so far, in the order of their first declaration.
+    ///
+    /// Relative IRIs are resolved against the base IRI in effect when the prefix was declared.
+    pub fn prefixes(&self) -> &[(String, String)] {
+        &self.prefixes
+    }
+
+    /// The base IRI currently in effect, if any.
+    pub fn base(&self) -> Option<&str> {
+        self.base.as_deref()
+    }
+
+    fn resolve(&self, iri: &str) -> String {
+        match self.base.as_deref().map(IriParsed::new) {
+            Some(Ok(base)) => match base.resolve(iri) {
+                Ok(resolved) => resolved.to_string(),
+                Err(_) => iri.to_string(),
+            },
+            _ => iri.to_string(),
+        }
+    }
+
+    fn add_prefix(&mut self, prefix: String, iri: &str) {
+        let iri = self.resolve(iri);
+        match self.prefixes.iter_mut().find(|(p, _)| *p == prefix) {
+            Some(pair) => pair.1 = iri,
+            None => self.prefixes.push((prefix, iri)),
+        }
+    }
+
+    fn set_base(&mut self, iri: &str) {
+        self.base = Some(self.resolve(iri));
+    }
+}
+
+/// Wraps a `BufRead`, and feeds every consumed byte to a [`Scanner`].
+///
+/// The scanner is shared with the source reading from this tracker,
+/// as the tracker itself is owned by the RIO parser.
+pub(crate) struct PrefixTracker<B> {
+    inner: B,
+    scanner: Rc<RefCell<Scanner>>,
+}
+
+impl<B> PrefixTracker<B> {
+    pub fn new(inner: B, base: Option<String>) -> Self {
+        PrefixTracker {
+            inner,
+            scanner: Rc::new(RefCell::new(Scanner::new(base))),
+        }
+    }
+
+    /// The scanner shared with this tracker.
+    pub fn scanner(&self) -> Rc<RefCell<Scanner>> {
+        self.scanner.clone()
+    }
+}
+
+impl<B: BufRead> Read for PrefixTracker<B> {
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+        let n = self.inner.read(buf)?;
+        self.scanner.borrow_mut().feed(&buf[..n]);
+        Ok(n)
+    }
+}
+
+impl<B: BufRead> BufRead for PrefixTracker<B> {
+    fn fill_buf(&mut self) -> IoResult<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        // the buffer is already filled, so this does not perform any actual read
+        if let Ok(buf) = self.inner.fill_buf() {
+            let amt = amt.min(buf.len());
+            self.scanner.borrow_mut().feed(&buf[..amt]);
+        }
+        self.inner.consume(amt);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Lexer {
+    Normal,
+    Comment,
+    Iri,
+    // a quote has been read, followed by the given number of identical quotes
+    Quotes(u8, usize),
+    ShortString(u8),
+    // the number of consecutive closing quotes read so far
+    LongString(u8, usize),
+    Escape(&'static Lexer),
+}
+
+// the boolean flags indicate SPARQL-style directives, which are not terminated by a dot
+#[derive(Clone, Debug, PartialEq)]
+enum Directive {
+    None,
+    PrefixName(bool),
+    PrefixIri(String, bool),
+    BaseIri(bool),
+}
+
+/// A minimal Turtle lexer, only interested in directives and in the end of statements.
+pub(crate) struct Scanner {
+    lexer: Lexer,
+    directive: Directive,
+    word: Vec<u8>,
+    // offset of the first byte of word
+    word_start: usize,
+    iri: Vec<u8>,
+    // nesting level of brackets, parentheses and braces
+    depth: usize,
+    // set when a statement ends just before (0) or just after (1) the current byte
+    statement_end: Option<usize>,
+    // incremented every time a directive is encountered
+    version: usize,
+    declarations: Declarations,
+    // offset of the current byte, since the start of the data
+    offset: usize,
+    // if tracked, the offsets of blank node labels starting with `_:riog`
+    // (which RIO could confuse with the labels it generates), just after that prefix
+    rio_labels: Option<VecDeque<usize>>,
+}
+
+// string states to which an escape sequence returns
+static SHORT_DQ: Lexer = Lexer::ShortString(b'"');
+static SHORT_SQ: Lexer = Lexer::ShortString(b'\'');
+static LONG_DQ: Lexer = Lexer::LongString(b'"', 0);
+static LONG_SQ: Lexer = Lexer::LongString(b'\'', 0);
+
+fn escape_from(lexer: Lexer) -> Lexer {
+    Lexer::Escape(match lexer {
+        Lexer::ShortString(b'"') => &SHORT_DQ,
+        Lexer::ShortString(_) => &SHORT_SQ,
+        Lexer::LongString(b'"', _) => &LONG_DQ,
+        _ => &LONG_SQ,
+    })
+}
+
+impl Scanner {
+    pub fn new(base: Option<String>) -> Self {
+        Scanner {
+            lexer: Lexer::Normal,
+            directive: Directive::None,
+            word: vec![],
+            word_start: 0,
+            iri: vec![],
+            depth: 0,
+            statement_end: None,
+            version: 0,
+            declarations: Declarations {
+                prefixes: vec![],
+                base,
+            },
+            offset: 0,
+            rio_labels: None,
+        }
+    }
+
+    fn feed(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.feed_byte(*b);
+            self.offset += 1;
+        }
+    }
+
+    /// Feed `bytes` until the end of a statement is found.
+    ///
+    /// Return the offset of the end of the statement in `bytes`,
+    /// and the number of bytes fed (which may be one more than the former),
+    /// or `None` if all bytes were fed without finding the end of a statement.
+    #[cfg(any(test, feature = "async"))]
+    pub fn feed_until_statement_end(&mut self, bytes: &[u8]) -> Option<(usize, usize)> {
+        for (i, b) in bytes.iter().enumerate() {
+            self.feed_byte(*b);
+            self.offset += 1;
+            if let Some(offset) = self.statement_end.take() {
+                return Some((i + offset, i + 1));
+            }
+        }
+        None
+    }
+
+    /// Signal that all the data has been fed.
+    #[cfg(any(test, feature = "async"))]
+    pub fn finish(&mut self) {
+        if self.lexer == Lexer::Normal {
+            self.end_word();
+        }
+    }
+
+    /// Keep track of the blank node labels starting with `_:riog`,
+    /// which RIO could confuse with the labels it generates.
+    #[cfg(any(test, feature = "async"))]
+    pub fn track_rio_labels(&mut self) {
+        self.rio_labels = Some(VecDeque::new());
+    }
+
+    /// Remove and return the tracked labels found before offset `end`
+    /// (see [`track_rio_labels`](#method.track_rio_labels)).
+    ///
+    /// Each label is given as the offset of the byte following `_:riog`.
+    #[cfg(any(test, feature = "async"))]
+    pub fn take_rio_labels(&mut self, end: usize) -> Vec<usize> {
+        let mut labels = vec![];
+        if let Some(rio_labels) = &mut self.rio_labels {
+            while rio_labels.front().map(|o| *o < end).unwrap_or(false) {
+                labels.extend(rio_labels.pop_front());
+            }
+        }
+        labels
+    }
+
+    /// A counter incremented each time the declarations change.
+    #[cfg(any(test, feature = "async"))]
+    pub fn version(&self) -> usize {
+        self.version
+    }
+
+    /// The declarations read so far.
+    pub fn declarations(&self) -> &Declarations {
+        &self.declarations
+    }
+
+    fn feed_byte(&mut self, b: u8) {
+        match self.lexer {
+            Lexer::Normal => self.normal(b),
+            Lexer::Comment => {
+                if b == b'\n' || b == b'\r' {
+                    self.lexer = Lexer::Normal;
+                }
+            }
+            Lexer::Iri => {
+                if b == b'>' {
+                    self.lexer = Lexer::Normal;
+                    let iri = String::from_utf8_lossy(&self.iri).into_owned();
+                    self.iri.clear();
+                    self.iri_token(&iri);
+                } else {
+                    self.iri.push(b);
+                }
+            }
+            Lexer::Quotes(q, n) => {
+                if b == q {
+                    if n == 1 {
+                        self.lexer = Lexer::LongString(q, 0);
+                    } else {
+                        self.lexer = Lexer::Quotes(q, 1);
+                    }
+                } else if n == 1 {
+                    // empty string
+                    self.lexer = Lexer::Normal;
+                    self.normal(b);
+                } else {
+                    self.lexer = Lexer::ShortString(q);
+                    self.feed_byte(b);
+                }
+            }
+            Lexer::ShortString(q) => {
+                if b == b'\\' {
+                    self.lexer = escape_from(self.lexer);
+                } else if b == q {
+                    self.lexer = Lexer::Normal;
+                }
+            }
+            Lexer::LongString(q, n) => {
+                if b == b'\\' {
+                    self.lexer = escape_from(self.lexer);
+                } else if b == q {
+                    self.lexer = if n == 2 {
+                        Lexer::Normal
+                    } else {
+                        Lexer::LongString(q, n + 1)
+                    };
+                } else {
+                    self.lexer = Lexer::LongString(q, 0);
+                }
+            }
+            Lexer::Escape(back) => {
+                self.lexer = *back;
+            }
+        }
+    }
+
+    fn normal(&mut self, b: u8) {
+        match b {
+            b'#' | b'<' | b'"' | b'\'' | b';' | b',' | b'(' | b')' | b'[' | b']' | b'{' | b'}' => {
+                self.end_word();
+                match b {
+                    b'#' => self.lexer = Lexer::Comment,
+                    b'<' => self.lexer = Lexer::Iri,
+                    b'"' | b'\'' => {
+                        self.directive = Directive::None;
+                        self.lexer = Lexer::Quotes(b, 0);
+                    }
+                    b'(' | b'[' | b'{' => {
+                        self.directive = Directive::None;
+                        self.depth += 1;
+                    }
+                    b')' | b']' | b'}' => {
+                        self.directive = Directive::None;
+                        self.depth = self.depth.saturating_sub(1);
+                    }
+                    _ => self.directive = Directive::None,
+                }
+            }
+            b' ' | b'\t' | b'\n' | b'\r' => self.end_word(),
+            _ => {
+                if self.word.is_empty() {
+                    self.word_start = self.offset;
+                }
+                self.word.push(b)
+            }
+        }
+    }
+
+    fn end_word(&mut self) {
+        if self.word.is_empty() {
+            return;
+        }
+        let word = std::mem::take(&mut self.word);
+        if word.starts_with(b"_:riog") {
+            if let Some(rio_labels) = &mut self.rio_labels {
+                rio_labels.push_back(self.word_start + 6);
+            }
+        }
+        let word = String::from_utf8_lossy(&word);
+        // a prefixed name can not end with a dot, so this dot must end a statement
+        if word.ends_with('.') && self.depth == 0 {
+            self.statement_end = Some(0);
+        }
+        self.directive = match std::mem::replace(&mut self.directive, Directive::None) {
+            Directive::PrefixName(sparql) if word.ends_with(':') => {
+                Directive::PrefixIri(word[..word.len() - 1].to_string(), sparql)
+            }
+            _ if word == "@prefix" => Directive::PrefixName(false),
+            _ if word.eq_ignore_ascii_case("prefix") => Directive::PrefixName(true),
+            _ if word == "@base" => Directive::BaseIri(false),
+            _ if word.eq_ignore_ascii_case("base") => Directive::BaseIri(true),
+            _ => Directive::None,
+        };
+    }
+
+    fn iri_token(&mut self, iri: &str) {
+        let declarations = &mut self.declarations;
+        let sparql = match std::mem::replace(&mut self.directive, Directive::None) {
+            Directive::PrefixIri(prefix, sparql) => {
+                declarations.add_prefix(prefix, iri);
+                sparql
+            }
+            Directive::BaseIri(sparql) => {
+                declarations.set_base(iri);
+                sparql
+            }
+            _ => return,
+        };
+        self.version += 1;
+        if sparql {
+            self.statement_end = Some(1);
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn scan(txt: &str) -> Declarations {
+        let mut scanner = Scanner::new(None);
+        // feeding byte by byte ensures that the state is kept across calls
+        for b in txt.as_bytes() {
+            scanner.feed(&[*b]);
+        }
+        scanner.declarations
+    }
+
+    #[test]
+    fn directives() {
+        let d = scan(
+            r#"
+            @prefix ex: <http://example.org/> .
+            PREFIX foaf:<http://xmlns.com/foaf/0.1/>
+            @base <http://example.org/base/> .
+            @prefix : <rel#> .
+            ex:a foaf:name "x" .
+        "#,
+        );
+        assert_eq!(
+            d.prefixes(),
+            &[
+                ("ex".to_string(), "http://example.org/".to_string()),
+                ("foaf".to_string(), "http://xmlns.com/foaf/0.1/".to_string()),
+                ("".to_string(), "http://example.org/base/rel#".to_string()),
+            ]
+        );
+        assert_eq!(d.base(), Some("http://example.org/base/"));
+    }
+
+    #[test]
+    fn directives_in_strings_and_comments_are_ignored() {
+        let d = scan(
+            r#"
+            # @prefix c: <http://example.org/comment/> .
+            <a> <b> "@prefix s: <http://example.org/string/> ." .
+            <a> <b> """
+                @prefix l: <http://example.org/long/> . "" '
+            """ .
+            <a> <b> 'it\'s' .
+            <a> <b> "" .
+            @prefix ok: <http://example.org/ok/> .
+        "#,
+        );
+        assert_eq!(
+            d.prefixes(),
+            &[("ok".to_string(), "http://example.org/ok/".to_string())]
+        );
+        assert_eq!(d.base(), None);
+    }
+
+    #[test]
+    fn runs_of_quotes() {
+        let d = scan(
+            r#"
+            <a> <b> """""x""" , """x\"""" , """x"" . @prefix l1: <l1> .
+            """ , """""" , '''''x''' , "" , '' .
+            @prefix ok: <http://example.org/ok/> .
+        "#,
+        );
+        assert_eq!(
+            d.prefixes(),
+            &[("ok".to_string(), "http://example.org/ok/".to_string())]
+        );
+    }
+
+    #[test]
+    fn statement_ends() {
+        let txt = r#"@prefix : <http://a/>.
+            PREFIX b: <http://b/>
+            :s :p "a.b", 1.5, (1. 2) ; :q [ :r :t ] .
+            :s :p """x.
+            """.#c
+            :s :p :o."#;
+        let bytes = txt.as_bytes();
+        let mut scanner = Scanner::new(None);
+        let mut statements = vec![];
+        let (mut start, mut fed) = (0, 0);
+        while let Some((end, n)) = scanner.feed_until_statement_end(&bytes[fed..]) {
+            statements.push(txt[start..fed + end].trim());
+            start = fed + end;
+            fed += n;
+        }
+        assert_eq!(
+            statements,
+            vec![
+                "@prefix : <http://a/>.",
+                "PREFIX b: <http://b/>",
+                r#":s :p "a.b", 1.5, (1. 2) ; :q [ :r :t ] ."#,
+                ":s :p \"\"\"x.\n            \"\"\".",
+            ]
+        );
+        // the last statement is not followed by anything, so its end can not be detected yet
+        assert_eq!(txt[start..].trim(), "#c\n            :s :p :o.");
+        assert_eq!(scanner.version(), 2);
+    }
+
+    #[test]
+    fn rio_labels() {
+        let txt = r#"_:riog1 <p> "_:riog2", _:a, [ <q> _:riog3 ] .
+            _:riog4."#;
+        let mut scanner = Scanner::new(None);
+        scanner.track_rio_labels();
+        scanner.feed(txt.as_bytes());
+        scanner.finish();
+        let labels = scanner.take_rio_labels(txt.find('\n').unwrap());
+        let ends: Vec<_> = labels.iter().map(|o| &txt[o - 6..o + 1]).collect();
+        assert_eq!(ends, vec!["_:riog1", "_:riog3"]);
+        let labels = scanner.take_rio_labels(txt.len());
+        assert_eq!(labels.len(), 1);
+        assert_eq!(&txt[labels[0]..], "4.");
+        assert!(scanner.take_rio_labels(txt.len()).is_empty());
+    }
+
+    #[test]
+    fn redefined_prefix() {
+        let d = scan("@prefix a: <http://a/1> . @prefix a: <http://a/2> .");
+        assert_eq!(d.prefixes(), &[("a".to_string(), "http://a/2".to_string())]);
+    }
+}
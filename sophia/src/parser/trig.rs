@@ -1,9 +1,16 @@
 //! Adapter for the TriG parser from [RIO](https://github.com/Tpt/rio/blob/master/turtle/src/turtle.rs)
 
+use crate::parser::prefix_tracker::{PrefixTracker, Scanner};
 use crate::parser::rio_common::*;
+use crate::parser::turtle::Declarations;
 use rio_turtle::{TriGParser as RioTriGParser, TurtleError};
 use sophia_api::parser::QuadParser;
+use sophia_api::quad::stream::{QuadSource, StreamResult};
+use sophia_api::quad::streaming_mode::StreamedQuad;
+use std::cell::RefCell;
+use std::error::Error;
 use std::io::BufRead;
+use std::rc::Rc;
 
 /// TriG parser based on RIO.
 #[derive(Clone, Debug, Default)]
@@ -11,18 +18,79 @@ pub struct TriGParser {
     pub base: Option<String>,
 }
 
+impl TriGParser {
+    /// Parse `data` like [`QuadParser::parse`],
+    /// but also keep track of the prefixes and base IRI declared in it.
+    ///
+    /// See [`TurtleParser::parse_with_declarations`](../turtle/struct.TurtleParser.html#method.parse_with_declarations).
+    pub fn parse_with_declarations<B: BufRead>(&self, data: B) -> TriGDeclarationsSource<B> {
+        let data = PrefixTracker::new(data, self.base.clone());
+        let scanner = data.scanner();
+        TriGDeclarationsSource {
+            inner: StrictRioSource::from(RioTriGParser::new(data, self.rio_base())),
+            scanner,
+        }
+    }
+
+    fn rio_base(&self) -> &str {
+        match &self.base {
+            Some(base) => base,
+            None => "x-no-base:///",
+        }
+    }
+}
+
 impl<B: BufRead> QuadParser<B> for TriGParser {
     type Source = StrictRioSource<RioTriGParser<B>, TurtleError>;
     fn parse(&self, data: B) -> Self::Source {
-        let base: &str = match &self.base {
-            Some(base) => &base,
-            None => "x-no-base:///",
-        };
-        StrictRioSource::from(RioTriGParser::new(data, base))
+        StrictRioSource::from(RioTriGParser::new(data, self.rio_base()))
     }
 }
 
-sophia_api::def_mod_functions_for_bufread_parser!(TriGParser, QuadParser);
+/// The [`QuadSource`] returned by [`TriGParser::parse_with_declarations`].
+///
+/// See [`TurtleDeclarationsSource`](../turtle/struct.TurtleDeclarationsSource.html)
+/// regarding the prefixes and base IRI it exposes.
+pub struct TriGDeclarationsSource<B: BufRead> {
+    inner: StrictRioSource<RioTriGParser<PrefixTracker<B>>, TurtleError>,
+    scanner: Rc<RefCell<Scanner>>,
+}
+
+impl<B: BufRead> TriGDeclarationsSource<B> {
+    /// The prefixes declared so far, as (prefix, namespace) pairs.
+    pub fn prefixes(&self) -> Vec<(String, String)> {
+        self.scanner.borrow().declarations().prefixes().to_vec()
+    }
+
+    /// The base IRI currently in effect, if any.
+    pub fn base(&self) -> Option<String> {
+        self.scanner
+            .borrow()
+            .declarations()
+            .base()
+            .map(str::to_string)
+    }
+
+    /// A snapshot of both the prefixes and the base IRI declared so far.
+    pub fn declarations(&self) -> Declarations {
+        self.scanner.borrow().declarations().clone()
+    }
+}
+
+impl<B: BufRead> QuadSource for TriGDeclarationsSource<B> {
+    type Error = TurtleError;
+    type Quad = ScopedRioSourceQuad;
+
+    fn try_for_some_quad<F, EF>(&mut self, f: &mut F) -> StreamResult<bool, TurtleError, EF>
+    where
+        F: FnMut(StreamedQuad<Self::Quad>) -> Result<(), EF>,
+        EF: Error,
+    {
+        self.inner.try_for_some_quad(f)
+    }
+}
+
+def_mod_functions_for_bufread_parser!(TriGParser, QuadParser);
 
 // ---------------------------------------------------------------------------------
 //                                      tests
@@ -55,7 +123,7 @@ mod test {
         let p = TriGParser {
             base: Some("http://localhost/ex".into()),
         };
-        let c = p.parse_str(&trig).add_to_dataset(&mut d)?;
+        let c = p.parse_str(trig).add_to_dataset(&mut d)?;
         assert_eq!(c, 3);
         assert!(d
             .quads_matching(
@@ -86,4 +154,31 @@ mod test {
             .is_some());
         Ok(())
     }
+
+    #[test]
+    fn test_prefixes_and_base() {
+        let trig = r#"
+            @prefix : <http://example.org/ns/> .
+            <#g1> {
+                <#me> :knows _:alice.
+            }
+            BASE <http://example.org/base/>
+            PREFIX foaf: <foaf#>
+        "#;
+
+        let mut src = TriGParser::default().parse_with_declarations(trig.as_bytes());
+        src.for_each_quad(|_| ()).unwrap();
+        let declarations = src.declarations();
+        assert_eq!(
+            declarations.prefixes(),
+            &[
+                ("".to_string(), "http://example.org/ns/".to_string()),
+                (
+                    "foaf".to_string(),
+                    "http://example.org/base/foaf#".to_string()
+                ),
+            ]
+        );
+        assert_eq!(declarations.base(), Some("http://example.org/base/"));
+    }
 }
@@ -0,0 +1,396 @@
+//! Parser for [TriX], an XML syntax for named graphs.
+//!
+//! This parser relies on the local names of the elements,
+//! so it does not check that they are in the TriX namespace.
+//! Since TriX puts no constraint on the kind of term in each position,
+//! it may produce generalized quads.
+//!
+//! [TriX]: https://www.hpl.hp.com/techreports/2004/HPL-2004-56.html
+
+use crate::format::BoxQuad;
+use crate::parser::xml_legacy::XmlReader;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use sophia_api::parser::{Location, QuadParser, WithLocation};
+use sophia_term::iri::Iri;
+use sophia_term::{BoxTerm, TermError};
+use std::io::BufRead;
+
+/// The namespace of TriX elements.
+pub const TRIX_NS: &str = "http://www.w3.org/2004/03/trix/trix-1/";
+
+/// TriX parser based on quick-xml.
+#[derive(Clone, Debug, Default)]
+pub struct TrixParser {}
+
+impl<B: BufRead> QuadParser<B> for TrixParser {
+    type Source = TrixSource<B>;
+    fn parse(&self, data: B) -> Self::Source {
+        TrixSource::new(data)
+    }
+}
+
+def_mod_functions_for_bufread_parser!(TrixParser, QuadParser);
+
+/// This error is raised when parsing TriX documents.
+#[derive(Debug, thiserror::Error)]
+pub enum TrixError {
+    /// Errors raised by malformed XML.
+    #[error("Invalid XML: {source} at {location}")]
+    Xml {
+        source: quick_xml::Error,
+        location: Location,
+    },
+    /// Errors raised by well-formed XML which is not valid TriX.
+    #[error("Invalid TriX: {message} at {location}")]
+    Trix { message: String, location: Location },
+}
+
+impl WithLocation for TrixError {
+    fn location(&self) -> Location {
+        match self {
+            TrixError::Xml { location, .. } | TrixError::Trix { location, .. } => location.clone(),
+        }
+    }
+}
+
+/// The quad source returned by [`TrixParser`](struct.TrixParser.html).
+pub struct TrixSource<B: BufRead> {
+    reader: XmlReader<B>,
+    buffer: Vec<u8>,
+    stack: Vec<Element>,
+    graph_name: Option<BoxTerm>,
+    in_body: bool,
+    terms: Vec<BoxTerm>,
+    text: String,
+    attribute: Option<String>,
+    rooted: bool,
+    done: bool,
+}
+
+/// The TriX elements that may be open at a given time.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Element {
+    TriX,
+    Graph,
+    Triple,
+    Uri,
+    Id,
+    PlainLiteral,
+    TypedLiteral,
+}
+
+impl Element {
+    fn is_term(self) -> bool {
+        !matches!(self, Element::TriX | Element::Graph | Element::Triple)
+    }
+}
+
+/// An owned version of the XML events relevant to TriX.
+enum Token {
+    Start(Element, Option<String>),
+    Empty(Element, Option<String>),
+    Text(String),
+    End,
+    Eof,
+    Ignored,
+}
+
+impl<B: BufRead> TrixSource<B> {
+    fn new(data: B) -> Self {
+        TrixSource {
+            reader: XmlReader::from(Reader::from_reader(data)),
+            buffer: Vec::new(),
+            stack: Vec::new(),
+            graph_name: None,
+            in_body: false,
+            terms: Vec::with_capacity(3),
+            text: String::new(),
+            attribute: None,
+            rooted: false,
+            done: false,
+        }
+    }
+
+    fn next_token(&mut self) -> Result<Token, TrixError> {
+        let mut buffer = std::mem::take(&mut self.buffer);
+        buffer.clear();
+        let token = match self.reader.read_event(&mut buffer) {
+            Ok(Event::Start(e)) => {
+                let (elt, att) = self.element(&e)?;
+                Token::Start(elt, att)
+            }
+            Ok(Event::Empty(e)) => {
+                let (elt, att) = self.element(&e)?;
+                Token::Empty(elt, att)
+            }
+            Ok(Event::Text(e)) => Token::Text(
+                e.unescape_and_decode(&self.reader)
+                    .map_err(|err| self.xml_error(err))?,
+            ),
+            Ok(Event::CData(e)) => Token::Text(
+                self.reader
+                    .decode(&e)
+                    .map_err(|err| self.xml_error(err))?
+                    .to_string(),
+            ),
+            Ok(Event::End(_)) => Token::End,
+            Ok(Event::Eof) => Token::Eof,
+            Ok(_) => Token::Ignored,
+            Err(err) => return Err(self.xml_error(err)),
+        };
+        self.buffer = buffer;
+        Ok(token)
+    }
+
+    /// Identify the TriX element `e`,
+    /// and extract its relevant attribute (language tag or datatype) if any.
+    fn element(&self, e: &BytesStart) -> Result<(Element, Option<String>), TrixError> {
+        let elt = match e.local_name() {
+            b"TriX" => Element::TriX,
+            b"graph" => Element::Graph,
+            b"triple" => Element::Triple,
+            b"uri" => Element::Uri,
+            b"id" => Element::Id,
+            b"plainLiteral" => Element::PlainLiteral,
+            b"typedLiteral" => Element::TypedLiteral,
+            other => {
+                return Err(self.trix_error(format!(
+                    "unexpected element <{}>",
+                    String::from_utf8_lossy(other)
+                )))
+            }
+        };
+        let key: &[u8] = match elt {
+            Element::PlainLiteral => b"xml:lang",
+            Element::TypedLiteral => b"datatype",
+            _ => return Ok((elt, None)),
+        };
+        for att in e.attributes() {
+            let att = att.map_err(|err| self.xml_error(err))?;
+            if att.key == key {
+                let value = att
+                    .unescape_and_decode_value(&self.reader)
+                    .map_err(|err| self.xml_error(err))?;
+                return Ok((elt, Some(value)));
+            }
+        }
+        if elt == Element::TypedLiteral {
+            return Err(self.trix_error("missing datatype in <typedLiteral>".to_string()));
+        }
+        Ok((elt, None))
+    }
+
+    fn start(&mut self, elt: Element, attribute: Option<String>) -> Result<(), TrixError> {
+        let expected = match self.stack.last() {
+            None => elt == Element::TriX && !self.rooted,
+            Some(Element::TriX) => elt == Element::Graph,
+            Some(Element::Graph) => {
+                elt == Element::Triple
+                    || (elt.is_term() && !self.in_body && self.graph_name.is_none())
+            }
+            Some(Element::Triple) => elt.is_term() && self.terms.len() < 3,
+            Some(_) => false,
+        };
+        if !expected {
+            return Err(self.trix_error(format!("unexpected element {:?}", elt)));
+        }
+        match elt {
+            Element::TriX => self.rooted = true,
+            Element::Graph => {
+                self.graph_name = None;
+                self.in_body = false;
+            }
+            Element::Triple => {
+                self.in_body = true;
+                self.terms.clear();
+            }
+            _ => {
+                self.text.clear();
+                self.attribute = attribute;
+            }
+        }
+        self.stack.push(elt);
+        Ok(())
+    }
+
+    /// Close the current element, and return a quad if it was a `<triple>`.
+    fn end(&mut self) -> Result<Option<BoxQuad>, TrixError> {
+        let elt = self.stack.pop().unwrap();
+        match elt {
+            Element::TriX | Element::Graph => Ok(None),
+            Element::Triple => {
+                if self.terms.len() != 3 {
+                    return Err(self.trix_error("<triple> must contain 3 terms".to_string()));
+                }
+                let mut terms = self.terms.drain(..);
+                let s = terms.next().unwrap();
+                let p = terms.next().unwrap();
+                let o = terms.next().unwrap();
+                Ok(Some(([s, p, o], self.graph_name.clone())))
+            }
+            _ => {
+                let term = self
+                    .term(elt)
+                    .map_err(|err| self.trix_error(err.to_string()))?;
+                if self.stack.last() == Some(&Element::Graph) {
+                    self.graph_name = Some(term);
+                } else {
+                    self.terms.push(term);
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    fn term(&mut self, elt: Element) -> Result<BoxTerm, TermError> {
+        let text = std::mem::take(&mut self.text);
+        match elt {
+            Element::Uri => BoxTerm::new_iri(text.trim()),
+            Element::Id => BoxTerm::new_bnode(text.trim()),
+            Element::PlainLiteral => match self.attribute.take() {
+                Some(lang) => BoxTerm::new_literal_lang(text, lang),
+                None => Ok(BoxTerm::from(text)),
+            },
+            Element::TypedLiteral => {
+                let dt = self.attribute.take().unwrap_or_default();
+                BoxTerm::new_literal_dt(text, Iri::<Box<str>>::new(dt.trim())?)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn xml_error(&self, source: quick_xml::Error) -> TrixError {
+        TrixError::Xml {
+            source,
+            location: Location::from_offset(self.reader.buffer_position()),
+        }
+    }
+
+    fn trix_error(&self, message: String) -> TrixError {
+        TrixError::Trix {
+            message,
+            location: Location::from_offset(self.reader.buffer_position()),
+        }
+    }
+
+    fn step(&mut self) -> Result<Option<BoxQuad>, TrixError> {
+        loop {
+            match self.next_token()? {
+                Token::Start(elt, att) => self.start(elt, att)?,
+                Token::Empty(elt, att) => {
+                    self.start(elt, att)?;
+                    if let Some(quad) = self.end()? {
+                        return Ok(Some(quad));
+                    }
+                }
+                Token::Text(txt) => match self.stack.last() {
+                    Some(elt) if elt.is_term() => self.text.push_str(&txt),
+                    _ if txt.trim().is_empty() => (),
+                    _ => return Err(self.trix_error("unexpected text".to_string())),
+                },
+                Token::End => {
+                    if let Some(quad) = self.end()? {
+                        return Ok(Some(quad));
+                    }
+                }
+                Token::Eof => {
+                    if !self.rooted || !self.stack.is_empty() {
+                        return Err(self.trix_error("unexpected end of document".to_string()));
+                    }
+                    return Ok(None);
+                }
+                Token::Ignored => (),
+            }
+        }
+    }
+}
+
+impl<B: BufRead> Iterator for TrixSource<B> {
+    type Item = Result<BoxQuad, TrixError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let res = self.step();
+        // stop at the end of the document, or at the first error
+        self.done = !matches!(res, Ok(Some(_)));
+        res.transpose()
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dataset::inmem::FastDataset;
+    use crate::dataset::Dataset;
+    use crate::quad::stream::QuadSource;
+    use sophia_term::StaticTerm;
+
+    const TRIX: &str = r#"<?xml version="1.0"?>
+<TriX xmlns="http://www.w3.org/2004/03/trix/trix-1/">
+  <graph>
+    <uri>http://example.org/g</uri>
+    <triple>
+      <uri>http://example.org/alice</uri>
+      <uri>http://example.org/name</uri>
+      <plainLiteral xml:lang="en">Alice &amp; co</plainLiteral>
+    </triple>
+    <triple>
+      <id>b1</id>
+      <uri>http://example.org/age</uri>
+      <typedLiteral datatype="http://www.w3.org/2001/XMLSchema#integer">42</typedLiteral>
+    </triple>
+  </graph>
+  <graph>
+    <triple>
+      <uri>http://example.org/alice</uri>
+      <uri>http://example.org/nick</uri>
+      <plainLiteral/>
+    </triple>
+  </graph>
+</TriX>
+"#;
+
+    #[test]
+    fn parse_trix() {
+        let d: FastDataset = parse_str(TRIX).collect_quads().unwrap();
+        assert_eq!(d.quads().count(), 3);
+        let g = StaticTerm::new_iri("http://example.org/g").unwrap();
+        let alice = StaticTerm::new_iri("http://example.org/alice").unwrap();
+        let name = StaticTerm::new_iri("http://example.org/name").unwrap();
+        let nick = StaticTerm::new_iri("http://example.org/nick").unwrap();
+        let lit = StaticTerm::new_literal_lang("Alice & co", "en").unwrap();
+        assert!(d.contains(&alice, &name, &lit, Some(&g)).unwrap());
+        assert!(d
+            .contains(
+                &alice,
+                &nick,
+                &BoxTerm::from(String::new()),
+                None as Option<&StaticTerm>
+            )
+            .unwrap());
+    }
+
+    #[test]
+    fn parse_invalid() {
+        let trix =
+            r#"<TriX><graph><triple><uri>tag:s</uri><uri>tag:p</uri></triple></graph></TriX>"#;
+        let res: Result<FastDataset, _> = parse_str(trix).collect_quads();
+        assert!(res.is_err());
+        let trix = r#"<TriX><graph><triple><uri>tag:s</uri><uri>tag:p</uri><uri>tag:o</uri>"#;
+        let mut src = parse_str(trix);
+        match src.next() {
+            Some(Err(err @ TrixError::Trix { .. })) => {
+                assert!(matches!(err.location(), Location::Pos(_)))
+            }
+            _ => panic!("expected a TriX error"),
+        }
+        assert!(src.next().is_none());
+    }
+}
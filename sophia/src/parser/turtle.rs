@@ -2,26 +2,101 @@
 
 use std::io::BufRead;
 
+use crate::parser::prefix_tracker::{PrefixTracker, Scanner};
 use crate::parser::rio_common::*;
 use rio_api::parser::ParseError;
 use rio_turtle::{TurtleError, TurtleParser as RioTurtleParser};
 use sophia_api::parser::{Location, TripleParser, WithLocation};
+use sophia_api::triple::stream::{StreamResult, TripleSource};
+use sophia_api::triple::streaming_mode::StreamedTriple;
+use std::cell::RefCell;
+use std::error::Error as StdError;
+use std::rc::Rc;
 use thiserror::Error;
 
+pub use crate::parser::prefix_tracker::Declarations;
+
 /// Turtle parser based on RIO.
 #[derive(Clone, Debug, Default)]
 pub struct TurtleParser {
     pub base: Option<String>,
 }
 
+impl TurtleParser {
+    /// Parse `data` like [`TripleParser::parse`],
+    /// but also keep track of the prefixes and base IRI declared in it.
+    ///
+    /// As RIO does not expose them, this lexes the data a second time,
+    /// so it should only be used when those declarations are needed.
+    pub fn parse_with_declarations<B: BufRead>(&self, data: B) -> TurtleDeclarationsSource<B> {
+        let data = PrefixTracker::new(data, self.base.clone());
+        let scanner = data.scanner();
+        TurtleDeclarationsSource {
+            inner: StrictRioSource::from(RioTurtleParser::new(data, self.rio_base())),
+            scanner,
+        }
+    }
+
+    fn rio_base(&self) -> &str {
+        match &self.base {
+            Some(base) => base,
+            None => "x-no-base:///",
+        }
+    }
+}
+
 impl<B: BufRead> TripleParser<B> for TurtleParser {
     type Source = StrictRioSource<RioTurtleParser<B>, TurtleError>;
     fn parse(&self, data: B) -> Self::Source {
-        let base: &str = match &self.base {
-            Some(base) => &base,
-            None => "x-no-base:///",
-        };
-        StrictRioSource::from(RioTurtleParser::new(data, base))
+        StrictRioSource::from(RioTurtleParser::new(data, self.rio_base()))
+    }
+}
+
+/// The [`TripleSource`] returned by [`TurtleParser::parse_with_declarations`].
+///
+/// Besides triples, it gives access to the prefixes and base IRI declared in the parsed data,
+/// for example to pass them to a serializer.
+pub struct TurtleDeclarationsSource<B: BufRead> {
+    inner: StrictRioSource<RioTurtleParser<PrefixTracker<B>>, TurtleError>,
+    scanner: Rc<RefCell<Scanner>>,
+}
+
+impl<B: BufRead> TurtleDeclarationsSource<B> {
+    /// The prefixes declared so far, as (prefix, namespace) pairs.
+    ///
+    /// Since the data is read by chunks, this may include prefixes declared
+    /// slightly after the last triple produced by this source.
+    pub fn prefixes(&self) -> Vec<(String, String)> {
+        self.scanner.borrow().declarations().prefixes().to_vec()
+    }
+
+    /// The base IRI currently in effect, if any.
+    ///
+    /// This is the `base` of the [`TurtleParser`], until a base directive is encountered.
+    pub fn base(&self) -> Option<String> {
+        self.scanner
+            .borrow()
+            .declarations()
+            .base()
+            .map(str::to_string)
+    }
+
+    /// A snapshot of both the prefixes and the base IRI declared so far.
+    pub fn declarations(&self) -> Declarations {
+        self.scanner.borrow().declarations().clone()
+    }
+}
+
+impl<B: BufRead> TripleSource for TurtleDeclarationsSource<B> {
+    type Error = TurtleError;
+    type Triple = ScopedRioSourceTriple;
+
+    fn try_for_some_triple<F, EF>(&mut self, f: &mut F) -> StreamResult<bool, TurtleError, EF>
+    where
+        F: FnMut(StreamedTriple<Self::Triple>) -> Result<(), EF>,
+        EF: StdError,
+    {
+        self.inner.try_for_some_triple(f)
     }
 }
 
@@ -38,7 +113,7 @@ impl WithLocation for SophiaTurtleError {
     }
 }
 
-sophia_api::def_mod_functions_for_bufread_parser!(TurtleParser, TripleParser);
+def_mod_functions_for_bufread_parser!(TurtleParser, TripleParser);
 
 // ---------------------------------------------------------------------------------
 //                                      tests
@@ -66,7 +141,7 @@ mod test {
         let p = TurtleParser {
             base: Some("http://localhost/ex".into()),
         };
-        let c = p.parse_str(&turtle).add_to_graph(&mut g)?;
+        let c = p.parse_str(turtle).add_to_graph(&mut g)?;
         assert_eq!(c, 3);
         assert!(g
             .triples_matching(
@@ -94,4 +169,45 @@ mod test {
             .is_some());
         Ok(())
     }
+
+    #[test]
+    fn test_prefixes_and_base() {
+        let turtle = r#"
+            @prefix : <http://example.org/ns/> .
+            @base <http://example.org/base/> .
+            PREFIX foaf: <foaf#>
+
+            <#me> :knows [ foaf:name "@prefix no: <http://no/> ." ].
+        "#;
+
+        let mut src = TurtleParser::default().parse_with_declarations(turtle.as_bytes());
+        assert_eq!(src.base(), None);
+        src.for_each_triple(|_| ()).unwrap();
+        assert_eq!(
+            src.prefixes(),
+            vec![
+                ("".to_string(), "http://example.org/ns/".to_string()),
+                (
+                    "foaf".to_string(),
+                    "http://example.org/base/foaf#".to_string()
+                ),
+            ]
+        );
+        assert_eq!(src.base(), Some("http://example.org/base/".to_string()));
+    }
+
+    #[test]
+    fn test_base_from_parser() {
+        let p = TurtleParser {
+            base: Some("http://localhost/ex".into()),
+        };
+        let src = p.parse_with_declarations(&b"@prefix : <ns#> ."[..]);
+        assert_eq!(src.base(), Some("http://localhost/ex".to_string()));
+        let c = src
+            .collect_triples::<FastGraph>()
+            .unwrap()
+            .triples()
+            .count();
+        assert_eq!(c, 0);
+    }
 }
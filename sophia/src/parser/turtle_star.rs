@@ -0,0 +1,161 @@
+//! Native parser for [Turtle-star].
+//!
+//! Unlike the parser of module [`turtle`](../turtle/index.html),
+//! this parser does not rely on RIO,
+//! and supports quoted triples (`<< s p o >>`) and annotations (`s p o {| p2 o2 |}`).
+//! It shares its implementation with the [N3 parser](../n3/index.html),
+//! restricted to the Turtle syntax.
+//! Note that it reads the whole document before producing any triple.
+//! Documents nested more deeply than [`MAX_NESTING`](../n3/constant.MAX_NESTING.html)
+//! are rejected.
+//!
+//! See also the [Turtle-star serializer](../../serializer/turtle_star/index.html).
+//!
+//! [Turtle-star]: https://w3c.github.io/rdf-star/cg-spec/editors_draft.html#turtle-star
+
+use crate::parser::n3::{N3Error, N3Source, Parser};
+use sophia_api::parser::TripleParser;
+use sophia_term::BoxTerm;
+use std::io::BufRead;
+
+/// Turtle-star parser.
+#[derive(Clone, Debug, Default)]
+pub struct TurtleStarParser {
+    pub base: Option<String>,
+}
+
+impl<B: BufRead> TripleParser<B> for TurtleStarParser {
+    type Source = TurtleStarSource;
+    fn parse(&self, mut data: B) -> Self::Source {
+        let mut txt = String::new();
+        if let Err(err) = data.read_to_string(&mut txt) {
+            return TurtleStarSource(N3Source::new(vec![], Some(err.into())));
+        }
+        let mut parser = Parser::new(&txt, self.base.clone());
+        parser.turtle = true;
+        let res = parser.document();
+        TurtleStarSource(N3Source::new(parser.quads, res.err()))
+    }
+}
+
+def_mod_functions_for_bufread_parser!(TurtleStarParser, TripleParser);
+
+/// The triple source returned by [`TurtleStarParser`](struct.TurtleStarParser.html).
+///
+/// It yields the triples parsed before the first error, if any, then that error.
+pub struct TurtleStarSource(N3Source);
+
+impl Iterator for TurtleStarSource {
+    type Item = Result<[BoxTerm; 3], N3Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|res| res.map(|(spo, _)| spo))
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::inmem::FastGraph;
+    use crate::graph::Graph;
+    use crate::parser::ntq;
+    use crate::serializer::nt::NtSerializer;
+    use crate::serializer::{Stringifier, TripleSerializer};
+    use crate::triple::stream::TripleSource;
+    use sophia_api::graph::isomorphic_graphs;
+    use sophia_api::term::matcher::ANY;
+    use sophia_api::term::{TTerm, TermKind};
+    use sophia_api::triple::Triple;
+
+    const TTL: &str = r#"
+        @prefix : <http://example.org/> .
+        :alice :says << :bob :age 42 >> {| :confidence 0.8 |} .
+        << :bob a :Person >> :source :doc .
+    "#;
+
+    #[test]
+    fn parse() {
+        let g: FastGraph = parse_str(TTL).collect_triples().unwrap();
+        assert_eq!(g.triples().count(), 3);
+        let t = g
+            .triples_matching(
+                &ANY,
+                &ANY,
+                &[|t: &dyn TTerm| t.kind() == TermKind::QuotedTriple],
+            )
+            .next()
+            .unwrap()
+            .unwrap();
+        let says = t.o().triple().unwrap();
+        assert_eq!(says[0].value(), "http://example.org/bob");
+        assert_eq!(says[2].value(), "42");
+        let annotation = g
+            .triples_matching(&ANY, &ANY, &ANY)
+            .filter_map(Result::ok)
+            .find(|t| {
+                t.s().kind() == TermKind::QuotedTriple
+                    && t.s().triple().unwrap()[0].value() == "http://example.org/alice"
+            })
+            .unwrap();
+        assert_eq!(annotation.o().value(), "0.8");
+    }
+
+    #[test]
+    fn round_trip() {
+        let g1: FastGraph = parse_str(TTL).collect_triples().unwrap();
+        let nt = NtSerializer::new_stringifier()
+            .serialize_graph(&g1)
+            .unwrap()
+            .to_string();
+        let g2: FastGraph = ntq::NTriplesParser::new()
+            .parse_str(&nt)
+            .collect_triples()
+            .unwrap();
+        assert!(isomorphic_graphs(&g1, &g2).unwrap());
+        let g3: FastGraph = parse_str(&nt).collect_triples().unwrap();
+        assert!(isomorphic_graphs(&g1, &g3).unwrap());
+    }
+
+    #[test]
+    fn bnode_labels() {
+        let g: FastGraph = parse_str("_:n3g1 <tag:p> <tag:a>. [ <tag:p> <tag:b> ].")
+            .collect_triples()
+            .unwrap();
+        assert_eq!(g.subjects().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn n3_syntax_is_rejected() {
+        for ttl in &[
+            "<tag:s> <tag:p> ?x .",
+            "<tag:s> <tag:p> { <tag:a> <tag:b> <tag:c> } .",
+            "<tag:s> => <tag:o> .",
+            "@forAll <tag:x> .",
+        ] {
+            assert!(parse_str(ttl).next().unwrap().is_err(), "{}", ttl);
+        }
+    }
+
+    #[test]
+    fn nesting() {
+        use crate::parser::n3::MAX_NESTING;
+
+        let nested = |n: usize| {
+            format!(
+                "<tag:s> <tag:p> {}<tag:o>{} .",
+                "[ <tag:p> ".repeat(n),
+                " ]".repeat(n)
+            )
+        };
+        assert!(parse_str(&nested(MAX_NESTING)).all(|res| res.is_ok()));
+        // must not overflow the stack
+        match parse_str(&nested(10_000)).last() {
+            Some(Err(err)) => assert!(err.to_string().contains("nesting depth")),
+            _ => panic!("expected an error"),
+        }
+    }
+}
@@ -0,0 +1,342 @@
+//! A parser adapter checking literals against a [`DatatypeRegistry`].
+//!
+//! [`ValidatingParser`] wraps any triple or quad parser,
+//! and checks every literal it produces (including in quoted triples).
+//! By default, the first ill-typed literal stops the parser with an error;
+//! in [lenient](struct.ValidatingParser.html#method.lenient) mode,
+//! ill-typed literals are yielded anyway,
+//! and the corresponding errors can be retrieved afterwards
+//! (only the first [`MAX_ERRORS`] of them are kept):
+//!
+//! ```
+//! # use sophia::parser::ntq::NTriplesParser;
+//! # use sophia::parser::validating::ValidatingParser;
+//! # use sophia::parser::TripleParser;
+//! # use sophia::term::literal::registry::DatatypeRegistry;
+//! # use sophia::triple::stream::TripleSource;
+//! # use std::sync::Arc;
+//! let nt = r#"
+//!     <http://example.org/a> <http://example.org/p> "42"^^<http://www.w3.org/2001/XMLSchema#int> .
+//!     <http://example.org/a> <http://example.org/p> "4.2"^^<http://www.w3.org/2001/XMLSchema#int> .
+//! "#;
+//! let registry = Arc::new(DatatypeRegistry::xsd());
+//!
+//! let parser = ValidatingParser::new(NTriplesParser::new(), registry.clone());
+//! assert!(parser.parse_str(nt).for_each_triple(|_| ()).is_err());
+//!
+//! let parser = ValidatingParser::lenient(NTriplesParser::new(), registry);
+//! let mut source = parser.parse_str(nt);
+//! let mut count = 0;
+//! source.for_each_triple(|_| count += 1)?;
+//! assert_eq!(count, 2);
+//! assert_eq!(source.errors().len(), 1);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [`DatatypeRegistry`]: https://docs.rs/sophia_term/latest/sophia_term/literal/registry/struct.DatatypeRegistry.html
+//! [`ValidatingParser`]: struct.ValidatingParser.html
+//! [`MAX_ERRORS`]: ../ntq/constant.MAX_ERRORS.html
+
+use crate::parser::ntq::MAX_ERRORS;
+use sophia_api::parser::{QuadParser, TripleParser};
+use sophia_api::quad::stream::QuadSource;
+use sophia_api::quad::streaming_mode::StreamedQuad;
+use sophia_api::quad::Quad;
+use sophia_api::term::TTerm;
+use sophia_api::triple::stream::*;
+use sophia_api::triple::streaming_mode::StreamedTriple;
+use sophia_api::triple::Triple;
+use sophia_term::literal::registry::DatatypeRegistry;
+use sophia_term::TermError;
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+
+/// A parser wrapping another parser,
+/// and checking the literals it produces against a [`DatatypeRegistry`].
+///
+/// [`DatatypeRegistry`]: https://docs.rs/sophia_term/latest/sophia_term/literal/registry/struct.DatatypeRegistry.html
+#[derive(Clone, Debug)]
+pub struct ValidatingParser<P> {
+    /// The wrapped parser.
+    pub parser: P,
+    /// The registry used to validate literals.
+    pub registry: Arc<DatatypeRegistry>,
+    /// If true, ill-typed literals do not stop the parser
+    /// (see [`ValidatingSource::errors`](struct.ValidatingSource.html#method.errors)).
+    pub lenient: bool,
+}
+
+impl<P> ValidatingParser<P> {
+    /// Build a parser rejecting ill-typed literals.
+    pub fn new(parser: P, registry: Arc<DatatypeRegistry>) -> Self {
+        ValidatingParser {
+            parser,
+            registry,
+            lenient: false,
+        }
+    }
+
+    /// Build a parser flagging ill-typed literals, but still yielding them.
+    pub fn lenient(parser: P, registry: Arc<DatatypeRegistry>) -> Self {
+        ValidatingParser {
+            parser,
+            registry,
+            lenient: true,
+        }
+    }
+
+    fn wrap<S>(&self, source: S) -> ValidatingSource<S> {
+        ValidatingSource {
+            source,
+            registry: self.registry.clone(),
+            lenient: self.lenient,
+            errors: vec![],
+            error_count: 0,
+        }
+    }
+}
+
+impl<P, T> TripleParser<T> for ValidatingParser<P>
+where
+    P: TripleParser<T>,
+{
+    type Source = ValidatingSource<P::Source>;
+    fn parse(&self, data: T) -> Self::Source {
+        self.wrap(self.parser.parse(data))
+    }
+}
+
+impl<P, T> QuadParser<T> for ValidatingParser<P>
+where
+    P: QuadParser<T>,
+{
+    type Source = ValidatingSource<P::Source>;
+    fn parse(&self, data: T) -> Self::Source {
+        self.wrap(self.parser.parse(data))
+    }
+}
+
+/// The triple or quad source produced by [`ValidatingParser`](struct.ValidatingParser.html).
+pub struct ValidatingSource<S> {
+    source: S,
+    registry: Arc<DatatypeRegistry>,
+    lenient: bool,
+    errors: Vec<TermError>,
+    error_count: usize,
+}
+
+impl<S> ValidatingSource<S> {
+    /// The ill-typed literals encountered so far, in lenient mode.
+    ///
+    /// Only the first [`MAX_ERRORS`](../ntq/constant.MAX_ERRORS.html) errors are kept,
+    /// see also [`error_count`](#method.error_count).
+    pub fn errors(&self) -> &[TermError] {
+        &self.errors
+    }
+
+    /// The number of ill-typed literals encountered so far, in lenient mode,
+    /// including those that were not kept.
+    pub fn error_count(&self) -> usize {
+        self.error_count
+    }
+}
+
+impl<S> TripleSource for ValidatingSource<S>
+where
+    S: TripleSource,
+{
+    type Error = ValidatingError<S::Error>;
+    type Triple = S::Triple;
+
+    fn try_for_some_triple<F, E>(&mut self, f: &mut F) -> StreamResult<bool, Self::Error, E>
+    where
+        F: FnMut(StreamedTriple<Self::Triple>) -> Result<(), E>,
+        E: Error,
+    {
+        let ValidatingSource { source, .. } = self;
+        let mut checker = Checker {
+            registry: &self.registry,
+            lenient: self.lenient,
+            errors: &mut self.errors,
+            error_count: &mut self.error_count,
+        };
+        let ret = source.try_for_some_triple(&mut |t| {
+            checker.check([t.s().as_dyn(), t.p().as_dyn(), t.o().as_dyn()])?;
+            f(t).map_err(Interrupt::Sink)
+        });
+        convert_result(ret)
+    }
+}
+
+impl<S> QuadSource for ValidatingSource<S>
+where
+    S: QuadSource,
+{
+    type Error = ValidatingError<S::Error>;
+    type Quad = S::Quad;
+
+    fn try_for_some_quad<F, E>(&mut self, f: &mut F) -> StreamResult<bool, Self::Error, E>
+    where
+        F: FnMut(StreamedQuad<Self::Quad>) -> Result<(), E>,
+        E: Error,
+    {
+        let ValidatingSource { source, .. } = self;
+        let mut checker = Checker {
+            registry: &self.registry,
+            lenient: self.lenient,
+            errors: &mut self.errors,
+            error_count: &mut self.error_count,
+        };
+        let ret = source.try_for_some_quad(&mut |q| {
+            checker.check([q.s().as_dyn(), q.p().as_dyn(), q.o().as_dyn()])?;
+            if let Some(g) = q.g() {
+                checker.check([g.as_dyn()])?;
+            }
+            f(q).map_err(Interrupt::Sink)
+        });
+        convert_result(ret)
+    }
+}
+
+/// The error raised by [`ValidatingSource`](struct.ValidatingSource.html).
+#[derive(Debug, thiserror::Error)]
+pub enum ValidatingError<E>
+where
+    E: Error + 'static,
+{
+    /// Errors raised by the wrapped parser.
+    #[error(transparent)]
+    Parser(E),
+    /// Ill-typed literals (see [`TermError::InvalidLexicalValue`]).
+    ///
+    /// [`TermError::InvalidLexicalValue`]: https://docs.rs/sophia_term/latest/sophia_term/enum.TermError.html#variant.InvalidLexicalValue
+    #[error(transparent)]
+    IllTyped(TermError),
+}
+
+struct Checker<'a> {
+    registry: &'a DatatypeRegistry,
+    lenient: bool,
+    errors: &'a mut Vec<TermError>,
+    error_count: &'a mut usize,
+}
+
+impl<'a> Checker<'a> {
+    fn check<E, const N: usize>(&mut self, terms: [&dyn TTerm; N]) -> Result<(), Interrupt<E>> {
+        for term in terms {
+            if let Err(err) = self.registry.validate(term) {
+                if self.lenient {
+                    *self.error_count += 1;
+                    if self.errors.len() < MAX_ERRORS {
+                        self.errors.push(err);
+                    }
+                } else {
+                    return Err(Interrupt::IllTyped(err));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The error used internally to interrupt the wrapped source,
+/// either because the sink failed or because an ill-typed literal was found.
+#[derive(Debug)]
+enum Interrupt<E> {
+    Sink(E),
+    IllTyped(TermError),
+}
+
+impl<E: fmt::Display> fmt::Display for Interrupt<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Interrupt::Sink(err) => write!(f, "{}", err),
+            Interrupt::IllTyped(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl<E: Error> Error for Interrupt<E> {}
+
+fn convert_result<T, E1, E2>(
+    ret: StreamResult<T, E1, Interrupt<E2>>,
+) -> StreamResult<T, ValidatingError<E1>, E2>
+where
+    E1: Error + 'static,
+    E2: Error + 'static,
+{
+    ret.map_err(|err| match err {
+        SourceError(err) => SourceError(ValidatingError::Parser(err)),
+        SinkError(Interrupt::Sink(err)) => SinkError(err),
+        SinkError(Interrupt::IllTyped(err)) => SourceError(ValidatingError::IllTyped(err)),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parser::ntq::NQuadsParser;
+
+    const NQ: &str = r#"
+        <tag:a> <tag:p> "true"^^<http://www.w3.org/2001/XMLSchema#boolean> <tag:g> .
+        <tag:a> <tag:p> << <tag:a> <tag:p> "yes"^^<http://www.w3.org/2001/XMLSchema#boolean> >> .
+        <tag:a> <tag:p> "1"^^<http://www.w3.org/2001/XMLSchema#boolean> .
+        <tag:a> <tag:p> "2"^^<http://www.w3.org/2001/XMLSchema#boolean> .
+    "#;
+
+    #[test]
+    fn strict_quads() {
+        let registry = Arc::new(DatatypeRegistry::xsd());
+        let parser = ValidatingParser::new(NQuadsParser::new(), registry);
+        let mut count = 0;
+        let err = parser
+            .parse_str(NQ)
+            .for_each_quad(|_| count += 1)
+            .unwrap_err();
+        assert_eq!(count, 1);
+        match err {
+            ValidatingError::IllTyped(TermError::InvalidLexicalValue { lex, .. }) => {
+                assert_eq!(lex, "yes")
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn lenient_quads() {
+        let registry = Arc::new(DatatypeRegistry::xsd());
+        let parser = ValidatingParser::lenient(NQuadsParser::new(), registry);
+        let mut source = parser.parse_str(NQ);
+        let mut count = 0;
+        source.for_each_quad(|_| count += 1).unwrap();
+        assert_eq!(count, 4);
+        assert_eq!(source.errors().len(), 2);
+        assert_eq!(source.error_count(), 2);
+    }
+
+    #[test]
+    fn lenient_caps_errors() {
+        let registry = Arc::new(DatatypeRegistry::xsd());
+        let parser = ValidatingParser::lenient(NQuadsParser::new(), registry);
+        let nq = "<tag:a> <tag:p> \"maybe\"^^<http://www.w3.org/2001/XMLSchema#boolean> .\n"
+            .repeat(MAX_ERRORS + 10);
+        let mut source = parser.parse_str(&nq);
+        let mut count = 0;
+        source.for_each_quad(|_| count += 1).unwrap();
+        assert_eq!(count, MAX_ERRORS + 10);
+        assert_eq!(source.errors().len(), MAX_ERRORS);
+        assert_eq!(source.error_count(), MAX_ERRORS + 10);
+    }
+
+    #[test]
+    fn parser_error() {
+        let registry = Arc::new(DatatypeRegistry::xsd());
+        let parser = ValidatingParser::lenient(NQuadsParser::new(), registry);
+        let err = parser
+            .parse_str("<tag:a> <tag:p> .")
+            .for_each_quad(|_| ())
+            .unwrap_err();
+        assert!(matches!(err, ValidatingError::Parser(_)));
+    }
+}
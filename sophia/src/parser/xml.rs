@@ -26,7 +26,7 @@ impl<B: BufRead> TripleParser<B> for RdfXmlParser {
     }
 }
 
-sophia_api::def_mod_functions_for_bufread_parser!(RdfXmlParser, TripleParser);
+def_mod_functions_for_bufread_parser!(RdfXmlParser, TripleParser);
 
 // ---------------------------------------------------------------------------------
 //                                      tests
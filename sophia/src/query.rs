@@ -32,12 +32,12 @@ impl Query {
                     .iter()
                     .map(|t| {
                         let tm = vec![
-                            matcher(t.s(), &initial_bindings),
-                            matcher(t.p(), &initial_bindings),
-                            matcher(t.o(), &initial_bindings),
+                            matcher(t.s(), initial_bindings),
+                            matcher(t.p(), initial_bindings),
+                            matcher(t.o(), initial_bindings),
                         ];
                         let hint = triples_matching(graph, &tm).size_hint();
-                        (hint.1.unwrap_or(std::usize::MAX), hint.0)
+                        (hint.1.unwrap_or(usize::MAX), hint.0)
                     })
                     .collect();
                 for i in 1..hints.len() {
@@ -108,6 +108,8 @@ fn bindings_for_triple<'a, G>(
 where
     G: Graph,
 {
+    // tm must be heap-allocated (see below), so it can not be an array
+    #[allow(clippy::useless_vec)]
     let tm = vec![
         matcher(tq.s(), &b),
         matcher(tq.p(), &b),
@@ -0,0 +1,334 @@
+//! Typed access to the properties of resources in a graph.
+//!
+//! This module provides [`Resource`], a handle on a term in a graph,
+//! allowing to navigate from resource to resource:
+//!
+//! ```
+//! # use sophia::graph::inmem::FastGraph;
+//! # use sophia::parser::turtle;
+//! # use sophia::resource::Resource;
+//! # use sophia::term::BoxTerm;
+//! # use sophia::term::literal::value::Integer;
+//! # use sophia::term::TTerm;
+//! # use sophia::triple::stream::TripleSource;
+//! let graph: FastGraph = turtle::parse_str(r#"
+//!     @prefix : <http://example.org/>.
+//!     :alice :knows :bob.
+//!     :bob :name "Bob"; :age 42.
+//! "#).collect_triples()?;
+//! let ex = |suffix| BoxTerm::new_iri(format!("http://example.org/{}", suffix));
+//!
+//! let alice = Resource::new(&graph, &ex("alice")?);
+//! let bob = alice.get(&ex("knows")?)?;
+//! assert_eq!(bob.get(&ex("name")?)?.value(), "Bob");
+//! assert_eq!(bob.get_as::<Integer, _>(&ex("age")?)?.to_i128(), Some(42));
+//! assert!(bob.inverse(&ex("knows")?)?[0] == alice);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! It also provides generic functions
+//! to read and write the values of a given property of a given subject,
+//! converting them from and to native types implementing [`TermValue`].
+//!
+//! With the `derive` feature, it also provides the `#[derive(RdfResource)]` macro,
+//! which generates typed accessors using those functions,
+//! for a struct whose fields are annotated with their predicate.
+//! The type of each field determines the expected number of values:
+//! `Option<T>` for at most one value, `Vec<T>` for any number of values,
+//! and any other type for exactly one value.
+//!
+#![cfg_attr(feature = "derive", doc = "```")]
+#![cfg_attr(not(feature = "derive"), doc = "```ignore")]
+//! # use sophia::graph::inmem::FastGraph;
+//! # use sophia::ns::rdfs;
+//! # use sophia::resource::RdfResource;
+//! # use sophia::term::BoxTerm;
+//! mod foaf {
+//!     sophia_api::namespace!("http://xmlns.com/foaf/0.1/", name, age, knows);
+//! }
+//!
+//! #[derive(RdfResource)]
+//! struct Person {
+//!     #[rdf(foaf::name)]
+//!     name: String,
+//!     #[rdf(foaf::age)]
+//!     age: Option<u8>,
+//!     #[rdf(foaf::knows)]
+//!     knows: Vec<BoxTerm>,
+//! }
+//!
+//! let alice = BoxTerm::new_iri("http://example.org/alice")?;
+//! let bob = BoxTerm::new_iri("http://example.org/bob")?;
+//! let mut graph = FastGraph::new();
+//! Person::set_name(&mut graph, &alice, &"Alice".to_string())?;
+//! Person::add_knows(&mut graph, &alice, &bob)?;
+//!
+//! assert_eq!(Person::name(&graph, &alice)?, "Alice");
+//! assert_eq!(Person::age(&graph, &alice)?, None);
+//! let person = Person::from_graph(&graph, &alice)?;
+//! assert_eq!(person.knows, vec![bob]);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [`Resource`]: struct.Resource.html
+//! [`TermValue`]: ../term/literal/convert/trait.TermValue.html
+
+use sophia_api::graph::{Graph, MGResult, MutableGraph};
+use sophia_api::term::{term_eq, CopyTerm, TTerm};
+use sophia_api::triple::Triple;
+use sophia_term::literal::convert::TermValue;
+use sophia_term::{BoxTerm, TermError};
+use std::error::Error;
+
+#[cfg(feature = "derive")]
+pub use sophia_derive::RdfResource;
+
+mod _resource;
+pub use self::_resource::*;
+
+/// The error raised when reading the properties of a resource.
+#[derive(Debug, thiserror::Error)]
+pub enum ResourceError<E>
+where
+    E: Error + 'static,
+{
+    /// Errors raised by the underlying graph.
+    #[error(transparent)]
+    Graph(E),
+    /// A value could not be converted to the expected type.
+    #[error(transparent)]
+    Term(#[from] TermError),
+    /// The property does not have the expected number of values.
+    #[error("expected {expected} value for <{predicate}>, found {found}")]
+    Cardinality {
+        /// The IRI of the property
+        predicate: String,
+        /// The expected number of values
+        expected: &'static str,
+        /// The actual number of values
+        found: usize,
+    },
+}
+
+/// Get all the values of `predicate` for `subject` in `graph`.
+pub fn get_all<G, TS, TP, T>(
+    graph: &G,
+    subject: &TS,
+    predicate: &TP,
+) -> Result<Vec<T>, ResourceError<G::Error>>
+where
+    G: Graph,
+    TS: TTerm + ?Sized,
+    TP: TTerm + ?Sized,
+    T: TermValue,
+{
+    graph
+        .triples_with_sp(subject, predicate)
+        .map(|t| Ok(T::from_term(t.map_err(ResourceError::Graph)?.o())?))
+        .collect()
+}
+
+/// Get the value of `predicate` for `subject` in `graph`, if any.
+///
+/// Fail if the property has several values.
+pub fn get_opt<G, TS, TP, T>(
+    graph: &G,
+    subject: &TS,
+    predicate: &TP,
+) -> Result<Option<T>, ResourceError<G::Error>>
+where
+    G: Graph,
+    TS: TTerm + ?Sized,
+    TP: TTerm + ?Sized,
+    T: TermValue,
+{
+    let mut values = get_all(graph, subject, predicate)?;
+    if values.len() > 1 {
+        return Err(cardinality(predicate, "at most one", values.len()));
+    }
+    Ok(values.pop())
+}
+
+/// Get the value of `predicate` for `subject` in `graph`.
+///
+/// Fail if the property has no value or several values.
+pub fn get_one<G, TS, TP, T>(
+    graph: &G,
+    subject: &TS,
+    predicate: &TP,
+) -> Result<T, ResourceError<G::Error>>
+where
+    G: Graph,
+    TS: TTerm + ?Sized,
+    TP: TTerm + ?Sized,
+    T: TermValue,
+{
+    let mut values = get_all(graph, subject, predicate)?;
+    if values.len() != 1 {
+        return Err(cardinality(predicate, "exactly one", values.len()));
+    }
+    Ok(values.pop().unwrap())
+}
+
+/// Replace all the values of `predicate` for `subject` in `graph` with `values`.
+pub fn set_values<G, TS, TP, T>(
+    graph: &mut G,
+    subject: &TS,
+    predicate: &TP,
+    values: &[T],
+) -> MGResult<G, ()>
+where
+    G: MutableGraph,
+    G::Error: Into<G::MutationError>,
+    TS: TTerm + ?Sized,
+    TP: TTerm + ?Sized,
+    T: TermValue,
+{
+    let old = graph
+        .triples_with_sp(subject, predicate)
+        .map(|t| t.map(|t| BoxTerm::copy(t.o())))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(Into::into)?;
+    for o in old {
+        graph.remove(subject, predicate, &o)?;
+    }
+    for value in values {
+        graph.insert(subject, predicate, &value.to_term())?;
+    }
+    Ok(())
+}
+
+/// Add a value of `predicate` for `subject` in `graph`.
+///
+/// Return `true` if the value was not already present.
+pub fn add_value<G, TS, TP, T>(
+    graph: &mut G,
+    subject: &TS,
+    predicate: &TP,
+    value: &T,
+) -> MGResult<G, bool>
+where
+    G: MutableGraph,
+    TS: TTerm + ?Sized,
+    TP: TTerm + ?Sized,
+    T: TermValue,
+{
+    graph.insert(subject, predicate, &value.to_term())
+}
+
+fn cardinality<TP, E>(predicate: &TP, expected: &'static str, found: usize) -> ResourceError<E>
+where
+    TP: TTerm + ?Sized,
+    E: Error + 'static,
+{
+    ResourceError::Cardinality {
+        predicate: predicate.value().to_string(),
+        expected,
+        found,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::inmem::FastGraph;
+    use sophia_api::ns::{rdfs, xsd};
+
+    fn ex(suffix: &str) -> BoxTerm {
+        BoxTerm::new_iri(format!("http://example.org/{}", suffix)).unwrap()
+    }
+
+    #[test]
+    fn get_and_set() {
+        let mut g = FastGraph::new();
+        let s = ex("s");
+        assert!(get_all::<_, _, _, i32>(&g, &s, &rdfs::label)
+            .unwrap()
+            .is_empty());
+        assert_eq!(get_opt::<_, _, _, i32>(&g, &s, &rdfs::label).unwrap(), None);
+        assert!(matches!(
+            get_one::<_, _, _, i32>(&g, &s, &rdfs::label),
+            Err(ResourceError::Cardinality { found: 0, .. })
+        ));
+
+        set_values(&mut g, &s, &rdfs::label, &[1, 2]).unwrap();
+        let mut values: Vec<i32> = get_all(&g, &s, &rdfs::label).unwrap();
+        values.sort_unstable();
+        assert_eq!(values, vec![1, 2]);
+        assert!(matches!(
+            get_opt::<_, _, _, i32>(&g, &s, &rdfs::label),
+            Err(ResourceError::Cardinality { found: 2, .. })
+        ));
+
+        set_values(&mut g, &s, &rdfs::label, &[3]).unwrap();
+        assert_eq!(get_one::<_, _, _, i32>(&g, &s, &rdfs::label).unwrap(), 3);
+        assert!(!add_value(&mut g, &s, &rdfs::label, &3).unwrap());
+        assert!(matches!(
+            get_one::<_, _, _, String>(&g, &s, &rdfs::label),
+            Err(ResourceError::Term(_))
+        ));
+        assert_eq!(
+            get_one::<_, _, _, BoxTerm>(&g, &s, &rdfs::label).unwrap(),
+            BoxTerm::new_literal_dt("3", xsd::int).unwrap()
+        );
+    }
+
+    #[cfg(feature = "derive")]
+    mod derive {
+        use super::*;
+        use crate::resource::RdfResource;
+        use sophia_term::literal::value::Integer;
+
+        mod foaf {
+            sophia_api::namespace!("http://xmlns.com/foaf/0.1/", name, age, nick, knows);
+        }
+
+        #[derive(Debug, PartialEq, RdfResource)]
+        struct Person {
+            #[rdf(foaf::name)]
+            name: String,
+            #[rdf(foaf::age)]
+            age: Option<Integer>,
+            #[rdf(foaf::nick)]
+            nick: Vec<String>,
+            #[rdf(rdfs::seeAlso)]
+            see_also: Option<BoxTerm>,
+        }
+
+        #[test]
+        fn accessors() {
+            let mut g = FastGraph::new();
+            let alice = ex("alice");
+            Person::set_name(&mut g, &alice, &"Alice".to_string()).unwrap();
+            Person::set_age(&mut g, &alice, Some(&Integer::from(42_i64))).unwrap();
+            Person::add_nick(&mut g, &alice, &"Al".to_string()).unwrap();
+            assert_eq!(Person::name(&g, &alice).unwrap(), "Alice");
+            assert_eq!(
+                Person::age(&g, &alice).unwrap().unwrap().to_i128(),
+                Some(42)
+            );
+            assert_eq!(Person::nick(&g, &alice).unwrap(), vec!["Al".to_string()]);
+            assert_eq!(Person::see_also(&g, &alice).unwrap(), None);
+
+            Person::set_age(&mut g, &alice, None).unwrap();
+            assert!(Person::age(&g, &alice).unwrap().is_none());
+            assert!(Person::name(&g, &ex("bob")).is_err());
+        }
+
+        #[test]
+        fn whole_struct() {
+            let mut g = FastGraph::new();
+            let bob = ex("bob");
+            let person = Person {
+                name: "Bob".into(),
+                age: None,
+                nick: vec!["B".into(), "Bobby".into()],
+                see_also: Some(ex("bob.html")),
+            };
+            person.to_graph(&mut g, &bob).unwrap();
+            let mut back = Person::from_graph(&g, &bob).unwrap();
+            back.nick.sort();
+            assert_eq!(back, person);
+        }
+    }
+}
@@ -0,0 +1,245 @@
+// this module is transparently re-exported by its parent `resource`
+
+use super::*;
+use sophia_api::ns::rdf;
+use sophia_term::literal::convert::TryConvertTerm;
+use std::fmt;
+
+/// A handle on a resource (i.e. a term) in a graph,
+/// allowing to navigate the graph from resource to resource.
+///
+/// All navigation methods are built on
+/// [`Graph::triples_with_sp`] and [`Graph::triples_with_po`].
+///
+/// [`Graph::triples_with_sp`]: ../graph/trait.Graph.html#method.triples_with_sp
+/// [`Graph::triples_with_po`]: ../graph/trait.Graph.html#method.triples_with_po
+pub struct Resource<'g, G> {
+    graph: &'g G,
+    term: BoxTerm,
+}
+
+impl<'g, G> Resource<'g, G>
+where
+    G: Graph,
+{
+    /// Build a handle on `term` in `graph`.
+    pub fn new<T>(graph: &'g G, term: &T) -> Self
+    where
+        T: TTerm + ?Sized,
+    {
+        Resource {
+            graph,
+            term: BoxTerm::copy(term),
+        }
+    }
+
+    /// The graph in which this resource lives.
+    pub fn graph(&self) -> &'g G {
+        self.graph
+    }
+
+    /// The term identifying this resource.
+    pub fn term(&self) -> &BoxTerm {
+        &self.term
+    }
+
+    /// The value of `predicate` for this resource.
+    ///
+    /// Fail if the property has no value or several values.
+    pub fn get<TP>(&self, predicate: &TP) -> Result<Self, ResourceError<G::Error>>
+    where
+        TP: TTerm + ?Sized,
+    {
+        get_one(self.graph, &self.term, predicate).map(|term: BoxTerm| self.with_term(term))
+    }
+
+    /// The value of `predicate` for this resource, if any.
+    ///
+    /// Fail if the property has several values.
+    pub fn get_opt<TP>(&self, predicate: &TP) -> Result<Option<Self>, ResourceError<G::Error>>
+    where
+        TP: TTerm + ?Sized,
+    {
+        let value: Option<BoxTerm> = get_opt(self.graph, &self.term, predicate)?;
+        Ok(value.map(|term| self.with_term(term)))
+    }
+
+    /// All the values of `predicate` for this resource.
+    pub fn get_all<TP>(&self, predicate: &TP) -> Result<Vec<Self>, ResourceError<G::Error>>
+    where
+        TP: TTerm + ?Sized,
+    {
+        let values: Vec<BoxTerm> = get_all(self.graph, &self.term, predicate)?;
+        Ok(values
+            .into_iter()
+            .map(|term| self.with_term(term))
+            .collect())
+    }
+
+    /// The value of `predicate` for this resource, converted to `T`.
+    ///
+    /// Fail if the property has no value or several values,
+    /// or if the value can not be converted.
+    pub fn get_as<T, TP>(&self, predicate: &TP) -> Result<T, ResourceError<G::Error>>
+    where
+        T: TryConvertTerm,
+        TP: TTerm + ?Sized,
+    {
+        Ok(T::try_convert(self.get(predicate)?.term())?)
+    }
+
+    /// All the resources having this resource as the value of `predicate`.
+    pub fn inverse<TP>(&self, predicate: &TP) -> Result<Vec<Self>, ResourceError<G::Error>>
+    where
+        TP: TTerm + ?Sized,
+    {
+        self.graph
+            .triples_with_po(predicate, &self.term)
+            .map(|t| {
+                let t = t.map_err(ResourceError::Graph)?;
+                Ok(self.with_term(BoxTerm::copy(t.s())))
+            })
+            .collect()
+    }
+
+    /// The types (`rdf:type`) of this resource.
+    pub fn types(&self) -> Result<Vec<Self>, ResourceError<G::Error>> {
+        self.get_all(&rdf::type_)
+    }
+
+    fn with_term(&self, term: BoxTerm) -> Self {
+        Resource {
+            graph: self.graph,
+            term,
+        }
+    }
+}
+
+impl<'g, G> Clone for Resource<'g, G> {
+    fn clone(&self) -> Self {
+        Resource {
+            graph: self.graph,
+            term: self.term.clone(),
+        }
+    }
+}
+
+impl<'g, G> fmt::Debug for Resource<'g, G> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Resource").field(&self.term).finish()
+    }
+}
+
+impl<'g, G, T> PartialEq<T> for Resource<'g, G>
+where
+    T: TTerm + ?Sized,
+{
+    fn eq(&self, other: &T) -> bool {
+        term_eq(&self.term, other)
+    }
+}
+
+impl<'g, G> TTerm for Resource<'g, G> {
+    fn kind(&self) -> sophia_api::term::TermKind {
+        self.term.kind()
+    }
+    fn value_raw(&self) -> sophia_api::term::RawValue<'_> {
+        self.term.value_raw()
+    }
+    fn datatype(&self) -> Option<sophia_api::term::SimpleIri<'_>> {
+        self.term.datatype()
+    }
+    fn language(&self) -> Option<&str> {
+        self.term.language()
+    }
+    fn direction(&self) -> Option<sophia_api::term::Direction> {
+        self.term.direction()
+    }
+    fn triple(&self) -> Option<[&dyn TTerm; 3]> {
+        self.term.triple()
+    }
+    fn as_dyn(&self) -> &dyn TTerm {
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::inmem::FastGraph;
+    use crate::parser::turtle;
+    use sophia_api::triple::stream::TripleSource;
+    use sophia_term::literal::value::Integer;
+
+    const TTL: &str = r#"
+        @prefix : <http://example.org/>.
+        @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#>.
+
+        :alice a :Person, :Agent; :name "Alice"; :age 42; :knows :bob, :carol.
+        :bob a :Person; :name "Bob"; :address [ :city "Lyon" ].
+        :carol :knows :bob.
+    "#;
+
+    fn ex(suffix: &str) -> BoxTerm {
+        BoxTerm::new_iri(format!("http://example.org/{}", suffix)).unwrap()
+    }
+
+    fn graph() -> FastGraph {
+        turtle::parse_str(TTL).collect_triples().unwrap()
+    }
+
+    #[test]
+    fn navigate() {
+        let g = graph();
+        let bob = Resource::new(&g, &ex("bob"));
+        let city = bob.get(&ex("address")).unwrap().get(&ex("city")).unwrap();
+        assert_eq!(city.value(), "Lyon");
+        assert_eq!(
+            bob.get_as::<String, _>(&ex("name")).unwrap(),
+            "Bob".to_string()
+        );
+        assert!(bob.get_opt(&ex("age")).unwrap().is_none());
+        assert!(matches!(
+            bob.get(&ex("age")),
+            Err(ResourceError::Cardinality { found: 0, .. })
+        ));
+
+        let alice = Resource::new(&g, &ex("alice"));
+        assert_eq!(
+            alice.get_as::<Integer, _>(&ex("age")).unwrap().to_i128(),
+            Some(42)
+        );
+        assert!(matches!(
+            alice.get_as::<i32, _>(&ex("name")),
+            Err(ResourceError::Term(_))
+        ));
+        assert!(matches!(
+            alice.get(&ex("knows")),
+            Err(ResourceError::Cardinality { found: 2, .. })
+        ));
+        let mut names = alice
+            .get_all(&ex("knows"))
+            .unwrap()
+            .iter()
+            .map(|r| r.get_as::<String, _>(&ex("name")).ok())
+            .collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, vec![None, Some("Bob".to_string())]);
+    }
+
+    #[test]
+    fn inverse_and_types() {
+        let g = graph();
+        let bob = Resource::new(&g, &ex("bob"));
+        let mut known_by = bob.inverse(&ex("knows")).unwrap();
+        known_by.sort_by_key(|r| r.value().to_string());
+        assert_eq!(known_by.len(), 2);
+        assert!(known_by[0] == ex("alice"));
+        assert!(known_by[1] == ex("carol"));
+
+        let alice = Resource::new(&g, &ex("alice"));
+        assert_eq!(alice.types().unwrap().len(), 2);
+        assert!(bob.types().unwrap()[0] == ex("Person"));
+        assert!(Resource::new(&g, &ex("dave")).types().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,574 @@
+//! A [serde] data format mapping Rust structs to RDF, and back
+//! (only available with the `serde` feature).
+//!
+//! [`to_triples`] turns a struct into triples about a given subject,
+//! where each field becomes a property.
+//! [`from_graph`] reads a resource from any [`Graph`] back into the struct.
+//!
+//! The predicate of each field is determined by a [`Mapping`]:
+//! * if the mapping has an explicit predicate for the field name, it is used;
+//! * otherwise, if the field name is an absolute IRI
+//!   (e.g. through `#[serde(rename = "http://xmlns.com/foaf/0.1/name")]`),
+//!   it is used as the predicate;
+//! * otherwise, the field name is appended to the namespace of the mapping, if any.
+//!
+//! Field values are mapped as follows:
+//! * `bool`, numbers, `char` and strings become literals with the corresponding XSD datatype;
+//! * byte arrays become `xsd:base64Binary` literals;
+//! * fields of type [`Term`](../term/enum.Term.html) (e.g. `BoxTerm`) are kept as is,
+//!   which is the way to have IRIs as values;
+//! * nested structs become blank nodes, described by their own fields;
+//! * unit variants of enums become string literals;
+//! * `Option` fields become optional properties (`None` produces no triple);
+//! * `Vec` fields become repeated properties,
+//!   unless the field is declared as a list in the [`Mapping`]
+//!   or annotated with `#[serde(with = "sophia::serde_rdf::as_list")]`,
+//!   in which case they become an `rdf:List`.
+//!
+//! ```
+//! # use sophia::graph::inmem::FastGraph;
+//! # use sophia::serde_rdf::{from_graph, to_graph, Mapping};
+//! # use sophia::term::BoxTerm;
+//! # use serde::{Deserialize, Serialize};
+//! #[derive(Debug, PartialEq, Deserialize, Serialize)]
+//! struct Person {
+//!     name: String,
+//!     #[serde(rename = "http://xmlns.com/foaf/0.1/age")]
+//!     age: u8,
+//!     nick: Vec<String>,
+//!     homepage: Option<BoxTerm>,
+//! }
+//!
+//! let mut mapping = Mapping::new();
+//! mapping.set_namespace("http://xmlns.com/foaf/0.1/");
+//! let alice = BoxTerm::new_iri("http://example.org/alice")?;
+//! let person = Person {
+//!     name: "Alice".into(),
+//!     age: 42,
+//!     nick: vec!["Al".into()],
+//!     homepage: None,
+//! };
+//!
+//! let mut graph = FastGraph::new();
+//! let inserted = to_graph(&person, &alice, &mapping, &mut graph)?;
+//! assert_eq!(inserted, 3);
+//!
+//! let back: Person = from_graph(&graph, &alice, &mapping)?;
+//! assert_eq!(back, person);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [serde]: https://serde.rs/
+//! [`to_triples`]: fn.to_triples.html
+//! [`from_graph`]: fn.from_graph.html
+//! [`Graph`]: ../graph/trait.Graph.html
+//! [`Mapping`]: struct.Mapping.html
+
+use crate::collection::{self, CollectionGraph, ListError};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sophia_api::graph::{Graph, MutableGraph};
+use sophia_api::ns::rdf;
+use sophia_api::term::{term_eq, CopyTerm, TTerm};
+use sophia_term::{BoxTerm, TermError};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+
+mod _de;
+mod _ser;
+
+/// Map the names of struct fields to predicates.
+///
+/// See the [module documentation](index.html) for how predicates are determined.
+#[derive(Clone, Debug, Default)]
+pub struct Mapping {
+    namespace: Option<String>,
+    predicates: HashMap<String, BoxTerm>,
+    lists: HashSet<String>,
+}
+
+impl Mapping {
+    /// Build an empty mapping.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the namespace used for fields that have no explicit predicate.
+    pub fn set_namespace(&mut self, namespace: &str) -> &mut Self {
+        self.namespace = Some(namespace.to_string());
+        self
+    }
+
+    /// Set the predicate used for the given field.
+    pub fn set_predicate<T>(&mut self, field: &str, predicate: &T) -> &mut Self
+    where
+        T: TTerm + ?Sized,
+    {
+        self.predicates
+            .insert(field.to_string(), BoxTerm::copy(predicate));
+        self
+    }
+
+    /// Map the given field (which must be a sequence) to an `rdf:List`
+    /// rather than to repeated properties.
+    pub fn set_list(&mut self, field: &str) -> &mut Self {
+        self.lists.insert(field.to_string());
+        self
+    }
+
+    /// The predicate used for the given field.
+    pub fn predicate(&self, field: &str) -> Result<BoxTerm, Error> {
+        if let Some(predicate) = self.predicates.get(field) {
+            return Ok(predicate.clone());
+        }
+        if let Ok(iri) = BoxTerm::new_iri(field) {
+            if iri.is_absolute() {
+                return Ok(iri);
+            }
+        }
+        match &self.namespace {
+            Some(ns) => Ok(BoxTerm::new_iri(format!("{}{}", ns, field))?),
+            None => Err(Error::UnmappedField(field.to_string())),
+        }
+    }
+
+    /// Whether the given field is mapped to an `rdf:List`.
+    pub fn is_list(&self, field: &str) -> bool {
+        self.lists.contains(field)
+    }
+}
+
+/// The error type of this module.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// A field has no corresponding predicate in the [`Mapping`](struct.Mapping.html).
+    #[error("no predicate for field {0:?}")]
+    UnmappedField(String),
+    /// A value can not be mapped to RDF, or RDF data can not be mapped to the expected type.
+    #[error("{0} is not supported")]
+    Unsupported(String),
+    /// A property has several values where only one was expected.
+    #[error("several values for field {0:?}")]
+    MultipleValues(String),
+    /// An invalid term was produced.
+    #[error(transparent)]
+    Term(#[from] TermError),
+    /// An error raised by the underlying graph.
+    #[error("graph error: {0}")]
+    Graph(String),
+    /// Any other error.
+    #[error("{0}")]
+    Custom(String),
+}
+
+impl serde::ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::Custom(msg.to_string())
+    }
+}
+
+impl serde::de::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::Custom(msg.to_string())
+    }
+}
+
+/// Serialize `value` (which must be a struct) into triples about `subject`.
+///
+/// Nested structs and lists are represented by fresh blank nodes.
+pub fn to_triples<T, TS>(
+    value: &T,
+    subject: &TS,
+    mapping: &Mapping,
+) -> Result<Vec<[BoxTerm; 3]>, Error>
+where
+    T: Serialize + ?Sized,
+    TS: TTerm + ?Sized,
+{
+    let triples = _ser::to_triples(value, BoxTerm::copy(subject), mapping)?;
+    _ser::relabel(triples, |n, taken| {
+        Ok((0..)
+            .map(|i| format!("sr{}", i))
+            .filter(|label| !taken.contains(label))
+            .take(n)
+            .map(BoxTerm::new_bnode_unchecked)
+            .collect())
+    })
+}
+
+/// Serialize `value` (which must be a struct) into `graph`,
+/// as triples about `subject`.
+///
+/// Nested structs and lists are represented by
+/// [fresh](../collection/trait.CollectionGraph.html#method.fresh_bnode) blank nodes,
+/// which are not already used in `graph`.
+///
+/// Return the number of triples actually inserted.
+pub fn to_graph<T, TS, G>(
+    value: &T,
+    subject: &TS,
+    mapping: &Mapping,
+    graph: &mut G,
+) -> Result<usize, Error>
+where
+    T: Serialize + ?Sized,
+    TS: TTerm + ?Sized,
+    G: MutableGraph,
+{
+    let triples = _ser::to_triples(value, BoxTerm::copy(subject), mapping)?;
+    let triples = _ser::relabel(triples, |n, taken| {
+        collection::fresh_bnodes(graph, n, |label| taken.contains(label)).map_err(graph_error)
+    })?;
+    let mut count = 0;
+    for [s, p, o] in triples {
+        if graph.insert(&s, &p, &o).map_err(graph_error)? {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+/// Deserialize a struct of type `T` from the properties of `subject` in `graph`.
+pub fn from_graph<T, G, TS>(graph: &G, subject: &TS, mapping: &Mapping) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+    G: Graph,
+    TS: TTerm + ?Sized,
+{
+    _de::from_graph(graph, BoxTerm::copy(subject), mapping)
+}
+
+/// Use with `#[serde(with = "sophia::serde_rdf::as_list")]`
+/// on a sequence field, to map it to an `rdf:List`
+/// rather than to repeated properties.
+pub mod as_list {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Serialize a sequence as an `rdf:List`.
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize + ?Sized,
+        S: Serializer,
+    {
+        serializer.serialize_newtype_struct(super::LIST, value)
+    }
+
+    /// Deserialize a sequence from an `rdf:List`.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        struct ListVisitor<T>(std::marker::PhantomData<T>);
+        impl<'de, T: Deserialize<'de>> serde::de::Visitor<'de> for ListVisitor<T> {
+            type Value = T;
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "an RDF list")
+            }
+            fn visit_newtype_struct<D: Deserializer<'de>>(self, d: D) -> Result<T, D::Error> {
+                T::deserialize(d)
+            }
+        }
+        deserializer.deserialize_newtype_struct(super::LIST, ListVisitor(std::marker::PhantomData))
+    }
+}
+
+/// The name of the newtype struct used by [`as_list`](as_list/index.html).
+const LIST: &str = "sophia::serde_rdf::List";
+
+fn graph_error<E: std::error::Error>(err: E) -> Error {
+    Error::Graph(err.to_string())
+}
+
+fn list_error<E: std::error::Error>(err: ListError<E>) -> Error {
+    match err {
+        ListError::Graph(err) => graph_error(err),
+        ListError::Cyclic(_) => Error::Unsupported("cyclic list".into()),
+        err => Error::Custom(err.to_string()),
+    }
+}
+
+fn objects<G, TS, TP>(graph: &G, s: &TS, p: &TP) -> Result<Vec<BoxTerm>, Error>
+where
+    G: Graph,
+    TS: TTerm + ?Sized,
+    TP: TTerm + ?Sized,
+{
+    use sophia_api::triple::Triple;
+    graph
+        .triples_with_sp(s, p)
+        .map(|t| t.map(|t| BoxTerm::copy(t.o())).map_err(graph_error))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::inmem::FastGraph;
+    use serde::{Deserialize, Serialize};
+    use sophia_api::ns::xsd;
+    use sophia_api::triple::stream::TripleSource;
+
+    const EX: &str = "http://example.org/";
+
+    fn mapping() -> Mapping {
+        let mut mapping = Mapping::new();
+        mapping.set_namespace(EX);
+        mapping
+    }
+
+    fn ex(suffix: &str) -> BoxTerm {
+        BoxTerm::new_iri(format!("{}{}", EX, suffix)).unwrap()
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    enum Color {
+        Red,
+        Green,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    struct Address {
+        street: String,
+        zip: Option<u32>,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    struct Record {
+        #[serde(rename = "http://www.w3.org/2000/01/rdf-schema#label")]
+        label: String,
+        flag: bool,
+        small: i8,
+        big: u64,
+        ratio: f64,
+        initial: char,
+        color: Color,
+        tags: Vec<String>,
+        #[serde(with = "as_list")]
+        ordered: Vec<i32>,
+        ranks: Vec<u16>,
+        address: Address,
+        friend: Option<BoxTerm>,
+        missing: Option<String>,
+        #[serde(with = "serde_bytes_like")]
+        data: Vec<u8>,
+        unit: (),
+    }
+
+    // a minimal equivalent of the serde_bytes crate
+    mod serde_bytes_like {
+        use serde::{Deserializer, Serializer};
+        pub fn serialize<S: Serializer>(v: &[u8], s: S) -> Result<S::Ok, S::Error> {
+            s.serialize_bytes(v)
+        }
+        pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+            struct V;
+            impl<'de> serde::de::Visitor<'de> for V {
+                type Value = Vec<u8>;
+                fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    write!(f, "bytes")
+                }
+                fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Vec<u8>, E> {
+                    Ok(v)
+                }
+            }
+            d.deserialize_byte_buf(V)
+        }
+    }
+
+    fn record() -> Record {
+        Record {
+            label: "a \"record\"".into(),
+            flag: true,
+            small: -3,
+            big: u64::MAX,
+            ratio: 0.5,
+            initial: 'r',
+            color: Color::Green,
+            tags: vec!["x".into(), "y".into()],
+            ordered: vec![3, 1, 2],
+            ranks: vec![],
+            address: Address {
+                street: "Main street".into(),
+                zip: Some(12345),
+            },
+            friend: Some(ex("bob")),
+            missing: None,
+            data: b"hello".to_vec(),
+            unit: (),
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        let mut mapping = mapping();
+        mapping.set_predicate("initial", &ex("firstLetter"));
+        let mut g = FastGraph::new();
+        to_graph(&record(), &ex("r"), &mapping, &mut g).unwrap();
+        let back: Record = from_graph(&g, &ex("r"), &mapping).unwrap();
+        assert_eq!(back, record());
+    }
+
+    #[test]
+    fn triples() {
+        let mut mapping = mapping();
+        mapping.set_list("tags");
+        let triples = to_triples(&record(), &ex("r"), &mapping).unwrap();
+        let has = |p: &BoxTerm, o: &BoxTerm| {
+            triples
+                .iter()
+                .any(|[s, p2, o2]| s == &ex("r") && p2 == p && o2 == o)
+        };
+        assert!(has(
+            &BoxTerm::copy(&sophia_api::ns::rdfs::label),
+            &BoxTerm::new_literal_dt("a \"record\"", xsd::string).unwrap()
+        ));
+        assert!(has(
+            &ex("small"),
+            &BoxTerm::new_literal_dt("-3", xsd::byte).unwrap()
+        ));
+        assert!(has(
+            &ex("color"),
+            &BoxTerm::new_literal_dt("Green", xsd::string).unwrap()
+        ));
+        assert!(has(&ex("friend"), &ex("bob")));
+        assert!(has(
+            &ex("data"),
+            &BoxTerm::new_literal_dt("aGVsbG8=", xsd::base64Binary).unwrap()
+        ));
+        // tags is a list: only one tags triple
+        assert_eq!(triples.iter().filter(|t| t[1] == ex("tags")).count(), 1);
+        // ranks is an empty sequence, missing is None: no triple
+        assert!(!triples
+            .iter()
+            .any(|t| t[1] == ex("ranks") || t[1] == ex("missing")));
+        // 3 list cells for ordered, 2 for tags, 2 triples for the address
+        assert_eq!(triples.len(), 12 + 3 * 2 + 2 * 2 + 2);
+    }
+
+    #[test]
+    fn fresh_bnodes() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Holder {
+            address: Address,
+            friend: BoxTerm,
+        }
+        let holder = |street: &str| Holder {
+            address: Address {
+                street: street.into(),
+                zip: None,
+            },
+            friend: BoxTerm::new_bnode("g1").unwrap(),
+        };
+        // blank nodes of the value are preserved, and not reused for nested structs
+        let triples = to_triples(&holder("a"), &ex("h"), &mapping()).unwrap();
+        let address = &triples.iter().find(|t| t[1] == ex("address")).unwrap()[2];
+        assert_eq!(address, &BoxTerm::new_bnode("sr0").unwrap());
+        let friend = [ex("h"), ex("friend"), BoxTerm::new_bnode("g1").unwrap()];
+        assert!(triples.iter().any(|t| t == &friend));
+        let bnode_subject = BoxTerm::new_bnode("sr0").unwrap();
+        let triples = to_triples(&holder("a"), &bnode_subject, &mapping()).unwrap();
+        let address = &triples.iter().find(|t| t[1] == ex("address")).unwrap()[2];
+        assert_eq!(address, &BoxTerm::new_bnode("sr1").unwrap());
+        assert_eq!(triples.iter().filter(|t| t[0] == bnode_subject).count(), 2);
+
+        // blank nodes already in the graph are not reused either
+        let mut g = FastGraph::new();
+        let sr2 = BoxTerm::new_bnode("sr2").unwrap();
+        g.insert(&sr2, &ex("street"), &ex("x")).unwrap();
+        to_graph(&holder("a"), &ex("h1"), &mapping(), &mut g).unwrap();
+        to_graph(&holder("b"), &ex("h2"), &mapping(), &mut g).unwrap();
+        assert_eq!(g.triples_with_p(&ex("street")).count(), 3);
+        assert_eq!(g.triples_with_s(&sr2).count(), 1);
+        let back: Holder = from_graph(&g, &ex("h2"), &mapping()).unwrap();
+        assert_eq!(back, holder("b"));
+        // nor those used only inside quoted triples
+        let mut g = FastGraph::new();
+        let quoted = BoxTerm::new_triple(
+            BoxTerm::new_bnode("sr0").unwrap(),
+            BoxTerm::copy(&rdf::value),
+            BoxTerm::copy(&rdf::nil),
+        );
+        g.insert(&quoted, &rdf::type_, &rdf::Statement).unwrap();
+        to_graph(&holder("a"), &ex("h"), &mapping(), &mut g).unwrap();
+        assert_eq!(g.triples_with_p(&ex("address")).count(), 1);
+        let sr0 = BoxTerm::new_bnode("sr0").unwrap();
+        assert_eq!(g.triples_with_o(&sr0).count(), 0);
+    }
+
+    #[test]
+    fn repeated_and_list_from_turtle() {
+        let ttl = r#"
+            @prefix : <http://example.org/>.
+            :r :label "r"; :tags "a", "b"; :ordered (1 2 3); :address [ :street "s" ].
+        "#;
+        #[derive(Debug, Deserialize)]
+        struct Partial {
+            #[serde(rename = "http://example.org/label")]
+            name: String,
+            tags: Vec<String>,
+            #[serde(with = "as_list")]
+            ordered: Vec<u8>,
+            address: Address,
+        }
+        let g: FastGraph = crate::parser::turtle::parse_str(ttl)
+            .collect_triples()
+            .unwrap();
+        let mut p: Partial = from_graph(&g, &ex("r"), &mapping()).unwrap();
+        p.tags.sort();
+        assert_eq!(p.name, "r");
+        assert_eq!(p.tags, vec!["a", "b"]);
+        assert_eq!(p.ordered, vec![1, 2, 3]);
+        assert_eq!(p.address.zip, None);
+    }
+
+    #[test]
+    fn errors() {
+        #[derive(Debug, Serialize, Deserialize)]
+        struct Single {
+            value: u8,
+        }
+        // no namespace
+        assert!(matches!(
+            to_triples(&Single { value: 1 }, &ex("s"), &Mapping::new()),
+            Err(Error::UnmappedField(_))
+        ));
+        // not a struct
+        assert!(to_triples(&42, &ex("s"), &mapping()).is_err());
+
+        let mut g = FastGraph::new();
+        // missing value
+        assert!(from_graph::<Single, _, _>(&g, &ex("s"), &mapping()).is_err());
+        // multiple values
+        g.insert(
+            &ex("s"),
+            &ex("value"),
+            &BoxTerm::new_literal_dt("1", xsd::byte).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            from_graph::<Single, _, _>(&g, &ex("s"), &mapping())
+                .unwrap()
+                .value,
+            1
+        );
+        g.insert(
+            &ex("s"),
+            &ex("value"),
+            &BoxTerm::new_literal_dt("2", xsd::byte).unwrap(),
+        )
+        .unwrap();
+        assert!(matches!(
+            from_graph::<Single, _, _>(&g, &ex("s"), &mapping()),
+            Err(Error::MultipleValues(_))
+        ));
+        // invalid lexical value
+        g.insert(
+            &ex("t"),
+            &ex("value"),
+            &BoxTerm::new_literal_dt("300", xsd::byte).unwrap(),
+        )
+        .unwrap();
+        assert!(from_graph::<Single, _, _>(&g, &ex("t"), &mapping()).is_err());
+    }
+}
@@ -0,0 +1,562 @@
+// this module is private to its parent `serde_rdf`
+
+use super::*;
+use serde::de::value::SeqDeserializer;
+use serde::de::{
+    self, DeserializeSeed, Deserializer, EnumAccess, IntoDeserializer, MapAccess, SeqAccess,
+    VariantAccess, Visitor,
+};
+use sophia_api::ns::xsd;
+use sophia_api::term::TermKind;
+use sophia_term::literal::convert::{Base64Binary, HexBinary};
+use std::str::FromStr;
+
+pub(super) fn from_graph<T, G>(graph: &G, subject: BoxTerm, mapping: &Mapping) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+    G: Graph,
+{
+    T::deserialize(TermDeserializer {
+        graph,
+        mapping,
+        term: subject,
+    })
+}
+
+/// Deserializes a value from a single RDF term.
+struct TermDeserializer<'g, G> {
+    graph: &'g G,
+    mapping: &'g Mapping,
+    term: BoxTerm,
+}
+
+impl<'g, G: Graph> TermDeserializer<'g, G> {
+    fn lexical(&self, expected: &str) -> Result<String, Error> {
+        match self.term.kind() {
+            TermKind::Literal => Ok(self.term.value().to_string()),
+            _ => Err(Error::Custom(format!(
+                "expected {}, found {}",
+                expected, self.term
+            ))),
+        }
+    }
+
+    fn parse<T: FromStr>(&self, expected: &str) -> Result<T, Error> {
+        self.lexical(expected)?
+            .trim()
+            .parse()
+            .map_err(|_| Error::Custom(format!("expected {}, found {}", expected, self.term)))
+    }
+
+    fn has_datatype(&self, datatypes: &[sophia_api::term::SimpleIri]) -> bool {
+        self.term
+            .datatype()
+            .is_some_and(|dt| datatypes.iter().any(|other| term_eq(&dt, other)))
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                visitor.$visit(self.parse(stringify!($method))?)
+            }
+        )*
+    };
+}
+
+impl<'de, 'g, G: Graph> Deserializer<'de> for TermDeserializer<'g, G> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if self.has_datatype(&[xsd::boolean]) {
+            self.deserialize_bool(visitor)
+        } else if self.has_datatype(&[xsd::double, xsd::float, xsd::decimal]) {
+            self.deserialize_f64(visitor)
+        } else if self.has_datatype(&[
+            xsd::integer,
+            xsd::long,
+            xsd::int,
+            xsd::short,
+            xsd::byte,
+            xsd::unsignedLong,
+            xsd::unsignedInt,
+            xsd::unsignedShort,
+            xsd::unsignedByte,
+            xsd::nonNegativeInteger,
+            xsd::negativeInteger,
+            xsd::nonPositiveInteger,
+            xsd::positiveInteger,
+        ]) {
+            match self.parse::<i64>("integer") {
+                Ok(i) => visitor.visit_i64(i),
+                Err(_) => self.deserialize_u64(visitor),
+            }
+        } else if term_eq(&self.term, &rdf::nil)
+            || !objects(self.graph, &self.term, &rdf::first)?.is_empty()
+        {
+            self.deserialize_seq(visitor)
+        } else {
+            self.deserialize_string(visitor)
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.lexical("boolean")?.trim() {
+            "true" | "1" => visitor.visit_bool(true),
+            "false" | "0" => visitor.visit_bool(false),
+            _ => Err(Error::Custom(format!(
+                "expected boolean, found {}",
+                self.term
+            ))),
+        }
+    }
+
+    deserialize_parsed! {
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let lex = self.lexical("char")?;
+        let mut chars = lex.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => visitor.visit_char(c),
+            _ => Err(Error::Custom(format!("expected char, found {}", self.term))),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.term.kind() {
+            TermKind::QuotedTriple => Err(Error::Custom(format!(
+                "expected string, found {}",
+                self.term
+            ))),
+            _ => visitor.visit_string(self.term.value().to_string()),
+        }
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let lex = self.lexical("bytes")?;
+        let bytes = if self.has_datatype(&[xsd::hexBinary]) {
+            HexBinary::from_str(&lex).map(|bin| bin.0)
+        } else {
+            Base64Binary::from_str(&lex).map(|bin| bin.0)
+        };
+        visitor.visit_byte_buf(
+            bytes.map_err(|_| Error::Custom(format!("expected bytes, found {}", self.term)))?,
+        )
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let items = self.graph.read_list(&self.term).map_err(list_error)?;
+        visitor.visit_seq(TermsAccess {
+            graph: self.graph,
+            mapping: self.mapping,
+            items: items.into_iter(),
+        })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Error> {
+        Err(Error::Unsupported("map".into()))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self.term.kind() {
+            TermKind::Iri | TermKind::BlankNode => visitor.visit_map(FieldsAccess {
+                graph: self.graph,
+                mapping: self.mapping,
+                subject: self.term,
+                fields: fields.iter(),
+                current: None,
+            }),
+            _ => Err(Error::Custom(format!(
+                "expected IRI or blank node, found {}",
+                self.term
+            ))),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        if name == "Term" {
+            // terms (see sophia_term's serde support) are kept as is
+            visitor.visit_enum(TermVariant::new(&self.term)?)
+        } else {
+            let variant: de::value::StringDeserializer<Error> =
+                self.term.value().to_string().into_deserializer();
+            visitor.visit_enum(variant)
+        }
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+}
+
+/// Deserializes the values of `subject`'s fields.
+struct FieldsAccess<'g, G> {
+    graph: &'g G,
+    mapping: &'g Mapping,
+    subject: BoxTerm,
+    fields: std::slice::Iter<'static, &'static str>,
+    current: Option<&'static str>,
+}
+
+impl<'de, 'g, G: Graph> MapAccess<'de> for FieldsAccess<'g, G> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        match self.fields.next() {
+            Some(field) => {
+                self.current = Some(field);
+                let field: de::value::StrDeserializer<Error> = field.into_deserializer();
+                seed.deserialize(field).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let field = self
+            .current
+            .take()
+            .ok_or_else(|| Error::Custom("value requested before key".into()))?;
+        let predicate = self.mapping.predicate(field)?;
+        seed.deserialize(ValuesDeserializer {
+            graph: self.graph,
+            mapping: self.mapping,
+            field,
+            values: objects(self.graph, &self.subject, &predicate)?,
+            list: self.mapping.is_list(field),
+        })
+    }
+}
+
+/// Deserializes a value from all the values of a property.
+struct ValuesDeserializer<'g, G> {
+    graph: &'g G,
+    mapping: &'g Mapping,
+    field: &'static str,
+    values: Vec<BoxTerm>,
+    list: bool,
+}
+
+impl<'g, G: Graph> ValuesDeserializer<'g, G> {
+    fn single(mut self) -> Result<TermDeserializer<'g, G>, Error> {
+        match self.values.len() {
+            1 => Ok(TermDeserializer {
+                graph: self.graph,
+                mapping: self.mapping,
+                term: self.values.pop().unwrap(),
+            }),
+            0 => Err(de::Error::missing_field(self.field)),
+            _ => Err(Error::MultipleValues(self.field.to_string())),
+        }
+    }
+}
+
+macro_rules! forward_to_single {
+    ($($method:ident($($arg:ident: $ty:ty),*),)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, Error> {
+                self.single()?.$method($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, 'g, G: Graph> Deserializer<'de> for ValuesDeserializer<'g, G> {
+    type Error = Error;
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if self.values.is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if self.list {
+            self.single()?.deserialize_seq(visitor)
+        } else {
+            visitor.visit_seq(TermsAccess {
+                graph: self.graph,
+                mapping: self.mapping,
+                items: self.values.into_iter(),
+            })
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        if name == LIST {
+            visitor.visit_newtype_struct(ValuesDeserializer { list: true, ..self })
+        } else {
+            visitor.visit_newtype_struct(self)
+        }
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    forward_to_single! {
+        deserialize_any(),
+        deserialize_bool(),
+        deserialize_i8(),
+        deserialize_i16(),
+        deserialize_i32(),
+        deserialize_i64(),
+        deserialize_i128(),
+        deserialize_u8(),
+        deserialize_u16(),
+        deserialize_u32(),
+        deserialize_u64(),
+        deserialize_u128(),
+        deserialize_f32(),
+        deserialize_f64(),
+        deserialize_char(),
+        deserialize_str(),
+        deserialize_string(),
+        deserialize_bytes(),
+        deserialize_byte_buf(),
+        deserialize_unit_struct(name: &'static str),
+        deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize),
+        deserialize_map(),
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+        deserialize_identifier(),
+    }
+}
+
+/// Deserializes the items of a sequence (repeated values or list items).
+struct TermsAccess<'g, G> {
+    graph: &'g G,
+    mapping: &'g Mapping,
+    items: std::vec::IntoIter<BoxTerm>,
+}
+
+impl<'de, 'g, G: Graph> SeqAccess<'de> for TermsAccess<'g, G> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        self.items
+            .next()
+            .map(|term| {
+                seed.deserialize(TermDeserializer {
+                    graph: self.graph,
+                    mapping: self.mapping,
+                    term,
+                })
+            })
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.items.len())
+    }
+}
+
+/// Deserializes terms and literals, as encoded by sophia_term's serde support.
+struct TermVariant {
+    variant: &'static str,
+    content: VariantContent,
+}
+
+enum VariantContent {
+    Str(String),
+    Literal(Box<TermVariant>),
+    Fields(Vec<String>),
+}
+
+impl TermVariant {
+    fn new(term: &BoxTerm) -> Result<Self, Error> {
+        let value = term.value().to_string();
+        let (variant, content) = match term.kind() {
+            TermKind::Iri => ("Iri", VariantContent::Str(value)),
+            TermKind::BlankNode => ("BNode", VariantContent::Str(value)),
+            TermKind::Variable => ("Variable", VariantContent::Str(value)),
+            TermKind::Literal => {
+                let (variant, fields) = match (term.language(), term.direction()) {
+                    (None, _) => {
+                        let dt = term.datatype().map(|dt| dt.value().to_string());
+                        ("Typed", vec![value, dt.unwrap_or_default()])
+                    }
+                    (Some(tag), None) => ("Lang", vec![value, tag.to_string()]),
+                    (Some(tag), Some(dir)) => (
+                        "LangDir",
+                        vec![value, tag.to_string(), dir.as_str().to_string()],
+                    ),
+                };
+                let literal = TermVariant {
+                    variant,
+                    content: VariantContent::Fields(fields),
+                };
+                ("Literal", VariantContent::Literal(Box::new(literal)))
+            }
+            TermKind::QuotedTriple => return Err(Error::Unsupported("quoted triple".into())),
+            kind => return Err(Error::Unsupported(format!("{:?}", kind))),
+        };
+        Ok(TermVariant { variant, content })
+    }
+}
+
+impl<'de> EnumAccess<'de> for TermVariant {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), Error> {
+        let variant: de::value::StrDeserializer<Error> = self.variant.into_deserializer();
+        Ok((seed.deserialize(variant)?, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for TermVariant {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        Err(Error::Custom(format!(
+            "unexpected variant {}",
+            self.variant
+        )))
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        match self.content {
+            VariantContent::Str(txt) => {
+                let txt: de::value::StringDeserializer<Error> = txt.into_deserializer();
+                seed.deserialize(txt)
+            }
+            VariantContent::Literal(literal) => seed.deserialize(*literal),
+            VariantContent::Fields(_) => Err(Error::Custom(format!(
+                "unexpected variant {}",
+                self.variant
+            ))),
+        }
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        match self.content {
+            VariantContent::Fields(fields) => {
+                SeqDeserializer::<_, Error>::new(fields.into_iter()).deserialize_any(visitor)
+            }
+            _ => Err(Error::Custom(format!(
+                "unexpected variant {}",
+                self.variant
+            ))),
+        }
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Error> {
+        Err(Error::Custom(format!(
+            "unexpected variant {}",
+            self.variant
+        )))
+    }
+}
+
+impl<'de> Deserializer<'de> for TermVariant {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_enum(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
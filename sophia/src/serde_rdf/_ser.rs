@@ -0,0 +1,492 @@
+// this module is private to its parent `serde_rdf`
+
+use super::*;
+use serde::ser::{self, Impossible};
+use sophia_api::ns::xsd;
+use sophia_api::term::{Direction, TermKind};
+use sophia_term::iri::Iri;
+use sophia_term::literal::convert::Base64Binary;
+use sophia_term::literal::Literal;
+
+type Link = Option<(BoxTerm, BoxTerm)>;
+
+pub(super) fn to_triples<T>(
+    value: &T,
+    subject: BoxTerm,
+    mapping: &Mapping,
+) -> Result<Vec<[BoxTerm; 3]>, Error>
+where
+    T: Serialize + ?Sized,
+{
+    let mut ctx = Context {
+        mapping,
+        triples: vec![],
+        bnodes: 0,
+    };
+    let subject = user_bnode(&subject);
+    let ser = TermSerializer {
+        ctx: &mut ctx,
+        subject: Some(subject.clone()),
+        link: None,
+        list: true,
+    };
+    match value.serialize(ser)? {
+        Some(term) if term == subject => Ok(ctx.triples),
+        _ => Err(Error::Unsupported(
+            "serializing anything but a struct".into(),
+        )),
+    }
+}
+
+// During serialization, the blank nodes of the value are prefixed with 'u',
+// and the blank nodes generated for nested structs and lists are labelled 'g{n}',
+// so that they can not clash;
+// they are given their final labels by [`relabel`].
+
+/// The blank node standing for `term` during serialization, if it is a blank node.
+fn user_bnode(term: &BoxTerm) -> BoxTerm {
+    match term.kind() {
+        TermKind::BlankNode => BoxTerm::new_bnode_unchecked(format!("u{}", term.value_raw().0)),
+        _ => term.clone(),
+    }
+}
+
+/// Restore the blank nodes of the value in `triples`,
+/// and replace the generated blank nodes with those returned by `fresh`.
+///
+/// `fresh` is called once, with the number of blank nodes required,
+/// and the labels of the blank nodes of the value, which it must not return.
+pub(super) fn relabel<F>(
+    mut triples: Vec<[BoxTerm; 3]>,
+    fresh: F,
+) -> Result<Vec<[BoxTerm; 3]>, Error>
+where
+    F: FnOnce(usize, &HashSet<String>) -> Result<Vec<BoxTerm>, Error>,
+{
+    let mut taken = HashSet::new();
+    let mut generated = vec![];
+    let mut distinct: HashMap<BoxTerm, usize> = HashMap::new();
+    for (i, term) in triples.iter_mut().flatten().enumerate() {
+        if term.kind() != TermKind::BlankNode {
+            continue;
+        }
+        if term.value_raw().0.starts_with('u') {
+            *term = BoxTerm::new_bnode_unchecked(&term.value_raw().0[1..]);
+            taken.insert(term.value_raw().0.to_string());
+        } else {
+            let n = distinct.len();
+            generated.push((i, *distinct.entry(term.clone()).or_insert(n)));
+        }
+    }
+    let labels = fresh(distinct.len(), &taken)?;
+    for (i, n) in generated {
+        triples[i / 3][i % 3] = labels[n].clone();
+    }
+    Ok(triples)
+}
+
+struct Context<'a> {
+    mapping: &'a Mapping,
+    triples: Vec<[BoxTerm; 3]>,
+    bnodes: usize,
+}
+
+impl<'a> Context<'a> {
+    fn fresh_bnode(&mut self) -> BoxTerm {
+        self.bnodes += 1;
+        BoxTerm::new_bnode_unchecked(format!("g{}", self.bnodes))
+    }
+
+    /// Serialize `value` in term position.
+    fn term<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<BoxTerm, Error> {
+        let ser = TermSerializer {
+            ctx: self,
+            subject: None,
+            link: None,
+            list: true,
+        };
+        value
+            .serialize(ser)?
+            .ok_or_else(|| Error::Unsupported("empty value".into()))
+    }
+
+    /// Emit the triple linking `term` to its subject, if any.
+    fn finish(&mut self, link: Link, term: BoxTerm) -> Result<Option<BoxTerm>, Error> {
+        if let Some((s, p)) = link {
+            self.triples.push([s, p, term.clone()]);
+        }
+        Ok(Some(term))
+    }
+
+    /// Emit the triples of an `rdf:List`, and return its head.
+    fn list(&mut self, items: Vec<BoxTerm>) -> BoxTerm {
+        let mut head = BoxTerm::copy(&rdf::nil);
+        for item in items.into_iter().rev() {
+            let cell = self.fresh_bnode();
+            self.triples
+                .push([cell.clone(), BoxTerm::copy(&rdf::first), item]);
+            self.triples
+                .push([cell.clone(), BoxTerm::copy(&rdf::rest), head]);
+            head = cell;
+        }
+        head
+    }
+}
+
+/// Serializes a value as an RDF term.
+///
+/// If `link` is set, the value is a property value of `link.0` through `link.1`,
+/// and the corresponding triple(s) are emitted;
+/// in that case, the value may also produce no term at all (e.g. `None`),
+/// or several terms (sequences, unless `list` is true).
+struct TermSerializer<'c, 'a> {
+    ctx: &'c mut Context<'a>,
+    subject: Option<BoxTerm>,
+    link: Link,
+    list: bool,
+}
+
+impl<'c, 'a> TermSerializer<'c, 'a> {
+    fn literal(
+        self,
+        lex: String,
+        dt: sophia_api::term::SimpleIri,
+    ) -> Result<Option<BoxTerm>, Error> {
+        let term = BoxTerm::new_literal_dt(lex, dt)?;
+        self.ctx.finish(self.link, term)
+    }
+
+    fn nothing(self, what: &str) -> Result<Option<BoxTerm>, Error> {
+        if self.link.is_some() {
+            Ok(None)
+        } else {
+            Err(Error::Unsupported(format!("{} outside of a field", what)))
+        }
+    }
+}
+
+fn float_lex<F: Into<f64> + Display + Copy>(v: F) -> String {
+    let f: f64 = v.into();
+    if f.is_nan() {
+        "NaN".into()
+    } else if f.is_infinite() {
+        if f > 0.0 { "INF" } else { "-INF" }.into()
+    } else {
+        v.to_string()
+    }
+}
+
+impl<'c, 'a> ser::Serializer for TermSerializer<'c, 'a> {
+    type Ok = Option<BoxTerm>;
+    type Error = Error;
+    type SerializeSeq = SeqSerializer<'c, 'a>;
+    type SerializeTuple = SeqSerializer<'c, 'a>;
+    type SerializeTupleStruct = SeqSerializer<'c, 'a>;
+    type SerializeTupleVariant = LiteralSerializer<'c, 'a>;
+    type SerializeMap = Impossible<Option<BoxTerm>, Error>;
+    type SerializeStruct = StructSerializer<'c, 'a>;
+    type SerializeStructVariant = Impossible<Option<BoxTerm>, Error>;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Error> {
+        self.literal(v.to_string(), xsd::boolean)
+    }
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, Error> {
+        self.literal(v.to_string(), xsd::byte)
+    }
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, Error> {
+        self.literal(v.to_string(), xsd::short)
+    }
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, Error> {
+        self.literal(v.to_string(), xsd::int)
+    }
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, Error> {
+        self.literal(v.to_string(), xsd::long)
+    }
+    fn serialize_i128(self, v: i128) -> Result<Self::Ok, Error> {
+        self.literal(v.to_string(), xsd::integer)
+    }
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, Error> {
+        self.literal(v.to_string(), xsd::unsignedByte)
+    }
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, Error> {
+        self.literal(v.to_string(), xsd::unsignedShort)
+    }
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, Error> {
+        self.literal(v.to_string(), xsd::unsignedInt)
+    }
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, Error> {
+        self.literal(v.to_string(), xsd::unsignedLong)
+    }
+    fn serialize_u128(self, v: u128) -> Result<Self::Ok, Error> {
+        self.literal(v.to_string(), xsd::nonNegativeInteger)
+    }
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Error> {
+        self.literal(float_lex(v), xsd::float)
+    }
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Error> {
+        self.literal(float_lex(v), xsd::double)
+    }
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Error> {
+        self.literal(v.to_string(), xsd::string)
+    }
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Error> {
+        self.literal(v.to_string(), xsd::string)
+    }
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Error> {
+        self.literal(Base64Binary(v.to_vec()).to_string(), xsd::base64Binary)
+    }
+    fn serialize_none(self) -> Result<Self::Ok, Error> {
+        self.nothing("None")
+    }
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok, Error> {
+        value.serialize(self)
+    }
+    fn serialize_unit(self) -> Result<Self::Ok, Error> {
+        self.nothing("unit")
+    }
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Error> {
+        self.nothing("unit struct")
+    }
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Error> {
+        self.literal(variant.to_string(), xsd::string)
+    }
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Error> {
+        if name == LIST {
+            value.serialize(TermSerializer { list: true, ..self })
+        } else {
+            value.serialize(self)
+        }
+    }
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Error> {
+        // terms (see sophia_term's serde support) are kept as is
+        if name != "Term" || variant == "Triple" {
+            return Err(Error::Unsupported(format!("{}::{}", name, variant)));
+        }
+        let inner = self.ctx.term(value)?;
+        let term = if variant == "Literal" {
+            inner
+        } else {
+            let value: &str = &inner.value();
+            match variant {
+                "Iri" => BoxTerm::new_iri(value)?,
+                "BNode" => user_bnode(&BoxTerm::new_bnode(value)?),
+                _ => BoxTerm::new_variable(value)?,
+            }
+        };
+        self.ctx.finish(self.link, term)
+    }
+    fn serialize_seq(self, _len: Option<usize>) -> Result<SeqSerializer<'c, 'a>, Error> {
+        let items = if self.link.is_some() && !self.list {
+            None
+        } else {
+            Some(vec![])
+        };
+        Ok(SeqSerializer {
+            ctx: self.ctx,
+            link: self.link,
+            items,
+        })
+    }
+    fn serialize_tuple(self, len: usize) -> Result<SeqSerializer<'c, 'a>, Error> {
+        TermSerializer { list: true, ..self }.serialize_seq(Some(len))
+    }
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SeqSerializer<'c, 'a>, Error> {
+        self.serialize_tuple(len)
+    }
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<LiteralSerializer<'c, 'a>, Error> {
+        // literals (see sophia_term's serde support) are kept as is
+        if name != "Literal" {
+            return Err(Error::Unsupported(format!("{}::{}", name, variant)));
+        }
+        Ok(LiteralSerializer {
+            ctx: self.ctx,
+            link: self.link,
+            variant,
+            fields: Vec::with_capacity(len),
+        })
+    }
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(Error::Unsupported("map".into()))
+    }
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<StructSerializer<'c, 'a>, Error> {
+        let subject = match self.subject {
+            Some(subject) => subject,
+            None => self.ctx.fresh_bnode(),
+        };
+        Ok(StructSerializer {
+            ctx: self.ctx,
+            link: self.link,
+            subject,
+        })
+    }
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(Error::Unsupported(format!("{}::{}", name, variant)))
+    }
+}
+
+/// Serializes sequences, either as repeated properties (if `items` is `None`),
+/// or as an `rdf:List`.
+struct SeqSerializer<'c, 'a> {
+    ctx: &'c mut Context<'a>,
+    link: Link,
+    items: Option<Vec<BoxTerm>>,
+}
+
+impl<'c, 'a> ser::SerializeSeq for SeqSerializer<'c, 'a> {
+    type Ok = Option<BoxTerm>;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        match &mut self.items {
+            Some(items) => items.push(self.ctx.term(value)?),
+            None => {
+                value.serialize(TermSerializer {
+                    ctx: self.ctx,
+                    subject: None,
+                    link: self.link.clone(),
+                    list: true,
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    fn end(self) -> Result<Option<BoxTerm>, Error> {
+        match self.items {
+            Some(items) => {
+                let head = self.ctx.list(items);
+                self.ctx.finish(self.link, head)
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+impl<'c, 'a> ser::SerializeTuple for SeqSerializer<'c, 'a> {
+    type Ok = Option<BoxTerm>;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Option<BoxTerm>, Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl<'c, 'a> ser::SerializeTupleStruct for SeqSerializer<'c, 'a> {
+    type Ok = Option<BoxTerm>;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Option<BoxTerm>, Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+/// Serializes the fields of a struct as properties of `subject`.
+struct StructSerializer<'c, 'a> {
+    ctx: &'c mut Context<'a>,
+    link: Link,
+    subject: BoxTerm,
+}
+
+impl<'c, 'a> ser::SerializeStruct for StructSerializer<'c, 'a> {
+    type Ok = Option<BoxTerm>;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        let predicate = self.ctx.mapping.predicate(key)?;
+        let list = self.ctx.mapping.is_list(key);
+        value.serialize(TermSerializer {
+            ctx: self.ctx,
+            subject: None,
+            link: Some((self.subject.clone(), predicate)),
+            list,
+        })?;
+        Ok(())
+    }
+
+    fn end(self) -> Result<Option<BoxTerm>, Error> {
+        self.ctx.finish(self.link, self.subject)
+    }
+}
+
+/// Serializes literals, as encoded by sophia_term's serde support.
+struct LiteralSerializer<'c, 'a> {
+    ctx: &'c mut Context<'a>,
+    link: Link,
+    variant: &'static str,
+    fields: Vec<String>,
+}
+
+impl<'c, 'a> ser::SerializeTupleVariant for LiteralSerializer<'c, 'a> {
+    type Ok = Option<BoxTerm>;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let term = self.ctx.term(value)?;
+        self.fields.push(term.value().to_string());
+        Ok(())
+    }
+
+    fn end(self) -> Result<Option<BoxTerm>, Error> {
+        let term = match (self.variant, self.fields.as_slice()) {
+            ("Typed", [txt, dt]) => {
+                let dt = Iri::<Box<str>>::new(dt.as_str())?;
+                Literal::<Box<str>>::new_dt(txt.as_str(), dt).into()
+            }
+            ("Lang", [txt, tag]) => BoxTerm::new_literal_lang(txt.as_str(), tag.as_str())?,
+            ("LangDir", [txt, tag, dir]) => {
+                let dir = Direction::parse(dir)
+                    .ok_or_else(|| Error::Custom(format!("invalid base direction: {}", dir)))?;
+                BoxTerm::new_literal_lang_dir(txt.as_str(), tag.as_str(), dir)?
+            }
+            _ => return Err(Error::Unsupported(format!("Literal::{}", self.variant))),
+        };
+        self.ctx.finish(self.link, term)
+    }
+}
@@ -10,6 +10,7 @@
 //! [`BufWriter`]: https://doc.rust-lang.org/std/io/struct.BufWriter.html
 
 use super::nt::write_term;
+use crate::compression::{Compression, Compressor};
 use sophia_api::quad::{stream::*, Quad};
 use sophia_api::serializer::*;
 use std::io;
@@ -54,6 +55,24 @@ where
     }
 }
 
+impl<W> NqSerializer<Compressor<W>>
+where
+    W: io::Write,
+{
+    /// Build a new N-Quads serializer writing to `write`, compressed with `compression`.
+    ///
+    /// [`finish`](#method.finish) must be called once serialization is done.
+    /// See also [`Compressor`](../../compression/struct.Compressor.html).
+    pub fn new_compressed(write: W, compression: Compression) -> io::Result<Self> {
+        Ok(Self::new(Compressor::new(write, compression)?))
+    }
+
+    /// Terminate the compressed stream, and return the underlying `write`.
+    pub fn finish(self) -> io::Result<W> {
+        self.write.finish()
+    }
+}
+
 impl<W> QuadSerializer for NqSerializer<W>
 where
     W: io::Write,
@@ -71,26 +90,29 @@ where
             todo!("Pure-ASCII N-Quads is not implemented yet")
         }
         source
-            .try_for_each_quad(|q| {
-                {
-                    let w = &mut self.write;
-                    write_term(w, q.s())?;
-                    w.write_all(b" ")?;
-                    write_term(w, q.p())?;
-                    w.write_all(b" ")?;
-                    write_term(w, q.o())?;
-                    if let Some(n) = q.g() {
-                        w.write_all(b" ")?;
-                        write_term(w, n)?;
-                    }
-                    w.write_all(b".\n")
-                }
-                .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
-            })
+            .try_for_each_quad(|q| write_quad(&mut self.write, &q).map_err(io::Error::other))
             .map(|_| self)
     }
 }
 
+/// Write a single quad to `w`, in N-Quads.
+pub(crate) fn write_quad<W, Q>(w: &mut W, q: &Q) -> io::Result<()>
+where
+    W: io::Write,
+    Q: Quad + ?Sized,
+{
+    write_term(w, q.s())?;
+    w.write_all(b" ")?;
+    write_term(w, q.p())?;
+    w.write_all(b" ")?;
+    write_term(w, q.o())?;
+    if let Some(n) = q.g() {
+        w.write_all(b" ")?;
+        write_term(w, n)?;
+    }
+    w.write_all(b".\n")
+}
+
 impl NqSerializer<Vec<u8>> {
     /// Create a new serializer which targets a `String`.
     #[inline]
@@ -127,7 +149,7 @@ pub(crate) mod test {
         let d = vec![
             (
                 [
-                    me,
+                    me.clone(),
                     rdf::type_.into(),
                     StaticTerm::new_iri("http://schema.org/Person").unwrap(),
                 ],
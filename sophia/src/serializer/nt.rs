@@ -9,6 +9,7 @@
 //! [`Write`]: https://doc.rust-lang.org/std/io/trait.Write.html
 //! [`BufWriter`]: https://doc.rust-lang.org/std/io/struct.BufWriter.html
 
+use crate::compression::{Compression, Compressor};
 use sophia_api::ns::xsd;
 use sophia_api::serializer::*;
 use sophia_api::term::{TTerm, TermKind};
@@ -56,6 +57,24 @@ where
     }
 }
 
+impl<W> NtSerializer<Compressor<W>>
+where
+    W: io::Write,
+{
+    /// Build a new N-Triples serializer writing to `write`, compressed with `compression`.
+    ///
+    /// [`finish`](#method.finish) must be called once serialization is done.
+    /// See also [`Compressor`](../../compression/struct.Compressor.html).
+    pub fn new_compressed(write: W, compression: Compression) -> io::Result<Self> {
+        Ok(Self::new(Compressor::new(write, compression)?))
+    }
+
+    /// Terminate the compressed stream, and return the underlying `write`.
+    pub fn finish(self) -> io::Result<W> {
+        self.write.finish()
+    }
+}
+
 impl<W> TripleSerializer for NtSerializer<W>
 where
     W: io::Write,
@@ -73,18 +92,7 @@ where
             todo!("Pure-ASCII N-Triples is not implemented yet")
         }
         source
-            .try_for_each_triple(|t| {
-                {
-                    let w = &mut self.write;
-                    write_term(w, t.s())?;
-                    w.write_all(b" ")?;
-                    write_term(w, t.p())?;
-                    w.write_all(b" ")?;
-                    write_term(w, t.o())?;
-                    w.write_all(b".\n")
-                }
-                .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
-            })
+            .try_for_each_triple(|t| write_triple(&mut self.write, &t).map_err(io::Error::other))
             .map(|_| self)
     }
 }
@@ -108,6 +116,20 @@ impl Stringifier for NtSerializer<Vec<u8>> {
     }
 }
 
+/// Write a single triple to `w`, in N-Triples.
+pub(crate) fn write_triple<W, T>(w: &mut W, t: &T) -> io::Result<()>
+where
+    W: io::Write,
+    T: Triple + ?Sized,
+{
+    write_term(w, t.s())?;
+    w.write_all(b" ")?;
+    write_term(w, t.p())?;
+    w.write_all(b" ")?;
+    write_term(w, t.o())?;
+    w.write_all(b".\n")
+}
+
 /// Write the given term into the given write in the N-Triples format.
 pub fn write_term<W, T>(w: &mut W, t: &T) -> io::Result<()>
 where
@@ -131,7 +153,12 @@ where
             match t.language() {
                 Some(tag) => {
                     w.write_all(b"\"@")?;
-                    w.write_all(tag.as_bytes())
+                    w.write_all(tag.as_bytes())?;
+                    if let Some(dir) = t.direction() {
+                        w.write_all(b"--")?;
+                        w.write_all(dir.as_str().as_bytes())?;
+                    }
+                    Ok(())
                 }
                 None => {
                     let dt = t.datatype().unwrap();
@@ -152,10 +179,24 @@ where
             w.write_all(b"?")?;
             w.write_all(t.value_raw().0.as_bytes())
         }
+        QuotedTriple => {
+            let spo = t.triple().unwrap();
+            w.write_all(b"<< ")?;
+            write_term(w, spo[0])?;
+            w.write_all(b" ")?;
+            write_term(w, spo[1])?;
+            w.write_all(b" ")?;
+            write_term(w, spo[2])?;
+            w.write_all(b" >>")
+        }
+        kind => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{:?} terms can not be serialized in N-Triples", kind),
+        )),
     }
 }
 
-fn quoted_string<W: io::Write>(w: &mut W, txt: &[u8]) -> io::Result<()> {
+pub(crate) fn quoted_string<W: io::Write>(w: &mut W, txt: &[u8]) -> io::Result<()> {
     let mut cut = txt.len();
     let mut cutchar = b'\0';
     for (pos, chr) in txt.iter().enumerate() {
@@ -207,7 +248,7 @@ pub(crate) mod test {
         let me = StaticTerm::new_iri("http://champin.net/#pa").unwrap();
         let g = vec![
             [
-                me,
+                me.clone(),
                 rdf::type_.into(),
                 StaticTerm::new_iri("http://schema.org/Person").unwrap(),
             ],
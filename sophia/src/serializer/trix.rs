@@ -0,0 +1,304 @@
+//! Serializer for [TriX], an XML syntax for named graphs.
+//!
+//! Consecutive quads in the same graph are grouped in the same `<graph>` element,
+//! but quads are not sorted, so a graph may be split across several `<graph>` elements.
+//!
+//! **Important**:
+//! the methods in this module accepting a [`Write`]
+//! make no effort to minimize the number of write operations.
+//! Hence, in most cased, they should be passed a [`BufWriter`].
+//!
+//! [TriX]: https://www.hpl.hp.com/techreports/2004/HPL-2004-56.html
+//! [`Write`]: https://doc.rust-lang.org/std/io/trait.Write.html
+//! [`BufWriter`]: https://doc.rust-lang.org/std/io/struct.BufWriter.html
+
+use crate::parser::trix::TRIX_NS;
+use sophia_api::ns::xsd;
+use sophia_api::quad::{stream::*, Quad};
+use sophia_api::serializer::*;
+use sophia_api::term::{term_eq, CopyTerm, TTerm, TermKind};
+use sophia_api::triple::stream::TripleSource;
+use sophia_api::triple::Triple;
+use sophia_term::BoxTerm;
+use std::io;
+
+/// TriX serializer.
+pub struct TrixSerializer<W> {
+    write: W,
+}
+
+impl<W> TrixSerializer<W>
+where
+    W: io::Write,
+{
+    /// Build a new TriX serializer writing to `write`.
+    pub fn new(write: W) -> TrixSerializer<W> {
+        TrixSerializer { write }
+    }
+
+    fn start_graph<T: TTerm + ?Sized>(&mut self, name: Option<&T>) -> io::Result<()> {
+        self.write.write_all(b"  <graph>\n")?;
+        if let Some(name) = name {
+            self.write.write_all(b"    ")?;
+            write_term(&mut self.write, name)?;
+            self.write.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    fn write_triple<T: TTerm + ?Sized>(&mut self, spo: [&T; 3]) -> io::Result<()> {
+        self.write.write_all(b"    <triple>\n")?;
+        for term in spo.iter() {
+            self.write.write_all(b"      ")?;
+            write_term(&mut self.write, *term)?;
+            self.write.write_all(b"\n")?;
+        }
+        self.write.write_all(b"    </triple>\n")
+    }
+}
+
+impl<W> TripleSerializer for TrixSerializer<W>
+where
+    W: io::Write,
+{
+    type Error = io::Error;
+
+    fn serialize_triples<TS>(
+        &mut self,
+        mut source: TS,
+    ) -> StreamResult<&mut Self, TS::Error, Self::Error>
+    where
+        TS: TripleSource,
+    {
+        write_header(&mut self.write).map_err(SinkError)?;
+        self.start_graph::<BoxTerm>(None).map_err(SinkError)?;
+        source.try_for_each_triple(|t| self.write_triple([t.s(), t.p(), t.o()]))?;
+        self.write
+            .write_all(b"  </graph>\n</TriX>\n")
+            .map_err(SinkError)?;
+        Ok(self)
+    }
+}
+
+impl<W> QuadSerializer for TrixSerializer<W>
+where
+    W: io::Write,
+{
+    type Error = io::Error;
+
+    fn serialize_quads<QS>(
+        &mut self,
+        mut source: QS,
+    ) -> StreamResult<&mut Self, QS::Error, Self::Error>
+    where
+        QS: QuadSource,
+    {
+        write_header(&mut self.write).map_err(SinkError)?;
+        // the name of the current graph, if a <graph> element is open
+        let mut current: Option<Option<BoxTerm>> = None;
+        source.try_for_each_quad(|q| {
+            let same = match (&current, q.g()) {
+                (Some(Some(n1)), Some(n2)) => term_eq(n1, n2),
+                (Some(None), None) => true,
+                _ => false,
+            };
+            if !same {
+                if current.is_some() {
+                    self.write.write_all(b"  </graph>\n")?;
+                }
+                self.start_graph(q.g())?;
+                current = Some(q.g().map(BoxTerm::copy));
+            }
+            self.write_triple([q.s(), q.p(), q.o()])
+        })?;
+        if current.is_some() {
+            self.write.write_all(b"  </graph>\n").map_err(SinkError)?;
+        }
+        self.write.write_all(b"</TriX>\n").map_err(SinkError)?;
+        Ok(self)
+    }
+}
+
+impl TrixSerializer<Vec<u8>> {
+    /// Create a new serializer which targets a `String`.
+    #[inline]
+    pub fn new_stringifier() -> Self {
+        TrixSerializer::new(Vec::new())
+    }
+}
+
+impl Stringifier for TrixSerializer<Vec<u8>> {
+    fn as_utf8(&self) -> &[u8] {
+        &self.write[..]
+    }
+}
+
+fn write_header<W: io::Write>(w: &mut W) -> io::Result<()> {
+    w.write_all(b"<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<TriX xmlns=\"")?;
+    w.write_all(TRIX_NS.as_bytes())?;
+    w.write_all(b"\">\n")
+}
+
+/// Write a single term to `w`, as a TriX element.
+///
+/// Variables, quoted triples and directional language-tagged literals
+/// can not be represented in TriX, and cause an error.
+fn write_term<W, T>(w: &mut W, t: &T) -> io::Result<()>
+where
+    W: io::Write,
+    T: TTerm + ?Sized,
+{
+    use TermKind::*;
+    match t.kind() {
+        Iri => {
+            w.write_all(b"<uri>")?;
+            escaped(w, &t.value())?;
+            w.write_all(b"</uri>")
+        }
+        BlankNode => {
+            w.write_all(b"<id>")?;
+            escaped(w, &t.value())?;
+            w.write_all(b"</id>")
+        }
+        Literal => match t.language() {
+            Some(tag) => {
+                if let Some(dir) = t.direction() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "directional literal {:?}@{}--{} can not be serialized in TriX",
+                            t.value().as_ref(),
+                            tag,
+                            dir
+                        ),
+                    ));
+                }
+                w.write_all(b"<plainLiteral xml:lang=\"")?;
+                escaped(w, tag)?;
+                w.write_all(b"\">")?;
+                escaped(w, &t.value())?;
+                w.write_all(b"</plainLiteral>")
+            }
+            None => {
+                let dt = t.datatype().unwrap();
+                if xsd::string == dt {
+                    w.write_all(b"<plainLiteral>")?;
+                    escaped(w, &t.value())?;
+                    w.write_all(b"</plainLiteral>")
+                } else {
+                    w.write_all(b"<typedLiteral datatype=\"")?;
+                    escaped(w, &dt.value())?;
+                    w.write_all(b"\">")?;
+                    escaped(w, &t.value())?;
+                    w.write_all(b"</typedLiteral>")
+                }
+            }
+        },
+        Variable => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("variable ?{} can not be serialized in TriX", t.value()),
+        )),
+        QuotedTriple => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "quoted triples can not be serialized in TriX",
+        )),
+        kind => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{:?} terms can not be serialized in TriX", kind),
+        )),
+    }
+}
+
+/// Write `txt` to `w`, escaping XML special characters.
+fn escaped<W: io::Write>(w: &mut W, txt: &str) -> io::Result<()> {
+    let mut start = 0;
+    for (pos, chr) in txt.char_indices() {
+        let esc: &[u8] = match chr {
+            '&' => b"&amp;",
+            '<' => b"&lt;",
+            '>' => b"&gt;",
+            '"' => b"&quot;",
+            '\r' => b"&#13;",
+            _ => continue,
+        };
+        w.write_all(&txt.as_bytes()[start..pos])?;
+        w.write_all(esc)?;
+        start = pos + 1;
+    }
+    w.write_all(&txt.as_bytes()[start..])
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dataset::inmem::FastDataset;
+    use crate::dataset::Dataset;
+    use crate::graph::inmem::FastGraph;
+    use crate::parser::trix;
+    use crate::triple::stream::TripleSource;
+    use sophia_api::dataset::isomorphic_datasets;
+    use sophia_api::term::Direction;
+
+    const NQ: &str = r#"<tag:s> <tag:p> "a < b & c"@en <tag:g>.
+<tag:s> <tag:p> _:b <tag:g>.
+_:b <tag:p> "42"^^<http://www.w3.org/2001/XMLSchema#integer>.
+_:b <tag:p> "\"quoted\"".
+"#;
+
+    #[test]
+    fn round_trip() {
+        let d1: FastDataset = crate::parser::nq::parse_str(NQ).collect_quads().unwrap();
+        let trix = TrixSerializer::new_stringifier()
+            .serialize_dataset(&d1)
+            .unwrap()
+            .to_string();
+        let d2: FastDataset = trix::parse_str(&trix).collect_quads().unwrap();
+        assert!(isomorphic_datasets(&d1, &d2).unwrap());
+    }
+
+    #[test]
+    fn graph() {
+        let g: FastGraph = crate::parser::nt::parse_str("<tag:s> <tag:p> <tag:o>.\n")
+            .collect_triples()
+            .unwrap();
+        let trix = TrixSerializer::new_stringifier()
+            .serialize_graph(&g)
+            .unwrap()
+            .to_string();
+        assert_eq!(
+            trix,
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<TriX xmlns="http://www.w3.org/2004/03/trix/trix-1/">
+  <graph>
+    <triple>
+      <uri>tag:s</uri>
+      <uri>tag:p</uri>
+      <uri>tag:o</uri>
+    </triple>
+  </graph>
+</TriX>
+"#
+        );
+        let d: FastDataset = trix::parse_str(&trix).collect_quads().unwrap();
+        assert_eq!(d.quads().count(), 1);
+    }
+
+    #[test]
+    fn directional_literal() {
+        let g = vec![[
+            BoxTerm::new_iri("tag:s").unwrap(),
+            BoxTerm::new_iri("tag:p").unwrap(),
+            BoxTerm::new_literal_lang_dir("shalom", "he", Direction::Rtl).unwrap(),
+        ]];
+        let err = match TrixSerializer::new_stringifier().serialize_graph(&g) {
+            Err(SinkError(err)) => err,
+            _ => panic!("directional literal should not be serialized"),
+        };
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("@he--rtl"), "{}", err);
+    }
+}
@@ -0,0 +1,403 @@
+//! Serializer for the [Turtle-star] concrete syntax of RDF-star.
+//!
+//! The triples are written in the order of the source;
+//! consecutive triples sharing the same subject (resp. subject and predicate)
+//! are grouped with `;` (resp. `,`).
+//! IRIs are abbreviated with the prefixes declared in the [`TurtleStarConfig`],
+//! and `rdf:type` is written `a` in predicate position.
+//!
+//! Variables, and terms in a position that Turtle-star does not allow
+//! (e.g. a literal in subject position),
+//! can not be serialized and raise an error.
+//!
+//! **Important**:
+//! the methods in this module accepting a [`Write`]
+//! make no effort to minimize the number of write operations.
+//! Hence, in most cased, they should be passed a [`BufWriter`].
+//!
+//! [Turtle-star]: https://w3c.github.io/rdf-star/cg-spec/editors_draft.html#turtle-star
+//! [`TurtleStarConfig`]: struct.TurtleStarConfig.html
+//! [`Write`]: https://doc.rust-lang.org/std/io/trait.Write.html
+//! [`BufWriter`]: https://doc.rust-lang.org/std/io/struct.BufWriter.html
+
+use crate::serializer::nt::quoted_string;
+use sophia_api::ns::{rdf, xsd};
+use sophia_api::serializer::*;
+use sophia_api::term::{term_eq, term_to_string, CopyTerm, TTerm, TermKind};
+use sophia_api::triple::stream::{SinkError, StreamResult, TripleSource};
+use sophia_api::triple::Triple;
+use sophia_term::BoxTerm;
+use std::io;
+
+/// Turtle-star serializer configuration.
+#[derive(Clone, Debug, Default)]
+pub struct TurtleStarConfig {
+    prefixes: Vec<(String, String)>,
+}
+
+impl TurtleStarConfig {
+    /// Declare `prefix` for the namespace `ns`.
+    ///
+    /// Fail if `prefix` is not a valid Turtle prefix,
+    /// or if `ns` contains characters that are not allowed in an IRI reference.
+    pub fn add_prefix(&mut self, prefix: &str, ns: &str) -> io::Result<&mut Self> {
+        if !is_valid_prefix(prefix) {
+            return Err(invalid(format!("invalid prefix {:?}", prefix)));
+        }
+        if !ns.chars().all(is_iri_char) {
+            return Err(invalid(format!("invalid namespace {:?}", ns)));
+        }
+        self.prefixes.push((prefix.to_string(), ns.to_string()));
+        Ok(self)
+    }
+
+    /// The prefixes declared in this configuration.
+    pub fn prefixes(&self) -> &[(String, String)] {
+        &self.prefixes
+    }
+}
+
+/// Turtle-star serializer.
+pub struct TurtleStarSerializer<W> {
+    config: TurtleStarConfig,
+    write: W,
+}
+
+impl<W> TurtleStarSerializer<W>
+where
+    W: io::Write,
+{
+    /// Build a new Turtle-star serializer writing to `write`, with the default config.
+    #[inline]
+    pub fn new(write: W) -> TurtleStarSerializer<W> {
+        Self::new_with_config(write, TurtleStarConfig::default())
+    }
+
+    /// Build a new Turtle-star serializer writing to `write`, with the given config.
+    pub fn new_with_config(write: W, config: TurtleStarConfig) -> TurtleStarSerializer<W> {
+        TurtleStarSerializer { write, config }
+    }
+
+    /// Borrow this serializer's configuration.
+    pub fn config(&self) -> &TurtleStarConfig {
+        &self.config
+    }
+}
+
+impl<W> TripleSerializer for TurtleStarSerializer<W>
+where
+    W: io::Write,
+{
+    type Error = io::Error;
+
+    fn serialize_triples<TS>(
+        &mut self,
+        mut source: TS,
+    ) -> StreamResult<&mut Self, TS::Error, Self::Error>
+    where
+        TS: TripleSource,
+    {
+        let config = &self.config;
+        let w = &mut self.write;
+        for (prefix, ns) in &config.prefixes {
+            writeln!(w, "@prefix {}: <{}>.", prefix, ns).map_err(SinkError)?;
+        }
+        let mut previous: Option<[BoxTerm; 2]> = None;
+        source.try_for_each_triple(|t| -> io::Result<()> {
+            match &previous {
+                Some([s, p]) if term_eq(s, t.s()) && term_eq(p, t.p()) => {
+                    w.write_all(b", ")?;
+                }
+                Some([s, _]) if term_eq(s, t.s()) => {
+                    w.write_all(b";\n    ")?;
+                    write_predicate(w, config, t.p())?;
+                    w.write_all(b" ")?;
+                }
+                _ => {
+                    if previous.is_some() {
+                        w.write_all(b".\n")?;
+                    }
+                    write_subject(w, config, t.s())?;
+                    w.write_all(b" ")?;
+                    write_predicate(w, config, t.p())?;
+                    w.write_all(b" ")?;
+                }
+            }
+            write_object(w, config, t.o())?;
+            previous = Some([BoxTerm::copy(t.s()), BoxTerm::copy(t.p())]);
+            Ok(())
+        })?;
+        if previous.is_some() {
+            self.write.write_all(b".\n").map_err(SinkError)?;
+        }
+        Ok(self)
+    }
+}
+
+impl TurtleStarSerializer<Vec<u8>> {
+    /// Create a new serializer which targets a `String`.
+    #[inline]
+    pub fn new_stringifier() -> Self {
+        TurtleStarSerializer::new(Vec::new())
+    }
+    /// Create a new serializer which targets a `String` with a custom config.
+    #[inline]
+    pub fn new_stringifier_with_config(config: TurtleStarConfig) -> Self {
+        TurtleStarSerializer::new_with_config(Vec::new(), config)
+    }
+}
+
+impl Stringifier for TurtleStarSerializer<Vec<u8>> {
+    fn as_utf8(&self) -> &[u8] {
+        &self.write[..]
+    }
+}
+
+fn write_subject<W, T>(w: &mut W, config: &TurtleStarConfig, t: &T) -> io::Result<()>
+where
+    W: io::Write,
+    T: TTerm + ?Sized,
+{
+    match t.kind() {
+        TermKind::Iri | TermKind::BlankNode | TermKind::QuotedTriple => write_term(w, config, t),
+        _ => Err(invalid(format!(
+            "{} can not be a subject in Turtle-star",
+            term_to_string(t)
+        ))),
+    }
+}
+
+fn write_predicate<W, T>(w: &mut W, config: &TurtleStarConfig, t: &T) -> io::Result<()>
+where
+    W: io::Write,
+    T: TTerm + ?Sized,
+{
+    if t.kind() != TermKind::Iri {
+        return Err(invalid(format!(
+            "{} can not be a predicate in Turtle-star",
+            term_to_string(t)
+        )));
+    }
+    if term_eq(t, &rdf::type_) {
+        w.write_all(b"a")
+    } else {
+        write_term(w, config, t)
+    }
+}
+
+fn write_object<W, T>(w: &mut W, config: &TurtleStarConfig, t: &T) -> io::Result<()>
+where
+    W: io::Write,
+    T: TTerm + ?Sized,
+{
+    if t.kind() == TermKind::Variable {
+        return Err(invalid(format!(
+            "{} can not be serialized in Turtle-star",
+            term_to_string(t)
+        )));
+    }
+    write_term(w, config, t)
+}
+
+fn write_term<W, T>(w: &mut W, config: &TurtleStarConfig, t: &T) -> io::Result<()>
+where
+    W: io::Write,
+    T: TTerm + ?Sized,
+{
+    match t.kind() {
+        TermKind::Iri => write_iri(w, config, &t.value()),
+        TermKind::Literal => {
+            w.write_all(b"\"")?;
+            quoted_string(w, t.value_raw().0.as_bytes())?;
+            w.write_all(b"\"")?;
+            match t.language() {
+                Some(tag) => {
+                    w.write_all(b"@")?;
+                    w.write_all(tag.as_bytes())?;
+                    if let Some(dir) = t.direction() {
+                        w.write_all(b"--")?;
+                        w.write_all(dir.as_str().as_bytes())?;
+                    }
+                    Ok(())
+                }
+                None => {
+                    let dt = t.datatype().unwrap();
+                    if xsd::string != dt {
+                        w.write_all(b"^^")?;
+                        write_iri(w, config, &dt.value())?;
+                    }
+                    Ok(())
+                }
+            }
+        }
+        TermKind::BlankNode => {
+            w.write_all(b"_:")?;
+            w.write_all(t.value_raw().0.as_bytes())
+        }
+        TermKind::QuotedTriple => {
+            let [s, p, o] = t.triple().unwrap();
+            w.write_all(b"<< ")?;
+            write_subject(w, config, s)?;
+            w.write_all(b" ")?;
+            write_predicate(w, config, p)?;
+            w.write_all(b" ")?;
+            write_object(w, config, o)?;
+            w.write_all(b" >>")
+        }
+        _ => Err(invalid(format!(
+            "{} can not be serialized in Turtle-star",
+            term_to_string(t)
+        ))),
+    }
+}
+
+/// Write `iri` as a prefixed name if possible, or as an IRI reference otherwise.
+fn write_iri<W: io::Write>(w: &mut W, config: &TurtleStarConfig, iri: &str) -> io::Result<()> {
+    for (prefix, ns) in &config.prefixes {
+        if let Some(local) = iri.strip_prefix(ns.as_str()) {
+            if is_valid_local(local) {
+                return write!(w, "{}:{}", prefix, local);
+            }
+        }
+    }
+    if !iri.chars().all(is_iri_char) {
+        return Err(invalid(format!("invalid IRI <{}>", iri)));
+    }
+    write!(w, "<{}>", iri)
+}
+
+/// A conservative approximation of Turtle's `PN_PREFIX` (possibly empty).
+fn is_valid_prefix(prefix: &str) -> bool {
+    match prefix.chars().next() {
+        None => true,
+        Some(c) => {
+            c.is_ascii_alphabetic()
+                && prefix
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
+                && !prefix.ends_with('.')
+        }
+    }
+}
+
+/// A conservative approximation of Turtle's `PN_LOCAL` (possibly empty).
+fn is_valid_local(local: &str) -> bool {
+    match local.chars().next() {
+        None => true,
+        Some(c) => {
+            (c.is_ascii_alphanumeric() || c == '_')
+                && local
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
+                && !local.ends_with('.')
+        }
+    }
+}
+
+fn is_iri_char(c: char) -> bool {
+    !matches!(
+        c,
+        '\0'..=' ' | '<' | '>' | '"' | '{' | '}' | '|' | '^' | '`' | '\\'
+    )
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use crate::graph::inmem::FastGraph;
+    use crate::parser::turtle_star;
+    use crate::triple::stream::TripleSource;
+    use sophia_api::graph::isomorphic_graphs;
+
+    const TTL: &str = r#"
+        @prefix : <http://example.org/> .
+        @prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+        :alice a :Person;
+            :name "Alice", "Alicia"@es;
+            :age "42"^^xsd:integer;
+            :knows _:b.
+        _:b :says << :bob :age "42"^^xsd:integer >>.
+        << :alice :knows << _:b a :Person >> >> :source <http://example.org/doc/1.html>.
+        :alice :motto "a \"quoted\"\nmotto".
+    "#;
+
+    fn config() -> TurtleStarConfig {
+        let mut config = TurtleStarConfig::default();
+        config
+            .add_prefix("", "http://example.org/")
+            .unwrap()
+            .add_prefix("xsd", "http://www.w3.org/2001/XMLSchema#")
+            .unwrap();
+        config
+    }
+
+    #[test]
+    fn round_trip() {
+        let g1: FastGraph = turtle_star::parse_str(TTL).collect_triples().unwrap();
+        for config in &[TurtleStarConfig::default(), config()] {
+            let ttl = TurtleStarSerializer::new_stringifier_with_config(config.clone())
+                .serialize_graph(&g1)
+                .unwrap()
+                .to_string();
+            let g2: FastGraph = turtle_star::parse_str(&ttl).collect_triples().unwrap();
+            assert!(isomorphic_graphs(&g1, &g2).unwrap(), "{}", ttl);
+        }
+    }
+
+    #[test]
+    fn abbreviations() {
+        let triples: Vec<[BoxTerm; 3]> = turtle_star::parse_str(
+            r#"
+            @prefix : <http://example.org/> .
+            :a a :B; :p :c, :d; :q << :a :p <http://example.org/x/y> >>.
+            :e :r "1"^^<http://example.org/dt>.
+        "#,
+        )
+        .collect_triples()
+        .unwrap();
+        let ttl = TurtleStarSerializer::new_stringifier_with_config(config())
+            .serialize_triples(triples.into_iter().map(Ok::<_, std::convert::Infallible>))
+            .unwrap()
+            .to_string();
+        assert_eq!(
+            ttl,
+            r#"@prefix : <http://example.org/>.
+@prefix xsd: <http://www.w3.org/2001/XMLSchema#>.
+:a a :B;
+    :p :c, :d;
+    :q << :a :p <http://example.org/x/y> >>.
+:e :r "1"^^:dt.
+"#
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let mut config = TurtleStarConfig::default();
+        assert!(config.add_prefix("1x", "http://example.org/").is_err());
+        assert!(config.add_prefix("x", "http://example.org/ ").is_err());
+        let lit = BoxTerm::new_literal_dt("a", xsd::string).unwrap();
+        let iri = BoxTerm::new_iri("http://example.org/").unwrap();
+        let var = BoxTerm::new_variable("v").unwrap();
+        let quoted = BoxTerm::new_triple(iri.clone(), iri.clone(), var.clone());
+        for t in &[
+            [lit.clone(), iri.clone(), iri.clone()],
+            [iri.clone(), lit, iri.clone()],
+            [iri.clone(), iri.clone(), var],
+            [iri.clone(), iri, quoted],
+        ] {
+            let res = TurtleStarSerializer::new_stringifier()
+                .serialize_triples(Some(Ok::<_, std::convert::Infallible>(t)).into_iter())
+                .map(|_| ());
+            assert!(matches!(res, Err(SinkError(_))), "{:?}", t);
+        }
+    }
+}
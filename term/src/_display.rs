@@ -33,6 +33,14 @@ where
             BNode(bn) => bn.write_fmt(w),
             Literal(lit) => lit.write_fmt(w),
             Variable(var) => var.write_fmt(w),
+            Triple(spo) => {
+                w.write_str("<< ")?;
+                for t in spo.iter() {
+                    t.write_fmt(w)?;
+                    w.write_char(' ')?;
+                }
+                w.write_str(">>")
+            }
         }
     }
 
@@ -47,6 +55,14 @@ where
             BNode(bn) => bn.write_io(w),
             Literal(lit) => lit.write_io(w),
             Variable(var) => var.write_io(w),
+            Triple(spo) => {
+                w.write_all(b"<< ")?;
+                for t in spo.iter() {
+                    t.write_io(w)?;
+                    w.write_all(b" ")?;
+                }
+                w.write_all(b">>")
+            }
         }
     }
 }
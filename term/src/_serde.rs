@@ -0,0 +1,245 @@
+//! Implementation of `Serialize` and `Deserialize` for terms
+//! (only available with the `serde` feature).
+//!
+//! Terms are encoded as enums mirroring [`Term`](../enum.Term.html),
+//! whose data are plain strings, without any N-Triples escaping:
+//! * IRIs are encoded as their full text,
+//! * blank nodes and variables as their identifier (without `_:` or `?`),
+//! * literals as a `Typed(text, datatype)`, `Lang(text, tag)`
+//!   or `LangDir(text, tag, direction)` variant,
+//! * quoted triples as a sequence of three terms.
+//!
+//! Deserialization checks the validity of the terms,
+//! just like the checked constructors (e.g. [`Term::new_iri`](../enum.Term.html#method.new_iri)).
+
+use super::*;
+use serde::de::Error as _;
+use serde::ser::SerializeTupleVariant;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+impl<TD: TermData> Serialize for Iri<TD> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.value())
+    }
+}
+
+impl<'de, TD> Deserialize<'de> for Iri<TD>
+where
+    TD: TermData + Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Iri::new(TD::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+impl<TD: TermData> Serialize for BlankNode<TD> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de, TD> Deserialize<'de> for BlankNode<TD>
+where
+    TD: TermData + Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        BlankNode::new(TD::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+impl<TD: TermData> Serialize for Variable<TD> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de, TD> Deserialize<'de> for Variable<TD>
+where
+    TD: TermData + Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Variable::new(TD::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+impl<TD: TermData> Serialize for Literal<TD> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let txt = self.txt().as_ref();
+        let variant = match (self.lang(), self.dir()) {
+            (None, _) => {
+                let mut variant = serializer.serialize_tuple_variant("Literal", 0, "Typed", 2)?;
+                variant.serialize_field(txt)?;
+                variant.serialize_field(&self.dt())?;
+                variant
+            }
+            (Some(tag), None) => {
+                let mut variant = serializer.serialize_tuple_variant("Literal", 1, "Lang", 2)?;
+                variant.serialize_field(txt)?;
+                variant.serialize_field(tag.as_ref())?;
+                variant
+            }
+            (Some(tag), Some(dir)) => {
+                let mut variant = serializer.serialize_tuple_variant("Literal", 2, "LangDir", 3)?;
+                variant.serialize_field(txt)?;
+                variant.serialize_field(tag.as_ref())?;
+                variant.serialize_field(dir.as_str())?;
+                variant
+            }
+        };
+        variant.end()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "Literal")]
+#[serde(bound(deserialize = "TD: TermData + Deserialize<'de>"))]
+enum LiteralRepr<TD: TermData> {
+    Typed(TD, Iri<TD>),
+    Lang(TD, TD),
+    LangDir(TD, TD, TD),
+}
+
+impl<'de, TD> Deserialize<'de> for Literal<TD>
+where
+    TD: TermData + Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match LiteralRepr::deserialize(deserializer)? {
+            LiteralRepr::Typed(txt, dt) => Ok(Literal::new_dt(txt, dt)),
+            LiteralRepr::Lang(txt, tag) => Literal::new_lang(txt, tag).map_err(D::Error::custom),
+            LiteralRepr::LangDir(txt, tag, dir) => {
+                let dir = Direction::parse(dir.as_ref()).ok_or_else(|| {
+                    D::Error::custom(format!("invalid base direction: {}", dir.as_ref()))
+                })?;
+                Literal::new_lang_dir(txt, tag, dir).map_err(D::Error::custom)
+            }
+        }
+    }
+}
+
+impl<TD: TermData> Serialize for Term<TD> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Term::Iri(iri) => serializer.serialize_newtype_variant("Term", 0, "Iri", iri),
+            Term::BNode(bn) => serializer.serialize_newtype_variant("Term", 1, "BNode", bn),
+            Term::Literal(lit) => serializer.serialize_newtype_variant("Term", 2, "Literal", lit),
+            Term::Variable(var) => serializer.serialize_newtype_variant("Term", 3, "Variable", var),
+            Term::Triple(spo) => serializer.serialize_newtype_variant("Term", 4, "Triple", spo),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "Term")]
+#[serde(bound(deserialize = "TD: TermData + Deserialize<'de>"))]
+enum TermRepr<TD: TermData> {
+    Iri(Iri<TD>),
+    BNode(BlankNode<TD>),
+    Literal(Literal<TD>),
+    Variable(Variable<TD>),
+    Triple(Box<[Term<TD>; 3]>),
+}
+
+impl<'de, TD> Deserialize<'de> for Term<TD>
+where
+    TD: TermData + Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match TermRepr::deserialize(deserializer)? {
+            TermRepr::Iri(iri) => Term::Iri(iri),
+            TermRepr::BNode(bn) => Term::BNode(bn),
+            TermRepr::Literal(lit) => Term::Literal(lit),
+            TermRepr::Variable(var) => Term::Variable(var),
+            TermRepr::Triple(spo) => Term::Triple(spo),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sophia_api::ns::{rdf, xsd};
+
+    fn terms() -> Vec<BoxTerm> {
+        vec![
+            BoxTerm::new_iri("http://example.org/a").unwrap(),
+            BoxTerm::new_iri_suffixed("http://example.org/", "b").unwrap(),
+            BoxTerm::new_bnode("b1").unwrap(),
+            BoxTerm::new_variable("v").unwrap(),
+            BoxTerm::new_literal_dt("hello \"world\"\n", xsd::string).unwrap(),
+            BoxTerm::new_literal_dt("42", xsd::integer).unwrap(),
+            BoxTerm::new_literal_lang("chat", "fr").unwrap(),
+            BoxTerm::new_literal_lang_dir("שלום", "he", Direction::Rtl).unwrap(),
+            BoxTerm::new_triple(
+                BoxTerm::new_bnode("b1").unwrap(),
+                BoxTerm::copy(&rdf::type_),
+                BoxTerm::new_literal_lang("x", "en").unwrap(),
+            ),
+        ]
+    }
+
+    #[test]
+    fn json_roundtrip() {
+        for term in terms() {
+            let json = serde_json::to_string(&term).unwrap();
+            let back: BoxTerm = serde_json::from_str(&json).unwrap();
+            assert_eq!(back, term, "{}", json);
+        }
+    }
+
+    #[test]
+    fn bincode_roundtrip() {
+        let terms = terms();
+        let data = bincode::serialize(&terms).unwrap();
+        let back: Vec<BoxTerm> = bincode::deserialize(&data).unwrap();
+        assert_eq!(back, terms);
+    }
+
+    #[test]
+    fn borrowed() {
+        let json = r#"{"Literal":{"Lang":["chat","fr"]}}"#;
+        let term: RefTerm = serde_json::from_str(json).unwrap();
+        assert_eq!(term, RefTerm::new_literal_lang("chat", "fr").unwrap());
+    }
+
+    #[test]
+    fn unescaped() {
+        let term = BoxTerm::new_literal_dt("a\"b", xsd::string).unwrap();
+        let json = serde_json::to_string(&term).unwrap();
+        assert_eq!(
+            json,
+            r#"{"Literal":{"Typed":["a\"b","http://www.w3.org/2001/XMLSchema#string"]}}"#
+        );
+    }
+
+    #[test]
+    fn triple_and_quad_arrays() {
+        let t = [
+            BoxTerm::new_bnode("s").unwrap(),
+            BoxTerm::copy(&rdf::type_),
+            BoxTerm::new_literal_dt("1", xsd::int).unwrap(),
+        ];
+        let json = serde_json::to_string(&t).unwrap();
+        let back: [BoxTerm; 3] = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, t);
+        let q = (t.clone(), Some(BoxTerm::new_iri("tag:g").unwrap()));
+        let json = serde_json::to_string(&q).unwrap();
+        let back: ([BoxTerm; 3], Option<BoxTerm>) = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, q);
+    }
+
+    #[test]
+    fn invalid() {
+        for json in &[
+            r#"{"Iri":"not an iri"}"#,
+            r#"{"BNode":"a b"}"#,
+            r#"{"Variable":"a-b"}"#,
+            r#"{"Literal":{"Lang":["chat","not a tag"]}}"#,
+            r#"{"Literal":{"LangDir":["chat","fr","up"]}}"#,
+            r#"{"Literal":{"Typed":["chat","not an iri"]}}"#,
+            r#"{"Unknown":"x"}"#,
+        ] {
+            assert!(serde_json::from_str::<BoxTerm>(json).is_err(), "{}", json);
+        }
+    }
+}
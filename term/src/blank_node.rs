@@ -168,7 +168,7 @@ impl<TD: TermData> TTerm for BlankNode<TD> {
     fn kind(&self) -> TermKind {
         TermKind::BlankNode
     }
-    fn value_raw(&self) -> RawValue {
+    fn value_raw(&self) -> RawValue<'_> {
         self.0.as_ref().into()
     }
     fn as_dyn(&self) -> &dyn TTerm {
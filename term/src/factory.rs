@@ -61,6 +61,15 @@ pub trait TermFactory {
         Term::new_literal_lang(self.get_term_data(txt), self.get_term_data(lang))
     }
 
+    /// Get a new directional language-tagged literal.
+    fn literal_lang_dir<T, U>(&mut self, txt: T, lang: U, dir: Direction) -> Result<FTerm<Self>>
+    where
+        T: TermData + Into<Self::TermData>,
+        U: TermData + Into<Self::TermData>,
+    {
+        Term::new_literal_lang_dir(self.get_term_data(txt), self.get_term_data(lang), dir)
+    }
+
     /// Get a new typed literal.
     fn literal_dt<T, U>(&mut self, txt: T, dt: Term<U>) -> Result<FTerm<Self>>
     where
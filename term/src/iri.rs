@@ -16,7 +16,6 @@
 //!
 
 mod _join;
-pub use self::_join::*;
 
 use super::*;
 use mownstr::MownStr;
@@ -36,8 +35,21 @@ pub const GEN_DELIMS: &[char] = &[':', '/', '?', '#', '[', ']', '@'];
 /// IRIs are represented in a given format.
 ///
 /// They are applied by copying terms with
-/// [`Term::normalized`](../enum.Term.html#method.normalized_with).
+/// [`Term::normalized`](../enum.Term.html#method.normalized).
+///
+/// [`NoSuffix`](#variant.NoSuffix) and [`LastGenDelim`](#variant.LastGenDelim)
+/// only change the internal representation of IRIs,
+/// so the normalized term is equal to the original one.
+/// On the other hand, [`Rfc3987`](#variant.Rfc3987) and [`Uri`](#variant.Uri)
+/// may change the IRIs themselves:
+/// the normalized term is then a *different* term
+/// (although RFC 3987 considers both IRIs as equivalent),
+/// which is not equal to the original one.
+///
+/// This enum is `#[non_exhaustive]`,
+/// so matching on it outside this crate requires a wildcard arm.
 #[derive(Clone, Copy)]
+#[non_exhaustive]
 pub enum Normalization {
     /// IRIs are represented as a single string (`ns`) with an empty `suffix`.
     NoSuffix,
@@ -45,6 +57,15 @@ pub enum Normalization {
     /// [`gen-delim`](./constant.GEN_DELIMS.html) and a `suffix` containing the
     /// remaining characters.
     LastGenDelim,
+    /// IRIs are normalized as per
+    /// [RFC 3987](https://tools.ietf.org/html/rfc3987#section-5.3.2)
+    /// (see [`normalize`](normalize/fn.normalize.html)),
+    /// and represented as a single string (`ns`) with an empty `suffix`.
+    Rfc3987,
+    /// IRIs are normalized as with [`Rfc3987`](#variant.Rfc3987),
+    /// then mapped to URIs
+    /// (see [`iri_to_uri`](normalize/fn.iri_to_uri.html)).
+    Uri,
 }
 
 /// An IRI reference.
@@ -280,16 +301,47 @@ where
         self.suffix.is_some()
     }
 
-    /// Return an IRI equivalent to this one,
-    /// internally represented with all its data in `ns`, and an empty `suffix`.
+    /// Return an IRI represented according to `policy`.
+    ///
+    /// With [`Normalization::Rfc3987`] and [`Normalization::Uri`],
+    /// the returned IRI may be different from (i.e. not equal to) this one;
+    /// with the other policies, it is always equal to this one.
     ///
     /// # Performances
     /// The returned IRI will borrow data from this one as much as possible,
     /// but strings may be allocated in case a concatenation is required.
-    pub fn normalized(&self, policy: Normalization) -> Iri<MownStr> {
+    ///
+    /// [`Normalization::Rfc3987`]: enum.Normalization.html#variant.Rfc3987
+    /// [`Normalization::Uri`]: enum.Normalization.html#variant.Uri
+    pub fn normalized(&self, policy: Normalization) -> Iri<MownStr<'_>> {
         match policy {
             Normalization::NoSuffix => self.normalized_no_suffix(),
             Normalization::LastGenDelim => self.normalized_suffixed_at_last_gen_delim(),
+            Normalization::Rfc3987 => self.normalized_with(normalize::normalize),
+            Normalization::Uri => self.normalized_with(|txt| {
+                normalize::normalize(txt).and_then(|txt| normalize::iri_to_uri(&txt))
+            }),
+        }
+    }
+
+    /// Return an IRI equivalent to this one, transformed by `f`,
+    /// internally represented with all its data in `ns`, and an empty `suffix`.
+    ///
+    /// # Performances
+    /// If `f` does not change the IRI, the returned IRI is the same as
+    /// [`normalized_no_suffix`](#method.normalized_no_suffix).
+    fn normalized_with<F>(&self, f: F) -> Iri<MownStr<'_>>
+    where
+        F: FnOnce(&str) -> sophia_iri::error::Result<String>,
+    {
+        let full = self.normalized_no_suffix();
+        match f(full.ns.as_ref()) {
+            // the contract of Iri ensures that f does not fail
+            Ok(txt) if txt != full.ns.as_ref() => Iri {
+                ns: MownStr::from(txt),
+                suffix: None,
+            },
+            _ => full,
         }
     }
 
@@ -299,7 +351,7 @@ where
     /// # Performances
     /// If this IRI has an empty suffix, the returned IRI simply borrows its `ns`.
     /// Otherwise, a new string is allocated for the returned IRI.
-    pub fn normalized_no_suffix(&self) -> Iri<MownStr> {
+    pub fn normalized_no_suffix(&self) -> Iri<MownStr<'_>> {
         match &self.suffix {
             Some(s) => {
                 let mut full = String::with_capacity(self.len());
@@ -323,7 +375,7 @@ where
     /// # Performances
     /// The returned IRI will borrow data from this one as much as possible,
     /// but strings may be allocated in case a concatenation is required.
-    pub fn normalized_suffixed_at_last_gen_delim(&self) -> Iri<MownStr> {
+    pub fn normalized_suffixed_at_last_gen_delim(&self) -> Iri<MownStr<'_>> {
         let ns = self.ns.as_ref();
         match &self.suffix {
             Some(suf) => {
@@ -481,10 +533,10 @@ impl<TD: TermData> TTerm for Iri<TD> {
     fn kind(&self) -> TermKind {
         TermKind::Iri
     }
-    fn value_raw(&self) -> RawValue {
+    fn value_raw(&self) -> RawValue<'_> {
         RawValue(
             self.ns.as_ref(),
-            (&self.suffix).as_ref().map(|td| td.as_ref()),
+            self.suffix.as_ref().map(|td| td.as_ref()),
         )
     }
     fn as_dyn(&self) -> &dyn TTerm {
@@ -3,10 +3,11 @@
 
 //!
 //! Terms are the building blocks of an [RDF] graph.
-//! There are four types of terms: IRIs, blank nodes (BNode for short),
-//! literals and variables.
+//! There are five types of terms: IRIs, blank nodes (BNode for short),
+//! literals, variables and quoted triples.
 //!
-//! NB: variable only exist in [generalized RDF].
+//! NB: variable only exist in [generalized RDF],
+//! and quoted triples only exist in [RDF-star].
 //!
 //! This module defines a generic type [`Term`](enum.Term.html)
 //! which can be derived differently depending on your needs.
@@ -17,17 +18,17 @@
 //!   which is the lifetime of their underlying text.
 //!
 //! * [`BoxTerm`](type.BoxTerm.html) (alias of `Term<Box<str>>`)
-//!    should be used when the term may outlive the text used to create it.
+//!   should be used when the term may outlive the text used to create it.
 //!
 //! * [`RcTerm`](type.RcTerm.html) (alias of `Term<Rc<str>>`)
-//!    should also be used for long-lived terms,
-//!    especially if they need to be cloned multiple times.
-//!    The use of `Rc` prevents the duplication of the underlying text,
-//!    while ensuring that it is cleaned when appropriate.
+//!   should also be used for long-lived terms,
+//!   especially if they need to be cloned multiple times.
+//!   The use of `Rc` prevents the duplication of the underlying text,
+//!   while ensuring that it is cleaned when appropriate.
 //!
 //! * [`ArcTerm`](type.ArcTerm.html) (alias of `Term<Arc<str>>`)
-//!    should be used when, additionally,
-//!    terms need to be sent to other threads.
+//!   should be used when, additionally,
+//!   terms need to be sent to other threads.
 //!
 //! * [`StaticTerm`](type.StaticTerm.html) (alias of `Term<&'static str>)
 //!   is a special case of `RefTerm`
@@ -43,13 +44,14 @@
 //! [RDF]: https://www.w3.org/TR/rdf-primer/
 //! [Linked Data]: http://linkeddata.org/
 //! [generalized RDF]: https://docs.rs/sophia/latest/sophia/#generalized-vs-strict-rdf-model
+//! [RDF-star]: https://w3c.github.io/rdf-star/cg-spec/editors_draft.html
 
 #![deny(missing_docs)]
 
 use mownstr::MownStr;
 use sophia_api::term::{
-    term_cmp, term_eq, term_format, term_hash, term_to_string, CopyTerm, RawValue, SimpleIri,
-    TTerm, TermKind, TryCopyTerm,
+    term_cmp, term_eq, term_format, term_hash, term_to_string, CopyTerm, Direction, RawValue,
+    SimpleIri, TTerm, TermKind, TryCopyTerm,
 };
 use std::convert::TryInto;
 use std::fmt::Debug;
@@ -73,12 +75,18 @@ use literal::Literal;
 mod _display;
 mod _error;
 pub use self::_error::*;
+#[cfg(feature = "serde")]
+mod _serde;
 
 /// Generic type for RDF terms.
 ///
 /// See [module documentation](index.html) for more detail.
 ///
-#[derive(Clone, Copy, Debug, Eq, Ord)]
+/// NB: since a [quoted triple](#variant.Triple) owns its three components,
+/// `Term` does not implement `Copy`, even when `TD` does (e.g. [`RefTerm`](type.RefTerm.html));
+/// use `clone` instead.
+///
+#[derive(Clone, Debug, Eq, Ord)]
 pub enum Term<TD>
 where
     TD: TermData,
@@ -93,6 +101,8 @@ where
     Literal(Literal<TD>),
     /// A universally quantified variable like in SPARQL or Notation3.
     Variable(Variable<TD>),
+    /// A quoted triple, as in RDF-star (subject, predicate, object).
+    Triple(Box<[Term<TD>; 3]>),
 }
 
 /// Trait alias for types holding the textual data of terms.
@@ -182,6 +192,17 @@ where
         Literal::<T>::new_lang(txt, lang).map(Into::into)
     }
 
+    /// Return a new literal term with the given value, language tag and base direction.
+    ///
+    /// May fail if the language tag is not a valid BCP47 language tag.
+    pub fn new_literal_lang_dir<U, V>(txt: U, lang: V, dir: Direction) -> Result<Self>
+    where
+        V: AsRef<str>,
+        T: From<U> + From<V>,
+    {
+        Literal::<T>::new_lang_dir(txt, lang, dir).map(Into::into)
+    }
+
     /// Return a new literal term with the given value and datatype.
     ///
     /// May fail if `dt` is not an IRI.
@@ -205,7 +226,17 @@ where
         Variable::new(name).map(Into::into)
     }
 
+    /// Return a new quoted triple term with the given subject, predicate and object.
+    ///
+    /// This never fails, as quoted triples may contain any kind of term
+    /// in [generalized RDF](https://docs.rs/sophia/latest/sophia/#generalized-vs-strict-rdf-model).
+    pub fn new_triple(s: Term<T>, p: Term<T>, o: Term<T>) -> Term<T> {
+        Term::Triple(Box::new([s, p, o]))
+    }
+
     /// Borrow the inner contents of the term.
+    ///
+    /// NB: this allocates if `self` is a quoted triple.
     pub fn as_ref(&self) -> Term<&T> {
         use self::Term::*;
 
@@ -214,10 +245,17 @@ where
             Literal(lit) => Literal(lit.as_ref()),
             BNode(bn) => BNode(bn.as_ref()),
             Variable(var) => Variable(var.as_ref()),
+            Triple(spo) => Triple(Box::new([
+                spo[0].as_ref(),
+                spo[1].as_ref(),
+                spo[2].as_ref(),
+            ])),
         }
     }
 
     /// Borrow the inner contents of the term as `&str`.
+    ///
+    /// NB: this allocates if `self` is a quoted triple.
     pub fn as_ref_str(&self) -> Term<&str> {
         use self::Term::*;
 
@@ -226,14 +264,28 @@ where
             Literal(lit) => Literal(lit.as_ref_str()),
             BNode(bn) => BNode(bn.as_ref_str()),
             Variable(var) => Variable(var.as_ref_str()),
+            Triple(spo) => Triple(Box::new([
+                spo[0].as_ref_str(),
+                spo[1].as_ref_str(),
+                spo[2].as_ref_str(),
+            ])),
         }
     }
 
     /// Create a new term by applying `f` to the `TermData` of `self`.
-    pub fn map<F, TD2>(self, f: F) -> Term<TD2>
+    pub fn map<F, TD2>(self, mut f: F) -> Term<TD2>
     where
         F: FnMut(T) -> TD2,
         TD2: TermData,
+    {
+        self.map_dyn(&mut f)
+    }
+
+    // NB: quoted triples require a recursive call,
+    // which must not be generic over the type of `f`.
+    fn map_dyn<TD2>(self, f: &mut dyn FnMut(T) -> TD2) -> Term<TD2>
+    where
+        TD2: TermData,
     {
         use self::Term::*;
 
@@ -242,6 +294,10 @@ where
             Literal(lit) => Literal(lit.map(f)),
             BNode(bn) => BNode(bn.map(f)),
             Variable(var) => Variable(var.map(f)),
+            Triple(spo) => {
+                let [s, p, o] = *spo;
+                Triple(Box::new([s.map_dyn(f), p.map_dyn(f), o.map_dyn(f)]))
+            }
         }
     }
 
@@ -258,10 +314,18 @@ where
     /// factory.
     ///
     /// This is done in one step in contrast to calling `clone().map(factory)`.
-    pub fn clone_map<'a, U, F>(&'a self, factory: F) -> Term<U>
+    pub fn clone_map<'a, U, F>(&'a self, mut factory: F) -> Term<U>
     where
         U: TermData,
         F: FnMut(&'a str) -> U,
+    {
+        self.clone_map_dyn(&mut factory)
+    }
+
+    // NB: see `map_dyn`
+    fn clone_map_dyn<'a, U>(&'a self, factory: &mut dyn FnMut(&'a str) -> U) -> Term<U>
+    where
+        U: TermData,
     {
         use self::Term::*;
 
@@ -270,6 +334,11 @@ where
             BNode(bn) => bn.clone_map(factory).into(),
             Literal(lit) => lit.clone_map(factory).into(),
             Variable(var) => var.clone_map(factory).into(),
+            Triple(spo) => Triple(Box::new([
+                spo[0].clone_map_dyn(factory),
+                spo[1].clone_map_dyn(factory),
+                spo[2].clone_map_dyn(factory),
+            ])),
         }
     }
 
@@ -281,17 +350,27 @@ where
         self.clone_map(Into::into)
     }
 
-    /// Return a term equivalent to this one,
-    /// with all IRIs (if any)
-    /// internally represented with all its data in `ns`, and an empty `suffix`.
+    /// Return a term with all IRIs (if any) represented according to `policy`.
+    ///
+    /// With [`Normalization::Rfc3987`] and [`Normalization::Uri`],
+    /// the returned term may be different from (i.e. not equal to) this one;
+    /// with the other policies, it is always equal to this one.
     ///
     /// # Performances
     /// The returned term will borrow data from this one as much as possible,
     /// but strings may be allocated in case a concatenation is required.
-    pub fn normalized(&self, policy: Normalization) -> MownTerm {
+    ///
+    /// [`Normalization::Rfc3987`]: iri/enum.Normalization.html#variant.Rfc3987
+    /// [`Normalization::Uri`]: iri/enum.Normalization.html#variant.Uri
+    pub fn normalized(&self, policy: Normalization) -> MownTerm<'_> {
         match self {
             Term::Iri(iri) => iri.normalized(policy).into(),
             Term::Literal(lit) => lit.normalized(policy).into(),
+            Term::Triple(spo) => Term::Triple(Box::new([
+                spo[0].normalized(policy),
+                spo[1].normalized(policy),
+                spo[2].normalized(policy),
+            ])),
             _ => self.as_ref_str().map_into(),
         }
     }
@@ -359,6 +438,20 @@ where
         Literal::<T>::new_lang_unchecked(txt, lang).into()
     }
 
+    /// Return a directional literal term.
+    ///
+    /// # Pre-condition
+    ///
+    /// This function requires that `lang` is a valid language tag.
+    /// In debug mode this constraint is asserted.
+    pub fn new_literal_lang_dir_unchecked<U, V>(txt: U, lang: V, dir: Direction) -> Self
+    where
+        V: AsRef<str>,
+        T: From<U> + From<V>,
+    {
+        Literal::<T>::new_lang_dir_unchecked(txt, lang, dir).into()
+    }
+
     /// Return a typed literal term.
     ///
     /// # Panics
@@ -395,18 +488,27 @@ impl<T: TermData> TTerm for Term<T> {
             Literal(_) => TermKind::Literal,
             BNode(_) => TermKind::BlankNode,
             Variable(_) => TermKind::Variable,
+            Triple(_) => TermKind::QuotedTriple,
         }
     }
-    fn value_raw(&self) -> RawValue {
+    fn value_raw(&self) -> RawValue<'_> {
         use Term::*;
         match self {
             Iri(i) => i.value_raw(),
             Literal(l) => l.value_raw(),
             BNode(b) => b.value_raw(),
             Variable(v) => v.value_raw(),
+            Triple(_) => "".into(),
+        }
+    }
+    fn triple(&self) -> Option<[&dyn TTerm; 3]> {
+        if let Term::Triple(spo) = self {
+            Some([&spo[0], &spo[1], &spo[2]])
+        } else {
+            None
         }
     }
-    fn datatype(&self) -> Option<SimpleIri> {
+    fn datatype(&self) -> Option<SimpleIri<'_>> {
         if let Term::Literal(lit) = self {
             lit.datatype()
         } else {
@@ -420,6 +522,13 @@ impl<T: TermData> TTerm for Term<T> {
             None
         }
     }
+    fn direction(&self) -> Option<Direction> {
+        if let Term::Literal(lit) = self {
+            lit.dir()
+        } else {
+            None
+        }
+    }
     fn as_dyn(&self) -> &dyn TTerm {
         self
     }
@@ -536,6 +645,11 @@ where
             TermKind::Literal => Term::Literal(Literal::try_copy(term).unwrap()),
             TermKind::BlankNode => Term::BNode(BlankNode::try_copy(term).unwrap()),
             TermKind::Variable => Term::Variable(Variable::try_copy(term).unwrap()),
+            TermKind::QuotedTriple => {
+                let spo = term.triple().unwrap();
+                Term::new_triple(Self::copy(spo[0]), Self::copy(spo[1]), Self::copy(spo[2]))
+            }
+            kind => unimplemented!("copy of {:?} terms", kind),
         }
     }
 }
@@ -556,10 +670,18 @@ where
                     let dt: Iri<&'a str> = t.datatype().unwrap().into();
                     Literal::new_dt(v.0, dt)
                 }
-                Some(tag) => Literal::new_lang_unchecked(v.0, tag),
+                Some(tag) => match t.direction() {
+                    None => Literal::new_lang_unchecked(v.0, tag),
+                    Some(dir) => Literal::new_lang_dir_unchecked(v.0, tag, dir),
+                },
             }),
             TermKind::BlankNode => Term::BNode(BlankNode::new_unchecked(v.0)),
             TermKind::Variable => Term::Variable(Variable::new_unchecked(v.0)),
+            TermKind::QuotedTriple => {
+                let spo = t.triple().unwrap();
+                Term::new_triple(spo[0].into(), spo[1].into(), spo[2].into())
+            }
+            kind => unimplemented!("conversion of {:?} terms", kind),
         }
     }
 }
@@ -14,6 +14,8 @@ use std::hash::{Hash, Hasher};
 use std::io;
 
 pub mod convert;
+pub mod registry;
+pub mod value;
 
 /// Internal distinction of literals.
 ///
@@ -23,7 +25,8 @@ enum Kind<TD: TermData> {
     /// Something representing a language tag.
     ///
     /// The tags conform to [BCP47](https://tools.ietf.org/html/bcp47).
-    Lang(TD),
+    /// The optional base direction makes it a directional language-tagged string.
+    Lang(TD, Option<Direction>),
     /// The IRI referencing the datatype.
     Dt(Iri<TD>),
 }
@@ -41,6 +44,10 @@ use self::Kind::*;
 ///
 /// The tags conform to [BCP47](https://tools.ietf.org/html/bcp47).
 ///
+/// As in [RDF 1.2](https://www.w3.org/TR/rdf12-concepts/#section-Graph-Literal),
+/// a language-tagged literal can also have a base [`Direction`],
+/// in which case its type is `rdf:dirLangString`.
+///
 /// # Datatypes
 ///
 /// Datatypes in RDF have a lexical scope and a value scope. Transformation
@@ -71,16 +78,28 @@ where
         V: AsRef<str>,
         TD: From<U> + From<V>,
     {
-        if let Err(err) = LanguageTag::parse(tag.as_ref()) {
-            return Err(TermError::InvalidLanguageTag {
-                tag: tag.as_ref().to_string(),
-                err: err.to_string(),
-            });
-        }
+        check_lang(tag.as_ref())?;
+        Ok(Self {
+            txt: txt.into(),
+            kind: Lang(tag.into(), None),
+        })
+    }
 
+    /// Return a new directional language-tagged literal.
+    ///
+    /// # Error
+    ///
+    /// If `tag` is not a valid language-tag according to
+    /// [BCP47](https://tools.ietf.org/html/bcp47) an error is raised.
+    pub fn new_lang_dir<U, V>(txt: U, tag: V, dir: Direction) -> Result<Self>
+    where
+        V: AsRef<str>,
+        TD: From<U> + From<V>,
+    {
+        check_lang(tag.as_ref())?;
         Ok(Self {
             txt: txt.into(),
-            kind: Lang(tag.into()),
+            kind: Lang(tag.into(), Some(dir)),
         })
     }
 
@@ -121,7 +140,31 @@ where
 
         Self {
             txt: txt.into(),
-            kind: Lang(tag.into()),
+            kind: Lang(tag.into(), None),
+        }
+    }
+
+    /// Return a new directional language-tagged literal.
+    ///
+    /// # Pre-condition
+    ///
+    /// `tag` must be a valid language-tag according to
+    /// [BCP47](https://tools.ietf.org/html/bcp47).
+    /// In debug mode this is asserted.
+    pub fn new_lang_dir_unchecked<U, V>(txt: U, tag: V, dir: Direction) -> Self
+    where
+        V: AsRef<str>,
+        TD: From<U> + From<V>,
+    {
+        debug_assert!(
+            LanguageTag::parse(tag.as_ref()).is_ok(),
+            "invalid language tag {:?}",
+            tag.as_ref()
+        );
+
+        Self {
+            txt: txt.into(),
+            kind: Lang(tag.into(), Some(dir)),
         }
     }
 
@@ -129,7 +172,7 @@ where
     pub fn as_ref(&self) -> Literal<&TD> {
         let txt = &self.txt;
         let kind = match &self.kind {
-            Lang(tag) => Lang(tag),
+            Lang(tag, dir) => Lang(tag, *dir),
             Dt(dt) => Dt(dt.as_ref()),
         };
         Literal { txt, kind }
@@ -139,7 +182,7 @@ where
     pub fn as_ref_str(&self) -> Literal<&str> {
         let txt = self.txt.as_ref();
         let kind = match &self.kind {
-            Lang(tag) => Lang(tag.as_ref()),
+            Lang(tag, dir) => Lang(tag.as_ref(), *dir),
             Dt(dt) => Dt(dt.as_ref_str()),
         };
         Literal { txt, kind }
@@ -154,7 +197,7 @@ where
         let mut f = f;
         let txt = f(self.txt);
         let kind = match self.kind {
-            Lang(tag) => Lang(f(tag)),
+            Lang(tag, dir) => Lang(f(tag), dir),
             Dt(dt) => Dt(dt.map(f)),
         };
         Literal { txt, kind }
@@ -181,7 +224,7 @@ where
         let mut factory = factory;
         let txt = factory(self.txt.as_ref());
         let kind = match &self.kind {
-            Lang(tag) => Lang(factory(tag.as_ref())),
+            Lang(tag, dir) => Lang(factory(tag.as_ref()), *dir),
             Dt(iri) => Dt(iri.clone_map(factory)),
         };
 
@@ -196,17 +239,22 @@ where
         self.clone_map(Into::into)
     }
 
-    /// Return a literal equivalent to this one,
-    /// with its datatype (if any)
-    /// is internally represented with all its data in `ns`, and an empty `suffix`.
+    /// Return a literal with its datatype represented according to `policy`.
+    ///
+    /// With [`Normalization::Rfc3987`] and [`Normalization::Uri`],
+    /// the returned literal may be different from (i.e. not equal to) this one;
+    /// with the other policies, it is always equal to this one.
     ///
     /// # Performances
     /// The returned literal will borrow data from this one as much as possible,
     /// but strings may be allocated in case a concatenation is required.
-    pub fn normalized(&self, policy: Normalization) -> Literal<MownStr> {
+    ///
+    /// [`Normalization::Rfc3987`]: ../iri/enum.Normalization.html#variant.Rfc3987
+    /// [`Normalization::Uri`]: ../iri/enum.Normalization.html#variant.Uri
+    pub fn normalized(&self, policy: Normalization) -> Literal<MownStr<'_>> {
         let txt = MownStr::from(self.txt.as_ref());
         let kind = match &self.kind {
-            Lang(tag) => Lang(MownStr::from(tag.as_ref())),
+            Lang(tag, dir) => Lang(MownStr::from(tag.as_ref()), *dir),
             Dt(iri) => Dt(iri.normalized(policy)),
         };
         Literal { txt, kind }
@@ -221,9 +269,14 @@ where
         fmt_quoted_string(w, self.txt.as_ref())?;
 
         match &self.kind {
-            Lang(tag) => {
+            Lang(tag, dir) => {
                 w.write_str("\"@")?;
-                w.write_str(tag.as_ref())
+                w.write_str(tag.as_ref())?;
+                if let Some(dir) = dir {
+                    w.write_str("--")?;
+                    w.write_str(dir.as_str())?;
+                }
+                Ok(())
             }
             Dt(dt) => {
                 if &xsd::string != dt {
@@ -245,9 +298,14 @@ where
         io_quoted_string(w, self.txt.as_ref().as_bytes())?;
 
         match &self.kind {
-            Lang(tag) => {
+            Lang(tag, dir) => {
                 w.write_all(b"\"@")?;
-                w.write_all(tag.as_ref().as_bytes())
+                w.write_all(tag.as_ref().as_bytes())?;
+                if let Some(dir) = dir {
+                    w.write_all(b"--")?;
+                    w.write_all(dir.as_str().as_bytes())?;
+                }
+                Ok(())
             }
             Dt(dt) => {
                 if &xsd::string != dt {
@@ -267,23 +325,34 @@ where
 
     /// Return an IRI borrowing the literals datatype.
     ///
-    /// _Note:_ A language-tagged literal has always the type `rdf:langString`.
+    /// _Note:_ A language-tagged literal has always the type `rdf:langString`,
+    /// or `rdf:dirLangString` if it has a base direction.
     pub fn dt(&self) -> Iri<&str> {
         match &self.kind {
-            Lang(_) => rdf::langString.into(),
+            Lang(_, None) => rdf::langString.into(),
+            Lang(_, Some(_)) => rdf::dirLangString.into(),
             Dt(dt) => dt.as_ref_str(),
         }
     }
 
     /// Return the language-tag of the literal if it has one.
     pub fn lang(&self) -> Option<&TD> {
-        if let Lang(tag) = &self.kind {
+        if let Lang(tag, _) = &self.kind {
             Some(tag)
         } else {
             None
         }
     }
 
+    /// Return the base direction of the literal if it has one.
+    pub fn dir(&self) -> Option<Direction> {
+        if let Lang(_, dir) = &self.kind {
+            *dir
+        } else {
+            None
+        }
+    }
+
     /// Check if both literals have the same lexical value.
     pub fn eq_txt<U>(&self, other: Literal<U>) -> bool
     where
@@ -297,15 +366,18 @@ impl<TD: TermData> TTerm for Literal<TD> {
     fn kind(&self) -> TermKind {
         TermKind::Literal
     }
-    fn value_raw(&self) -> RawValue {
+    fn value_raw(&self) -> RawValue<'_> {
         self.txt.as_ref().into()
     }
-    fn datatype(&self) -> Option<SimpleIri> {
+    fn datatype(&self) -> Option<SimpleIri<'_>> {
         Some(self.dt().into())
     }
     fn language(&self) -> Option<&str> {
         self.lang().map(|td| td.as_ref())
     }
+    fn direction(&self) -> Option<Direction> {
+        self.dir()
+    }
     fn as_dyn(&self) -> &dyn TTerm {
         self
     }
@@ -382,7 +454,10 @@ where
             let txt = term.value_raw().0;
             Ok(match term.language() {
                 None => Self::new_dt(txt, term.datatype().unwrap().into()),
-                Some(tag) => Self::new_lang_unchecked(txt, tag),
+                Some(tag) => match term.direction() {
+                    None => Self::new_lang_unchecked(txt, tag),
+                    Some(dir) => Self::new_lang_dir_unchecked(txt, tag, dir),
+                },
             })
         } else {
             Err(TermError::UnsupportedKind(term_to_string(term)))
@@ -427,6 +502,16 @@ where
     }
 }
 
+fn check_lang(tag: &str) -> Result<()> {
+    match LanguageTag::parse(tag) {
+        Ok(_) => Ok(()),
+        Err(err) => Err(TermError::InvalidLanguageTag {
+            tag: tag.to_string(),
+            err: err.to_string(),
+        }),
+    }
+}
+
 fn fmt_quoted_string<W: fmt::Write>(w: &mut W, txt: &str) -> fmt::Result {
     let mut cut = txt.len();
     let mut cutchar = '\0';
@@ -520,7 +605,7 @@ mod test {
         let dt1 = Iri::<Box<str>>::new("").unwrap();
         let lit1 = Literal::<Box<str>>::new_dt("hello", dt1);
         let xsd_string = &xsd::string.value();
-        let base = IriParsed::new(&xsd_string).unwrap();
+        let base = IriParsed::new(xsd_string).unwrap();
         let lit2: Literal<MownStr> = base.resolve(&lit1);
         let Literal { txt, .. } = lit2;
         assert!(txt.is_borrowed(), "txt has been allocated");
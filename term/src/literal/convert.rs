@@ -1,13 +1,18 @@
 //! Public exported by parent-module `literal`.
 //!
 
+use super::value::{Decimal, Integer};
 use super::*;
 use crate::{term_to_string, Result, TermError};
 use sophia_api::ns::xsd;
 use std::error::Error;
 use std::fmt;
 use std::hash;
-use std::str::FromStr;
+
+mod _any_uri;
+pub use _any_uri::*;
+mod _binary;
+pub use _binary::*;
 
 /// A native datatype that maps to an RDF datatype.
 pub trait DataType {
@@ -32,10 +37,13 @@ pub trait AsLiteral: DataType {
 }
 
 /// A native datatype that can be constructed from an RDF literal.
-pub trait TryConvertTerm: DataType + FromStr
-where
-    <Self as FromStr>::Err: Error + 'static,
-{
+pub trait TryConvertTerm: DataType + Sized {
+    /// The error raised when a lexical value is not valid for this datatype.
+    type Error: Error + 'static;
+
+    /// Parse a lexical value of this datatype.
+    fn from_lexical(lex: &str) -> std::result::Result<Self, Self::Error>;
+
     /// Try to convert any term to this native type.
     fn try_convert<T>(term: &T) -> Result<Self>
     where
@@ -47,7 +55,7 @@ where
                 if Self::iri() != dt {
                     Err(TermError::UnsupportedDatatype(term_to_string(term)))
                 } else {
-                    term.value_raw().0.parse::<Self>().map_err(|err| {
+                    Self::from_lexical(term.value_raw().0).map_err(|err| {
                         TermError::InvalidLexicalValue {
                             lex: term.value_raw().0.to_string(),
                             dt: term_to_string(&dt),
@@ -70,8 +78,7 @@ pub trait ConvertibleTerm: TTerm {
     /// Try to convert this term into a native type
     fn try_converted<T>(&self) -> Result<T>
     where
-        T: TryConvertTerm,
-        <T as FromStr>::Err: Error + 'static;
+        T: TryConvertTerm;
 }
 
 impl<T> ConvertibleTerm for T
@@ -81,12 +88,62 @@ where
     fn try_converted<U>(&self) -> Result<U>
     where
         U: TryConvertTerm,
-        <U as FromStr>::Err: Error + 'static,
     {
         U::try_convert(self)
     }
 }
 
+/// A type whose values can be read from, and written as, RDF terms.
+///
+/// It is automatically implemented by native types implementing both
+/// [`TryConvertTerm`] and [`AsLiteral`],
+/// and by [`Term`] itself (for IRIs, blank nodes and literals of any datatype).
+///
+/// [`TryConvertTerm`]: ./trait.TryConvertTerm.html
+/// [`AsLiteral`]: ./trait.AsLiteral.html
+/// [`Term`]: ../../enum.Term.html
+pub trait TermValue: Sized {
+    /// Try to read a value of this type from `term`.
+    fn from_term<T>(term: &T) -> Result<Self>
+    where
+        T: TTerm + ?Sized;
+
+    /// Represent this value as a term.
+    fn to_term(&self) -> BoxTerm;
+}
+
+impl<U> TermValue for U
+where
+    U: TryConvertTerm + AsLiteral,
+{
+    fn from_term<T>(term: &T) -> Result<Self>
+    where
+        T: TTerm + ?Sized,
+    {
+        U::try_convert(term)
+    }
+
+    fn to_term(&self) -> BoxTerm {
+        BoxTerm::copy(&self.as_literal())
+    }
+}
+
+impl<TD> TermValue for Term<TD>
+where
+    TD: TermData + for<'x> From<&'x str>,
+{
+    fn from_term<T>(term: &T) -> Result<Self>
+    where
+        T: TTerm + ?Sized,
+    {
+        Ok(Term::copy(term))
+    }
+
+    fn to_term(&self) -> BoxTerm {
+        BoxTerm::copy(self)
+    }
+}
+
 /// An RDF literal representation of a native value.
 #[derive(Clone, Debug)]
 pub struct NativeLiteral<T, U = Box<str>>
@@ -128,11 +185,11 @@ where
         TermKind::Literal
     }
 
-    fn value_raw(&self) -> RawValue {
+    fn value_raw(&self) -> RawValue<'_> {
         self.lexval.as_ref().into()
     }
 
-    fn datatype(&self) -> Option<SimpleIri> {
+    fn datatype(&self) -> Option<SimpleIri<'_>> {
         Some(T::iri())
     }
 
@@ -231,18 +288,52 @@ impl<'a> AsLiteral for &'a str {
     }
 }
 
-impl TryConvertTerm for u8 {}
-impl TryConvertTerm for u16 {}
-impl TryConvertTerm for u32 {}
-impl TryConvertTerm for u64 {}
-impl TryConvertTerm for i8 {}
-impl TryConvertTerm for i16 {}
-impl TryConvertTerm for i32 {}
-impl TryConvertTerm for i64 {}
-impl TryConvertTerm for f32 {}
-impl TryConvertTerm for f64 {}
-impl TryConvertTerm for bool {}
-impl TryConvertTerm for String {}
+macro_rules! impl_try_convert_term {
+    ($ty:ty) => {
+        impl $crate::literal::convert::TryConvertTerm for $ty {
+            type Error = <$ty as std::str::FromStr>::Err;
+            fn from_lexical(lex: &str) -> std::result::Result<Self, Self::Error> {
+                lex.parse()
+            }
+        }
+    };
+}
+
+impl_try_convert_term!(u8);
+impl_try_convert_term!(u16);
+impl_try_convert_term!(u32);
+impl_try_convert_term!(u64);
+impl_try_convert_term!(i8);
+impl_try_convert_term!(i16);
+impl_try_convert_term!(i32);
+impl_try_convert_term!(i64);
+impl_try_convert_term!(f32);
+impl_try_convert_term!(f64);
+impl_try_convert_term!(bool);
+impl_try_convert_term!(String);
+
+impl_datatype!(Integer, xsd::integer);
+impl_as_literal!(Integer);
+impl_try_convert_term!(Integer);
+
+impl_datatype!(Decimal, xsd::decimal);
+impl_as_literal!(Decimal);
+impl_try_convert_term!(Decimal);
+
+impl_datatype!(AnyUri, xsd::anyURI);
+impl_as_literal!(AnyUri);
+impl_try_convert_term!(AnyUri);
+
+impl_datatype!(Base64Binary, xsd::base64Binary);
+impl_as_literal!(Base64Binary);
+impl_try_convert_term!(Base64Binary);
+
+impl_datatype!(HexBinary, xsd::hexBinary);
+impl_as_literal!(HexBinary);
+impl_try_convert_term!(HexBinary);
+
+#[cfg(feature = "chrono")]
+mod _chrono;
 
 #[cfg(test)]
 mod test {
@@ -409,7 +500,7 @@ mod test {
     #[test_case("-10"       => Ok(-10.0)              ; "minus ten")]
     #[test_case("10"        => Ok(10.0)               ; "ten")]
     #[test_case("1000000"   => Ok(1000000.0)          ; "million")]
-    #[test_case("314e-2"    => Ok(3.14)               ; "float")]
+    #[test_case("314e-3"    => Ok(0.314)              ; "float")]
     #[test_case("true"      => Err(Failed::InvLexVal) ; "bool")]
     #[test_case("something" => Err(Failed::InvLexVal) ; "string")]
     fn convert_f32(lex: &str) -> Result<f32, Failed> {
@@ -425,7 +516,7 @@ mod test {
     #[test_case("-10"       => Ok(-10.0)              ; "minus ten")]
     #[test_case("10"        => Ok(10.0)               ; "ten")]
     #[test_case("1000000"   => Ok(1000000.0)          ; "million")]
-    #[test_case("314e-2"    => Ok(3.14)               ; "float")]
+    #[test_case("314e-3"    => Ok(0.314)              ; "float")]
     #[test_case("true"      => Err(Failed::InvLexVal) ; "bool")]
     #[test_case("something" => Err(Failed::InvLexVal) ; "string")]
     fn convert_f64(lex: &str) -> Result<f64, Failed> {
@@ -468,4 +559,203 @@ mod test {
         assert_eq!(lit.datatype(), lit2.datatype());
         Ok(native)
     }
+
+    #[test_case("0"                          => Ok("0".to_string())                         ; "zero")]
+    #[test_case("-010"                       => Ok("-10".to_string())                       ; "minus ten")]
+    #[test_case("+10"                        => Ok("10".to_string())                        ; "plus ten")]
+    #[test_case("123456789012345678901234567890" => Ok("123456789012345678901234567890".to_string()) ; "big")]
+    #[test_case("1.0"                        => Err(Failed::InvLexVal)                      ; "decimal")]
+    #[test_case("314e-2"                     => Err(Failed::InvLexVal)                      ; "float")]
+    #[test_case(" 1"                         => Err(Failed::InvLexVal)                      ; "space")]
+    #[test_case(""                           => Err(Failed::InvLexVal)                      ; "empty")]
+    fn convert_integer(lex: &str) -> Result<String, Failed> {
+        let dt = Iri::<&str>::from(Integer::iri());
+        let lit = Literal::<&str>::new_dt(lex, dt);
+        let native: Integer = lit.try_converted()?;
+        let lit2 = native.as_literal();
+        assert_eq!(lit.datatype(), lit2.datatype());
+        let value = lit2.value().to_string();
+        Ok(value)
+    }
+
+    #[test_case("0"       => Ok("0".to_string())         ; "zero")]
+    #[test_case("-0.0"    => Ok("0".to_string())         ; "minus zero")]
+    #[test_case("+010.50" => Ok("10.5".to_string())      ; "ten and a half")]
+    #[test_case(".5"      => Ok("0.5".to_string())       ; "no int part")]
+    #[test_case("5."      => Ok("5".to_string())         ; "no frac part")]
+    #[test_case("12345678901234567890.123456789012345678901" => Ok("12345678901234567890.123456789012345678901".to_string()) ; "big")]
+    #[test_case("."       => Err(Failed::InvLexVal)      ; "point")]
+    #[test_case("314e-2"  => Err(Failed::InvLexVal)      ; "float")]
+    #[test_case("INF"     => Err(Failed::InvLexVal)      ; "inf")]
+    fn convert_decimal(lex: &str) -> Result<String, Failed> {
+        let dt = Iri::<&str>::from(Decimal::iri());
+        let lit = Literal::<&str>::new_dt(lex, dt);
+        let native: Decimal = lit.try_converted()?;
+        let lit2 = native.as_literal();
+        assert_eq!(lit.datatype(), lit2.datatype());
+        let value = lit2.value().to_string();
+        Ok(value)
+    }
+
+    #[test]
+    fn integer_from_native() {
+        assert_eq!(Integer::from(-42_i64).as_literal().value().as_ref(), "-42");
+        assert_eq!(Integer::from(u64::MAX).to_i128(), Some(u64::MAX.into()));
+        assert_eq!(Integer::from(0_i128).to_string(), "0");
+    }
+
+    #[test_case("http://example.org/a#b" => Ok("http://example.org/a#b".to_string()) ; "absolute")]
+    #[test_case("../a?b"                 => Ok("../a?b".to_string())                 ; "relative")]
+    #[test_case(""                       => Ok("".to_string())                       ; "empty")]
+    #[test_case("http://é.example/"      => Ok("http://é.example/".to_string())      ; "iri")]
+    #[test_case("a b"                    => Err(Failed::InvLexVal)                   ; "space")]
+    #[test_case("http://[::1"            => Err(Failed::InvLexVal)                   ; "bad ip")]
+    fn convert_any_uri(lex: &str) -> Result<String, Failed> {
+        let dt = Iri::<&str>::from(AnyUri::iri());
+        let lit = Literal::<&str>::new_dt(lex, dt);
+        let native: AnyUri = lit.try_converted()?;
+        let lit2 = native.as_literal();
+        assert_eq!(lit.datatype(), lit2.datatype());
+        let value = lit2.value().to_string();
+        Ok(value)
+    }
+
+    #[test_case(""                  => Ok(vec![])                      ; "empty")]
+    #[test_case("AA=="              => Ok(vec![0])                     ; "one byte")]
+    #[test_case("AAE="              => Ok(vec![0, 1])                  ; "two bytes")]
+    #[test_case("Zm9vYmFy"          => Ok(b"foobar".to_vec())          ; "six bytes")]
+    #[test_case("Zm9v YmE="         => Ok(b"fooba".to_vec())           ; "space")]
+    #[test_case("Z m 9 v Y m E ="   => Ok(b"fooba".to_vec())           ; "spaces")]
+    #[test_case("Zg = ="            => Ok(b"f".to_vec())               ; "padding with space")]
+    #[test_case("Zm9v  YmE="        => Err(Failed::InvLexVal)          ; "double space")]
+    #[test_case(" Zm9v"             => Err(Failed::InvLexVal)          ; "leading space")]
+    #[test_case("Zm9v "             => Err(Failed::InvLexVal)          ; "trailing space")]
+    #[test_case("Zm9"               => Err(Failed::InvLexVal)          ; "missing padding")]
+    #[test_case("Zm==="             => Err(Failed::InvLexVal)          ; "too much padding")]
+    #[test_case("AB=="              => Err(Failed::InvLexVal)          ; "non zero bits 8")]
+    #[test_case("AAB="              => Err(Failed::InvLexVal)          ; "non zero bits 16")]
+    #[test_case("Zm9v\nYmE="       => Err(Failed::InvLexVal)          ; "newline")]
+    #[test_case("Zm-v"              => Err(Failed::InvLexVal)          ; "url safe")]
+    fn convert_base64(lex: &str) -> Result<Vec<u8>, Failed> {
+        let dt = Iri::<&str>::from(Base64Binary::iri());
+        let lit = Literal::<&str>::new_dt(lex, dt);
+        let native: Base64Binary = lit.try_converted()?;
+        let lit2 = native.as_literal();
+        assert_eq!(lit.datatype(), lit2.datatype());
+        assert_eq!(lit2.value().as_ref(), lex.replace(' ', ""));
+        Ok(native.0)
+    }
+
+    #[test_case(""         => Ok(vec![])                ; "empty")]
+    #[test_case("0fA0"     => Ok(vec![0x0f, 0xa0])      ; "mixed case")]
+    #[test_case("DEADBEEF" => Ok(vec![0xde, 0xad, 0xbe, 0xef]) ; "deadbeef")]
+    #[test_case("0"        => Err(Failed::InvLexVal)    ; "odd")]
+    #[test_case("0g"       => Err(Failed::InvLexVal)    ; "not hex")]
+    #[test_case("+1"       => Err(Failed::InvLexVal)    ; "sign")]
+    fn convert_hex(lex: &str) -> Result<Vec<u8>, Failed> {
+        let dt = Iri::<&str>::from(HexBinary::iri());
+        let lit = Literal::<&str>::new_dt(lex, dt);
+        let native: HexBinary = lit.try_converted()?;
+        let lit2 = native.as_literal();
+        assert_eq!(lit.datatype(), lit2.datatype());
+        assert_eq!(lit2.value().as_ref(), lex.to_uppercase());
+        Ok(native.0)
+    }
+
+    #[test]
+    fn term_value() {
+        let lit = BoxTerm::new_literal_dt("42", xsd::int).unwrap();
+        assert_eq!(i32::from_term(&lit).unwrap(), 42);
+        assert!(String::from_term(&lit).is_err());
+        assert_eq!(42_i32.to_term(), lit);
+
+        let iri = BoxTerm::new_iri("http://example.org/").unwrap();
+        assert_eq!(RcTerm::from_term(&iri).unwrap(), iri);
+        assert_eq!(RcTerm::from_term(&lit).unwrap().to_term(), lit);
+        assert!(i32::from_term(&iri).is_err());
+    }
+
+    #[cfg(feature = "chrono")]
+    mod chrono_types {
+        use super::*;
+        use ::chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+        use test_case::test_case;
+
+        fn roundtrip<T: TryConvertTerm + AsLiteral>(lex: &str) -> Result<String, Failed> {
+            let dt = Iri::<&str>::from(T::iri());
+            let lit = Literal::<&str>::new_dt(lex, dt);
+            let native: T = lit.try_converted()?;
+            let lit2 = native.as_literal();
+            assert_eq!(lit.datatype(), lit2.datatype());
+            let value = lit2.value().to_string();
+            Ok(value)
+        }
+
+        #[test_case("2020-02-29T12:34:56"       => Ok("2020-02-29T12:34:56".to_string())     ; "simple")]
+        #[test_case("2020-02-29T12:34:56.120"   => Ok("2020-02-29T12:34:56.12".to_string())  ; "fraction")]
+        #[test_case("2020-12-31T24:00:00"       => Ok("2021-01-01T00:00:00".to_string())     ; "end of day")]
+        #[test_case("-0044-03-15T00:00:00"      => Ok("-0044-03-15T00:00:00".to_string())    ; "bce")]
+        #[test_case("2021-02-29T12:34:56"       => Err(Failed::InvLexVal)                    ; "not leap")]
+        #[test_case("2020-02-29T12:34:56Z"      => Err(Failed::InvLexVal)                    ; "timezone")]
+        #[test_case("2020-02-29T12:34:56.0123456789" => Err(Failed::InvLexVal)               ; "too precise")]
+        #[test_case("2020-02-29 12:34:56"       => Err(Failed::InvLexVal)                    ; "space")]
+        fn naive_date_time(lex: &str) -> Result<String, Failed> {
+            roundtrip::<NaiveDateTime>(lex)
+        }
+
+        #[test_case("2020-02-29T12:34:56Z"      => Ok("2020-02-29T12:34:56Z".to_string())      ; "utc")]
+        #[test_case("2020-02-29T12:34:56+00:00" => Ok("2020-02-29T12:34:56Z".to_string())      ; "zero offset")]
+        #[test_case("2020-02-29T12:34:56-05:30" => Ok("2020-02-29T12:34:56-05:30".to_string()) ; "offset")]
+        #[test_case("2020-02-29T12:34:56"       => Err(Failed::InvLexVal)                      ; "no timezone")]
+        #[test_case("2020-02-29T12:34:56+15:00" => Err(Failed::InvLexVal)                      ; "bad timezone")]
+        fn date_time_fixed_offset(lex: &str) -> Result<String, Failed> {
+            roundtrip::<DateTime<FixedOffset>>(lex)
+        }
+
+        #[test_case("2020-02-29T12:34:56Z"      => Ok("2020-02-29T12:34:56Z".to_string()) ; "utc")]
+        #[test_case("2020-02-29T12:34:56-05:30" => Ok("2020-02-29T18:04:56Z".to_string()) ; "offset")]
+        #[test_case("2020-02-29T12:34:56"       => Err(Failed::InvLexVal)                 ; "no timezone")]
+        fn date_time_utc(lex: &str) -> Result<String, Failed> {
+            roundtrip::<DateTime<Utc>>(lex)
+        }
+
+        #[test_case("2020-02-29"  => Ok("2020-02-29".to_string()) ; "simple")]
+        #[test_case("12020-01-01" => Ok("12020-01-01".to_string()) ; "five digit year")]
+        #[test_case("02020-01-01" => Err(Failed::InvLexVal)       ; "leading zero")]
+        #[test_case("2020-13-01"  => Err(Failed::InvLexVal)       ; "bad month")]
+        #[test_case("2020-01-01Z" => Err(Failed::InvLexVal)       ; "timezone")]
+        fn naive_date(lex: &str) -> Result<String, Failed> {
+            roundtrip::<NaiveDate>(lex)
+        }
+
+        #[test_case("12:34:56"     => Ok("12:34:56".to_string())    ; "simple")]
+        #[test_case("00:00:00.500" => Ok("00:00:00.5".to_string())  ; "fraction")]
+        #[test_case("24:00:00"     => Ok("00:00:00".to_string())    ; "end of day")]
+        #[test_case("12:60:00"     => Err(Failed::InvLexVal)        ; "bad minute")]
+        #[test_case("12:34"        => Err(Failed::InvLexVal)        ; "no second")]
+        #[test_case("12:34:56Z"    => Err(Failed::InvLexVal)        ; "timezone")]
+        fn naive_time(lex: &str) -> Result<String, Failed> {
+            roundtrip::<NaiveTime>(lex)
+        }
+
+        #[test_case("PT0S"          => Ok("PT0S".to_string())          ; "zero")]
+        #[test_case("-P1DT1.5S"     => Ok("-P1DT1.5S".to_string())     ; "negative")]
+        #[test_case("PT36H"         => Ok("P1DT12H".to_string())       ; "hours")]
+        #[test_case("P0Y0M1D"       => Ok("P1D".to_string())           ; "zero months")]
+        #[test_case("P1M"           => Err(Failed::InvLexVal)          ; "months")]
+        #[test_case("P1DT"          => Err(Failed::InvLexVal)          ; "empty time")]
+        #[test_case("PT1.0000000001S" => Err(Failed::InvLexVal)        ; "too precise")]
+        fn duration(lex: &str) -> Result<String, Failed> {
+            roundtrip::<Duration>(lex)
+        }
+
+        #[test]
+        fn duration_from_native() {
+            assert_eq!(
+                Duration::milliseconds(-1500).as_literal().value(),
+                "-PT1.5S"
+            );
+            assert_eq!(Duration::zero().as_literal().value().as_ref(), "PT0S");
+        }
+    }
 }
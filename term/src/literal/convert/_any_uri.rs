@@ -0,0 +1,47 @@
+//! Implementation of [`AnyUri`](./struct.AnyUri.html).
+
+use super::super::value::InvalidLexicalForm;
+use sophia_iri::is_valid_iri_ref;
+use std::fmt;
+use std::str::FromStr;
+
+/// A native value of `xsd:anyURI`,
+/// i.e. an absolute or relative [IRI reference](https://tools.ietf.org/html/rfc3987).
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AnyUri(String);
+
+impl AnyUri {
+    /// Borrow this IRI reference as a `&str`.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Convert this IRI reference into a `String`.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl FromStr for AnyUri {
+    type Err = InvalidLexicalForm;
+
+    fn from_str(txt: &str) -> Result<AnyUri, InvalidLexicalForm> {
+        if is_valid_iri_ref(txt) {
+            Ok(AnyUri(txt.to_string()))
+        } else {
+            Err(InvalidLexicalForm)
+        }
+    }
+}
+
+impl AsRef<str> for AnyUri {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for AnyUri {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
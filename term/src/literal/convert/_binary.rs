@@ -0,0 +1,119 @@
+//! Implementation of [`Base64Binary`](./struct.Base64Binary.html)
+//! and [`HexBinary`](./struct.HexBinary.html).
+
+use super::super::value::InvalidLexicalForm;
+use std::fmt;
+use std::str::FromStr;
+
+/// A native value of `xsd:base64Binary`.
+///
+/// Its `Display` implementation produces the canonical lexical form of the value
+/// (without any whitespace).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Base64Binary(pub Vec<u8>);
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64_digit(b: u8) -> Option<u8> {
+    match b {
+        b'A'..=b'Z' => Some(b - b'A'),
+        b'a'..=b'z' => Some(b - b'a' + 26),
+        b'0'..=b'9' => Some(b - b'0' + 52),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None,
+    }
+}
+
+impl FromStr for Base64Binary {
+    type Err = InvalidLexicalForm;
+
+    /// Parse the lexical form of `xsd:base64Binary`,
+    /// which allows a single space after any character but the last one.
+    fn from_str(txt: &str) -> Result<Base64Binary, InvalidLexicalForm> {
+        if txt.starts_with(' ') || txt.ends_with(' ') || txt.contains("  ") {
+            return Err(InvalidLexicalForm);
+        }
+        let chars: Vec<u8> = txt.bytes().filter(|b| *b != b' ').collect();
+        if !chars.len().is_multiple_of(4) {
+            return Err(InvalidLexicalForm);
+        }
+        let padding = chars.iter().rev().take_while(|b| **b == b'=').count();
+        if padding > 2 {
+            return Err(InvalidLexicalForm);
+        }
+        let digits = chars[..chars.len() - padding]
+            .iter()
+            .map(|b| base64_digit(*b))
+            .collect::<Option<Vec<u8>>>()
+            .ok_or(InvalidLexicalForm)?;
+        // the bits of the last digit that do not fit in the decoded bytes must be zero
+        let unused_bits = match padding {
+            1 => 0b11,
+            2 => 0b1111,
+            _ => 0,
+        };
+        if digits.last().is_some_and(|d| d & unused_bits != 0) {
+            return Err(InvalidLexicalForm);
+        }
+        let mut bytes = Vec::with_capacity(digits.len() * 3 / 4);
+        for chunk in digits.chunks(4) {
+            let mut group = [0_u8; 4];
+            group[..chunk.len()].copy_from_slice(chunk);
+            let n = group
+                .iter()
+                .fold(0_u32, |acc, d| (acc << 6) | u32::from(*d));
+            bytes.extend_from_slice(&n.to_be_bytes()[1..chunk.len()]);
+        }
+        Ok(Base64Binary(bytes))
+    }
+}
+
+/// Write the canonical form of `xsd:base64Binary`.
+impl fmt::Display for Base64Binary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for chunk in self.0.chunks(3) {
+            let mut group = [0_u8; 4];
+            group[1..=chunk.len()].copy_from_slice(chunk);
+            let n = u32::from_be_bytes(group);
+            let mut out = [b'='; 4];
+            for (i, c) in out.iter_mut().enumerate().take(chunk.len() + 1) {
+                *c = BASE64[(n >> (18 - 6 * i) & 0x3f) as usize];
+            }
+            f.write_str(std::str::from_utf8(&out).unwrap())?;
+        }
+        Ok(())
+    }
+}
+
+/// A native value of `xsd:hexBinary`.
+///
+/// Its `Display` implementation produces the canonical lexical form of the value
+/// (with upper-case digits).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct HexBinary(pub Vec<u8>);
+
+impl FromStr for HexBinary {
+    type Err = InvalidLexicalForm;
+
+    fn from_str(txt: &str) -> Result<HexBinary, InvalidLexicalForm> {
+        if !txt.len().is_multiple_of(2) || !txt.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(InvalidLexicalForm);
+        }
+        (0..txt.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&txt[i..i + 2], 16).map_err(|_| InvalidLexicalForm))
+            .collect::<Result<Vec<u8>, _>>()
+            .map(HexBinary)
+    }
+}
+
+/// Write the canonical form of `xsd:hexBinary`.
+impl fmt::Display for HexBinary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for b in &self.0 {
+            write!(f, "{:02X}", b)?;
+        }
+        Ok(())
+    }
+}
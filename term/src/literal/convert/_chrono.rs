@@ -0,0 +1,221 @@
+//! Conversions between literals and [chrono](https://docs.rs/chrono) types.
+//!
+//! * `xsd:dateTime` converts to and from `NaiveDateTime` (without timezone),
+//!   as well as `DateTime<FixedOffset>` and `DateTime<Utc>` (with a timezone);
+//! * `xsd:date` converts to and from `NaiveDate` (without timezone);
+//! * `xsd:time` converts to and from `NaiveTime` (without timezone);
+//! * `xsd:duration` converts to and from `chrono::Duration`,
+//!   provided that the duration has no year or month component.
+//!
+//! Values that chrono can not represent
+//! (e.g. years out of chrono's range, or more than 9 fractional digits of seconds)
+//! are rejected as invalid.
+
+use super::super::value::{self, InvalidLexicalForm};
+use super::*;
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc,
+};
+use std::fmt::Write;
+
+type ConvResult<T> = std::result::Result<T, InvalidLexicalForm>;
+
+impl_datatype!(NaiveDateTime, xsd::dateTime);
+impl_datatype!(DateTime<FixedOffset>, xsd::dateTime);
+impl_datatype!(DateTime<Utc>, xsd::dateTime);
+impl_datatype!(NaiveDate, xsd::date);
+impl_datatype!(NaiveTime, xsd::time);
+impl_datatype!(Duration, xsd::duration);
+
+impl AsLiteral for NaiveDateTime {
+    type Term = NativeLiteral<Self>;
+    fn as_literal(&self) -> Self::Term {
+        let mut lex = String::new();
+        write_date(&mut lex, &self.date());
+        lex.push('T');
+        write_time(&mut lex, &self.time());
+        NativeLiteral::new(lex.into_boxed_str())
+    }
+}
+
+impl AsLiteral for DateTime<FixedOffset> {
+    type Term = NativeLiteral<Self>;
+    fn as_literal(&self) -> Self::Term {
+        let mut lex = String::new();
+        write_date(&mut lex, &self.date_naive());
+        lex.push('T');
+        write_time(&mut lex, &self.time());
+        write_tz(&mut lex, self.offset().local_minus_utc() / 60);
+        NativeLiteral::new(lex.into_boxed_str())
+    }
+}
+
+impl AsLiteral for DateTime<Utc> {
+    type Term = NativeLiteral<Self>;
+    fn as_literal(&self) -> Self::Term {
+        let mut lex = String::new();
+        write_date(&mut lex, &self.date_naive());
+        lex.push('T');
+        write_time(&mut lex, &self.time());
+        lex.push('Z');
+        NativeLiteral::new(lex.into_boxed_str())
+    }
+}
+
+impl AsLiteral for NaiveDate {
+    type Term = NativeLiteral<Self>;
+    fn as_literal(&self) -> Self::Term {
+        let mut lex = String::new();
+        write_date(&mut lex, self);
+        NativeLiteral::new(lex.into_boxed_str())
+    }
+}
+
+impl AsLiteral for NaiveTime {
+    type Term = NativeLiteral<Self>;
+    fn as_literal(&self) -> Self::Term {
+        let mut lex = String::new();
+        write_time(&mut lex, self);
+        NativeLiteral::new(lex.into_boxed_str())
+    }
+}
+
+impl AsLiteral for Duration {
+    type Term = NativeLiteral<Self>;
+    fn as_literal(&self) -> Self::Term {
+        let duration = value::Duration::from_seconds(
+            *self < Duration::zero(),
+            self.num_seconds().unsigned_abs(),
+            self.subsec_nanos().unsigned_abs(),
+        );
+        NativeLiteral::new(duration.to_string().into_boxed_str())
+    }
+}
+
+impl TryConvertTerm for NaiveDateTime {
+    type Error = InvalidLexicalForm;
+    fn from_lexical(lex: &str) -> ConvResult<Self> {
+        let dt = value::DateTime::parse_date_time(lex).ok_or(InvalidLexicalForm)?;
+        if dt.timezone().is_some() {
+            return Err(InvalidLexicalForm);
+        }
+        naive_date_time(&dt)
+    }
+}
+
+impl TryConvertTerm for DateTime<FixedOffset> {
+    type Error = InvalidLexicalForm;
+    fn from_lexical(lex: &str) -> ConvResult<Self> {
+        let dt = value::DateTime::parse_date_time(lex).ok_or(InvalidLexicalForm)?;
+        let tz = dt.timezone().ok_or(InvalidLexicalForm)?;
+        let offset = FixedOffset::east_opt(i32::from(tz) * 60).ok_or(InvalidLexicalForm)?;
+        naive_date_time(&dt)?
+            .and_local_timezone(offset)
+            .single()
+            .ok_or(InvalidLexicalForm)
+    }
+}
+
+impl TryConvertTerm for DateTime<Utc> {
+    type Error = InvalidLexicalForm;
+    fn from_lexical(lex: &str) -> ConvResult<Self> {
+        DateTime::<FixedOffset>::from_lexical(lex).map(|dt| dt.with_timezone(&Utc))
+    }
+}
+
+impl TryConvertTerm for NaiveDate {
+    type Error = InvalidLexicalForm;
+    fn from_lexical(lex: &str) -> ConvResult<Self> {
+        let dt = value::DateTime::parse_date(lex).ok_or(InvalidLexicalForm)?;
+        if dt.timezone().is_some() {
+            return Err(InvalidLexicalForm);
+        }
+        naive_date(&dt)
+    }
+}
+
+impl TryConvertTerm for NaiveTime {
+    type Error = InvalidLexicalForm;
+    fn from_lexical(lex: &str) -> ConvResult<Self> {
+        let dt = value::DateTime::parse_time(lex).ok_or(InvalidLexicalForm)?;
+        if dt.timezone().is_some() {
+            return Err(InvalidLexicalForm);
+        }
+        naive_time(&dt)
+    }
+}
+
+impl TryConvertTerm for Duration {
+    type Error = InvalidLexicalForm;
+    fn from_lexical(lex: &str) -> ConvResult<Self> {
+        let duration = value::Duration::parse(lex, true, true).ok_or(InvalidLexicalForm)?;
+        if duration.months() != 0 {
+            return Err(InvalidLexicalForm);
+        }
+        let (seconds, nanos) = duration.seconds_nanos().ok_or(InvalidLexicalForm)?;
+        let seconds = i64::try_from(seconds).map_err(|_| InvalidLexicalForm)?;
+        let abs = Duration::new(seconds, nanos).ok_or(InvalidLexicalForm)?;
+        Ok(if duration.is_negative() { -abs } else { abs })
+    }
+}
+
+fn naive_date(dt: &value::DateTime) -> ConvResult<NaiveDate> {
+    let year = i32::try_from(dt.year()).map_err(|_| InvalidLexicalForm)?;
+    NaiveDate::from_ymd_opt(year, dt.month().into(), dt.day().into()).ok_or(InvalidLexicalForm)
+}
+
+fn naive_time(dt: &value::DateTime) -> ConvResult<NaiveTime> {
+    let (second, nanos) = dt.second_nanos().ok_or(InvalidLexicalForm)?;
+    NaiveTime::from_hms_nano_opt(dt.hour().into(), dt.minute().into(), second.into(), nanos)
+        .ok_or(InvalidLexicalForm)
+}
+
+fn naive_date_time(dt: &value::DateTime) -> ConvResult<NaiveDateTime> {
+    Ok(naive_date(dt)?.and_time(naive_time(dt)?))
+}
+
+/// Write the lexical form of `xsd:date` (without timezone).
+fn write_date(lex: &mut String, date: &NaiveDate) {
+    let year = date.year();
+    if year < 0 {
+        lex.push('-');
+    }
+    write!(
+        lex,
+        "{:04}-{:02}-{:02}",
+        year.unsigned_abs(),
+        date.month(),
+        date.day()
+    )
+    .unwrap();
+}
+
+/// Write the canonical lexical form of `xsd:time` (without timezone).
+///
+/// Leap seconds, which XML Schema does not support, are written as the preceding second.
+fn write_time(lex: &mut String, time: &NaiveTime) {
+    write!(
+        lex,
+        "{:02}:{:02}:{:02}",
+        time.hour(),
+        time.minute(),
+        time.second()
+    )
+    .unwrap();
+    let nanos = time.nanosecond().min(999_999_999);
+    if nanos > 0 {
+        let frac = format!("{:09}", nanos);
+        write!(lex, ".{}", frac.trim_end_matches('0')).unwrap();
+    }
+}
+
+/// Write a timezone offset (in minutes) as `Z` or `(+|-)hh:mm`.
+fn write_tz(lex: &mut String, tz: i32) {
+    if tz == 0 {
+        lex.push('Z');
+    } else {
+        let sign = if tz < 0 { '-' } else { '+' };
+        let tz = tz.unsigned_abs();
+        write!(lex, "{}{:02}:{:02}", sign, tz / 60, tz % 60).unwrap();
+    }
+}
@@ -0,0 +1,388 @@
+//! A registry of datatypes, used to validate and convert literals.
+//!
+//! A [`DatatypeRegistry`] maps datatype IRIs to *validators*,
+//! checking that a lexical value is valid for that datatype,
+//! and optionally to *converters*, producing a native value from a lexical value.
+//! It can be extended with domain-specific datatypes
+//! (e.g. `geo:wktLiteral`, or units of measure),
+//! either with a simple validation function ([`register`]),
+//! or with a native type implementing [`TryConvertTerm`] ([`register_native`]).
+//!
+//! Literals whose datatype is not registered are always considered valid.
+//!
+//! # Example
+//! ```
+//! # use sophia_term::literal::registry::DatatypeRegistry;
+//! # use sophia_term::literal::value::InvalidLexicalForm;
+//! # use sophia_term::{Term, RefTerm};
+//! # use sophia_api::ns::xsd;
+//! let mut registry = DatatypeRegistry::xsd();
+//! registry.register("http://www.opengis.net/ont/geosparql#wktLiteral", |lex: &str| {
+//!     if lex.trim_start().starts_with("POINT") {
+//!         Ok(())
+//!     } else {
+//!         Err(InvalidLexicalForm)
+//!     }
+//! });
+//!
+//! let wkt = RefTerm::new_iri("http://www.opengis.net/ont/geosparql#wktLiteral")?;
+//! assert!(registry.validate(&RefTerm::new_literal_dt("POINT(1 2)", wkt.clone())?).is_ok());
+//! assert!(registry.validate(&RefTerm::new_literal_dt("LINE(1 2)", wkt)?).is_err());
+//! assert!(registry.validate(&RefTerm::new_literal_dt("42", xsd::integer)?).is_ok());
+//! assert!(registry.validate(&RefTerm::new_literal_dt("4.2", xsd::integer)?).is_err());
+//! # Ok::<(), sophia_term::TermError>(())
+//! ```
+//!
+//! [`DatatypeRegistry`]: struct.DatatypeRegistry.html
+//! [`register`]: struct.DatatypeRegistry.html#method.register
+//! [`register_native`]: struct.DatatypeRegistry.html#method.register_native
+//! [`TryConvertTerm`]: ../convert/trait.TryConvertTerm.html
+
+use super::convert::{AnyUri, Base64Binary, HexBinary, TryConvertTerm};
+use super::value::{Decimal, Integer, Value};
+use super::*;
+use sophia_api::graph::Graph;
+use sophia_api::triple::Triple;
+use std::any::Any;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+type Validator = Box<dyn Fn(&str) -> std::result::Result<(), Box<dyn Error>> + Send + Sync>;
+type Converter =
+    Box<dyn Fn(&str) -> std::result::Result<Box<dyn Any>, Box<dyn Error>> + Send + Sync>;
+
+/// A registry mapping datatype IRIs to validators and converters.
+///
+/// See the [module documentation](index.html) for more details.
+#[derive(Default)]
+pub struct DatatypeRegistry {
+    validators: HashMap<String, Validator>,
+    converters: HashMap<String, Converter>,
+}
+
+impl DatatypeRegistry {
+    /// Build an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a registry supporting the XML Schema datatypes handled by this crate,
+    /// i.e. those supported by the [`value`](../value/index.html) module,
+    /// as well as `xsd:string`, `xsd:anyURI`, `xsd:base64Binary` and `xsd:hexBinary`.
+    pub fn xsd() -> Self {
+        let mut registry = Self::new();
+        registry.register_native::<i8>();
+        registry.register_native::<i16>();
+        registry.register_native::<i32>();
+        registry.register_native::<i64>();
+        registry.register_native::<u8>();
+        registry.register_native::<u16>();
+        registry.register_native::<u32>();
+        registry.register_native::<u64>();
+        registry.register_native::<f32>();
+        registry.register_native::<f64>();
+        registry.register_native::<bool>();
+        registry.register_native::<String>();
+        registry.register_native::<Integer>();
+        registry.register_native::<Decimal>();
+        registry.register_native::<AnyUri>();
+        registry.register_native::<Base64Binary>();
+        registry.register_native::<HexBinary>();
+        // the lexical rules of the value module are stricter than Rust's FromStr
+        for local in XSD_VALUES {
+            let iri = format!("{}{}", xsd::PREFIX, local);
+            let dt = iri.clone();
+            registry.validators.insert(
+                iri,
+                Box::new(move |lex| {
+                    let dt = Iri::<&str>::new_unchecked(dt.as_str());
+                    match Value::try_from_term(&Literal::<&str>::new_dt(lex, dt)) {
+                        Ok(_) => Ok(()),
+                        Err(TermError::InvalidLexicalValue { source, .. }) => Err(source),
+                        Err(err) => Err(Box::new(err)),
+                    }
+                }),
+            );
+        }
+        registry
+    }
+
+    /// Register a validator for the given datatype,
+    /// replacing any validator previously registered for it.
+    pub fn register<F, E>(&mut self, datatype: &str, validator: F)
+    where
+        F: Fn(&str) -> std::result::Result<(), E> + Send + Sync + 'static,
+        E: Error + 'static,
+    {
+        self.validators.insert(
+            datatype.to_string(),
+            Box::new(move |lex| validator(lex).map_err(|err| Box::new(err) as Box<dyn Error>)),
+        );
+    }
+
+    /// Register the native type `T` as a validator and a converter
+    /// for its datatype (see [`DataType::iri`](../convert/trait.DataType.html#tymethod.iri)),
+    /// replacing any validator or converter previously registered for it.
+    pub fn register_native<T>(&mut self)
+    where
+        T: TryConvertTerm + 'static,
+    {
+        let datatype = T::iri().value().to_string();
+        self.validators.insert(
+            datatype.clone(),
+            Box::new(|lex| {
+                T::from_lexical(lex)
+                    .map(|_| ())
+                    .map_err(|err| Box::new(err) as Box<dyn Error>)
+            }),
+        );
+        self.converters.insert(
+            datatype,
+            Box::new(|lex| {
+                T::from_lexical(lex)
+                    .map(|val| Box::new(val) as Box<dyn Any>)
+                    .map_err(|err| Box::new(err) as Box<dyn Error>)
+            }),
+        );
+    }
+
+    /// Whether a validator is registered for the given datatype.
+    pub fn contains(&self, datatype: &str) -> bool {
+        self.validators.contains_key(datatype)
+    }
+
+    /// Check that `term` is not an ill-typed literal.
+    ///
+    /// Terms that are not literals are always valid, except for quoted triples,
+    /// which are valid if all their constituents are.
+    ///
+    /// # Error
+    ///
+    /// A [`TermError::InvalidLexicalValue`](../../enum.TermError.html#variant.InvalidLexicalValue)
+    /// is raised for the first ill-typed literal found in `term`.
+    pub fn validate<T>(&self, term: &T) -> Result<()>
+    where
+        T: TTerm + ?Sized,
+    {
+        let mut errors = vec![];
+        self.check(term.as_dyn(), &mut errors);
+        match errors.into_iter().next() {
+            None => Ok(()),
+            Some(err) => Err(err),
+        }
+    }
+
+    /// Convert `term` to a native value,
+    /// using the converter registered for its datatype.
+    ///
+    /// # Error
+    ///
+    /// An error is raised if `term` is not a literal,
+    /// if no converter registered for its datatype produces values of type `U`,
+    /// or if its lexical value is not valid for its datatype.
+    pub fn convert<U, T>(&self, term: &T) -> Result<U>
+    where
+        U: 'static,
+        T: TTerm + ?Sized,
+    {
+        let dt = match term.datatype() {
+            None => return Err(TermError::UnsupportedKind(term_to_string(term))),
+            Some(dt) => dt,
+        };
+        let unsupported = || TermError::UnsupportedDatatype(term_to_string(term));
+        let converter = self
+            .converters
+            .get(dt.value().as_ref())
+            .ok_or_else(unsupported)?;
+        let lex = term.value_raw().0;
+        let value = converter(lex).map_err(|source| TermError::InvalidLexicalValue {
+            lex: lex.to_string(),
+            dt: term_to_string(&dt),
+            source,
+        })?;
+        value.downcast().map(|b| *b).map_err(|_| unsupported())
+    }
+
+    /// Report every ill-typed literal contained in `graph`
+    /// (including in quoted triples),
+    /// as a [`TermError::InvalidLexicalValue`](../../enum.TermError.html#variant.InvalidLexicalValue).
+    ///
+    /// Literals occurring several times are reported for each occurrence.
+    pub fn ill_typed_literals<G>(&self, graph: &G) -> std::result::Result<Vec<TermError>, G::Error>
+    where
+        G: Graph + ?Sized,
+    {
+        let mut errors = vec![];
+        for t in graph.triples() {
+            let t = t?;
+            self.check(t.s().as_dyn(), &mut errors);
+            self.check(t.p().as_dyn(), &mut errors);
+            self.check(t.o().as_dyn(), &mut errors);
+        }
+        Ok(errors)
+    }
+
+    fn check(&self, term: &dyn TTerm, errors: &mut Vec<TermError>) {
+        if let Some(spo) = term.triple() {
+            for t in spo.iter() {
+                self.check(*t, errors);
+            }
+            return;
+        }
+        let dt = match term.datatype() {
+            None => return,
+            Some(dt) => dt,
+        };
+        let iri = dt.value();
+        if let Some(validator) = self.validators.get(iri.as_ref()) {
+            let lex = term.value_raw().0;
+            if let Err(source) = validator(lex) {
+                errors.push(TermError::InvalidLexicalValue {
+                    lex: lex.to_string(),
+                    dt: term_to_string(&dt),
+                    source,
+                });
+            }
+        }
+    }
+}
+
+impl fmt::Debug for DatatypeRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut datatypes: Vec<_> = self.validators.keys().collect();
+        datatypes.sort();
+        f.debug_struct("DatatypeRegistry")
+            .field("datatypes", &datatypes)
+            .finish()
+    }
+}
+
+/// The local names of the datatypes supported by [`Value`](../value/enum.Value.html).
+const XSD_VALUES: &[&str] = &[
+    "decimal",
+    "integer",
+    "nonPositiveInteger",
+    "negativeInteger",
+    "nonNegativeInteger",
+    "positiveInteger",
+    "long",
+    "int",
+    "short",
+    "byte",
+    "unsignedLong",
+    "unsignedInt",
+    "unsignedShort",
+    "unsignedByte",
+    "float",
+    "double",
+    "boolean",
+    "dateTime",
+    "dateTimeStamp",
+    "date",
+    "time",
+    "duration",
+    "yearMonthDuration",
+    "dayTimeDuration",
+];
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::literal::value::InvalidLexicalForm;
+    use crate::RefTerm;
+    use sophia_api::ns::rdf;
+
+    fn lit<'a>(lex: &'a str, dt: SimpleIri<'a>) -> RefTerm<'a> {
+        RefTerm::new_literal_dt(lex, dt).unwrap()
+    }
+
+    #[test]
+    fn xsd() {
+        let reg = DatatypeRegistry::xsd();
+        assert!(reg.validate(&lit("+42", xsd::byte)).is_ok());
+        assert!(reg.validate(&lit("420", xsd::byte)).is_err());
+        assert!(reg.validate(&lit("INF", xsd::double)).is_ok());
+        assert!(reg.validate(&lit("inf", xsd::double)).is_err());
+        assert!(reg.validate(&lit("2020-02-29", xsd::date)).is_ok());
+        assert!(reg.validate(&lit("2021-02-29", xsd::date)).is_err());
+        assert!(reg.validate(&lit("AA==", xsd::base64Binary)).is_ok());
+        assert!(reg.validate(&lit("AB==", xsd::base64Binary)).is_err());
+        assert!(reg.validate(&lit("anything", xsd::string)).is_ok());
+        // unregistered datatypes are always valid
+        assert!(reg.validate(&lit("whatever", xsd::NOTATION)).is_ok());
+        // non-literals are always valid
+        assert!(reg.validate(&rdf::type_).is_ok());
+    }
+
+    #[test]
+    fn custom() {
+        let mut reg = DatatypeRegistry::new();
+        assert!(!reg.contains("tag:even"));
+        reg.register("tag:even", |lex: &str| match lex.parse::<u32>() {
+            Ok(n) if n % 2 == 0 => Ok(()),
+            Ok(_) => Err(InvalidLexicalForm),
+            Err(_) => Err(InvalidLexicalForm),
+        });
+        assert!(reg.contains("tag:even"));
+        let even = RefTerm::new_iri("tag:even").unwrap();
+        assert!(reg
+            .validate(&RefTerm::new_literal_dt("42", even.clone()).unwrap())
+            .is_ok());
+        match reg.validate(&RefTerm::new_literal_dt("43", even.clone()).unwrap()) {
+            Err(TermError::InvalidLexicalValue { lex, dt, .. }) => {
+                assert_eq!(lex, "43");
+                assert_eq!(dt, "<tag:even>");
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn convert() {
+        let reg = DatatypeRegistry::xsd();
+        let x: i32 = reg.convert(&lit("42", xsd::int)).unwrap();
+        assert_eq!(x, 42);
+        let x: Integer = reg.convert(&lit("0042", xsd::integer)).unwrap();
+        assert_eq!(x.to_string(), "42");
+        assert!(reg.convert::<i32, _>(&lit("x", xsd::int)).is_err());
+        // wrong native type
+        assert!(reg.convert::<i64, _>(&lit("42", xsd::int)).is_err());
+        // no converter
+        assert!(reg.convert::<i32, _>(&lit("42", xsd::NOTATION)).is_err());
+        assert!(reg.convert::<i32, _>(&rdf::type_).is_err());
+    }
+
+    #[test]
+    fn quoted_triple() {
+        let reg = DatatypeRegistry::xsd();
+        let ok = RefTerm::new_triple(rdf::type_.into(), rdf::type_.into(), lit("1", xsd::int));
+        assert!(reg.validate(&ok).is_ok());
+        let ko = RefTerm::new_triple(rdf::type_.into(), rdf::type_.into(), lit("x", xsd::int));
+        assert!(reg.validate(&ko).is_err());
+    }
+
+    #[test]
+    fn graph() {
+        let reg = DatatypeRegistry::xsd();
+        let g = vec![
+            [
+                RefTerm::from(rdf::type_),
+                rdf::type_.into(),
+                lit("1", xsd::int),
+            ],
+            [
+                RefTerm::from(rdf::type_),
+                rdf::type_.into(),
+                lit("x", xsd::int),
+            ],
+            [
+                lit("y", xsd::boolean),
+                rdf::type_.into(),
+                RefTerm::new_triple(rdf::type_.into(), rdf::type_.into(), lit("z", xsd::date)),
+            ],
+        ];
+        let errors = reg.ill_typed_literals(&g).unwrap();
+        assert_eq!(errors.len(), 3);
+    }
+}
@@ -0,0 +1,1086 @@
+//! Value-space comparison and canonicalization of literals.
+//!
+//! The equality and ordering of terms ([`term_eq`] and [`term_cmp`]) are purely lexical,
+//! so for example `"01"^^xsd:int` and `"1"^^xsd:int` are considered different.
+//! This module provides an opt-in layer comparing literals by their *value*:
+//! [`value_eq`] and [`value_cmp`] compare literals according to the value space of their datatype,
+//! and [`Literal::canonicalize`] rewrites a literal to the canonical lexical form of its datatype,
+//! as defined by [XML Schema 1.1](https://www.w3.org/TR/xmlschema11-2/).
+//!
+//! The following datatypes are supported:
+//! * `xsd:decimal` and all its derived types (`xsd:integer`, `xsd:int`, `xsd:unsignedByte`...),
+//!   as well as `xsd:float` and `xsd:double`, which are all comparable with each other;
+//! * `xsd:boolean`;
+//! * `xsd:dateTime`, `xsd:dateTimeStamp`, `xsd:date` and `xsd:time`;
+//! * `xsd:duration`, `xsd:dayTimeDuration` and `xsd:yearMonthDuration`.
+//!
+//! Literals with any other datatype are compared lexically.
+//!
+//! [`term_eq`]: https://docs.rs/sophia_api/latest/sophia_api/term/fn.term_eq.html
+//! [`term_cmp`]: https://docs.rs/sophia_api/latest/sophia_api/term/fn.term_cmp.html
+//! [`value_eq`]: fn.value_eq.html
+//! [`value_cmp`]: fn.value_cmp.html
+//! [`Literal::canonicalize`]: ../struct.Literal.html#method.canonicalize
+
+use super::*;
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+/// Compare two terms by value.
+///
+/// If both terms are literals with a [supported](index.html) datatype,
+/// their values are compared;
+/// otherwise, this is equivalent to [`term_eq`](https://docs.rs/sophia_api/latest/sophia_api/term/fn.term_eq.html).
+///
+/// NB: ill-typed literals are never equal to any well-typed literal.
+pub fn value_eq<T1, T2>(t1: &T1, t2: &T2) -> bool
+where
+    T1: TTerm + ?Sized,
+    T2: TTerm + ?Sized,
+{
+    match (Value::try_from_term(t1), Value::try_from_term(t2)) {
+        (Ok(v1), Ok(v2)) => v1 == v2,
+        _ => term_eq(t1, t2),
+    }
+}
+
+/// Order two terms by value, if they are comparable.
+///
+/// * Literals with a [supported](index.html) datatype are ordered by value,
+///   provided that their datatypes are compatible
+///   (e.g. numbers can be compared with each other, but not with dates).
+/// * `xsd:string` literals are ordered by their lexical value.
+/// * Any other terms are only comparable if they are equal
+///   according to [`term_eq`](https://docs.rs/sophia_api/latest/sophia_api/term/fn.term_eq.html).
+///
+/// Note that some values are only partially ordered;
+/// for example, a `xsd:dateTime` without timezone can not always be compared
+/// with a `xsd:dateTime` with a timezone.
+pub fn value_cmp<T1, T2>(t1: &T1, t2: &T2) -> Option<Ordering>
+where
+    T1: TTerm + ?Sized,
+    T2: TTerm + ?Sized,
+{
+    match (Value::try_from_term(t1), Value::try_from_term(t2)) {
+        (Ok(v1), Ok(v2)) => v1.partial_cmp(&v2),
+        _ => {
+            let is_string = |t: &dyn TTerm| t.datatype().is_some_and(|dt| dt == xsd::string);
+            if is_string(t1.as_dyn()) && is_string(t2.as_dyn()) {
+                Some(t1.value_raw().0.cmp(t2.value_raw().0))
+            } else if term_eq(t1, t2) {
+                Some(Ordering::Equal)
+            } else {
+                None
+            }
+        }
+    }
+}
+
+impl<TD> Literal<TD>
+where
+    TD: TermData,
+{
+    /// Return a literal equivalent to this one,
+    /// with the canonical lexical form of its datatype.
+    ///
+    /// Literals whose datatype is not [supported](value/index.html) are returned unchanged.
+    ///
+    /// # Error
+    ///
+    /// If the lexical value of this literal is not valid for its datatype,
+    /// an error is raised.
+    ///
+    /// # Performances
+    /// The returned literal will borrow data from this one as much as possible,
+    /// so no allocation occurs if this literal is already canonical.
+    pub fn canonicalize(&self) -> Result<Literal<MownStr<'_>>> {
+        let mut lit = self.clone_map(MownStr::from);
+        match Value::try_from_term(self) {
+            Ok(value) => {
+                let mut canonical = value.to_string();
+                if canonical == "PT0S" && self.dt() == xsd::yearMonthDuration {
+                    canonical = "P0M".to_string();
+                }
+                if canonical != self.txt.as_ref() {
+                    lit.txt = MownStr::from(canonical);
+                }
+                Ok(lit)
+            }
+            Err(TermError::UnsupportedDatatype(_)) => Ok(lit),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// The value of a literal whose datatype is [supported](index.html).
+///
+/// Its `Display` implementation produces the canonical lexical form of the value.
+#[derive(Clone, Debug)]
+pub enum Value {
+    /// The value of `xsd:decimal` and all its derived types.
+    Decimal(Decimal),
+    /// The value of `xsd:float`.
+    Float(f32),
+    /// The value of `xsd:double`.
+    Double(f64),
+    /// The value of `xsd:boolean`.
+    Boolean(bool),
+    /// The value of `xsd:dateTime` and `xsd:dateTimeStamp`.
+    DateTime(DateTime),
+    /// The value of `xsd:date`.
+    Date(DateTime),
+    /// The value of `xsd:time`.
+    Time(DateTime),
+    /// The value of `xsd:duration`, `xsd:dayTimeDuration` and `xsd:yearMonthDuration`.
+    Duration(Duration),
+}
+
+impl Value {
+    /// Compute the value of the given term.
+    ///
+    /// # Error
+    ///
+    /// An error is raised if `term` is not a literal,
+    /// if its datatype is not [supported](index.html),
+    /// or if its lexical value is not valid for its datatype.
+    pub fn try_from_term<T>(term: &T) -> Result<Value>
+    where
+        T: TTerm + ?Sized,
+    {
+        let dt = match term.datatype() {
+            None => return Err(TermError::UnsupportedKind(term_to_string(term))),
+            Some(dt) => dt,
+        };
+        let unsupported = || TermError::UnsupportedDatatype(term_to_string(term));
+        let dt_value = dt.value();
+        let local = dt_value.strip_prefix(xsd::PREFIX).ok_or_else(unsupported)?;
+        let lex = term.value_raw().0;
+        let value = match local {
+            "decimal" => Decimal::parse(lex, true).map(Value::Decimal),
+            "float" => parse_float(lex).map(Value::Float),
+            "double" => parse_float(lex).map(Value::Double),
+            "boolean" => match lex {
+                "true" | "1" => Some(Value::Boolean(true)),
+                "false" | "0" => Some(Value::Boolean(false)),
+                _ => None,
+            },
+            "dateTime" => DateTime::parse_date_time(lex).map(Value::DateTime),
+            "dateTimeStamp" => DateTime::parse_date_time(lex)
+                .filter(|dt| dt.tz.is_some())
+                .map(Value::DateTime),
+            "date" => DateTime::parse_date(lex).map(Value::Date),
+            "time" => DateTime::parse_time(lex).map(Value::Time),
+            "duration" => Duration::parse(lex, true, true).map(Value::Duration),
+            "yearMonthDuration" => Duration::parse(lex, true, false).map(Value::Duration),
+            "dayTimeDuration" => Duration::parse(lex, false, true).map(Value::Duration),
+            _ => match integer_bounds(local) {
+                Some((min, max)) => Decimal::parse(lex, false)
+                    .filter(|d| {
+                        let i = d.to_i128();
+                        min.is_none_or(|min| i.is_some_and(|i| i >= min))
+                            && max.is_none_or(|max| i.is_some_and(|i| i <= max))
+                    })
+                    .map(Value::Decimal),
+                None => return Err(unsupported()),
+            },
+        };
+        value.ok_or_else(|| TermError::InvalidLexicalValue {
+            lex: lex.to_string(),
+            dt: term_to_string(&dt),
+            source: Box::new(InvalidLexicalForm),
+        })
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Decimal(d) => Some(d.to_f64()),
+            Value::Float(f) => Some(f64::from(*f)),
+            Value::Double(f) => Some(*f),
+            _ => None,
+        }
+    }
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Value) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Value) -> Option<Ordering> {
+        use Value::*;
+        match (self, other) {
+            (Decimal(d1), Decimal(d2)) => Some(d1.cmp(d2)),
+            (Float(f1), Float(f2)) => f1.partial_cmp(f2),
+            (Boolean(b1), Boolean(b2)) => Some(b1.cmp(b2)),
+            (DateTime(d1), DateTime(d2)) | (Date(d1), Date(d2)) | (Time(d1), Time(d2)) => {
+                d1.partial_cmp(d2)
+            }
+            (Duration(d1), Duration(d2)) => d1.partial_cmp(d2),
+            _ => match (self.as_f64(), other.as_f64()) {
+                (Some(f1), Some(f2)) => f1.partial_cmp(&f2),
+                _ => None,
+            },
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Decimal(d) => write!(f, "{}", d),
+            Value::Float(x) => fmt_float(f, *x, x.is_nan(), x.is_infinite()),
+            Value::Double(x) => fmt_float(f, *x, x.is_nan(), x.is_infinite()),
+            Value::Boolean(b) => write!(f, "{}", b),
+            Value::DateTime(dt) => {
+                dt.fmt_date(f)?;
+                f.write_str("T")?;
+                dt.fmt_time(f)?;
+                dt.fmt_tz(f)
+            }
+            Value::Date(dt) => {
+                dt.fmt_date(f)?;
+                dt.fmt_tz(f)
+            }
+            Value::Time(dt) => {
+                dt.fmt_time(f)?;
+                dt.fmt_tz(f)
+            }
+            Value::Duration(d) => write!(f, "{}", d),
+        }
+    }
+}
+
+/// The error raised when a lexical value is not valid for its datatype.
+#[derive(Debug, thiserror::Error)]
+#[error("invalid lexical form")]
+pub struct InvalidLexicalForm;
+
+/// An arbitrary precision decimal number.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Decimal {
+    negative: bool,
+    /// digits of the integer part, without leading zeros
+    int: String,
+    /// digits of the fractional part, without trailing zeros
+    frac: String,
+}
+
+impl Decimal {
+    /// Parse the lexical form of `xsd:decimal`, or of `xsd:integer` if `!point`.
+    fn parse(txt: &str, point: bool) -> Option<Decimal> {
+        let (negative, unsigned) = match txt.as_bytes().first() {
+            Some(b'-') => (true, &txt[1..]),
+            Some(b'+') => (false, &txt[1..]),
+            _ => (false, txt),
+        };
+        let (int, frac) = match unsigned.find('.') {
+            Some(i) if point => (&unsigned[..i], &unsigned[i + 1..]),
+            Some(_) => return None,
+            None => (unsigned, ""),
+        };
+        if int.is_empty() && frac.is_empty()
+            || !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit())
+        {
+            return None;
+        }
+        let int = int.trim_start_matches('0').to_string();
+        let frac = frac.trim_end_matches('0').to_string();
+        let negative = negative && !(int.is_empty() && frac.is_empty());
+        Some(Decimal {
+            negative,
+            int,
+            frac,
+        })
+    }
+
+    /// Whether this number has no fractional part.
+    pub fn is_integer(&self) -> bool {
+        self.frac.is_empty()
+    }
+
+    /// Whether this number is strictly negative.
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// Convert this number to an `i128`, if it is an integer in the range of `i128`.
+    pub fn to_i128(&self) -> Option<i128> {
+        if !self.is_integer() {
+            return None;
+        }
+        if self.int.is_empty() {
+            return Some(0);
+        }
+        let abs: u128 = self.int.parse().ok()?;
+        if self.negative {
+            0_i128.checked_sub_unsigned(abs)
+        } else {
+            i128::try_from(abs).ok()
+        }
+    }
+
+    /// Convert this number to the nearest `f64`.
+    pub fn to_f64(&self) -> f64 {
+        self.to_string().parse().unwrap()
+    }
+
+    fn cmp_abs(&self, other: &Decimal) -> Ordering {
+        self.int
+            .len()
+            .cmp(&other.int.len())
+            .then_with(|| self.int.cmp(&other.int))
+            .then_with(|| self.frac.cmp(&other.frac))
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = InvalidLexicalForm;
+
+    fn from_str(txt: &str) -> std::result::Result<Decimal, InvalidLexicalForm> {
+        Decimal::parse(txt, true).ok_or(InvalidLexicalForm)
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Decimal) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => self.cmp_abs(other),
+            (true, true) => other.cmp_abs(self),
+        }
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Decimal) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Write the canonical form of `xsd:decimal` (without a decimal point for integers).
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.negative {
+            f.write_str("-")?;
+        }
+        f.write_str(if self.int.is_empty() { "0" } else { &self.int })?;
+        if !self.frac.is_empty() {
+            write!(f, ".{}", self.frac)?;
+        }
+        Ok(())
+    }
+}
+
+/// An arbitrary precision integer number (`xsd:integer`).
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Integer(Decimal);
+
+impl Integer {
+    /// Whether this number is strictly negative.
+    pub fn is_negative(&self) -> bool {
+        self.0.is_negative()
+    }
+
+    /// Convert this number to an `i128`, if it is in the range of `i128`.
+    pub fn to_i128(&self) -> Option<i128> {
+        self.0.to_i128()
+    }
+
+    /// Convert this number to the nearest `f64`.
+    pub fn to_f64(&self) -> f64 {
+        self.0.to_f64()
+    }
+}
+
+impl FromStr for Integer {
+    type Err = InvalidLexicalForm;
+
+    fn from_str(txt: &str) -> std::result::Result<Integer, InvalidLexicalForm> {
+        Decimal::parse(txt, false)
+            .map(Integer)
+            .ok_or(InvalidLexicalForm)
+    }
+}
+
+impl From<i128> for Integer {
+    fn from(other: i128) -> Integer {
+        Integer(Decimal {
+            negative: other < 0,
+            int: match other {
+                0 => String::new(),
+                _ => other.unsigned_abs().to_string(),
+            },
+            frac: String::new(),
+        })
+    }
+}
+
+impl From<i64> for Integer {
+    fn from(other: i64) -> Integer {
+        Integer::from(i128::from(other))
+    }
+}
+
+impl From<u64> for Integer {
+    fn from(other: u64) -> Integer {
+        Integer::from(i128::from(other))
+    }
+}
+
+impl From<Integer> for Decimal {
+    fn from(other: Integer) -> Decimal {
+        other.0
+    }
+}
+
+/// Write the canonical form of `xsd:integer`.
+impl fmt::Display for Integer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A value of `xsd:dateTime`, `xsd:date` or `xsd:time`.
+///
+/// Dates have their time set to 00:00:00,
+/// and times have their date set to 1972-12-31
+/// (the reference date used by XML Schema).
+#[derive(Clone, Debug)]
+pub struct DateTime {
+    year: i64,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+    /// digits of the fractional part of the seconds, without trailing zeros
+    frac: String,
+    /// timezone offset, in minutes
+    tz: Option<i16>,
+}
+
+impl DateTime {
+    /// The year (0 is 1 BCE, -1 is 2 BCE, etc.).
+    pub fn year(&self) -> i64 {
+        self.year
+    }
+
+    /// The month, from 1 to 12.
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    /// The day of the month, from 1 to 31.
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    /// The hour, from 0 to 23.
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    /// The minute, from 0 to 59.
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    /// The seconds, from 0 to 59 (with their fractional part).
+    pub fn second(&self) -> Decimal {
+        Decimal {
+            negative: false,
+            int: if self.second == 0 {
+                String::new()
+            } else {
+                self.second.to_string()
+            },
+            frac: self.frac.clone(),
+        }
+    }
+
+    /// The timezone offset in minutes, if any.
+    pub fn timezone(&self) -> Option<i16> {
+        self.tz
+    }
+
+    /// The whole seconds and their fractional part in nanoseconds,
+    /// or `None` if the latter can not be represented with that precision.
+    #[cfg(feature = "chrono")]
+    pub(crate) fn second_nanos(&self) -> Option<(u8, u32)> {
+        Some((self.second, frac_nanos(&self.frac)?))
+    }
+
+    pub(crate) fn parse_date_time(txt: &str) -> Option<DateTime> {
+        let i = txt.find('T')?;
+        let mut dt = Self::parse_date_part(&txt[..i])?;
+        let (time, tz) = split_tz(&txt[i + 1..])?;
+        let end_of_day = dt.parse_time_part(time)?;
+        dt.tz = tz;
+        if end_of_day {
+            dt.next_day();
+        }
+        Some(dt)
+    }
+
+    pub(crate) fn parse_date(txt: &str) -> Option<DateTime> {
+        let (date, tz) = split_tz(txt)?;
+        let mut dt = Self::parse_date_part(date)?;
+        dt.tz = tz;
+        Some(dt)
+    }
+
+    pub(crate) fn parse_time(txt: &str) -> Option<DateTime> {
+        let (time, tz) = split_tz(txt)?;
+        let mut dt = Self::parse_date_part("1972-12-31")?;
+        dt.parse_time_part(time)?;
+        dt.tz = tz;
+        Some(dt)
+    }
+
+    /// Parse `-?YYYY-MM-DD`.
+    fn parse_date_part(txt: &str) -> Option<DateTime> {
+        let unsigned = txt.strip_prefix('-').unwrap_or(txt);
+        let i = unsigned.find('-')?;
+        let year_txt = &unsigned[..i];
+        if year_txt.len() < 4 || (year_txt.len() > 4 && year_txt.starts_with('0')) {
+            return None;
+        }
+        let mut year: i64 = parse_digits(year_txt)?;
+        if unsigned.len() != txt.len() {
+            year = -year;
+        }
+        let rest = &unsigned[i..];
+        let month = two_digits(rest.strip_prefix('-')?)?;
+        let day = two_digits(rest.get(3..)?.strip_prefix('-')?)?;
+        if rest.len() != 6 || !(1..=12).contains(&month) || day < 1 || day > days_in(year, month) {
+            return None;
+        }
+        Some(DateTime {
+            year,
+            month,
+            day,
+            hour: 0,
+            minute: 0,
+            second: 0,
+            frac: String::new(),
+            tz: None,
+        })
+    }
+
+    /// Parse `hh:mm:ss(.s+)?` into `self`.
+    ///
+    /// Return whether the time is 24:00:00, which is normalized to 00:00:00.
+    fn parse_time_part(&mut self, txt: &str) -> Option<bool> {
+        let (hms, frac) = match txt.find('.') {
+            Some(i) => (&txt[..i], &txt[i + 1..]),
+            None => (txt, ""),
+        };
+        if hms.len() != 8 || &hms[2..3] != ":" || &hms[5..6] != ":" {
+            return None;
+        }
+        if txt.len() != hms.len() && (frac.is_empty() || !frac.bytes().all(|b| b.is_ascii_digit()))
+        {
+            return None;
+        }
+        self.hour = two_digits(hms)?;
+        self.minute = two_digits(&hms[3..])?;
+        self.second = two_digits(&hms[6..])?;
+        self.frac = frac.trim_end_matches('0').to_string();
+        if self.hour == 24 && self.minute == 0 && self.second == 0 && self.frac.is_empty() {
+            self.hour = 0;
+            return Some(true);
+        }
+        if self.hour > 23 || self.minute > 59 || self.second > 59 {
+            return None;
+        }
+        Some(false)
+    }
+
+    fn next_day(&mut self) {
+        if self.day < days_in(self.year, self.month) {
+            self.day += 1;
+        } else if self.month < 12 {
+            self.day = 1;
+            self.month += 1;
+        } else {
+            self.day = 1;
+            self.month = 1;
+            self.year += 1;
+        }
+    }
+
+    /// The position of this instant on the timeline, in seconds, and the fractional seconds.
+    fn timeline(&self) -> (i128, &str) {
+        let days = days_from_civil(self.year, self.month, self.day) as i128;
+        let secs = days * 86400
+            + self.hour as i128 * 3600
+            + self.minute as i128 * 60
+            + self.second as i128
+            - self.tz.unwrap_or(0) as i128 * 60;
+        (secs, &self.frac)
+    }
+
+    fn fmt_date(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.year < 0 {
+            f.write_str("-")?;
+        }
+        write!(
+            f,
+            "{:04}-{:02}-{:02}",
+            self.year.unsigned_abs(),
+            self.month,
+            self.day
+        )
+    }
+
+    fn fmt_time(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:02}:{:02}:{:02}", self.hour, self.minute, self.second)?;
+        if !self.frac.is_empty() {
+            write!(f, ".{}", self.frac)?;
+        }
+        Ok(())
+    }
+
+    fn fmt_tz(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.tz {
+            None => Ok(()),
+            Some(0) => f.write_str("Z"),
+            Some(tz) => {
+                let sign = if tz < 0 { '-' } else { '+' };
+                let tz = tz.unsigned_abs();
+                write!(f, "{}{:02}:{:02}", sign, tz / 60, tz % 60)
+            }
+        }
+    }
+}
+
+/// Instants are compared on the timeline.
+///
+/// As specified by XML Schema, an instant without a timezone is compared
+/// to an instant with a timezone by considering all possible timezones
+/// (from -14:00 to +14:00), so they are only comparable if they are far enough apart.
+impl PartialOrd for DateTime {
+    fn partial_cmp(&self, other: &DateTime) -> Option<Ordering> {
+        match (self.tz.is_some(), other.tz.is_some()) {
+            (true, false) => cmp_zoned_floating(self, other),
+            (false, true) => cmp_zoned_floating(other, self).map(Ordering::reverse),
+            _ => Some(self.timeline().cmp(&other.timeline())),
+        }
+    }
+}
+
+impl PartialEq for DateTime {
+    fn eq(&self, other: &DateTime) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+fn cmp_zoned_floating(zoned: &DateTime, floating: &DateTime) -> Option<Ordering> {
+    let (zsecs, zfrac) = zoned.timeline();
+    let (fsecs, ffrac) = floating.timeline();
+    const MAX_TZ: i128 = 14 * 3600;
+    if (zsecs, zfrac) < (fsecs - MAX_TZ, ffrac) {
+        Some(Ordering::Less)
+    } else if (zsecs, zfrac) > (fsecs + MAX_TZ, ffrac) {
+        Some(Ordering::Greater)
+    } else {
+        None
+    }
+}
+
+/// A value of `xsd:duration`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Duration {
+    negative: bool,
+    months: u64,
+    /// always positive
+    seconds: Decimal,
+}
+
+impl Duration {
+    /// Whether this duration is strictly negative.
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// The number of months of this duration (always positive).
+    pub fn months(&self) -> u64 {
+        self.months
+    }
+
+    /// The number of seconds of this duration (always positive).
+    pub fn seconds(&self) -> &Decimal {
+        &self.seconds
+    }
+
+    /// Build a duration of `seconds` and `nanos` nanoseconds, with no months.
+    #[cfg(feature = "chrono")]
+    pub(crate) fn from_seconds(negative: bool, seconds: u64, nanos: u32) -> Duration {
+        let seconds = Decimal {
+            negative: false,
+            int: match seconds {
+                0 => String::new(),
+                _ => seconds.to_string(),
+            },
+            frac: format!("{:09}", nanos).trim_end_matches('0').to_string(),
+        };
+        let negative = negative && !(seconds.int.is_empty() && seconds.frac.is_empty());
+        Duration {
+            negative,
+            months: 0,
+            seconds,
+        }
+    }
+
+    /// The number of whole seconds of this duration, and its remaining nanoseconds,
+    /// or `None` if the seconds can not be represented with that precision.
+    #[cfg(feature = "chrono")]
+    pub(crate) fn seconds_nanos(&self) -> Option<(u128, u32)> {
+        let whole = match self.seconds.int.as_str() {
+            "" => 0,
+            int => int.parse().ok()?,
+        };
+        Some((whole, frac_nanos(&self.seconds.frac)?))
+    }
+
+    /// Parse `-?PnYnMnDTnHnMnS`,
+    /// accepting only the components allowed by `year_month` (Y, M) and `day_time` (D, H, M, S).
+    pub(crate) fn parse(txt: &str, year_month: bool, day_time: bool) -> Option<Duration> {
+        let unsigned = txt.strip_prefix('-').unwrap_or(txt);
+        let body = unsigned.strip_prefix('P')?;
+        let (date, time) = match body.find('T') {
+            Some(i) => (&body[..i], Some(&body[i + 1..])),
+            None => (body, None),
+        };
+        let date = components(date, "YMD")?;
+        let time = match time {
+            Some(time) => Some(components(time, "HMS")?).filter(|c| !c.is_empty())?,
+            None => vec![],
+        };
+        if date.is_empty() && time.is_empty() {
+            return None;
+        }
+        let mut months: u64 = 0;
+        let mut whole: u128 = 0;
+        let mut frac = "";
+        for (designator, number) in date {
+            let n: u64 = parse_digits(number)?;
+            let (n, allowed) = match designator {
+                'Y' => (n.checked_mul(12)?, year_month),
+                'M' => (n, year_month),
+                _ => {
+                    whole = whole.checked_add(u128::from(n).checked_mul(86400)?)?;
+                    (0, day_time)
+                }
+            };
+            if !allowed {
+                return None;
+            }
+            months = months.checked_add(n)?;
+        }
+        for (designator, number) in time {
+            if !day_time {
+                return None;
+            }
+            let (int, factor) = match designator {
+                'H' => (number, 3600),
+                'M' => (number, 60),
+                _ => match number.find('.') {
+                    Some(i) => {
+                        frac = &number[i + 1..];
+                        if frac.is_empty() || !frac.bytes().all(|b| b.is_ascii_digit()) {
+                            return None;
+                        }
+                        (&number[..i], 1)
+                    }
+                    None => (number, 1),
+                },
+            };
+            let n: u128 = parse_digits(int)?;
+            whole = whole.checked_add(n.checked_mul(factor)?)?;
+        }
+        let seconds = Decimal {
+            negative: false,
+            int: if whole == 0 {
+                String::new()
+            } else {
+                whole.to_string()
+            },
+            frac: frac.trim_end_matches('0').to_string(),
+        };
+        let negative = unsigned.len() != txt.len()
+            && !(months == 0 && seconds.int.is_empty() && seconds.frac.is_empty());
+        Some(Duration {
+            negative,
+            months,
+            seconds,
+        })
+    }
+
+    fn signed(&self) -> (i128, Decimal) {
+        let mut seconds = self.seconds.clone();
+        if self.negative {
+            seconds.negative = !(seconds.int.is_empty() && seconds.frac.is_empty());
+            (-(self.months as i128), seconds)
+        } else {
+            (self.months as i128, seconds)
+        }
+    }
+}
+
+/// Durations are compared as specified by XML Schema:
+/// a number of months and a number of seconds can not always be compared
+/// (e.g. `P1M` and `P30D`), in which case they are not ordered.
+impl PartialOrd for Duration {
+    fn partial_cmp(&self, other: &Duration) -> Option<Ordering> {
+        let (m1, s1) = self.signed();
+        let (m2, s2) = other.signed();
+        let mc = m1.cmp(&m2);
+        let sc = s1.cmp(&s2);
+        if mc == Ordering::Equal {
+            return Some(sc);
+        }
+        if sc == Ordering::Equal || sc == mc {
+            return Some(mc);
+        }
+        // the months and the seconds disagree, so we need to know how long the months are
+        let dm = (m1 - m2).unsigned_abs();
+        let years = (dm / 12) as f64;
+        let rem = (dm % 12) as usize;
+        let min = (years * 365.0 + MIN_DAYS[rem]) * 86400.0;
+        let max = (years * 366.0 + MAX_DAYS[rem]) * 86400.0;
+        let ds = s1.to_f64() - s2.to_f64();
+        let (low, high) = if mc == Ordering::Greater {
+            (ds + min, ds + max)
+        } else {
+            (ds - max, ds - min)
+        };
+        if low > 0.0 {
+            Some(Ordering::Greater)
+        } else if high < 0.0 {
+            Some(Ordering::Less)
+        } else {
+            None
+        }
+    }
+}
+
+/// The minimum and maximum number of days in `n` consecutive months (`n` < 12).
+const MIN_DAYS: [f64; 12] = [
+    0.0, 28.0, 59.0, 89.0, 120.0, 150.0, 181.0, 212.0, 242.0, 273.0, 303.0, 334.0,
+];
+const MAX_DAYS: [f64; 12] = [
+    0.0, 31.0, 62.0, 92.0, 123.0, 153.0, 184.0, 215.0, 245.0, 276.0, 306.0, 337.0,
+];
+
+/// Write the canonical form of `xsd:duration`.
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let whole: u128 = self.seconds.int.parse().unwrap_or(0);
+        let frac = &self.seconds.frac;
+        if self.months == 0 && whole == 0 && frac.is_empty() {
+            return f.write_str("PT0S");
+        }
+        if self.negative {
+            f.write_str("-")?;
+        }
+        f.write_str("P")?;
+        let (years, months) = (self.months / 12, self.months % 12);
+        if years > 0 {
+            write!(f, "{}Y", years)?;
+        }
+        if months > 0 {
+            write!(f, "{}M", months)?;
+        }
+        let (days, hours) = (whole / 86400, whole % 86400 / 3600);
+        let (minutes, seconds) = (whole % 3600 / 60, whole % 60);
+        if days > 0 {
+            write!(f, "{}D", days)?;
+        }
+        if hours > 0 || minutes > 0 || seconds > 0 || !frac.is_empty() {
+            f.write_str("T")?;
+            if hours > 0 {
+                write!(f, "{}H", hours)?;
+            }
+            if minutes > 0 {
+                write!(f, "{}M", minutes)?;
+            }
+            if seconds > 0 || !frac.is_empty() {
+                write!(f, "{}", seconds)?;
+                if !frac.is_empty() {
+                    write!(f, ".{}", frac)?;
+                }
+                f.write_str("S")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Split the components of a duration (e.g. `1Y2M`) into designators and numbers,
+/// checking that designators appear at most once, in the given order.
+fn components<'a>(txt: &'a str, designators: &str) -> Option<Vec<(char, &'a str)>> {
+    let mut result = vec![];
+    let mut rest = txt;
+    let mut designators = designators;
+    while !rest.is_empty() {
+        let i = rest.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+        let designator = rest[i..].chars().next()?;
+        let pos = designators.find(designator)?;
+        designators = &designators[pos + 1..];
+        result.push((designator, &rest[..i]));
+        rest = &rest[i + designator.len_utf8()..];
+    }
+    Some(result)
+}
+
+/// Parse a non-empty sequence of ASCII digits.
+fn parse_digits<N: FromStr>(txt: &str) -> Option<N> {
+    if txt.is_empty() || !txt.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    txt.parse().ok()
+}
+
+/// Convert the digits of a fractional part of seconds to nanoseconds.
+#[cfg(feature = "chrono")]
+fn frac_nanos(frac: &str) -> Option<u32> {
+    if frac.len() > 9 {
+        return None;
+    }
+    Some(frac.parse::<u32>().unwrap_or(0) * 10_u32.pow(9 - frac.len() as u32))
+}
+
+/// Parse the two ASCII digits at the start of `txt`.
+fn two_digits(txt: &str) -> Option<u8> {
+    match txt.as_bytes() {
+        [d1, d2, ..] if d1.is_ascii_digit() && d2.is_ascii_digit() => {
+            Some((d1 - b'0') * 10 + (d2 - b'0'))
+        }
+        _ => None,
+    }
+}
+
+/// Split the optional timezone (`Z` or `(+|-)hh:mm`) at the end of `txt`.
+fn split_tz(txt: &str) -> Option<(&str, Option<i16>)> {
+    if !txt.is_ascii() {
+        return None;
+    }
+    if let Some(rest) = txt.strip_suffix('Z') {
+        return Some((rest, Some(0)));
+    }
+    let bytes = txt.as_bytes();
+    let len = bytes.len();
+    if len >= 6 && (bytes[len - 6] == b'+' || bytes[len - 6] == b'-') && bytes[len - 3] == b':' {
+        let hours = two_digits(&txt[len - 5..])?;
+        let minutes = two_digits(&txt[len - 2..])?;
+        if hours > 14 || minutes > 59 || (hours == 14 && minutes > 0) {
+            return None;
+        }
+        let mut tz = hours as i16 * 60 + minutes as i16;
+        if bytes[len - 6] == b'-' {
+            tz = -tz;
+        }
+        return Some((&txt[..len - 6], Some(tz)));
+    }
+    Some((txt, None))
+}
+
+fn is_leap(year: i64) -> bool {
+    year.rem_euclid(4) == 0 && (year.rem_euclid(100) != 0 || year.rem_euclid(400) == 0)
+}
+
+fn days_in(year: i64, month: u8) -> u8 {
+    match month {
+        2 if is_leap(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// The number of days since 1970-01-01 in the proleptic Gregorian calendar.
+///
+/// See <http://howardhinnant.github.io/date_algorithms.html#days_from_civil>
+fn days_from_civil(year: i64, month: u8, day: u8) -> i64 {
+    let (month, day) = (month as i64, day as i64);
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Parse the lexical form of `xsd:float` or `xsd:double`.
+fn parse_float<F: FromStr>(txt: &str) -> Option<F> {
+    let valid = match txt {
+        "INF" | "+INF" | "-INF" | "NaN" => true,
+        _ => {
+            let unsigned = txt.strip_prefix(['+', '-']).unwrap_or(txt);
+            let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+                Some(i) => (&unsigned[..i], Some(&unsigned[i + 1..])),
+                None => (unsigned, None),
+            };
+            let (int, frac) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+            let digits = |txt: &str| txt.bytes().all(|b| b.is_ascii_digit());
+            !(int.is_empty() && frac.is_empty())
+                && digits(int)
+                && digits(frac)
+                && exponent.is_none_or(|exp| {
+                    let exp = exp.strip_prefix(['+', '-']).unwrap_or(exp);
+                    !exp.is_empty() && digits(exp)
+                })
+        }
+    };
+    if valid {
+        txt.parse().ok()
+    } else {
+        None
+    }
+}
+
+/// Write the canonical form of `xsd:float` or `xsd:double` (e.g. `1.5E1`).
+fn fmt_float<F>(f: &mut fmt::Formatter, x: F, nan: bool, infinite: bool) -> fmt::Result
+where
+    F: fmt::UpperExp + PartialOrd + Default,
+{
+    if nan {
+        f.write_str("NaN")
+    } else if infinite {
+        f.write_str(if x < F::default() { "-INF" } else { "INF" })
+    } else {
+        let txt = format!("{:E}", x);
+        match txt.find('E') {
+            Some(i) if !txt[..i].contains('.') => write!(f, "{}.0{}", &txt[..i], &txt[i..]),
+            _ => f.write_str(&txt),
+        }
+    }
+}
+
+/// The bounds of `xsd:integer` and its derived types, or `None` for any other datatype.
+#[allow(clippy::type_complexity)]
+fn integer_bounds(local: &str) -> Option<(Option<i128>, Option<i128>)> {
+    Some(match local {
+        "integer" => (None, None),
+        "nonPositiveInteger" => (None, Some(0)),
+        "negativeInteger" => (None, Some(-1)),
+        "nonNegativeInteger" => (Some(0), None),
+        "positiveInteger" => (Some(1), None),
+        "long" => (Some(i64::MIN.into()), Some(i64::MAX.into())),
+        "int" => (Some(i32::MIN.into()), Some(i32::MAX.into())),
+        "short" => (Some(i16::MIN.into()), Some(i16::MAX.into())),
+        "byte" => (Some(i8::MIN.into()), Some(i8::MAX.into())),
+        "unsignedLong" => (Some(0), Some(u64::MAX.into())),
+        "unsignedInt" => (Some(0), Some(u32::MAX.into())),
+        "unsignedShort" => (Some(0), Some(u16::MAX.into())),
+        "unsignedByte" => (Some(0), Some(u8::MAX.into())),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,168 @@
+use super::*;
+use crate::{BoxTerm, RefTerm};
+use test_case::test_case;
+
+fn lit<'a>(txt: &'a str, dt: SimpleIri<'static>) -> RefTerm<'a> {
+    RefTerm::new_literal_dt(txt, dt).unwrap()
+}
+
+#[test_case("01", xsd::int, "1", xsd::int ; "leading zero")]
+#[test_case("+1", xsd::integer, "1", xsd::byte ; "sign and subtype")]
+#[test_case("-0", xsd::integer, "0", xsd::integer ; "negative zero")]
+#[test_case("1.50", xsd::decimal, "1.5", xsd::decimal ; "trailing zero")]
+#[test_case("1", xsd::integer, "1.0", xsd::decimal ; "integer and decimal")]
+#[test_case("1", xsd::integer, "1.0E0", xsd::double ; "integer and double")]
+#[test_case("0.5", xsd::float, ".5e0", xsd::double ; "float and double")]
+#[test_case("1", xsd::boolean, "true", xsd::boolean ; "boolean")]
+#[test_case("2020-01-01T00:00:00Z", xsd::dateTime, "2020-01-01T01:00:00.000+01:00", xsd::dateTime ; "timezones")]
+#[test_case("2019-12-31T24:00:00", xsd::dateTime, "2020-01-01T00:00:00", xsd::dateTime ; "end of day")]
+#[test_case("2020-01-01Z", xsd::date, "2020-01-01+00:00", xsd::date ; "date")]
+#[test_case("24:00:00", xsd::time, "00:00:00", xsd::time ; "time")]
+#[test_case("P1Y", xsd::duration, "P12M", xsd::yearMonthDuration ; "months")]
+#[test_case("PT36H", xsd::dayTimeDuration, "P1DT12H", xsd::duration ; "seconds")]
+#[test_case("-PT0S", xsd::duration, "P0D", xsd::duration ; "zero duration")]
+fn equal(lex1: &str, dt1: SimpleIri<'static>, lex2: &str, dt2: SimpleIri<'static>) {
+    let (l1, l2) = (lit(lex1, dt1), lit(lex2, dt2));
+    assert!(value_eq(&l1, &l2));
+    assert_eq!(value_cmp(&l1, &l2), Some(Ordering::Equal));
+}
+
+#[test_case("2", xsd::integer, "10", xsd::integer ; "integers")]
+#[test_case("-10", xsd::integer, "-2", xsd::integer ; "negative integers")]
+#[test_case("-0.5", xsd::decimal, "0", xsd::integer ; "decimals")]
+#[test_case("0.09", xsd::decimal, "0.1", xsd::decimal ; "fractions")]
+#[test_case("1", xsd::integer, "1.5E0", xsd::double ; "integer and double")]
+#[test_case("-INF", xsd::double, "-1E300", xsd::double ; "infinity")]
+#[test_case("false", xsd::boolean, "true", xsd::boolean ; "booleans")]
+#[test_case("2020-01-01T12:00:00+02:00", xsd::dateTime, "2020-01-01T11:00:00Z", xsd::dateTime ; "timezones")]
+#[test_case("2020-01-01T00:00:00Z", xsd::dateTime, "2020-01-02T00:00:00", xsd::dateTime ; "with and without timezone")]
+#[test_case("-0001-01-01", xsd::date, "0000-01-01", xsd::date ; "negative years")]
+#[test_case("23:59:59.5", xsd::time, "23:59:59.51", xsd::time ; "fractional seconds")]
+#[test_case("P1M", xsd::duration, "P1MT1S", xsd::duration ; "same months")]
+#[test_case("P27D", xsd::duration, "P1M", xsd::duration ; "less days than in a month")]
+#[test_case("P1Y", xsd::duration, "P367D", xsd::duration ; "more days than in a year")]
+#[test_case("abc", xsd::string, "abd", xsd::string ; "strings")]
+fn less(lex1: &str, dt1: SimpleIri<'static>, lex2: &str, dt2: SimpleIri<'static>) {
+    let (l1, l2) = (lit(lex1, dt1), lit(lex2, dt2));
+    assert!(!value_eq(&l1, &l2));
+    assert_eq!(value_cmp(&l1, &l2), Some(Ordering::Less));
+    assert_eq!(value_cmp(&l2, &l1), Some(Ordering::Greater));
+}
+
+#[test_case("NaN", xsd::double, "NaN", xsd::double ; "nan")]
+#[test_case("1", xsd::integer, "true", xsd::boolean ; "number and boolean")]
+#[test_case("2020-01-01", xsd::date, "2020-01-01T00:00:00", xsd::dateTime ; "date and dateTime")]
+#[test_case("2020-01-01T00:00:00Z", xsd::dateTime, "2020-01-01T05:00:00", xsd::dateTime ; "timezone uncertainty")]
+#[test_case("P1M", xsd::duration, "P30D", xsd::duration ; "month and days")]
+#[test_case("1", xsd::integer, "1", xsd::string ; "number and string")]
+#[test_case("01", xsd::integer, "1", xsd::hexBinary ; "unsupported datatype")]
+#[test_case("1.5", xsd::integer, "1.5", xsd::decimal ; "ill-typed")]
+fn incomparable(lex1: &str, dt1: SimpleIri<'static>, lex2: &str, dt2: SimpleIri<'static>) {
+    let (l1, l2) = (lit(lex1, dt1), lit(lex2, dt2));
+    assert!(!value_eq(&l1, &l2));
+    assert_eq!(value_cmp(&l1, &l2), None);
+}
+
+#[test]
+fn non_literals() {
+    let i1 = RefTerm::new_iri("http://example.org/").unwrap();
+    let i2 = BoxTerm::new_iri("http://example.org/").unwrap();
+    let l = RefTerm::new_literal_lang("chat", "en").unwrap();
+    assert!(value_eq(&i1, &i2));
+    assert_eq!(value_cmp(&i1, &i2), Some(Ordering::Equal));
+    assert_eq!(value_cmp(&i1, &l), None);
+    assert_eq!(value_cmp(&l, &l), Some(Ordering::Equal));
+}
+
+#[test_case("+01", xsd::integer, "1")]
+#[test_case("-0", xsd::int, "0")]
+#[test_case("255", xsd::unsignedByte, "255")]
+#[test_case("0010.500", xsd::decimal, "10.5")]
+#[test_case("-.5", xsd::decimal, "-0.5")]
+#[test_case("3.0", xsd::decimal, "3")]
+#[test_case("1", xsd::double, "1.0E0")]
+#[test_case("-0", xsd::double, "-0.0E0")]
+#[test_case("150.25e-3", xsd::double, "1.5025E-1")]
+#[test_case("+INF", xsd::float, "INF")]
+#[test_case("0.1", xsd::float, "1.0E-1")]
+#[test_case("1", xsd::boolean, "true")]
+#[test_case("0", xsd::boolean, "false")]
+#[test_case("2020-02-29T24:00:00.000+00:00", xsd::dateTime, "2020-03-01T00:00:00Z")]
+#[test_case(
+    "2020-06-15T08:30:00.250-05:30",
+    xsd::dateTime,
+    "2020-06-15T08:30:00.25-05:30"
+)]
+#[test_case("-12345-01-01T00:00:00", xsd::dateTime, "-12345-01-01T00:00:00")]
+#[test_case(
+    "2020-01-01T00:00:00.0+00:00",
+    xsd::dateTimeStamp,
+    "2020-01-01T00:00:00Z"
+)]
+#[test_case("2020-01-01+00:00", xsd::date, "2020-01-01Z")]
+#[test_case("24:00:00", xsd::time, "00:00:00")]
+#[test_case("P0Y0M0DT0H0M0.0S", xsd::duration, "PT0S")]
+#[test_case("P0Y", xsd::yearMonthDuration, "P0M")]
+#[test_case("P13M", xsd::yearMonthDuration, "P1Y1M")]
+#[test_case("-PT90061.5S", xsd::dayTimeDuration, "-P1DT1H1M1.5S")]
+#[test_case("P2DT0H", xsd::duration, "P2D")]
+#[test_case("  x ", xsd::string, "  x ")]
+fn canonicalize(lex: &str, dt: SimpleIri<'static>, exp: &str) {
+    let lit = Literal::<&str>::new_dt(lex, Iri::<&str>::from(dt));
+    let canonical = lit.canonicalize().unwrap();
+    assert_eq!(canonical.txt().as_ref(), exp);
+    assert_eq!(canonical.dt(), lit.dt());
+    assert!(value_eq(&lit, &canonical) || dt == xsd::string);
+}
+
+#[test]
+fn canonicalize_does_not_allocate() {
+    let lit = Literal::<Box<str>>::new_dt("42", Iri::<&str>::from(xsd::integer));
+    let canonical = lit.canonicalize().unwrap();
+    assert!(canonical.txt().is_borrowed());
+
+    let lit = Literal::<Box<str>>::new_dt("0042", Iri::<&str>::new("tag:unknown").unwrap());
+    let canonical = lit.canonicalize().unwrap();
+    assert_eq!(canonical.txt().as_ref(), "0042");
+    assert!(canonical.txt().is_borrowed());
+}
+
+#[test]
+fn invalid() {
+    for (lex, dt) in &[
+        ("1.5", xsd::integer),
+        ("", xsd::decimal),
+        (".", xsd::decimal),
+        ("256", xsd::unsignedByte),
+        ("-1", xsd::nonNegativeInteger),
+        ("0", xsd::positiveInteger),
+        ("1e", xsd::double),
+        ("inf", xsd::double),
+        ("yes", xsd::boolean),
+        ("2021-02-29", xsd::date),
+        ("2020-13-01", xsd::date),
+        ("20-01-01", xsd::date),
+        ("02020-01-01", xsd::date),
+        ("2020-01-01T24:00:01", xsd::dateTime),
+        ("2020-01-01T12:00:00+14:30", xsd::dateTime),
+        ("2020-01-01T12:00:00", xsd::dateTimeStamp),
+        ("12:00:00.", xsd::time),
+        ("12:60:00", xsd::time),
+        ("P", xsd::duration),
+        ("PT", xsd::duration),
+        ("P1D1Y", xsd::duration),
+        ("P1.5D", xsd::duration),
+        ("P1Y", xsd::dayTimeDuration),
+        ("PT1H", xsd::yearMonthDuration),
+    ] {
+        let lit = Literal::<&str>::new_dt(*lex, Iri::<&str>::from(*dt));
+        assert!(
+            matches!(
+                lit.canonicalize(),
+                Err(TermError::InvalidLexicalValue { .. })
+            ),
+            "{}",
+            lit
+        );
+    }
+}
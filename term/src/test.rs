@@ -1,6 +1,6 @@
 use super::Term::*;
 use super::*;
-use sophia_api::ns::xsd;
+use sophia_api::ns::{rdf, xsd};
 use sophia_api::term::CopiableTerm;
 
 fn h<H: std::hash::Hash>(x: &H) -> u64 {
@@ -23,7 +23,7 @@ fn iri() {
         assert_eq!(s1, exp);
         assert_eq!(s2, exp);
     } else {
-        assert!(false, "Should have returned Iri(_)");
+        panic!("Should have returned Iri(_)");
     }
     let res = RefTerm::new_iri("1://champin.net/");
     assert!(res.is_err());
@@ -43,7 +43,7 @@ fn iri2() {
         assert_eq!(s1, exp);
         assert_eq!(s2, exp);
     } else {
-        assert!(false, "Should have returned Iri(_)");
+        panic!("Should have returned Iri(_)");
     }
     let res = RefTerm::new_iri_suffixed("1://champin.net/", "pa");
     assert!(res.is_err());
@@ -140,7 +140,7 @@ fn iri_normalized_last_gen_delim() {
             "ê",
         ),
     ] {
-        let i1 = if sf1.len() == 0 {
+        let i1 = if sf1.is_empty() {
             BoxTerm::new_iri(*ns1).unwrap()
         } else {
             BoxTerm::new_iri_suffixed(*ns1, *sf1).unwrap()
@@ -149,12 +149,43 @@ fn iri_normalized_last_gen_delim() {
         assert_eq!(i1, i2);
         if let Iri(i2) = i2 {
             assert_eq!(&i2.ns[..], *ns2);
-            let sf2 = if sf2.len() == 0 { None } else { Some(*sf2) };
+            let sf2 = if sf2.is_empty() { None } else { Some(*sf2) };
             assert_eq!(i2.suffix.as_ref().map(AsRef::as_ref), sf2);
         }
     }
 }
 
+#[test]
+fn iri_normalized_rfc3987_and_uri() {
+    for (txt, rfc3987, uri) in &[
+        (
+            "HTTP://Example.org/a/../b",
+            "http://example.org/b",
+            "http://example.org/b",
+        ),
+        (
+            "http://bücher.example:80/%c3%a9t%C3%A9",
+            "http://bücher.example/été",
+            "http://xn--bcher-kva.example/%C3%A9t%C3%A9",
+        ),
+    ] {
+        let i1 = BoxTerm::new_iri(*txt).unwrap();
+        let i2 = i1.normalized(Normalization::Rfc3987);
+        assert_eq!(i2, BoxTerm::new_iri(*rfc3987).unwrap());
+        let i3 = i1.normalized(Normalization::Uri);
+        assert_eq!(i3, BoxTerm::new_iri(*uri).unwrap());
+    }
+    let i1 = BoxTerm::new_iri_suffixed("http://example.org/", "b").unwrap();
+    if let Iri(i2) = i1.normalized(Normalization::Rfc3987) {
+        assert!(i2.suffix.is_none());
+    }
+    let dt = BoxTerm::new_iri("HTTP://Example.org/dt").unwrap();
+    let l1 = BoxTerm::new_literal_dt("hello", dt).unwrap();
+    let dt = BoxTerm::new_iri("http://example.org/dt").unwrap();
+    let l2 = BoxTerm::new_literal_dt("hello", dt).unwrap();
+    assert_eq!(l1.normalized(Normalization::Rfc3987), l2);
+}
+
 #[test]
 fn bnode() {
     let b1 = BoxTerm::new_bnode("foo").unwrap();
@@ -261,6 +292,33 @@ fn literal_lang() {
     assert!(res.is_err());
 }
 
+#[test]
+fn literal_lang_dir() {
+    let lit = RefTerm::new_literal_lang_dir("مرحبا", "ar", Direction::Rtl).unwrap();
+    assert_eq!(lit.value(), "مرحبا");
+    assert_eq!(lit.language(), Some("ar"));
+    assert_eq!(lit.direction(), Some(Direction::Rtl));
+    assert_eq!(lit.datatype().unwrap(), rdf::dirLangString);
+    assert_eq!(&format!("{}", lit), "\"مرحبا\"@ar--rtl");
+
+    let ltr = RefTerm::new_literal_lang_dir("مرحبا", "AR", Direction::Ltr).unwrap();
+    let undirected = RefTerm::new_literal_lang("مرحبا", "ar").unwrap();
+    assert_ne!(lit, ltr);
+    assert_ne!(h(&lit), h(&ltr));
+    assert_ne!(lit, undirected);
+    assert_ne!(h(&lit), h(&undirected));
+    assert!(undirected < ltr && ltr < lit);
+
+    let copy = BoxTerm::copy(&lit);
+    assert_eq!(lit, copy);
+    assert_eq!(h(&lit), h(&copy));
+    let lit: literal::Literal<&str> = lit.try_into().expect("Should be a literal");
+    assert_eq!(lit.dir(), Some(Direction::Rtl));
+
+    let res = RefTerm::new_literal_lang_dir("hello", "", Direction::Ltr);
+    assert!(res.is_err());
+}
+
 #[test]
 fn literal_dt() {
     // Constructing from str
@@ -326,8 +384,8 @@ fn literal_eq_different_case() {
 fn literal_similar_but_not_eq() {
     let l1 = RefTerm::new_literal_lang("42", "en").unwrap();
     let l2 = RefTerm::new_literal_lang("42", "en-us").unwrap();
-    let l3 = RefTerm::new_literal_dt("42", xsd::string.clone()).unwrap();
-    let l4 = RefTerm::new_literal_dt("42", xsd::integer.clone()).unwrap();
+    let l3 = RefTerm::new_literal_dt("42", xsd::string).unwrap();
+    let l4 = RefTerm::new_literal_dt("42", xsd::integer).unwrap();
     assert_ne!(l1, l2);
     assert_ne!(h(&l1), h(&l2));
     assert_ne!(l1, l3);
@@ -371,7 +429,7 @@ fn literal_normalized_last_gen_delim() {
         ("tag:foo", "", "tag:", "foo"),
         ("tag:", "foo", "tag:", "foo"),
     ] {
-        let dt = if sf1.len() == 0 {
+        let dt = if sf1.is_empty() {
             BoxTerm::new_iri(*ns1).unwrap()
         } else {
             BoxTerm::new_iri_suffixed(*ns1, *sf1).unwrap()
@@ -381,8 +439,8 @@ fn literal_normalized_last_gen_delim() {
         assert_eq!(l1, l2);
         if let Literal(l2) = l2 {
             let i2 = l2.dt();
-            assert_eq!(&i2.ns[..], *ns2);
-            let sf2 = if sf2.len() == 0 { None } else { Some(*sf2) };
+            assert_eq!(i2.ns, *ns2);
+            let sf2 = if sf2.is_empty() { None } else { Some(*sf2) };
             assert_eq!(i2.suffix, sf2);
         }
     }
@@ -395,7 +453,8 @@ fn variable() {
         let res = BoxTerm::new_variable(*id);
         assert!(
             res.is_ok(),
-            format!("{:?} should be accepted as a variable name", *id)
+            "{:?} should be accepted as a variable name",
+            *id
         );
 
         let var = res.unwrap();
@@ -408,7 +467,8 @@ fn variable() {
         let res = BoxTerm::new_variable(*id);
         assert!(
             res.is_err(),
-            format!("{:?} should be refused as a variable name", *id)
+            "{:?} should be refused as a variable name",
+            *id
         );
     }
 }
@@ -462,6 +522,37 @@ fn term_similar_but_not_eq() {
     assert_ne!(h(&t3), h(&t4));
 }
 
+#[test]
+fn quoted_triple() {
+    let s = RefTerm::new_iri("http://champin.net/#pa").unwrap();
+    let p = RefTerm::new_iri("http://schema.org/name").unwrap();
+    let o = RefTerm::new_literal_lang("Pierre-Antoine", "fr").unwrap();
+    let t = RefTerm::new_triple(s.clone(), p.clone(), o.clone());
+    assert_eq!(t.kind(), TermKind::QuotedTriple);
+    assert_eq!(t.value(), "");
+    assert_eq!(
+        format!("{}", t),
+        r#"<< <http://champin.net/#pa> <http://schema.org/name> "Pierre-Antoine"@fr >>"#
+    );
+    let spo = t.triple().unwrap();
+    assert!(term_eq(spo[0], &s));
+    assert!(term_eq(spo[2], &o));
+
+    let t2: BoxTerm = t.clone_into();
+    assert_eq!(t, t2);
+    assert_eq!(h(&t), h(&t2));
+    let t3 = BoxTerm::copy(&t);
+    assert_eq!(t2, t3);
+
+    let t4 = RefTerm::new_triple(o, p, s);
+    assert_ne!(t, t4);
+    assert_ne!(h(&t), h(&t4));
+
+    let nested = RefTerm::new_triple(t.clone(), RefTerm::new_iri("tag:p").unwrap(), t4);
+    assert!(format!("{}", nested).starts_with("<< << <http://champin.net/#pa>"));
+    assert!(nested > t);
+}
+
 #[test]
 fn map() {
     let mut cnt = 0;
@@ -478,9 +569,9 @@ fn map() {
 fn map_into() {
     let t1 = StaticTerm::new_iri("http://champin.net/#pa").unwrap();
     let t2 = t1.map_into::<Box<str>>();
-    let _3 = t2.clone().map_into::<Rc<str>>();
-    let _4 = t2.clone().map_into::<Arc<str>>();
-    let _5 = t2.map_into::<String>();
+    let _t3 = t2.clone().map_into::<Rc<str>>();
+    let _t4 = t2.clone().map_into::<Arc<str>>();
+    let _t5 = t2.map_into::<String>();
 }
 
 #[test]
@@ -489,7 +580,7 @@ fn convert() {
     let t2 = t1.clone_into::<Box<str>>();
     let t3 = t2.clone_into::<Rc<str>>();
     let t4 = t3.clone_into::<Arc<str>>();
-    let _5 = t4.clone_into::<&str>();
+    let _t5 = t4.clone_into::<&str>();
 }
 
 pub(crate) const POSITIVE_1CHAR_IDS: &[&str] = &[
@@ -157,7 +157,7 @@ impl<TD: TermData> TTerm for Variable<TD> {
     fn kind(&self) -> TermKind {
         TermKind::Variable
     }
-    fn value_raw(&self) -> RawValue {
+    fn value_raw(&self) -> RawValue<'_> {
         self.0.as_ref().into()
     }
     fn as_dyn(&self) -> &dyn TTerm {